@@ -12,6 +12,9 @@ do
         os.cd(path.join(engine_root_dir, "rs_media_cmd"))
         os.exec("cargo build --package rs_media_cmd --bin rs_media_cmd")
         os.exec("cargo build --package rs_media_cmd --bin rs_media_cmd --release")
+        os.cd(path.join(engine_root_dir, "rs_artifact_cmd"))
+        os.exec("cargo build --package rs_artifact_cmd --bin rs_artifact_cmd")
+        os.exec("cargo build --package rs_artifact_cmd --bin rs_artifact_cmd --release")
         os.cd(path.join(engine_root_dir, "programs/rs_reflection_generator"))
         os.exec("cargo build --package rs_reflection_generator --bin rs_reflection_generator")
         os.exec("cargo build --package rs_reflection_generator --bin rs_reflection_generator --release")
@@ -57,7 +57,7 @@ impl Application {
             std::collections::HashMap::new(),
         )
         .map_err(|err| crate::error::Error::Engine(err))?;
-        engine.init_resources();
+        let _ = engine.init_resources_streaming();
         let mut camera = rs_engine::camera::Camera::default(width, height);
         camera.set_world_location(glam::vec3(0.0, 10.0, 20.0));
         Ok(Application {
@@ -151,8 +151,8 @@ pub fn Application_fromSurface(
         FileHeader::check_identification(&mut artifact_input_stream, ARTIFACT_FILE_MAGIC_NUMBERS)
             .map_err(|err| crate::error::Error::CheckIdentificationFail(err))?;
 
-        let _: ArtifactFileHeader =
-            FileHeader::get_header2(&mut artifact_input_stream, Some(EEndianType::Little))
+        let _ =
+            ArtifactFileHeader::read_from(&mut artifact_input_stream, Some(EEndianType::Little))
                 .map_err(|err| crate::error::Error::Artifact(err))?;
         let application =
             Application::from_native_window(native_window, artifact_input_stream, logger)?;
@@ -1,9 +1,12 @@
 use crate::error::Result;
+use crate::resource_info::{ResourceInfoV0, ResourceInfoV1};
 use crate::skeleton::Skeleton;
 use crate::{
     asset::{self, Asset},
+    compression::{self, CompressionPolicy, ECompressionType},
     file_header::{
-        self, FileHeader, ARTIFACT_FILE_MAGIC_NUMBERS, HEADER_LENGTH_SIZE, IDENTIFICATION_SIZE,
+        self, FileHeader, ARTIFACT_FILE_MAGIC_NUMBERS, ARTIFACT_FORMAT_VERSION, HEADER_LENGTH_SIZE,
+        IDENTIFICATION_SIZE,
     },
     image::Image,
     resource_info::ResourceInfo,
@@ -12,19 +15,273 @@ use crate::{
     static_mesh::StaticMesh,
     EEndianType,
 };
-use rs_core_minimal::settings::Settings;
+use bincode::Options;
+use rs_core_minimal::settings::{Settings, SettingsV0, SettingsV1};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::{
-    collections::HashMap,
+    borrow::Cow,
+    collections::{HashMap, HashSet},
     io::{BufWriter, Cursor, Read, Seek, SeekFrom, Write},
     path::Path,
 };
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ArtifactFileHeader {
+    /// Always the first field, so the version can be read even if the rest of the layout changed.
+    pub format_version: u32,
     pub settings: Settings,
     pub resource_map: std::collections::HashMap<url::Url, ResourceInfo>,
+    /// Hash of `settings` and `resource_map`, `None` for artifacts written before hashes were recorded.
+    pub header_hash: Option<u64>,
+}
+
+/// The layout of `ArtifactFileHeader` before resources could be compressed.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct ArtifactFileHeaderV0 {
+    settings: SettingsV0,
+    resource_map: std::collections::HashMap<url::Url, ResourceInfoV0>,
+}
+
+/// The layout of `ArtifactFileHeader` before hashes were recorded.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct ArtifactFileHeaderV1 {
+    settings: SettingsV0,
+    resource_map: std::collections::HashMap<url::Url, ResourceInfoV1>,
+}
+
+/// The layout of `ArtifactFileHeader` before the format version was recorded.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct ArtifactFileHeaderV2 {
+    settings: SettingsV0,
+    resource_map: std::collections::HashMap<url::Url, ResourceInfo>,
+    header_hash: Option<u64>,
+}
+
+/// The layout of `ArtifactFileHeader` before virtual texture tiles had borders.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct ArtifactFileHeaderV3 {
+    format_version: u32,
+    settings: SettingsV0,
+    resource_map: std::collections::HashMap<url::Url, ResourceInfo>,
+    header_hash: Option<u64>,
+}
+
+/// The layout of `ArtifactFileHeader` before cluster levels of detail were added.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct ArtifactFileHeaderV4 {
+    format_version: u32,
+    settings: SettingsV1,
+    resource_map: std::collections::HashMap<url::Url, ResourceInfo>,
+    header_hash: Option<u64>,
+}
+
+impl ArtifactFileHeader {
+    pub fn new(settings: Settings, resource_map: HashMap<url::Url, ResourceInfo>) -> Result<Self> {
+        let mut artifact_file_header = ArtifactFileHeader {
+            format_version: ARTIFACT_FORMAT_VERSION,
+            settings,
+            resource_map,
+            header_hash: None,
+        };
+        artifact_file_header.header_hash = Some(artifact_file_header.compute_header_hash()?);
+        Ok(artifact_file_header)
+    }
+
+    pub fn decode(data: &[u8]) -> Result<ArtifactFileHeader> {
+        let options = bincode::options()
+            .with_fixint_encoding()
+            .reject_trailing_bytes();
+        // Headers without a format version start with the settings, whose first bytes
+        // never read as a small version.
+        let format_version = data
+            .get(0..4)
+            .and_then(|x| <[u8; 4]>::try_from(x).ok())
+            .map(u32::from_le_bytes);
+        let err = match format_version {
+            Some(3) => match options.deserialize::<ArtifactFileHeaderV3>(data) {
+                Ok(header) => {
+                    log::trace!("Read artifact file header without tile borders.");
+                    return Self::from_legacy(
+                        header.format_version,
+                        header.settings,
+                        header.resource_map,
+                        header.header_hash,
+                    );
+                }
+                Err(err) => err,
+            },
+            Some(4) => match options.deserialize::<ArtifactFileHeaderV4>(data) {
+                Ok(header) => {
+                    log::trace!("Read artifact file header without cluster levels of detail.");
+                    return Self::from_legacy(
+                        header.format_version,
+                        header.settings,
+                        header.resource_map,
+                        header.header_hash,
+                    );
+                }
+                Err(err) => err,
+            },
+            _ => match options.deserialize::<ArtifactFileHeader>(data) {
+                Ok(artifact_file_header) => {
+                    Self::check_format_version(artifact_file_header.format_version)?;
+                    return Ok(artifact_file_header);
+                }
+                Err(err) => err,
+            },
+        };
+        if let Ok(header) = options.deserialize::<ArtifactFileHeaderV2>(data) {
+            log::trace!("Read artifact file header without format version.");
+            return Self::from_legacy(2, header.settings, header.resource_map, header.header_hash);
+        }
+        if let Ok(header) = options.deserialize::<ArtifactFileHeaderV1>(data) {
+            log::trace!("Read artifact file header without hashes.");
+            let resource_map = header
+                .resource_map
+                .into_iter()
+                .map(|(url, resource_info)| (url, resource_info.into()))
+                .collect();
+            return Self::from_legacy(1, header.settings, resource_map, None);
+        }
+        if let Ok(header) = options.deserialize::<ArtifactFileHeaderV0>(data) {
+            log::trace!("Read legacy artifact file header.");
+            let resource_map = header
+                .resource_map
+                .into_iter()
+                .map(|(url, resource_info)| (url, resource_info.into()))
+                .collect();
+            return Self::from_legacy(0, header.settings, resource_map, None);
+        }
+        // The layout of a newer format is unknown, but its version is still readable.
+        // Large values are more likely garbage than a real version.
+        if let Some(format_version) = format_version {
+            if format_version > ARTIFACT_FORMAT_VERSION && format_version < u16::MAX as u32 {
+                Self::check_format_version(format_version)?;
+            }
+        }
+        Err(crate::error::Error::Bincode(
+            err,
+            Some(String::from(
+                "Fail to deserialize artifact file header, the file may be corrupted.",
+            )),
+        ))
+    }
+
+    /// The recorded hash covers the legacy settings, so it is verified before the settings
+    /// are migrated and recomputed afterwards.
+    fn from_legacy<S>(
+        format_version: u32,
+        settings: S,
+        resource_map: HashMap<url::Url, ResourceInfo>,
+        header_hash: Option<u64>,
+    ) -> Result<ArtifactFileHeader>
+    where
+        S: Serialize + Into<Settings>,
+    {
+        if let Some(header_hash) = header_hash {
+            Self::verify_header_hash(&settings, &resource_map, header_hash)?;
+        }
+        let mut artifact_file_header = ArtifactFileHeader {
+            format_version,
+            settings: settings.into(),
+            resource_map,
+            header_hash: None,
+        };
+        if header_hash.is_some() {
+            artifact_file_header.header_hash = Some(artifact_file_header.compute_header_hash()?);
+        }
+        Ok(artifact_file_header)
+    }
+
+    fn check_format_version(format_version: u32) -> Result<()> {
+        if format_version > ARTIFACT_FORMAT_VERSION {
+            return Err(crate::error::Error::VersionNotSupported(Some(format!(
+                "Artifact format version {} is newer than the supported version {}, please update the engine.",
+                format_version, ARTIFACT_FORMAT_VERSION
+            ))));
+        }
+        Ok(())
+    }
+
+    pub fn compute_header_hash(&self) -> Result<u64> {
+        Self::compute_hash(&self.settings, &self.resource_map)
+    }
+
+    fn compute_hash(
+        settings: &impl Serialize,
+        resource_map: &HashMap<url::Url, ResourceInfo>,
+    ) -> Result<u64> {
+        // `HashMap` does not serialize in a stable order.
+        let mut resource_infos: Vec<&ResourceInfo> = resource_map.values().collect();
+        resource_infos.sort_by(|lhs, rhs| lhs.url.as_str().cmp(rhs.url.as_str()));
+        let data = bincode::serialize(&(settings, resource_infos)).map_err(|err| {
+            crate::error::Error::Bincode(err, Some(String::from("Fail to serialize.")))
+        })?;
+        Ok(crate::hash::content_hash(&data))
+    }
+
+    pub fn verify(&self) -> Result<()> {
+        let Some(header_hash) = self.header_hash else {
+            log::trace!("Artifact file header has no hash, skip verification.");
+            return Ok(());
+        };
+        Self::verify_header_hash(&self.settings, &self.resource_map, header_hash)
+    }
+
+    fn verify_header_hash(
+        settings: &impl Serialize,
+        resource_map: &HashMap<url::Url, ResourceInfo>,
+        header_hash: u64,
+    ) -> Result<()> {
+        let actual_hash = Self::compute_hash(settings, resource_map)?;
+        if actual_hash != header_hash {
+            return Err(crate::error::Error::Corrupted(
+                None,
+                Some(format!(
+                    "Artifact file header is corrupted, {} != expected hash: {}",
+                    actual_hash, header_hash
+                )),
+            ));
+        }
+        Ok(())
+    }
+
+    fn find_resource_info(
+        &self,
+        url: &url::Url,
+        expected_resource_type: Option<EResourceType>,
+    ) -> Result<&ResourceInfo> {
+        let resource_info = self
+            .resource_map
+            .get(url)
+            .ok_or(crate::error::Error::NotFound(Some(format!(
+                "Resource does not contain {}.",
+                url
+            ))))?;
+        if expected_resource_type.is_some() {
+            if Some(resource_info.resource_type) != expected_resource_type {
+                return Err(crate::error::Error::ResourceTypeNotMatch(Some(format!(
+                    "{:?} != expected resource type: {:?}",
+                    resource_info.resource_type, expected_resource_type
+                ))));
+            }
+        }
+        Ok(resource_info)
+    }
+
+    pub fn read_from<R>(
+        reader: &mut R,
+        endian_type: Option<EEndianType>,
+    ) -> Result<ArtifactFileHeader>
+    where
+        R: Seek + Read,
+    {
+        let header_encoded_data_length =
+            FileHeader::get_header_encoded_data_length(reader, endian_type)?;
+        let data = FileHeader::get_header_encoded_data(reader, header_encoded_data_length)?;
+        Self::decode(&data)
+    }
 }
 
 pub struct ResourceEncodeTask<R>
@@ -33,15 +290,256 @@ where
 {
     pub url: url::Url,
     pub resource_type: EResourceType,
+    pub compression_type: ECompressionType,
     pub reader: R,
 }
 
+enum ArtifactSource {
+    #[cfg(not(target_os = "android"))]
+    BufReader(std::io::BufReader<std::fs::File>, PathBuf),
+    #[cfg(target_os = "android")]
+    JavaInputStream(crate::java_input_stream::JavaInputStream),
+    #[cfg(not(target_os = "android"))]
+    Mmap(std::sync::Arc<memmap2::Mmap>),
+}
+
+impl ArtifactSource {
+    fn read_stored_data(
+        &mut self,
+        payload_offset: u64,
+        resource_info: &ResourceInfo,
+    ) -> Result<Cow<'_, [u8]>> {
+        match self {
+            #[cfg(not(target_os = "android"))]
+            ArtifactSource::BufReader(reader, _) => Ok(Cow::Owned(read_resource_stored_data(
+                reader,
+                payload_offset,
+                resource_info,
+            )?)),
+            #[cfg(target_os = "android")]
+            ArtifactSource::JavaInputStream(reader) => Ok(Cow::Owned(read_resource_stored_data(
+                reader,
+                payload_offset,
+                resource_info,
+            )?)),
+            #[cfg(not(target_os = "android"))]
+            ArtifactSource::Mmap(mmap) => Ok(Cow::Borrowed(read_mapped_stored_data(
+                mmap,
+                payload_offset,
+                resource_info,
+            )?)),
+        }
+    }
+
+    #[cfg(not(target_os = "android"))]
+    fn get_data_source(&self) -> EResourceDataSource {
+        match self {
+            ArtifactSource::BufReader(_, path) => EResourceDataSource::File(path.clone()),
+            ArtifactSource::Mmap(mmap) => EResourceDataSource::Mmap(mmap.clone()),
+        }
+    }
+}
+
+enum EResourceDataSource {
+    #[cfg(not(target_os = "android"))]
+    File(PathBuf),
+    #[cfg(not(target_os = "android"))]
+    Mmap(std::sync::Arc<memmap2::Mmap>),
+    /// A java input stream can not be shared, so the stored data is read up front.
+    #[cfg(target_os = "android")]
+    Stored(Vec<u8>),
+}
+
+/// Where the stored data of a resource is, so the data can be read and decompressed without
+/// borrowing the `ArtifactReader` it comes from.
+pub struct ResourceDataLocation {
+    source: EResourceDataSource,
+    payload_offset: u64,
+    resource_info: ResourceInfo,
+    endian_type: Option<EEndianType>,
+    is_verify: bool,
+}
+
+impl ResourceDataLocation {
+    pub fn get_resource_info(&self) -> &ResourceInfo {
+        &self.resource_info
+    }
+
+    pub fn get_endian_type(&self) -> Option<EEndianType> {
+        self.endian_type
+    }
+
+    /// Returns the encoded asset data of the resource, the same as
+    /// `ArtifactReader::get_resource_data`. The content hash is verified on every read unless
+    /// the resource was already verified by the reader.
+    pub fn read(&self) -> Result<Cow<'_, [u8]>> {
+        let payload_offset = self.payload_offset;
+        let resource_info = &self.resource_info;
+        let data = match &self.source {
+            #[cfg(not(target_os = "android"))]
+            EResourceDataSource::File(path) => {
+                let file = std::fs::File::open(path).map_err(|err| {
+                    let msg = format!("Can not open file {}", path.to_string_lossy());
+                    crate::error::Error::IO(err, Some(msg))
+                })?;
+                let mut reader = std::io::BufReader::new(file);
+                Cow::Owned(read_resource_stored_data(
+                    &mut reader,
+                    payload_offset,
+                    resource_info,
+                )?)
+            }
+            #[cfg(not(target_os = "android"))]
+            EResourceDataSource::Mmap(mmap) => Cow::Borrowed(read_mapped_stored_data(
+                mmap,
+                payload_offset,
+                resource_info,
+            )?),
+            #[cfg(target_os = "android")]
+            EResourceDataSource::Stored(data) => Cow::Borrowed(&data[..]),
+        };
+        if self.is_verify {
+            verify_resource_stored_data(resource_info, &data)?;
+        }
+        decompress_resource_stored_data(resource_info, data)
+    }
+}
+
+#[cfg(not(target_os = "android"))]
+fn read_mapped_stored_data<'a>(
+    mmap: &'a memmap2::Mmap,
+    payload_offset: u64,
+    resource_info: &ResourceInfo,
+) -> Result<&'a [u8]> {
+    let start = (payload_offset + resource_info.offset) as usize;
+    let end = start + resource_info.length as usize;
+    mmap.get(start..end).ok_or_else(|| {
+        let msg = format!(
+            "{}..{} is out of bounds of {}, the file may be truncated.",
+            start, end, resource_info.url
+        );
+        crate::error::Error::IO(
+            std::io::Error::from(std::io::ErrorKind::UnexpectedEof),
+            Some(msg),
+        )
+    })
+}
+
+fn read_resource_stored_data<R>(
+    reader: &mut R,
+    payload_offset: u64,
+    resource_info: &ResourceInfo,
+) -> Result<Vec<u8>>
+where
+    R: Seek + Read,
+{
+    let offset = payload_offset + resource_info.offset;
+    let _ = reader
+        .seek(SeekFrom::Start(offset))
+        .map_err(|err| crate::error::Error::IO(err, Some(format!("Failed to seek {}", offset))))?;
+    let mut buf: Vec<u8> = vec![0; resource_info.length as usize];
+    let _ = reader.read_exact(&mut buf).map_err(|err| {
+        let msg = format!(
+            "Failed to read the exact number of bytes of {}, the file may be truncated.",
+            resource_info.url
+        );
+        crate::error::Error::IO(err, Some(msg))
+    })?;
+    Ok(buf)
+}
+
+fn verify_resource_stored_data(resource_info: &ResourceInfo, data: &[u8]) -> Result<()> {
+    let Some(content_hash) = resource_info.content_hash else {
+        return Ok(());
+    };
+    let actual_hash = crate::hash::content_hash(data);
+    if actual_hash != content_hash {
+        return Err(crate::error::Error::Corrupted(
+            Some(resource_info.url.clone()),
+            Some(format!(
+                "{} is corrupted, {} != expected hash: {}",
+                resource_info.url, actual_hash, content_hash
+            )),
+        ));
+    }
+    Ok(())
+}
+
+fn decompress_resource_stored_data<'a>(
+    resource_info: &ResourceInfo,
+    data: Cow<'a, [u8]>,
+) -> Result<Cow<'a, [u8]>> {
+    match resource_info.compression_type {
+        ECompressionType::None => Ok(data),
+        compression_type => Ok(Cow::Owned(compression::decompress(
+            compression_type,
+            &data,
+            resource_info.uncompressed_length,
+        )?)),
+    }
+}
+
 pub fn encode_artifact_tasks_disk<R>(
     endian_type: Option<EEndianType>,
     settings: Settings,
     tasks: &mut [ResourceEncodeTask<R>],
     target_path: &Path,
 ) -> Result<()>
+where
+    R: Seek + Read,
+{
+    encode_tasks_disk(endian_type, settings, tasks, None, target_path)
+}
+
+/// Only resources that are missing in `base_artifact_file_header` or whose content changed are
+/// written, the result is meant to be mounted on top of the base artifact. A patch can not remove
+/// resources, resources of the base artifact which are not in `tasks` stay visible through the
+/// base artifact, they are only logged.
+pub fn encode_artifact_patch_tasks_disk<R>(
+    endian_type: Option<EEndianType>,
+    settings: Settings,
+    tasks: &mut [ResourceEncodeTask<R>],
+    base_artifact_file_header: &ArtifactFileHeader,
+    target_path: &Path,
+) -> Result<()>
+where
+    R: Seek + Read,
+{
+    encode_tasks_disk(
+        endian_type,
+        settings,
+        tasks,
+        Some(base_artifact_file_header),
+        target_path,
+    )
+}
+
+fn is_resource_unchanged(
+    base_artifact_file_header: Option<&ArtifactFileHeader>,
+    resource_info: &ResourceInfo,
+) -> bool {
+    let Some(base_artifact_file_header) = base_artifact_file_header else {
+        return false;
+    };
+    let Some(base_resource_info) = base_artifact_file_header
+        .resource_map
+        .get(&resource_info.url)
+    else {
+        return false;
+    };
+    base_resource_info.content_hash.is_some()
+        && base_resource_info.content_hash == resource_info.content_hash
+        && base_resource_info.resource_type == resource_info.resource_type
+        && base_resource_info.compression_type == resource_info.compression_type
+}
+
+fn encode_tasks_disk<R>(
+    endian_type: Option<EEndianType>,
+    settings: Settings,
+    tasks: &mut [ResourceEncodeTask<R>],
+    base_artifact_file_header: Option<&ArtifactFileHeader>,
+    target_path: &Path,
+) -> Result<()>
 where
     R: Seek + Read,
 {
@@ -57,48 +555,109 @@ where
     let file = std::fs::File::create(target_path).map_err(|err| {
         crate::error::Error::IO(err, Some(format!("Can not create file {:?}", target_path)))
     })?;
+    if let Some(base_artifact_file_header) = base_artifact_file_header {
+        let urls: HashSet<&url::Url> = tasks.iter().map(|x| &x.url).collect();
+        for url in base_artifact_file_header.resource_map.keys() {
+            if !urls.contains(url) {
+                log::warn!(
+                    "Url: {}, removed, a patch can not remove it from the base",
+                    url
+                );
+            }
+        }
+    }
     let mut buf_writer = BufWriter::new(file);
     let mut infos: Vec<ResourceInfo> = vec![];
+    let mut compressed_datas: Vec<Option<Vec<u8>>> = vec![];
+    let mut is_skips: Vec<bool> = vec![];
     let mut offset: u64 = 0;
     for task in tasks.iter_mut() {
-        let length = task
+        let uncompressed_length = task
             .reader
             .seek(SeekFrom::End(0))
-            .map_err(|err| crate::error::Error::IO(err, Some(format!("Seek fail"))))?;
+            .map_err(|err| crate::error::Error::IO(err, Some(String::from("Seek fail"))))?;
         let _ = task
             .reader
             .seek(SeekFrom::Start(0))
-            .map_err(|err| crate::error::Error::IO(err, Some(format!("Seek fail"))))?;
+            .map_err(|err| crate::error::Error::IO(err, Some(String::from("Seek fail"))))?;
+        let compressed_data = match task.compression_type {
+            ECompressionType::None => None,
+            compression_type => {
+                let mut data: Vec<u8> = Vec::with_capacity(uncompressed_length as usize);
+                task.reader.read_to_end(&mut data).map_err(|err| {
+                    crate::error::Error::IO(err, Some(String::from("Failed to read data.")))
+                })?;
+                Some(compression::compress(compression_type, &data)?)
+            }
+        };
+        let length = compressed_data
+            .as_ref()
+            .map(|x| x.len() as u64)
+            .unwrap_or(uncompressed_length);
+        let content_hash = match &compressed_data {
+            Some(compressed_data) => crate::hash::content_hash(compressed_data),
+            None => {
+                let mut data: Vec<u8> = Vec::with_capacity(uncompressed_length as usize);
+                task.reader.read_to_end(&mut data).map_err(|err| {
+                    crate::error::Error::IO(err, Some(String::from("Failed to read data.")))
+                })?;
+                let _ = task
+                    .reader
+                    .seek(SeekFrom::Start(0))
+                    .map_err(|err| crate::error::Error::IO(err, Some(String::from("Seek fail"))))?;
+                crate::hash::content_hash(&data)
+            }
+        };
         let info = ResourceInfo {
             url: task.url.clone(),
             resource_type: task.resource_type,
             offset,
             length,
+            compression_type: task.compression_type,
+            uncompressed_length,
+            content_hash: Some(content_hash),
         };
-        offset += length;
-        infos.push(info);
-    }
-    let mut fileheader = ArtifactFileHeader {
-        resource_map: HashMap::new(),
-        settings,
-    };
-    for info in infos {
-        fileheader
-            .resource_map
-            .insert(info.url.clone(), info.clone());
+        let is_skip = is_resource_unchanged(base_artifact_file_header, &info);
+        if is_skip {
+            log::trace!("Url: {}, unchanged, skip", task.url);
+        } else {
+            offset += length;
+            infos.push(info);
+        }
+        compressed_datas.push(compressed_data);
+        is_skips.push(is_skip);
     }
+    let resource_map = infos
+        .into_iter()
+        .map(|info| (info.url.clone(), info))
+        .collect();
+    let fileheader = ArtifactFileHeader::new(settings, resource_map)?;
     let header_encoded_data =
         FileHeader::write_header(ARTIFACT_FILE_MAGIC_NUMBERS, &fileheader, endian_type)?;
     buf_writer.write(&header_encoded_data).map_err(|err| {
-        crate::error::Error::IO(err, Some(format!("Failed to write header data.")))
+        crate::error::Error::IO(err, Some(String::from("Failed to write header data.")))
     })?;
-    for task in tasks.iter_mut() {
-        std::io::copy(&mut task.reader, &mut buf_writer)
-            .map_err(|err| crate::error::Error::IO(err, Some(format!("Failed to copy data."))))?;
+    for ((task, compressed_data), is_skip) in tasks.iter_mut().zip(compressed_datas).zip(is_skips) {
+        if is_skip {
+            continue;
+        }
+        match compressed_data {
+            Some(compressed_data) => {
+                buf_writer.write_all(&compressed_data).map_err(|err| {
+                    crate::error::Error::IO(err, Some(String::from("Failed to write data.")))
+                })?;
+            }
+            None => {
+                std::io::copy(&mut task.reader, &mut buf_writer).map_err(|err| {
+                    crate::error::Error::IO(err, Some(String::from("Failed to copy data.")))
+                })?;
+            }
+        }
         log::trace!(
-            "Url: {}, Resource type: {:?}",
-            task.url.to_string(),
-            task.resource_type
+            "Url: {}, Resource type: {:?}, Compression type: {:?}",
+            task.url,
+            task.resource_type,
+            task.compression_type
         );
     }
     Ok(())
@@ -113,12 +672,14 @@ pub fn encode_artifact_assets_disk<T>(
 where
     T: Asset,
 {
+    let compression_policy = CompressionPolicy::default();
     let mut tasks: Vec<ResourceEncodeTask<Cursor<Vec<u8>>>> = Vec::new();
     for asset in assets {
         let asset_encoded_data =
             asset::encode_asset(asset.get_resource_type(), endian_type, asset)?;
         let reader = Cursor::new(asset_encoded_data);
-        let task = asset.build_resource_encode_task(reader);
+        let mut task = asset.build_resource_encode_task(reader);
+        task.compression_type = compression_policy.get_compression_type(task.resource_type);
         tasks.push(task);
     }
     encode_artifact_tasks_disk(endian_type, settings, &mut tasks, target_path)
@@ -129,6 +690,8 @@ pub struct ArtifactAssetEncoder {
     tasks: Vec<ResourceEncodeTask<Cursor<Vec<u8>>>>,
     endian_type: Option<EEndianType>,
     target_path: PathBuf,
+    compression_policy: CompressionPolicy,
+    base_artifact_file_header: Option<ArtifactFileHeader>,
 }
 
 impl ArtifactAssetEncoder {
@@ -138,9 +701,25 @@ impl ArtifactAssetEncoder {
             tasks: vec![],
             endian_type,
             target_path: target_path.to_path_buf(),
+            compression_policy: CompressionPolicy::default(),
+            base_artifact_file_header: None,
         }
     }
 
+    /// When set, `finish` writes a patch artifact that only contains resources which are new or
+    /// changed relative to the base artifact. Resources removed since the base artifact can not
+    /// be expressed by a patch.
+    pub fn set_base_artifact_file_header(
+        &mut self,
+        base_artifact_file_header: Option<ArtifactFileHeader>,
+    ) {
+        self.base_artifact_file_header = base_artifact_file_header;
+    }
+
+    pub fn set_compression_policy(&mut self, compression_policy: CompressionPolicy) {
+        self.compression_policy = compression_policy;
+    }
+
     pub fn encode<T>(&mut self, asset: &T)
     where
         T: Asset,
@@ -148,15 +727,19 @@ impl ArtifactAssetEncoder {
         let asset_encoded_data =
             asset::encode_asset(asset.get_resource_type(), self.endian_type, asset).unwrap();
         let reader = Cursor::new(asset_encoded_data);
-        let task = asset.build_resource_encode_task(reader);
+        let mut task = asset.build_resource_encode_task(reader);
+        task.compression_type = self
+            .compression_policy
+            .get_compression_type(task.resource_type);
         self.tasks.push(task);
     }
 
     pub fn finish(&mut self) -> Result<()> {
-        encode_artifact_tasks_disk(
+        encode_tasks_disk(
             self.endian_type,
             self.settings.clone(),
             &mut self.tasks,
+            self.base_artifact_file_header.as_ref(),
             &self.target_path,
         )
     }
@@ -164,12 +747,11 @@ impl ArtifactAssetEncoder {
 
 pub struct ArtifactReader {
     artifact_file_header: ArtifactFileHeader,
-    #[cfg(not(target_os = "android"))]
-    buf_reader: std::io::BufReader<std::fs::File>,
-    #[cfg(target_os = "android")]
-    buf_reader: crate::java_input_stream::JavaInputStream,
+    source: ArtifactSource,
     payload_offset: u64,
     endian_type: Option<EEndianType>,
+    is_verify_on_access: bool,
+    verified_urls: HashSet<url::Url>,
 }
 
 impl ArtifactReader {
@@ -194,29 +776,34 @@ impl ArtifactReader {
                 }
             };
 
-        let artifact_file_header: ArtifactFileHeader =
-            match FileHeader::get_header2(&mut buf_reader, endian_type) {
-                Ok(artifact_file_header) => artifact_file_header,
-                Err(err) => {
-                    return Err(err);
-                }
-            };
+        let artifact_file_header = match ArtifactFileHeader::read_from(&mut buf_reader, endian_type)
+        {
+            Ok(artifact_file_header) => artifact_file_header,
+            Err(err) => {
+                return Err(err);
+            }
+        };
+        if let Err(err) = artifact_file_header.verify() {
+            return Err(err);
+        }
 
         let payload_offset: u64 =
             (IDENTIFICATION_SIZE + HEADER_LENGTH_SIZE) as u64 + header_encoded_data_length;
 
         return Ok(ArtifactReader {
             artifact_file_header,
-            buf_reader,
+            source: ArtifactSource::JavaInputStream(buf_reader),
             payload_offset,
             endian_type,
+            is_verify_on_access: true,
+            verified_urls: HashSet::new(),
         });
     }
 
     #[cfg(not(target_os = "android"))]
     pub fn new(path: &Path, endian_type: Option<EEndianType>) -> Result<ArtifactReader> {
         let file = std::fs::File::open(path).map_err(|err| {
-            let msg = format!("Can not open file {}", path.to_string_lossy().to_string());
+            let msg = format!("Can not open file {}", path.to_string_lossy());
             crate::error::Error::IO(err, Some(msg))
         })?;
 
@@ -229,24 +816,162 @@ impl ArtifactReader {
         let header_encoded_data_length =
             FileHeader::get_header_encoded_data_length(&mut buf_reader, endian_type)?;
 
-        let artifact_file_header: ArtifactFileHeader =
-            FileHeader::get_header2(&mut buf_reader, endian_type)?;
+        let artifact_file_header = ArtifactFileHeader::read_from(&mut buf_reader, endian_type)?;
+        artifact_file_header.verify()?;
 
         let payload_offset =
             (IDENTIFICATION_SIZE + HEADER_LENGTH_SIZE) as u64 + header_encoded_data_length;
 
         Ok(ArtifactReader {
             artifact_file_header,
-            buf_reader,
+            source: ArtifactSource::BufReader(buf_reader, path.to_path_buf()),
             payload_offset,
             endian_type,
+            is_verify_on_access: true,
+            verified_urls: HashSet::new(),
         })
     }
 
+    /// Map the whole artifact file into memory, uncompressed resources are then read directly
+    /// from the mapped pages instead of being copied into an intermediate buffer. Compressed
+    /// resources, which include meshes with the default compression policy, are decompressed into
+    /// a new buffer. Decoding an asset always allocates its own arrays, vertexes and indexes are
+    /// never borrowed from the mapped file.
+    #[cfg(not(target_os = "android"))]
+    pub fn new_mmap(path: &Path, endian_type: Option<EEndianType>) -> Result<ArtifactReader> {
+        let file = std::fs::File::open(path).map_err(|err| {
+            let msg = format!("Can not open file {}", path.to_string_lossy());
+            crate::error::Error::IO(err, Some(msg))
+        })?;
+        // Safety: The artifact file must not be modified while it is mapped.
+        let mmap = unsafe { memmap2::Mmap::map(&file) }.map_err(|err| {
+            let msg = format!("Can not map file {}", path.to_string_lossy());
+            crate::error::Error::IO(err, Some(msg))
+        })?;
+
+        let mut cursor = Cursor::new(&mmap[..]);
+        let _ = FileHeader::check_identification(
+            &mut cursor,
+            file_header::ARTIFACT_FILE_MAGIC_NUMBERS,
+        )?;
+
+        let header_encoded_data_length =
+            FileHeader::get_header_encoded_data_length(&mut cursor, endian_type)?;
+
+        let artifact_file_header = ArtifactFileHeader::read_from(&mut cursor, endian_type)?;
+        artifact_file_header.verify()?;
+
+        let payload_offset =
+            (IDENTIFICATION_SIZE + HEADER_LENGTH_SIZE) as u64 + header_encoded_data_length;
+
+        Ok(ArtifactReader {
+            artifact_file_header,
+            source: ArtifactSource::Mmap(std::sync::Arc::new(mmap)),
+            payload_offset,
+            endian_type,
+            is_verify_on_access: true,
+            verified_urls: HashSet::new(),
+        })
+    }
+
+    /// Verify the content hash of a resource the first time it is read.
+    pub fn set_verify_on_access(&mut self, is_verify_on_access: bool) {
+        self.is_verify_on_access = is_verify_on_access;
+    }
+
+    pub fn verify_resource(&mut self, url: &url::Url) -> Result<()> {
+        let resource_info = self.artifact_file_header.resource_map.get(url).ok_or(
+            crate::error::Error::NotFound(Some(format!("Resource does not contain {}.", url))),
+        )?;
+        let data = self
+            .source
+            .read_stored_data(self.payload_offset, resource_info)?;
+        verify_resource_stored_data(resource_info, &data)?;
+        self.verified_urls.insert(url.clone());
+        Ok(())
+    }
+
+    /// Returns the urls of all resources whose stored data does not match their content hash.
+    pub fn find_corrupted_resources(&mut self) -> Result<Vec<url::Url>> {
+        let urls: Vec<url::Url> = self
+            .artifact_file_header
+            .resource_map
+            .keys()
+            .cloned()
+            .collect();
+        let mut corrupted_urls = vec![];
+        for url in urls {
+            match self.verify_resource(&url) {
+                Ok(_) => {}
+                Err(crate::error::Error::Corrupted(_, msg)) => {
+                    log::warn!("{}", msg.unwrap_or_default());
+                    corrupted_urls.push(url);
+                }
+                Err(crate::error::Error::IO(err, msg))
+                    if err.kind() == std::io::ErrorKind::UnexpectedEof =>
+                {
+                    log::warn!("{}", msg.unwrap_or_default());
+                    corrupted_urls.push(url);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(corrupted_urls)
+    }
+
     pub fn get_artifact_file_header(&self) -> &ArtifactFileHeader {
         &self.artifact_file_header
     }
 
+    /// Returns the encoded asset data of a resource. The data is borrowed from the mapped file
+    /// when the reader is memory mapped and the resource is not compressed, compressed resources
+    /// are always decompressed into a new buffer.
+    pub fn get_resource_data(
+        &mut self,
+        url: &url::Url,
+        expected_resource_type: Option<EResourceType>,
+    ) -> Result<Cow<'_, [u8]>> {
+        let resource_info = self
+            .artifact_file_header
+            .find_resource_info(url, expected_resource_type)?;
+        let buf = self
+            .source
+            .read_stored_data(self.payload_offset, resource_info)?;
+        if self.is_verify_on_access && !self.verified_urls.contains(url) {
+            verify_resource_stored_data(resource_info, &buf)?;
+            self.verified_urls.insert(url.clone());
+        }
+        decompress_resource_stored_data(resource_info, buf)
+    }
+
+    /// Returns the location of the data of a resource, which is read later by
+    /// `ResourceDataLocation::read`, the reader is not needed any more at that time.
+    pub fn get_resource_data_location(
+        &mut self,
+        url: &url::Url,
+        expected_resource_type: Option<EResourceType>,
+    ) -> Result<ResourceDataLocation> {
+        let resource_info = self
+            .artifact_file_header
+            .find_resource_info(url, expected_resource_type)?
+            .clone();
+        #[cfg(not(target_os = "android"))]
+        let source = self.source.get_data_source();
+        #[cfg(target_os = "android")]
+        let source = EResourceDataSource::Stored(
+            self.source
+                .read_stored_data(self.payload_offset, &resource_info)?
+                .into_owned(),
+        );
+        Ok(ResourceDataLocation {
+            source,
+            payload_offset: self.payload_offset,
+            resource_info,
+            endian_type: self.endian_type,
+            is_verify: self.is_verify_on_access && !self.verified_urls.contains(url),
+        })
+    }
+
     pub fn get_resource<T>(
         &mut self,
         url: &url::Url,
@@ -255,51 +980,19 @@ impl ArtifactReader {
     where
         T: Asset,
     {
-        let resource_info = self.artifact_file_header.resource_map.get(url).ok_or(
-            crate::error::Error::NotFound(Some(format!("Resource does not contain {}.", url))),
-        )?;
-        if expected_resource_type.is_some() {
-            if Some(resource_info.resource_type) != expected_resource_type {
-                return Err(crate::error::Error::ResourceTypeNotMatch(Some(format!(
-                    "{:?} != expected resource type: {:?}",
-                    resource_info.resource_type, expected_resource_type
-                ))));
-            }
-        }
-        let offset = resource_info.offset;
-        let length = resource_info.length;
-        let _ = self
-            .buf_reader
-            .seek(SeekFrom::Start(self.payload_offset + offset))
-            .map_err(|err| {
-                crate::error::Error::IO(err, Some(format!("Failed to seek {}", offset)))
-            })?;
-        let mut buf: Vec<u8> = vec![0; length as usize];
-        let _ = self.buf_reader.read_exact(&mut buf).map_err(|err| {
-            let msg = format!("Failed to read the exact number of bytes.");
-            crate::error::Error::IO(err, Some(msg))
-        })?;
-        asset::decode_asset::<T>(&buf, self.endian_type, Some(resource_info.resource_type))
+        let endian_type = self.endian_type;
+        let buf = self.get_resource_data(url, expected_resource_type)?;
+        asset::decode_asset::<T>(&buf, endian_type, expected_resource_type)
     }
 
     pub fn check_assets(&mut self) -> Result<()> {
         for (_, resource_info) in &self.artifact_file_header.resource_map {
-            let offset = resource_info.offset;
-            let length = resource_info.length;
-            let _ = self
-                .buf_reader
-                .seek(SeekFrom::Start(self.payload_offset + offset))
-                .map_err(|err| {
-                    crate::error::Error::IO(
-                        err,
-                        Some(format!("Failed to seek {}", self.payload_offset + offset)),
-                    )
-                })?;
-            let mut buf: Vec<u8> = vec![0; length as usize];
-            let _ = self.buf_reader.read_exact(&mut buf).map_err(|err| {
-                let msg = format!("Failed to read the exact number of bytes.");
-                crate::error::Error::IO(err, Some(msg))
-            })?;
+            let buf = self
+                .source
+                .read_stored_data(self.payload_offset, resource_info)?;
+            verify_resource_stored_data(resource_info, &buf)?;
+            self.verified_urls.insert(resource_info.url.clone());
+            let buf = decompress_resource_stored_data(resource_info, buf)?;
             log::trace!(
                 "url: {}, type: {:?}",
                 resource_info.url,
@@ -347,12 +1040,130 @@ impl ArtifactReader {
 
 #[cfg(test)]
 mod test {
-    use super::ArtifactFileHeader;
+    use super::{ArtifactAssetEncoder, ArtifactFileHeader, ArtifactReader};
+    use crate::{
+        compression::ECompressionType, file_header::ARTIFACT_FORMAT_VERSION,
+        resource_info::ResourceInfo, resource_type::EResourceType,
+        shader_source_code::ShaderSourceCode, EEndianType,
+    };
+    use rs_core_minimal::settings::Settings;
+    use std::collections::HashMap;
 
     #[test]
     fn test_case_artifact() {
-        let artifact = ArtifactFileHeader::default();
+        let artifact = ArtifactFileHeader::new(Settings::default(), HashMap::new()).unwrap();
         let encoded: Vec<u8> = bincode::serialize(&artifact).unwrap();
         let decoded: ArtifactFileHeader = bincode::deserialize(&encoded[..]).unwrap();
+        assert_eq!(decoded.format_version, artifact.format_version);
+        assert_eq!(decoded.header_hash, artifact.header_hash);
+        assert!(decoded.resource_map.is_empty());
+    }
+
+    #[test]
+    fn test_case_legacy_artifact_file_header() {
+        // Headers encoded by the engine before each format change, indexed by format version.
+        let encoded_headers: [&[u8]; 5] = [
+            include_bytes!("../test_data/artifact_file_header_v0.bin"),
+            include_bytes!("../test_data/artifact_file_header_v1.bin"),
+            include_bytes!("../test_data/artifact_file_header_v2.bin"),
+            include_bytes!("../test_data/artifact_file_header_v3.bin"),
+            include_bytes!("../test_data/artifact_file_header_v4.bin"),
+        ];
+        let url = url::Url::parse("https://github.com/lai001/mesh").unwrap();
+        for (format_version, encoded) in (0..).zip(encoded_headers) {
+            let decoded = ArtifactFileHeader::decode(encoded).unwrap();
+            assert_eq!(decoded.format_version, format_version);
+            assert_eq!(decoded.header_hash.is_some(), format_version >= 2);
+            assert!(decoded.verify().is_ok());
+            let resource_info = decoded.resource_map.get(&url).unwrap();
+            assert_eq!(resource_info.resource_type, EResourceType::Binary);
+            assert_eq!(resource_info.length, 1024);
+            assert_eq!(resource_info.compression_type, ECompressionType::None);
+            assert_eq!(resource_info.uncompressed_length, 1024);
+            let content_hash = (format_version >= 2).then_some(42);
+            assert_eq!(resource_info.content_hash, content_hash);
+            let render_setting = &decoded.settings.render_setting;
+            let tile_border_size = if format_version >= 4 { 4 } else { 0 };
+            assert_eq!(
+                render_setting.virtual_texture_setting.tile_border_size,
+                tile_border_size
+            );
+            assert_eq!(render_setting.cluster_lod_setting.max_pixel_error, 1.0);
+        }
+    }
+
+    #[test]
+    fn test_case_artifact_file_header_hash() {
+        let url = url::Url::parse("https://github.com/lai001").unwrap();
+        let resource_info = ResourceInfo {
+            url: url.clone(),
+            resource_type: EResourceType::Binary,
+            offset: 0,
+            length: 1024,
+            compression_type: ECompressionType::None,
+            uncompressed_length: 1024,
+            content_hash: Some(0),
+        };
+        let mut header = ArtifactFileHeader::new(
+            Settings::default(),
+            HashMap::from([(url.clone(), resource_info)]),
+        )
+        .unwrap();
+        let encoded: Vec<u8> = bincode::serialize(&header).unwrap();
+        let decoded = ArtifactFileHeader::decode(&encoded).unwrap();
+        assert!(decoded.verify().is_ok());
+        assert_eq!(decoded.format_version, ARTIFACT_FORMAT_VERSION);
+        header.resource_map.get_mut(&url).unwrap().length = 1023;
+        assert!(header.verify().is_err());
+    }
+
+    #[test]
+    fn test_case_newer_artifact_file_header() {
+        let mut header = ArtifactFileHeader::new(Settings::default(), HashMap::new()).unwrap();
+        header.format_version = ARTIFACT_FORMAT_VERSION + 1;
+        let encoded: Vec<u8> = bincode::serialize(&header).unwrap();
+        let result = ArtifactFileHeader::decode(&encoded);
+        assert!(matches!(
+            result,
+            Err(crate::error::Error::VersionNotSupported(_))
+        ));
+    }
+
+    #[test]
+    fn test_case_resource_data_location() {
+        let path = std::env::temp_dir().join(format!(
+            "rs_artifact_resource_data_location_{}.rs",
+            std::process::id()
+        ));
+        let shader = ShaderSourceCode {
+            name: String::from("shader"),
+            id: uuid::Uuid::new_v4(),
+            url: url::Url::parse("asset://shader").unwrap(),
+            code: String::from("@compute fn main() {}"),
+        };
+        let mut encoder =
+            ArtifactAssetEncoder::new(Some(EEndianType::Little), Settings::default(), &path);
+        encoder.encode(&shader);
+        encoder.finish().unwrap();
+
+        let readers = [
+            ArtifactReader::new(&path, Some(EEndianType::Little)).unwrap(),
+            ArtifactReader::new_mmap(&path, Some(EEndianType::Little)).unwrap(),
+        ];
+        for mut reader in readers {
+            let location = reader
+                .get_resource_data_location(&shader.url, Some(EResourceType::ShaderSourceCode))
+                .unwrap();
+            drop(reader);
+            let data = location.read().unwrap();
+            let decoded = crate::asset::decode_asset::<ShaderSourceCode>(
+                &data,
+                location.get_endian_type(),
+                Some(EResourceType::ShaderSourceCode),
+            )
+            .unwrap();
+            assert_eq!(decoded.code, shader.code);
+        }
+        let _ = std::fs::remove_file(&path);
     }
 }
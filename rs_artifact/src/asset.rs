@@ -1,16 +1,33 @@
 use crate::error::Result;
 use crate::{
     artifact::ResourceEncodeTask,
+    compression::ECompressionType,
     file_header::{FileHeader, ASSET_FILE_MAGIC_NUMBERS, HEADER_LENGTH_SIZE},
     resource_type::EResourceType,
     EEndianType,
 };
+use bincode::Options;
 use serde::{Deserialize, Serialize};
 use std::io::{Read, Seek};
 
 pub trait Asset: for<'a> Deserialize<'a> + Serialize + Sized {
+    /// Version of the serialized layout. Bump it whenever the layout changes and upgrade older
+    /// payloads in `migrate`.
+    const VERSION: u32 = 0;
+
     fn get_url(&self) -> url::Url;
     fn get_resource_type(&self) -> EResourceType;
+
+    /// Decode a payload which was written with an older `version` of this asset.
+    fn migrate(version: u32, payload: &[u8]) -> Result<Self> {
+        let _ = payload;
+        Err(crate::error::Error::VersionNotSupported(Some(format!(
+            "No migration of {} from version {} to version {}.",
+            std::any::type_name::<Self>(),
+            version,
+            Self::VERSION
+        ))))
+    }
     fn build_resource_encode_task<R>(&self, reader: R) -> ResourceEncodeTask<R>
     where
         R: Seek + Read,
@@ -18,6 +35,7 @@ pub trait Asset: for<'a> Deserialize<'a> + Serialize + Sized {
         ResourceEncodeTask {
             url: self.get_url(),
             resource_type: self.get_resource_type(),
+            compression_type: ECompressionType::None,
             reader,
         }
     }
@@ -26,6 +44,33 @@ pub trait Asset: for<'a> Deserialize<'a> + Serialize + Sized {
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct AssetHeader {
     pub resource_type: EResourceType,
+    pub version: u32,
+}
+
+/// The layout of `AssetHeader` before assets were versioned.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct AssetHeaderV0 {
+    resource_type: EResourceType,
+}
+
+impl AssetHeader {
+    fn decode(data: &[u8]) -> Result<AssetHeader> {
+        let options = bincode::options()
+            .with_fixint_encoding()
+            .reject_trailing_bytes();
+        match options.deserialize::<AssetHeader>(data) {
+            Ok(asset_header) => Ok(asset_header),
+            Err(err) => {
+                let asset_header = options.deserialize::<AssetHeaderV0>(data).map_err(|_| {
+                    crate::error::Error::Bincode(err, Some(String::from("Fail to deserialize.")))
+                })?;
+                Ok(AssetHeader {
+                    resource_type: asset_header.resource_type,
+                    version: 0,
+                })
+            }
+        }
+    }
 }
 
 pub fn encode_asset<A>(
@@ -36,7 +81,10 @@ pub fn encode_asset<A>(
 where
     A: Asset,
 {
-    let asset_header = AssetHeader { resource_type };
+    let asset_header = AssetHeader {
+        resource_type,
+        version: A::VERSION,
+    };
     let header_data =
         FileHeader::write_header(ASSET_FILE_MAGIC_NUMBERS, &asset_header, endian_type)?;
     let payload = bincode::serialize(asset)
@@ -58,7 +106,8 @@ where
     let mut reader = std::io::Cursor::new(data);
     let _ = FileHeader::check_identification(&mut reader, ASSET_FILE_MAGIC_NUMBERS)?;
     let length = FileHeader::get_header_encoded_data_length(&mut reader, endian_type)?;
-    let asset_header: AssetHeader = FileHeader::get_header2(&mut reader, endian_type)?;
+    let asset_header =
+        AssetHeader::decode(&FileHeader::get_header_encoded_data(&mut reader, length)?)?;
     if let Some(expected_resource_type) = expected_resource_type {
         if asset_header.resource_type != expected_resource_type {
             return Err(crate::error::Error::ResourceTypeNotMatch(Some(format!(
@@ -68,15 +117,37 @@ where
         }
     }
     let offset = length + ASSET_FILE_MAGIC_NUMBERS.len() as u64 + HEADER_LENGTH_SIZE as u64;
-    let _ = reader
-        .seek(std::io::SeekFrom::Start(offset))
-        .map_err(|err| crate::error::Error::IO(err, Some(format!("Failed to seek {}", offset))));
-    let mut payload: Vec<u8> = vec![];
-    let _ = reader
-        .read_to_end(&mut payload)
-        .map_err(|err| crate::error::Error::IO(err, Some(format!("Failed to read all bytes."))))?;
-    let asset = bincode::deserialize::<T>(&payload).map_err(|err| {
+    // Decode straight from the input without copying the payload into another buffer. This is
+    // not zero-copy, serde still allocates the decoded arrays of the asset.
+    let payload = data
+        .get(offset as usize..)
+        .ok_or(crate::error::Error::NotFound(Some(format!(
+            "Failed to seek {}",
+            offset
+        ))))?;
+    if asset_header.version > T::VERSION {
+        return Err(crate::error::Error::VersionNotSupported(Some(format!(
+            "{:?} version {} is newer than the supported version {}.",
+            asset_header.resource_type,
+            asset_header.version,
+            T::VERSION
+        ))));
+    }
+    if asset_header.version < T::VERSION {
+        log::trace!(
+            "Migrate {:?} from version {} to version {}.",
+            asset_header.resource_type,
+            asset_header.version,
+            T::VERSION
+        );
+        return T::migrate(asset_header.version, payload);
+    }
+    decode_payload::<T>(payload)
+}
+
+/// Decodes a payload which is encoded by bincode, migrations use it to read the old layouts.
+pub fn decode_payload<T: serde::de::DeserializeOwned>(payload: &[u8]) -> Result<T> {
+    bincode::deserialize::<T>(payload).map_err(|err| {
         crate::error::Error::Bincode(err, Some(String::from("Fail to deserialize.")))
-    })?;
-    Ok(asset)
+    })
 }
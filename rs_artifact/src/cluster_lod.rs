@@ -0,0 +1,151 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct BoundingSphere {
+    pub center: glam::Vec3,
+    pub radius: f32,
+}
+
+impl BoundingSphere {
+    pub fn from_points(points: impl IntoIterator<Item = glam::Vec3> + Clone) -> BoundingSphere {
+        let mut min = glam::Vec3::splat(f32::MAX);
+        let mut max = glam::Vec3::splat(f32::MIN);
+        for point in points.clone() {
+            min = min.min(point);
+            max = max.max(point);
+        }
+        if min.x > max.x {
+            return BoundingSphere::default();
+        }
+        let center = (min + max) * 0.5;
+        let radius = points
+            .into_iter()
+            .map(|x| x.distance(center))
+            .fold(0.0, f32::max);
+        BoundingSphere { center, radius }
+    }
+
+    /// The smallest sphere centered between the spheres which contains all of them.
+    pub fn merge(spheres: &[BoundingSphere]) -> BoundingSphere {
+        let Some(first) = spheres.first() else {
+            return BoundingSphere::default();
+        };
+        let mut min = first.center - first.radius;
+        let mut max = first.center + first.radius;
+        for sphere in spheres {
+            min = min.min(sphere.center - sphere.radius);
+            max = max.max(sphere.center + sphere.radius);
+        }
+        let center = (min + max) * 0.5;
+        let radius = spheres
+            .iter()
+            .map(|x| x.center.distance(center) + x.radius)
+            .fold(0.0, f32::max);
+        BoundingSphere { center, radius }
+    }
+}
+
+/// A small group of triangles in the cluster level of detail hierarchy.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MeshCluster {
+    /// The triangles are `ClusterLod::indexes[index_offset..index_offset + index_count]`.
+    pub index_offset: u32,
+    pub index_count: u32,
+    /// 0 for clusters of the original mesh.
+    pub level: u32,
+    /// Bounds of the triangles of the cluster.
+    pub bounds: BoundingSphere,
+    /// The simplification error of the cluster in mesh units, measured from `error_bounds`.
+    pub error: f32,
+    pub error_bounds: BoundingSphere,
+    /// The error of the clusters which were simplified from this cluster, `f32::MAX` if the
+    /// cluster was never simplified.
+    pub parent_error: f32,
+    pub parent_error_bounds: BoundingSphere,
+}
+
+/// Clusters of a mesh and their simplified versions, which form a directed acyclic graph from
+/// the clusters of the original mesh to the coarsest clusters. Errors and error bounds grow
+/// from children to parents, so a threshold selects exactly one level for every part of the
+/// mesh and neighboring clusters of different levels share their borders without cracks.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ClusterLod {
+    /// Indexes of every cluster, which reference the vertexes of the mesh.
+    pub indexes: Vec<u32>,
+    pub clusters: Vec<MeshCluster>,
+}
+
+impl ClusterLod {
+    /// Returns the indices of the clusters whose own error is acceptable while the error of
+    /// their parents is not.
+    pub fn select_clusters(
+        &self,
+        is_error_acceptable: impl Fn(&BoundingSphere, f32) -> bool,
+    ) -> Vec<usize> {
+        self.clusters
+            .iter()
+            .enumerate()
+            .filter(|(_, cluster)| {
+                is_error_acceptable(&cluster.error_bounds, cluster.error)
+                    && (cluster.parent_error == f32::MAX
+                        || !is_error_acceptable(&cluster.parent_error_bounds, cluster.parent_error))
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    pub fn get_max_level(&self) -> u32 {
+        self.clusters.iter().map(|x| x.level).max().unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{BoundingSphere, ClusterLod, MeshCluster};
+
+    fn make_cluster(level: u32, error: f32, parent_error: f32) -> MeshCluster {
+        MeshCluster {
+            index_offset: 0,
+            index_count: 3,
+            level,
+            bounds: BoundingSphere::default(),
+            error,
+            error_bounds: BoundingSphere::default(),
+            parent_error,
+            parent_error_bounds: BoundingSphere::default(),
+        }
+    }
+
+    #[test]
+    fn test_case_select_clusters() {
+        let cluster_lod = ClusterLod {
+            indexes: vec![0, 1, 2],
+            clusters: vec![
+                make_cluster(0, 0.0, 1.0),
+                make_cluster(0, 0.0, 1.0),
+                make_cluster(1, 1.0, 4.0),
+                make_cluster(2, 4.0, f32::MAX),
+            ],
+        };
+        let select = |threshold: f32| cluster_lod.select_clusters(|_, error| error <= threshold);
+        assert_eq!(select(0.5), vec![0, 1]);
+        assert_eq!(select(2.0), vec![2]);
+        assert_eq!(select(10.0), vec![3]);
+        assert_eq!(cluster_lod.get_max_level(), 2);
+    }
+
+    #[test]
+    fn test_case_merge_bounding_spheres() {
+        let a = BoundingSphere {
+            center: glam::vec3(-1.0, 0.0, 0.0),
+            radius: 1.0,
+        };
+        let b = BoundingSphere {
+            center: glam::vec3(2.0, 0.0, 0.0),
+            radius: 1.0,
+        };
+        let merged = BoundingSphere::merge(&[a, b]);
+        assert_eq!(merged.center, glam::vec3(0.5, 0.0, 0.0));
+        assert_eq!(merged.radius, 2.5);
+    }
+}
@@ -0,0 +1,116 @@
+use crate::error::Result;
+use crate::resource_type::EResourceType;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, Deserialize, Serialize, Default)]
+pub enum ECompressionType {
+    #[default]
+    None,
+    Lz4,
+    Zstd(i32),
+}
+
+pub fn compress(compression_type: ECompressionType, data: &[u8]) -> Result<Vec<u8>> {
+    match compression_type {
+        ECompressionType::None => Ok(data.to_vec()),
+        ECompressionType::Lz4 => Ok(lz4_flex::block::compress(data)),
+        ECompressionType::Zstd(level) => zstd::bulk::compress(data, level).map_err(|err| {
+            crate::error::Error::IO(err, Some(String::from("Failed to compress with zstd.")))
+        }),
+    }
+}
+
+pub fn decompress(
+    compression_type: ECompressionType,
+    data: &[u8],
+    uncompressed_length: u64,
+) -> Result<Vec<u8>> {
+    let decompressed = match compression_type {
+        ECompressionType::None => data.to_vec(),
+        ECompressionType::Lz4 => lz4_flex::block::decompress(data, uncompressed_length as usize)
+            .map_err(|err| crate::error::Error::Decompress(Some(format!("{}", err))))?,
+        ECompressionType::Zstd(_) => zstd::bulk::decompress(data, uncompressed_length as usize)
+            .map_err(|err| {
+                crate::error::Error::IO(err, Some(String::from("Failed to decompress with zstd.")))
+            })?,
+    };
+    if decompressed.len() as u64 != uncompressed_length {
+        return Err(crate::error::Error::Decompress(Some(format!(
+            "{} != expected uncompressed length: {}",
+            decompressed.len(),
+            uncompressed_length
+        ))));
+    }
+    Ok(decompressed)
+}
+
+/// Chooses a codec for each resource type when encoding an artifact.
+#[derive(Clone, Debug)]
+pub struct CompressionPolicy {
+    pub default_compression_type: ECompressionType,
+    pub compression_types: HashMap<EResourceType, ECompressionType>,
+}
+
+impl CompressionPolicy {
+    pub fn none() -> CompressionPolicy {
+        CompressionPolicy {
+            default_compression_type: ECompressionType::None,
+            compression_types: HashMap::new(),
+        }
+    }
+
+    pub fn get_compression_type(&self, resource_type: EResourceType) -> ECompressionType {
+        self.compression_types
+            .get(&resource_type)
+            .copied()
+            .unwrap_or(self.default_compression_type)
+    }
+
+    pub fn set_compression_type(
+        &mut self,
+        resource_type: EResourceType,
+        compression_type: ECompressionType,
+    ) {
+        self.compression_types
+            .insert(resource_type, compression_type);
+    }
+}
+
+impl Default for CompressionPolicy {
+    fn default() -> Self {
+        // Images and sounds are usually stored in their already compressed source format.
+        let compression_types = HashMap::from([
+            (EResourceType::Image, ECompressionType::None),
+            (EResourceType::Sound, ECompressionType::None),
+            (EResourceType::StaticMesh, ECompressionType::Zstd(3)),
+            (EResourceType::SkinMesh, ECompressionType::Zstd(3)),
+            (EResourceType::SkeletonAnimation, ECompressionType::Zstd(3)),
+            (EResourceType::IBLBaking, ECompressionType::Zstd(3)),
+        ]);
+        CompressionPolicy {
+            default_compression_type: ECompressionType::Lz4,
+            compression_types,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{compress, decompress, ECompressionType};
+
+    #[test]
+    fn test_case_compression() {
+        let data: Vec<u8> = (0..4096).map(|x| (x % 7) as u8).collect();
+        for compression_type in [
+            ECompressionType::None,
+            ECompressionType::Lz4,
+            ECompressionType::Zstd(3),
+        ] {
+            let compressed = compress(compression_type, &data).unwrap();
+            let decompressed =
+                decompress(compression_type, &compressed, data.len() as u64).unwrap();
+            assert_eq!(decompressed, data);
+        }
+    }
+}
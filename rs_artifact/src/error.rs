@@ -10,6 +10,9 @@ pub enum Error {
     #[cfg(target_os = "android")]
     Jni(jni::errors::Error),
     NotFound(Option<String>),
+    Decompress(Option<String>),
+    Corrupted(Option<url::Url>, Option<String>),
+    VersionNotSupported(Option<String>),
 }
 
 impl std::fmt::Display for Error {
@@ -12,6 +12,9 @@ pub const HEADER_OFFSET: usize = HEADER_LENGTH_OFFSET + HEADER_LENGTH_SIZE;
 pub const ARTIFACT_FILE_MAGIC_NUMBERS: &[u8; IDENTIFICATION_SIZE] = &[b'r', b's', b'd', b'f'];
 pub const ASSET_FILE_MAGIC_NUMBERS: &[u8; IDENTIFICATION_SIZE] = &[b'a', b's', b'e', b't'];
 
+/// Version of the `ArtifactFileHeader` layout, bump it whenever the layout changes.
+pub const ARTIFACT_FORMAT_VERSION: u32 = 5;
+
 pub struct FileHeader {}
 
 impl FileHeader {
@@ -169,7 +172,8 @@ impl FileHeader {
 mod test {
     use super::{FileHeader, ARTIFACT_FILE_MAGIC_NUMBERS};
     use crate::{
-        artifact::ArtifactFileHeader, resource_info::ResourceInfo, resource_type::EResourceType,
+        artifact::ArtifactFileHeader, compression::ECompressionType, resource_info::ResourceInfo,
+        resource_type::EResourceType,
     };
     use rs_core_minimal::settings::Settings;
     use std::collections::HashMap;
@@ -187,11 +191,15 @@ mod test {
             offset: 0,
             length: 1024,
             resource_type: EResourceType::Binary,
+            compression_type: ECompressionType::None,
+            uncompressed_length: 1024,
+            content_hash: None,
         };
-        let fileheader = ArtifactFileHeader {
-            resource_map: HashMap::from([(resource.url.clone(), resource)]),
-            settings: Settings::default(),
-        };
+        let fileheader = ArtifactFileHeader::new(
+            Settings::default(),
+            HashMap::from([(resource.url.clone(), resource)]),
+        )
+        .unwrap();
         let data = FileHeader::write_header(
             ARTIFACT_FILE_MAGIC_NUMBERS,
             &fileheader,
@@ -1,5 +1,8 @@
 use crate::error::Result;
-use crate::{asset::Asset, resource_type::EResourceType};
+use crate::{
+    asset::{decode_payload, Asset},
+    resource_type::EResourceType,
+};
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
@@ -112,14 +115,95 @@ impl ColorType {
     }
 }
 
+/// Block compressed formats which are uploaded to the GPU without decoding.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, Deserialize, Serialize)]
+pub enum ECompressedTextureFormat {
+    BC1RgbaUnorm,
+    BC3RgbaUnorm,
+    BC5RgUnorm,
+    BC7RgbaUnorm,
+    Etc2Rgb8Unorm,
+    Astc4x4Unorm,
+}
+
+impl ECompressedTextureFormat {
+    pub fn block_dimensions(&self) -> (u32, u32) {
+        (4, 4)
+    }
+
+    /// The number of bytes of a block.
+    pub fn block_size(&self) -> u32 {
+        match self {
+            ECompressedTextureFormat::BC1RgbaUnorm => 8,
+            ECompressedTextureFormat::BC3RgbaUnorm => 16,
+            ECompressedTextureFormat::BC5RgUnorm => 16,
+            ECompressedTextureFormat::BC7RgbaUnorm => 16,
+            ECompressedTextureFormat::Etc2Rgb8Unorm => 8,
+            ECompressedTextureFormat::Astc4x4Unorm => 16,
+        }
+    }
+
+    /// The number of blocks per row and the number of rows of blocks of a mip level.
+    pub fn mip_level_blocks(&self, width: u32, height: u32, mip_level: u32) -> (u32, u32) {
+        let (block_width, block_height) = self.block_dimensions();
+        let width = (width >> mip_level).max(1);
+        let height = (height >> mip_level).max(1);
+        (width.div_ceil(block_width), height.div_ceil(block_height))
+    }
+
+    pub fn mip_level_length(&self, width: u32, height: u32, mip_level: u32) -> usize {
+        let (blocks_x, blocks_y) = self.mip_level_blocks(width, height, mip_level);
+        blocks_x as usize * blocks_y as usize * self.block_size() as usize
+    }
+}
+
+/// A texture which is transcoded at cook time, `data` contains every mip level in order from the
+/// largest one and each level is tightly packed.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CompressedTexture {
+    pub format: ECompressedTextureFormat,
+    pub width: u32,
+    pub height: u32,
+    pub mip_level_count: u32,
+    pub data: Vec<u8>,
+}
+
+impl CompressedTexture {
+    pub fn get_mip_level_data(&self, mip_level: u32) -> Option<&[u8]> {
+        if mip_level >= self.mip_level_count {
+            return None;
+        }
+        let offset: usize = (0..mip_level)
+            .map(|x| self.format.mip_level_length(self.width, self.height, x))
+            .sum();
+        let length = self
+            .format
+            .mip_level_length(self.width, self.height, mip_level);
+        self.data.get(offset..offset + length)
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Image {
     pub url: url::Url,
     pub image_format: ImageFormat,
+    /// The encoded source file, it is empty when the image is cooked to a compressed texture.
     pub data: Vec<u8>,
+    pub compressed_texture: Option<CompressedTexture>,
+}
+
+/// The layout of `Image` before compressed textures were added.
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+struct ImageV0 {
+    url: url::Url,
+    image_format: ImageFormat,
+    data: Vec<u8>,
 }
 
 impl Asset for Image {
+    const VERSION: u32 = 1;
+
     fn get_url(&self) -> url::Url {
         self.url.clone()
     }
@@ -127,6 +211,24 @@ impl Asset for Image {
     fn get_resource_type(&self) -> EResourceType {
         EResourceType::Image
     }
+
+    fn migrate(version: u32, payload: &[u8]) -> Result<Self> {
+        match version {
+            0 => {
+                let image = decode_payload::<ImageV0>(payload)?;
+                Ok(Image {
+                    url: image.url,
+                    image_format: image.image_format,
+                    data: image.data,
+                    compressed_texture: None,
+                })
+            }
+            _ => Err(crate::error::Error::VersionNotSupported(Some(format!(
+                "No migration of image from version {}.",
+                version
+            )))),
+        }
+    }
 }
 
 impl Image {
@@ -153,7 +255,41 @@ impl Image {
             image_format: ImageFormat::from_external_format(format),
             data,
             url: url::Url::from_str("").unwrap(),
+            compressed_texture: None,
         };
         Ok(image)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{ECompressedTextureFormat, Image, ImageFormat, ImageV0};
+    use crate::asset::Asset;
+
+    #[test]
+    fn test_case_mip_level_length() {
+        let format = ECompressedTextureFormat::BC1RgbaUnorm;
+        assert_eq!(format.mip_level_length(256, 128, 0), 64 * 32 * 8);
+        assert_eq!(format.mip_level_length(256, 128, 7), 8);
+        assert_eq!(format.mip_level_length(256, 128, 8), 8);
+        let format = ECompressedTextureFormat::Astc4x4Unorm;
+        assert_eq!(format.mip_level_length(6, 6, 0), 2 * 2 * 16);
+    }
+
+    #[test]
+    fn test_case_migrate() {
+        let url = url::Url::parse("asset://image").unwrap();
+        let payload = bincode::serialize(&ImageV0 {
+            url: url.clone(),
+            image_format: ImageFormat::Png,
+            data: vec![1, 2, 3],
+        })
+        .unwrap();
+        let image = Image::migrate(0, &payload).unwrap();
+        assert_eq!(image.url, url);
+        assert_eq!(image.image_format, ImageFormat::Png);
+        assert_eq!(image.data, vec![1, 2, 3]);
+        assert!(image.compressed_texture.is_none());
+        assert!(Image::migrate(1, &payload).is_err());
+    }
+}
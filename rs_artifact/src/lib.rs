@@ -1,18 +1,24 @@
 pub mod artifact;
 pub mod asset;
+pub mod cluster_lod;
+pub mod compression;
 pub mod content_type;
 pub mod endian;
 pub mod error;
 pub mod file_header;
+pub mod hash;
 pub mod ibl_baking;
 pub mod image;
 #[cfg(target_os = "android")]
 pub mod java_input_stream;
 pub mod material;
+pub mod mesh_lod;
 pub mod mesh_vertex;
 pub mod mesh_vertex_visitor;
+pub mod morph_target;
 pub mod node_anim;
 pub mod property_value_type;
+pub mod quantized_mesh_vertex;
 pub mod resource_info;
 pub mod resource_type;
 pub mod shader_source_code;
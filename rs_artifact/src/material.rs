@@ -1,4 +1,8 @@
-use crate::{asset::Asset, resource_type::EResourceType};
+use crate::{
+    asset::{decode_payload, Asset},
+    error::Result,
+    resource_type::EResourceType,
+};
 use rs_render_types::MaterialOptions;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
@@ -20,12 +24,39 @@ pub struct TextureBinding {
     pub group: usize,
     pub binding: usize,
     pub texture_url: url::Url,
+    /// The sampler of the texture is in the same group, materials which are compiled before
+    /// textures had their own samplers use the base color sampler.
+    pub sampler_binding: Option<usize>,
 }
 
 impl TextureBinding {
     pub fn get_texture_bind_name(&self) -> String {
         format!("_texture_{}_{}", self.group, self.binding)
     }
+
+    pub fn get_sampler_bind_name(&self) -> String {
+        format!("_sampler_{}_{}", self.group, self.binding)
+    }
+}
+
+/// The layout of `TextureBinding` before textures had their own samplers.
+#[derive(Deserialize, Hash, PartialEq, Eq)]
+#[cfg_attr(test, derive(Serialize, Clone))]
+struct TextureBindingV0 {
+    group: usize,
+    binding: usize,
+    texture_url: url::Url,
+}
+
+impl From<TextureBindingV0> for TextureBinding {
+    fn from(value: TextureBindingV0) -> Self {
+        TextureBinding {
+            group: value.group,
+            binding: value.binding,
+            texture_url: value.texture_url,
+            sampler_binding: None,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, Hash, PartialEq, Eq)]
@@ -55,6 +86,110 @@ pub struct MaterialInfo {
     pub cluster_light_binding: Option<GroupBinding>,
     pub cluster_light_index_binding: Option<GroupBinding>,
     pub material_paramenters_collection_bindings: HashSet<MaterialParamentersCollectionBinding>,
+    pub morph_target_deltas_binding: Option<GroupBinding>,
+}
+
+/// The layout of `MaterialInfo` before morph targets were added.
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(Serialize, Default))]
+struct MaterialInfoV0 {
+    map_textures: HashSet<TextureBindingV0>,
+    virtual_textures: HashSet<url::Url>,
+    global_constants_binding: Option<GroupBinding>,
+    base_color_sampler_binding: Option<GroupBinding>,
+    physical_texture_binding: Option<GroupBinding>,
+    page_table_texture_binding: Option<GroupBinding>,
+    brdflut_texture_binding: Option<GroupBinding>,
+    pre_filter_cube_map_texture_binding: Option<GroupBinding>,
+    irradiance_texture_binding: Option<GroupBinding>,
+    shadow_map_binding: Option<GroupBinding>,
+    constants_binding: Option<GroupBinding>,
+    point_lights_binding: Option<GroupBinding>,
+    spot_lights_binding: Option<GroupBinding>,
+    skin_constants_binding: Option<GroupBinding>,
+    virtual_texture_constants_binding: Option<GroupBinding>,
+    cluster_light_binding: Option<GroupBinding>,
+    cluster_light_index_binding: Option<GroupBinding>,
+    material_paramenters_collection_bindings: HashSet<MaterialParamentersCollectionBinding>,
+}
+
+impl From<MaterialInfoV0> for MaterialInfoV1 {
+    fn from(value: MaterialInfoV0) -> Self {
+        MaterialInfoV1 {
+            map_textures: value.map_textures,
+            virtual_textures: value.virtual_textures,
+            global_constants_binding: value.global_constants_binding,
+            base_color_sampler_binding: value.base_color_sampler_binding,
+            physical_texture_binding: value.physical_texture_binding,
+            page_table_texture_binding: value.page_table_texture_binding,
+            brdflut_texture_binding: value.brdflut_texture_binding,
+            pre_filter_cube_map_texture_binding: value.pre_filter_cube_map_texture_binding,
+            irradiance_texture_binding: value.irradiance_texture_binding,
+            shadow_map_binding: value.shadow_map_binding,
+            constants_binding: value.constants_binding,
+            point_lights_binding: value.point_lights_binding,
+            spot_lights_binding: value.spot_lights_binding,
+            skin_constants_binding: value.skin_constants_binding,
+            virtual_texture_constants_binding: value.virtual_texture_constants_binding,
+            cluster_light_binding: value.cluster_light_binding,
+            cluster_light_index_binding: value.cluster_light_index_binding,
+            material_paramenters_collection_bindings: value
+                .material_paramenters_collection_bindings,
+            morph_target_deltas_binding: None,
+        }
+    }
+}
+
+/// The layout of `MaterialInfo` before textures had their own samplers.
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(Serialize, Default))]
+struct MaterialInfoV1 {
+    map_textures: HashSet<TextureBindingV0>,
+    virtual_textures: HashSet<url::Url>,
+    global_constants_binding: Option<GroupBinding>,
+    base_color_sampler_binding: Option<GroupBinding>,
+    physical_texture_binding: Option<GroupBinding>,
+    page_table_texture_binding: Option<GroupBinding>,
+    brdflut_texture_binding: Option<GroupBinding>,
+    pre_filter_cube_map_texture_binding: Option<GroupBinding>,
+    irradiance_texture_binding: Option<GroupBinding>,
+    shadow_map_binding: Option<GroupBinding>,
+    constants_binding: Option<GroupBinding>,
+    point_lights_binding: Option<GroupBinding>,
+    spot_lights_binding: Option<GroupBinding>,
+    skin_constants_binding: Option<GroupBinding>,
+    virtual_texture_constants_binding: Option<GroupBinding>,
+    cluster_light_binding: Option<GroupBinding>,
+    cluster_light_index_binding: Option<GroupBinding>,
+    material_paramenters_collection_bindings: HashSet<MaterialParamentersCollectionBinding>,
+    morph_target_deltas_binding: Option<GroupBinding>,
+}
+
+impl From<MaterialInfoV1> for MaterialInfo {
+    fn from(value: MaterialInfoV1) -> Self {
+        MaterialInfo {
+            map_textures: value.map_textures.into_iter().map(|x| x.into()).collect(),
+            virtual_textures: value.virtual_textures,
+            global_constants_binding: value.global_constants_binding,
+            base_color_sampler_binding: value.base_color_sampler_binding,
+            physical_texture_binding: value.physical_texture_binding,
+            page_table_texture_binding: value.page_table_texture_binding,
+            brdflut_texture_binding: value.brdflut_texture_binding,
+            pre_filter_cube_map_texture_binding: value.pre_filter_cube_map_texture_binding,
+            irradiance_texture_binding: value.irradiance_texture_binding,
+            shadow_map_binding: value.shadow_map_binding,
+            constants_binding: value.constants_binding,
+            point_lights_binding: value.point_lights_binding,
+            spot_lights_binding: value.spot_lights_binding,
+            skin_constants_binding: value.skin_constants_binding,
+            virtual_texture_constants_binding: value.virtual_texture_constants_binding,
+            cluster_light_binding: value.cluster_light_binding,
+            cluster_light_index_binding: value.cluster_light_index_binding,
+            material_paramenters_collection_bindings: value
+                .material_paramenters_collection_bindings,
+            morph_target_deltas_binding: value.morph_target_deltas_binding,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -64,7 +199,27 @@ pub struct Material {
     pub material_info: HashMap<MaterialOptions, MaterialInfo>,
 }
 
+/// The layout of `Material` before morph targets were added.
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+struct MaterialV0 {
+    url: url::Url,
+    code: HashMap<MaterialOptions, String>,
+    material_info: HashMap<MaterialOptions, MaterialInfoV0>,
+}
+
+/// The layout of `Material` before textures had their own samplers.
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+struct MaterialV1 {
+    url: url::Url,
+    code: HashMap<MaterialOptions, String>,
+    material_info: HashMap<MaterialOptions, MaterialInfoV1>,
+}
+
 impl Asset for Material {
+    const VERSION: u32 = 2;
+
     fn get_url(&self) -> url::Url {
         self.url.clone()
     }
@@ -72,4 +227,113 @@ impl Asset for Material {
     fn get_resource_type(&self) -> EResourceType {
         EResourceType::Material
     }
+
+    fn migrate(version: u32, payload: &[u8]) -> Result<Self> {
+        match version {
+            0 => {
+                let material = decode_payload::<MaterialV0>(payload)?;
+                Ok(Material {
+                    url: material.url,
+                    code: material.code,
+                    material_info: material
+                        .material_info
+                        .into_iter()
+                        .map(|(options, material_info)| {
+                            (options, MaterialInfoV1::from(material_info).into())
+                        })
+                        .collect(),
+                })
+            }
+            1 => {
+                let material = decode_payload::<MaterialV1>(payload)?;
+                Ok(Material {
+                    url: material.url,
+                    code: material.code,
+                    material_info: material
+                        .material_info
+                        .into_iter()
+                        .map(|(options, material_info)| (options, material_info.into()))
+                        .collect(),
+                })
+            }
+            _ => Err(crate::error::Error::VersionNotSupported(Some(format!(
+                "No migration of material from version {}.",
+                version
+            )))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        GroupBinding, Material, MaterialInfoV0, MaterialInfoV1, MaterialV0, MaterialV1,
+        TextureBindingV0,
+    };
+    use crate::asset::Asset;
+    use rs_render_types::MaterialOptions;
+    use std::collections::{HashMap, HashSet};
+
+    #[test]
+    fn test_case_migrate() {
+        let url = url::Url::parse("asset://material").unwrap();
+        let texture_url = url::Url::parse("asset://texture").unwrap();
+        let options = MaterialOptions { is_skin: false };
+        let map_textures = HashSet::from([TextureBindingV0 {
+            group: 1,
+            binding: 2,
+            texture_url: texture_url.clone(),
+        }]);
+
+        let payload = bincode::serialize(&MaterialV0 {
+            url: url.clone(),
+            code: HashMap::from([(options.clone(), String::from("code"))]),
+            material_info: HashMap::from([(
+                options.clone(),
+                MaterialInfoV0 {
+                    map_textures: map_textures.clone(),
+                    shadow_map_binding: Some(GroupBinding::new(0, 9)),
+                    ..Default::default()
+                },
+            )]),
+        })
+        .unwrap();
+        let material = Material::migrate(0, &payload).unwrap();
+        assert_eq!(material.url, url);
+        assert_eq!(material.code[&options], "code");
+        let material_info = &material.material_info[&options];
+        let texture_binding = material_info.map_textures.iter().next().unwrap();
+        assert_eq!(texture_binding.group, 1);
+        assert_eq!(texture_binding.binding, 2);
+        assert_eq!(texture_binding.texture_url, texture_url);
+        assert_eq!(texture_binding.sampler_binding, None);
+        assert_eq!(
+            material_info.shadow_map_binding,
+            Some(GroupBinding::new(0, 9))
+        );
+        assert_eq!(material_info.morph_target_deltas_binding, None);
+
+        let payload = bincode::serialize(&MaterialV1 {
+            url: url.clone(),
+            code: HashMap::from([(options.clone(), String::from("code"))]),
+            material_info: HashMap::from([(
+                options.clone(),
+                MaterialInfoV1 {
+                    map_textures,
+                    morph_target_deltas_binding: Some(GroupBinding::new(3, 0)),
+                    ..Default::default()
+                },
+            )]),
+        })
+        .unwrap();
+        let material = Material::migrate(1, &payload).unwrap();
+        let material_info = &material.material_info[&options];
+        assert_eq!(material_info.map_textures.len(), 1);
+        assert_eq!(
+            material_info.morph_target_deltas_binding,
+            Some(GroupBinding::new(3, 0))
+        );
+
+        assert!(Material::migrate(2, &payload).is_err());
+    }
 }
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+/// A simplified index buffer which references the vertexes of the mesh it belongs to.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MeshLod {
+    /// The level is used when the projected size of the mesh relative to the screen height is
+    /// smaller than this value.
+    pub screen_size: f32,
+    pub indexes: Vec<u32>,
+}
+
+/// Returns 0 for the mesh itself and `n` for the `n`th level, the screen sizes of the levels are
+/// sorted from the largest to the smallest.
+pub fn select_lod(lod_screen_sizes: impl IntoIterator<Item = f32>, screen_size: f32) -> usize {
+    lod_screen_sizes
+        .into_iter()
+        .take_while(|x| screen_size < *x)
+        .count()
+}
+
+#[cfg(test)]
+mod test {
+    use super::select_lod;
+
+    #[test]
+    fn test_case_select_lod() {
+        let lod_screen_sizes = [0.5, 0.25];
+        assert_eq!(select_lod(lod_screen_sizes, 1.0), 0);
+        assert_eq!(select_lod(lod_screen_sizes, 0.4), 1);
+        assert_eq!(select_lod(lod_screen_sizes, 0.1), 2);
+        assert_eq!(select_lod([], 0.1), 0);
+    }
+}
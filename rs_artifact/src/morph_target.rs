@@ -0,0 +1,88 @@
+use crate::node_anim::find_segment;
+use serde::{Deserialize, Serialize};
+
+/// Offsets of every vertex of a mesh, which are added to the vertexes scaled by the weight.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MorphTarget {
+    pub name: String,
+    pub position_deltas: Vec<glam::Vec3>,
+    /// Empty if the target does not change normals.
+    pub normal_deltas: Vec<glam::Vec3>,
+    /// The weight when no animation drives the target.
+    pub default_weight: f32,
+}
+
+/// Weights of some morph targets of a mesh, targets which are not listed have a weight of zero.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MorphWeightKey {
+    pub time: f64,
+    /// Indices of the morph targets of the mesh.
+    pub targets: Vec<u32>,
+    pub weights: Vec<f32>,
+}
+
+impl MorphWeightKey {
+    fn to_weights(&self, target_count: usize) -> Vec<f32> {
+        let mut weights = vec![0.0; target_count];
+        for (target, weight) in self.targets.iter().zip(self.weights.iter()) {
+            if let Some(value) = weights.get_mut(*target as usize) {
+                *value = *weight;
+            }
+        }
+        weights
+    }
+}
+
+/// Time is in ticks.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MorphAnim {
+    /// Name of the mesh whose morph targets are animated.
+    pub mesh: String,
+    pub keys: Vec<MorphWeightKey>,
+}
+
+impl MorphAnim {
+    /// Returns the weight of every target at the time, the weights of the first or last key are
+    /// returned when the time is out of range.
+    pub fn sample(&self, time: f64, target_count: usize) -> Option<Vec<f32>> {
+        if self.keys.is_empty() {
+            return None;
+        }
+        let (start, end, alpha) = find_segment(|i| self.keys[i].time, self.keys.len(), time);
+        let start = self.keys[start].to_weights(target_count);
+        let end = self.keys[end].to_weights(target_count);
+        Some(
+            start
+                .iter()
+                .zip(end.iter())
+                .map(|(start, end)| start + (end - start) * alpha)
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{MorphAnim, MorphWeightKey};
+
+    #[test]
+    fn test_case_sample() {
+        let morph_anim = MorphAnim {
+            mesh: "Face".to_string(),
+            keys: vec![
+                MorphWeightKey {
+                    time: 0.0,
+                    targets: vec![0],
+                    weights: vec![1.0],
+                },
+                MorphWeightKey {
+                    time: 2.0,
+                    targets: vec![1],
+                    weights: vec![1.0],
+                },
+            ],
+        };
+        assert_eq!(morph_anim.sample(1.0, 2), Some(vec![0.5, 0.5]));
+        assert_eq!(morph_anim.sample(3.0, 3), Some(vec![0.0, 1.0, 0.0]));
+    }
+}
@@ -12,10 +12,340 @@ pub struct QuatKey {
     pub value: glam::Quat,
 }
 
+/// The range of the three smallest components of a normalized quaternion.
+const SMALLEST_THREE_RANGE: f32 = std::f32::consts::FRAC_1_SQRT_2;
+const SMALLEST_THREE_MAX: f32 = 0x7fff as f32;
+
+/// A rotation stored with the smallest three method, every component takes 15 bits and the two
+/// bits of the index of the largest component are stored in the highest bits of the first two
+/// components.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct QuantizedQuat([u16; 3]);
+
+impl QuantizedQuat {
+    pub fn encode(value: glam::Quat) -> QuantizedQuat {
+        let components = value.normalize().to_array();
+        let mut largest: usize = 0;
+        for i in 1..4 {
+            if components[i].abs() > components[largest].abs() {
+                largest = i;
+            }
+        }
+        // q and -q are the same rotation, the largest component is always positive.
+        let sign = components[largest].signum();
+        let mut encoded = [0u16; 3];
+        for (encoded, component) in encoded.iter_mut().zip(
+            components
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| *i != largest)
+                .map(|(_, x)| *x * sign),
+        ) {
+            let normalized = (component / SMALLEST_THREE_RANGE * 0.5 + 0.5).clamp(0.0, 1.0);
+            *encoded = (normalized * SMALLEST_THREE_MAX).round() as u16;
+        }
+        encoded[0] |= ((largest & 1) as u16) << 15;
+        encoded[1] |= ((largest >> 1) as u16) << 15;
+        QuantizedQuat(encoded)
+    }
+
+    pub fn decode(&self) -> glam::Quat {
+        let largest = ((self.0[0] >> 15) | ((self.0[1] >> 15) << 1)) as usize;
+        let mut smallest_three = self.0.iter().map(|x| {
+            let normalized = (x & 0x7fff) as f32 / SMALLEST_THREE_MAX;
+            (normalized * 2.0 - 1.0) * SMALLEST_THREE_RANGE
+        });
+        let mut components = [0.0_f32; 4];
+        let mut length_squared = 0.0;
+        for (i, component) in components.iter_mut().enumerate() {
+            if i == largest {
+                continue;
+            }
+            *component = smallest_three.next().unwrap_or_default();
+            length_squared += *component * *component;
+        }
+        components[largest] = (1.0 - length_squared).max(0.0).sqrt();
+        glam::Quat::from_array(components).normalize()
+    }
+}
+
+/// Finds the pair of samples around `time` and the interpolation factor between them.
+pub(crate) fn find_segment(
+    times: impl Fn(usize) -> f64,
+    len: usize,
+    time: f64,
+) -> (usize, usize, f32) {
+    debug_assert!(len > 0);
+    if len == 1 || time <= times(0) {
+        return (0, 0, 0.0);
+    }
+    if time >= times(len - 1) {
+        return (len - 1, len - 1, 0.0);
+    }
+    // The first sample with a time greater than `time`.
+    let (mut low, mut high) = (0, len - 1);
+    while low < high {
+        let middle = (low + high) / 2;
+        if times(middle) > time {
+            high = middle;
+        } else {
+            low = middle + 1;
+        }
+    }
+    let (start, end) = (low - 1, low);
+    let duration = times(end) - times(start);
+    let alpha = if duration > 0.0 {
+        ((time - times(start)) / duration) as f32
+    } else {
+        0.0
+    };
+    (start, end, alpha)
+}
+
+fn find_uniform_segment(
+    start_time: f64,
+    interval: f64,
+    len: usize,
+    time: f64,
+) -> (usize, usize, f32) {
+    debug_assert!(len > 0);
+    if len == 1 || interval <= 0.0 {
+        return (0, 0, 0.0);
+    }
+    let position = ((time - start_time) / interval).clamp(0.0, (len - 1) as f64);
+    let start = (position.floor() as usize).min(len - 2);
+    (start, start + 1, (position - start as f64) as f32)
+}
+
+fn uniform_key_times(start_time: f64, interval: f64, len: usize) -> Vec<f64> {
+    (0..len).map(|i| start_time + interval * i as f64).collect()
+}
+
+/// Time is in ticks for every track, ticks are kept in `f64` because long animations with a high
+/// tick rate lose precision in `f32`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum EVectorTrack {
+    /// Keys as they are imported.
+    Keys(Vec<VectorKey>),
+    /// Keys which are left after reduction.
+    SparseKeys {
+        times: Vec<f64>,
+        values: Vec<glam::Vec3>,
+    },
+    /// Values sampled at a fixed interval.
+    Uniform {
+        start_time: f64,
+        interval: f64,
+        values: Vec<glam::Vec3>,
+    },
+}
+
+impl EVectorTrack {
+    pub fn len(&self) -> usize {
+        match self {
+            EVectorTrack::Keys(keys) => keys.len(),
+            EVectorTrack::SparseKeys { values, .. } => values.len(),
+            EVectorTrack::Uniform { values, .. } => values.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The time of every stored value.
+    pub fn key_times(&self) -> Vec<f64> {
+        match self {
+            EVectorTrack::Keys(keys) => keys.iter().map(|x| x.time).collect(),
+            EVectorTrack::SparseKeys { times, .. } => times.clone(),
+            EVectorTrack::Uniform {
+                start_time,
+                interval,
+                values,
+            } => uniform_key_times(*start_time, *interval, values.len()),
+        }
+    }
+
+    /// Returns the value at the time, the value of the first or last key is returned when the
+    /// time is out of range.
+    pub fn sample(&self, time: f64) -> Option<glam::Vec3> {
+        if self.is_empty() {
+            return None;
+        }
+        let value = match self {
+            EVectorTrack::Keys(keys) => {
+                let (start, end, alpha) = find_segment(|i| keys[i].time, keys.len(), time);
+                keys[start].value.lerp(keys[end].value, alpha)
+            }
+            EVectorTrack::SparseKeys { times, values } => {
+                let (start, end, alpha) =
+                    find_segment(|i| times[i], times.len().min(values.len()), time);
+                values[start].lerp(values[end], alpha)
+            }
+            EVectorTrack::Uniform {
+                start_time,
+                interval,
+                values,
+            } => {
+                let (start, end, alpha) =
+                    find_uniform_segment(*start_time, *interval, values.len(), time);
+                values[start].lerp(values[end], alpha)
+            }
+        };
+        Some(value)
+    }
+}
+
+/// Time is in ticks for every track, the same as `EVectorTrack`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum ERotationTrack {
+    /// Keys as they are imported.
+    Keys(Vec<QuatKey>),
+    /// Keys which are left after reduction.
+    SparseKeys {
+        times: Vec<f64>,
+        values: Vec<QuantizedQuat>,
+    },
+    /// Values sampled at a fixed interval.
+    Uniform {
+        start_time: f64,
+        interval: f64,
+        values: Vec<QuantizedQuat>,
+    },
+}
+
+impl ERotationTrack {
+    pub fn len(&self) -> usize {
+        match self {
+            ERotationTrack::Keys(keys) => keys.len(),
+            ERotationTrack::SparseKeys { values, .. } => values.len(),
+            ERotationTrack::Uniform { values, .. } => values.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The time of every stored value.
+    pub fn key_times(&self) -> Vec<f64> {
+        match self {
+            ERotationTrack::Keys(keys) => keys.iter().map(|x| x.time).collect(),
+            ERotationTrack::SparseKeys { times, .. } => times.clone(),
+            ERotationTrack::Uniform {
+                start_time,
+                interval,
+                values,
+            } => uniform_key_times(*start_time, *interval, values.len()),
+        }
+    }
+
+    /// Returns the value at the time, the value of the first or last key is returned when the
+    /// time is out of range.
+    pub fn sample(&self, time: f64) -> Option<glam::Quat> {
+        if self.is_empty() {
+            return None;
+        }
+        let value = match self {
+            ERotationTrack::Keys(keys) => {
+                let (start, end, alpha) = find_segment(|i| keys[i].time, keys.len(), time);
+                keys[start].value.slerp(keys[end].value, alpha)
+            }
+            ERotationTrack::SparseKeys { times, values } => {
+                let (start, end, alpha) =
+                    find_segment(|i| times[i], times.len().min(values.len()), time);
+                values[start].decode().slerp(values[end].decode(), alpha)
+            }
+            ERotationTrack::Uniform {
+                start_time,
+                interval,
+                values,
+            } => {
+                let (start, end, alpha) =
+                    find_uniform_segment(*start_time, *interval, values.len(), time);
+                values[start].decode().slerp(values[end].decode(), alpha)
+            }
+        };
+        Some(value)
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct NodeAnim {
     pub node: String,
-    pub position_keys: Vec<VectorKey>,
-    pub scaling_keys: Vec<VectorKey>,
-    pub rotation_keys: Vec<QuatKey>,
+    pub position_track: EVectorTrack,
+    pub scaling_track: EVectorTrack,
+    pub rotation_track: ERotationTrack,
+}
+
+/// The layout of `NodeAnim` before tracks were added.
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+pub(crate) struct NodeAnimV0 {
+    pub(crate) node: String,
+    pub(crate) position_keys: Vec<VectorKey>,
+    pub(crate) scaling_keys: Vec<VectorKey>,
+    pub(crate) rotation_keys: Vec<QuatKey>,
+}
+
+impl From<NodeAnimV0> for NodeAnim {
+    fn from(value: NodeAnimV0) -> Self {
+        NodeAnim {
+            node: value.node,
+            position_track: EVectorTrack::Keys(value.position_keys),
+            scaling_track: EVectorTrack::Keys(value.scaling_keys),
+            rotation_track: ERotationTrack::Keys(value.rotation_keys),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ERotationTrack, EVectorTrack, QuantizedQuat};
+
+    #[test]
+    fn test_case_quantized_quat() {
+        let values = [
+            glam::Quat::IDENTITY,
+            glam::Quat::from_rotation_x(3.0),
+            glam::Quat::from_euler(glam::EulerRot::XYZ, 0.3, -1.2, 2.5),
+            -glam::Quat::from_rotation_y(0.7),
+        ];
+        for value in values {
+            let decoded = QuantizedQuat::encode(value).decode();
+            assert!(value.angle_between(decoded) < 0.001);
+        }
+    }
+
+    #[test]
+    fn test_case_sample() {
+        let position_track = EVectorTrack::Uniform {
+            start_time: 1.0,
+            interval: 2.0,
+            values: vec![glam::Vec3::ZERO, glam::Vec3::X, glam::Vec3::Y],
+        };
+        assert_eq!(position_track.sample(0.0), Some(glam::Vec3::ZERO));
+        assert_eq!(position_track.sample(2.0), Some(glam::Vec3::X * 0.5));
+        assert_eq!(position_track.sample(10.0), Some(glam::Vec3::Y));
+
+        let rotation_track = ERotationTrack::SparseKeys {
+            times: vec![0.0, 4.0],
+            values: vec![
+                QuantizedQuat::encode(glam::Quat::IDENTITY),
+                QuantizedQuat::encode(glam::Quat::from_rotation_z(1.0)),
+            ],
+        };
+        let rotation = rotation_track.sample(2.0).unwrap();
+        assert!(rotation.angle_between(glam::Quat::from_rotation_z(0.5)) < 0.001);
+
+        // Ticks which are not representable in f32.
+        let position_track = EVectorTrack::SparseKeys {
+            times: vec![16_777_216.0, 16_777_217.0],
+            values: vec![glam::Vec3::ZERO, glam::Vec3::X],
+        };
+        assert_eq!(
+            position_track.sample(16_777_216.5),
+            Some(glam::Vec3::X * 0.5)
+        );
+    }
 }
@@ -0,0 +1,177 @@
+use crate::mesh_vertex::MeshVertex;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Deserialize, Serialize)]
+pub enum EVertexEncoding {
+    #[default]
+    Full,
+    Quantized,
+}
+
+/// A compact encoding of `MeshVertex`, 33 bytes instead of 80. Color and uv are half floats,
+/// normal and tangent are octahedral encoded, the bitangent is rebuilt from the normal, the
+/// tangent and the sign.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct QuantizedMeshVertex {
+    pub position: [f32; 3],
+    pub vertex_color: [u16; 4],
+    pub normal: [i16; 2],
+    pub tangent: [i16; 2],
+    pub bitangent_sign: i8,
+    pub tex_coord: [u16; 2],
+}
+
+impl QuantizedMeshVertex {
+    pub fn from_mesh_vertex(vertex: &MeshVertex) -> QuantizedMeshVertex {
+        let bitangent_sign = if vertex.normal.cross(vertex.tangent).dot(vertex.bitangent) < 0.0 {
+            -1
+        } else {
+            1
+        };
+        QuantizedMeshVertex {
+            position: vertex.position.to_array(),
+            vertex_color: vertex.vertex_color.to_array().map(to_half),
+            normal: octahedral_encode(vertex.normal),
+            tangent: octahedral_encode(vertex.tangent),
+            bitangent_sign,
+            tex_coord: vertex.tex_coord.to_array().map(to_half),
+        }
+    }
+
+    pub fn to_mesh_vertex(&self) -> MeshVertex {
+        let normal = octahedral_decode(self.normal);
+        let tangent = octahedral_decode(self.tangent);
+        MeshVertex {
+            vertex_color: glam::Vec4::from_array(self.vertex_color.map(from_half)),
+            position: glam::Vec3::from_array(self.position),
+            normal,
+            tangent,
+            bitangent: normal.cross(tangent) * self.bitangent_sign as f32,
+            tex_coord: glam::Vec2::from_array(self.tex_coord.map(from_half)),
+        }
+    }
+}
+
+/// Vertexes as they are stored in an artifact.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub(crate) enum EEncodedMeshVertexes<'a> {
+    Full(Cow<'a, [MeshVertex]>),
+    Quantized(Vec<QuantizedMeshVertex>),
+}
+
+impl<'a> EEncodedMeshVertexes<'a> {
+    pub(crate) fn encode(
+        vertexes: &'a [MeshVertex],
+        vertex_encoding: EVertexEncoding,
+    ) -> EEncodedMeshVertexes<'a> {
+        match vertex_encoding {
+            EVertexEncoding::Full => EEncodedMeshVertexes::Full(Cow::Borrowed(vertexes)),
+            EVertexEncoding::Quantized => EEncodedMeshVertexes::Quantized(
+                vertexes
+                    .iter()
+                    .map(QuantizedMeshVertex::from_mesh_vertex)
+                    .collect(),
+            ),
+        }
+    }
+
+    pub(crate) fn decode(self) -> (Vec<MeshVertex>, EVertexEncoding) {
+        match self {
+            EEncodedMeshVertexes::Full(vertexes) => (vertexes.into_owned(), EVertexEncoding::Full),
+            EEncodedMeshVertexes::Quantized(vertexes) => (
+                vertexes.iter().map(|x| x.to_mesh_vertex()).collect(),
+                EVertexEncoding::Quantized,
+            ),
+        }
+    }
+}
+
+fn to_half(x: f32) -> u16 {
+    half::f16::from_f32(x).to_bits()
+}
+
+fn from_half(x: u16) -> f32 {
+    half::f16::from_bits(x).to_f32()
+}
+
+fn sign_not_zero(x: f32) -> f32 {
+    if x >= 0.0 {
+        1.0
+    } else {
+        -1.0
+    }
+}
+
+fn to_snorm16(x: f32) -> i16 {
+    (x.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i16
+}
+
+fn from_snorm16(x: i16) -> f32 {
+    (x as f32 / i16::MAX as f32).max(-1.0)
+}
+
+/// Maps a direction to a point on the octahedron unfolded to a square.
+pub fn octahedral_encode(direction: glam::Vec3) -> [i16; 2] {
+    let l1_norm = direction.x.abs() + direction.y.abs() + direction.z.abs();
+    if l1_norm == 0.0 {
+        return [0, 0];
+    }
+    let mut point = glam::vec2(direction.x, direction.y) / l1_norm;
+    if direction.z < 0.0 {
+        point = glam::vec2(
+            (1.0 - point.y.abs()) * sign_not_zero(point.x),
+            (1.0 - point.x.abs()) * sign_not_zero(point.y),
+        );
+    }
+    [to_snorm16(point.x), to_snorm16(point.y)]
+}
+
+pub fn octahedral_decode(encoded: [i16; 2]) -> glam::Vec3 {
+    let x = from_snorm16(encoded[0]);
+    let y = from_snorm16(encoded[1]);
+    let z = 1.0 - x.abs() - y.abs();
+    let t = (-z).max(0.0);
+    glam::vec3(x - t * sign_not_zero(x), y - t * sign_not_zero(y), z).normalize_or_zero()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{octahedral_decode, octahedral_encode, QuantizedMeshVertex};
+    use crate::mesh_vertex::MeshVertex;
+
+    #[test]
+    fn test_case_octahedral() {
+        for direction in [
+            glam::Vec3::X,
+            glam::Vec3::NEG_Y,
+            glam::Vec3::NEG_Z,
+            glam::vec3(0.3, -0.5, -0.8).normalize(),
+            glam::vec3(-0.7, 0.1, 0.2).normalize(),
+        ] {
+            let decoded = octahedral_decode(octahedral_encode(direction));
+            assert!(decoded.abs_diff_eq(direction, 1e-3));
+        }
+    }
+
+    #[test]
+    fn test_case_quantized_mesh_vertex() {
+        let normal = glam::vec3(0.0, 1.0, 0.0);
+        let tangent = glam::vec3(1.0, 0.0, 0.0);
+        let vertex = MeshVertex {
+            vertex_color: glam::vec4(1.0, 0.5, 0.25, 1.0),
+            position: glam::vec3(1.0, 2.0, 3.0),
+            normal,
+            tangent,
+            bitangent: -normal.cross(tangent),
+            tex_coord: glam::vec2(0.5, 0.75),
+        };
+        let decoded = QuantizedMeshVertex::from_mesh_vertex(&vertex).to_mesh_vertex();
+        assert_eq!(decoded.position, vertex.position);
+        assert_eq!(decoded.vertex_color, vertex.vertex_color);
+        assert_eq!(decoded.tex_coord, vertex.tex_coord);
+        assert!(decoded.normal.abs_diff_eq(vertex.normal, 1e-3));
+        assert!(decoded.tangent.abs_diff_eq(vertex.tangent, 1e-3));
+        assert!(decoded.bitangent.abs_diff_eq(vertex.bitangent, 1e-3));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::resource_type::EResourceType;
+use crate::{compression::ECompressionType, resource_type::EResourceType};
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -7,4 +7,56 @@ pub struct ResourceInfo {
     pub resource_type: EResourceType,
     pub offset: u64,
     pub length: u64,
+    pub compression_type: ECompressionType,
+    pub uncompressed_length: u64,
+    /// Hash of the stored bytes, `None` for artifacts written before hashes were recorded.
+    pub content_hash: Option<u64>,
+}
+
+/// The layout of `ResourceInfo` before resources could be compressed.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ResourceInfoV0 {
+    pub url: url::Url,
+    pub resource_type: EResourceType,
+    pub offset: u64,
+    pub length: u64,
+}
+
+/// The layout of `ResourceInfo` before content hashes were recorded.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ResourceInfoV1 {
+    pub url: url::Url,
+    pub resource_type: EResourceType,
+    pub offset: u64,
+    pub length: u64,
+    pub compression_type: ECompressionType,
+    pub uncompressed_length: u64,
+}
+
+impl From<ResourceInfoV0> for ResourceInfo {
+    fn from(value: ResourceInfoV0) -> Self {
+        ResourceInfo {
+            url: value.url,
+            resource_type: value.resource_type,
+            offset: value.offset,
+            length: value.length,
+            compression_type: ECompressionType::None,
+            uncompressed_length: value.length,
+            content_hash: None,
+        }
+    }
+}
+
+impl From<ResourceInfoV1> for ResourceInfo {
+    fn from(value: ResourceInfoV1) -> Self {
+        ResourceInfo {
+            url: value.url,
+            resource_type: value.resource_type,
+            offset: value.offset,
+            length: value.length,
+            compression_type: value.compression_type,
+            uncompressed_length: value.uncompressed_length,
+            content_hash: None,
+        }
+    }
 }
@@ -1,4 +1,10 @@
-use crate::{asset::Asset, node_anim::NodeAnim, resource_type::EResourceType};
+use crate::{
+    asset::{decode_payload, Asset},
+    error::Result,
+    morph_target::MorphAnim,
+    node_anim::{NodeAnim, NodeAnimV0},
+    resource_type::EResourceType,
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -8,6 +14,29 @@ pub struct SkeletonAnimation {
     pub duration: f64,
     pub ticks_per_second: f64,
     pub channels: Vec<NodeAnim>,
+    pub morph_channels: Vec<MorphAnim>,
+}
+
+/// The layout of `SkeletonAnimation` before morph target weights were added.
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+struct SkeletonAnimationV1 {
+    name: String,
+    url: url::Url,
+    duration: f64,
+    ticks_per_second: f64,
+    channels: Vec<NodeAnim>,
+}
+
+/// The layout of `SkeletonAnimation` before tracks were added.
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+struct SkeletonAnimationV0 {
+    name: String,
+    url: url::Url,
+    duration: f64,
+    ticks_per_second: f64,
+    channels: Vec<NodeAnimV0>,
 }
 
 impl SkeletonAnimation {
@@ -17,6 +46,8 @@ impl SkeletonAnimation {
 }
 
 impl Asset for SkeletonAnimation {
+    const VERSION: u32 = 2;
+
     fn get_url(&self) -> url::Url {
         self.url.clone()
     }
@@ -24,4 +55,113 @@ impl Asset for SkeletonAnimation {
     fn get_resource_type(&self) -> EResourceType {
         EResourceType::SkeletonAnimation
     }
+
+    fn migrate(version: u32, payload: &[u8]) -> Result<Self> {
+        match version {
+            0 => {
+                let skeleton_animation = decode_payload::<SkeletonAnimationV0>(payload)?;
+                Ok(SkeletonAnimation {
+                    name: skeleton_animation.name,
+                    url: skeleton_animation.url,
+                    duration: skeleton_animation.duration,
+                    ticks_per_second: skeleton_animation.ticks_per_second,
+                    channels: skeleton_animation
+                        .channels
+                        .into_iter()
+                        .map(NodeAnim::from)
+                        .collect(),
+                    morph_channels: vec![],
+                })
+            }
+            1 => {
+                let skeleton_animation = decode_payload::<SkeletonAnimationV1>(payload)?;
+                Ok(SkeletonAnimation {
+                    name: skeleton_animation.name,
+                    url: skeleton_animation.url,
+                    duration: skeleton_animation.duration,
+                    ticks_per_second: skeleton_animation.ticks_per_second,
+                    channels: skeleton_animation.channels,
+                    morph_channels: vec![],
+                })
+            }
+            _ => Err(crate::error::Error::VersionNotSupported(Some(format!(
+                "No migration of skeleton animation from version {}.",
+                version
+            )))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{SkeletonAnimation, SkeletonAnimationV0, SkeletonAnimationV1};
+    use crate::{
+        asset::Asset,
+        node_anim::{ERotationTrack, EVectorTrack, NodeAnim, NodeAnimV0, QuatKey, VectorKey},
+    };
+
+    #[test]
+    fn test_case_migrate() {
+        let url = url::Url::parse("asset://animation").unwrap();
+        let position_key = VectorKey {
+            time: 1.0,
+            value: glam::Vec3::X,
+        };
+        let rotation_key = QuatKey {
+            time: 1.0,
+            value: glam::Quat::IDENTITY,
+        };
+
+        let payload = bincode::serialize(&SkeletonAnimationV0 {
+            name: String::from("walk"),
+            url: url.clone(),
+            duration: 10.0,
+            ticks_per_second: 5.0,
+            channels: vec![NodeAnimV0 {
+                node: String::from("root"),
+                position_keys: vec![position_key.clone()],
+                scaling_keys: vec![],
+                rotation_keys: vec![rotation_key.clone()],
+            }],
+        })
+        .unwrap();
+        let animation = SkeletonAnimation::migrate(0, &payload).unwrap();
+        assert_eq!(animation.name, "walk");
+        assert_eq!(animation.url, url);
+        assert_eq!(animation.duration_as_secs_f32(), 2.0);
+        assert_eq!(animation.channels[0].node, "root");
+        assert!(matches!(
+            &animation.channels[0].position_track,
+            EVectorTrack::Keys(keys) if keys.len() == 1 && keys[0].value == glam::Vec3::X
+        ));
+        assert!(matches!(
+            &animation.channels[0].scaling_track,
+            EVectorTrack::Keys(keys) if keys.is_empty()
+        ));
+        assert!(matches!(
+            &animation.channels[0].rotation_track,
+            ERotationTrack::Keys(keys) if keys.len() == 1
+        ));
+        assert!(animation.morph_channels.is_empty());
+
+        let payload = bincode::serialize(&SkeletonAnimationV1 {
+            name: String::from("walk"),
+            url: url.clone(),
+            duration: 10.0,
+            ticks_per_second: 5.0,
+            channels: vec![NodeAnim {
+                node: String::from("root"),
+                position_track: EVectorTrack::Keys(vec![position_key]),
+                scaling_track: EVectorTrack::Keys(vec![]),
+                rotation_track: ERotationTrack::Keys(vec![rotation_key]),
+            }],
+        })
+        .unwrap();
+        let animation = SkeletonAnimation::migrate(1, &payload).unwrap();
+        assert_eq!(animation.channels.len(), 1);
+        assert_eq!(animation.channels[0].node, "root");
+        assert!(animation.morph_channels.is_empty());
+
+        assert!(SkeletonAnimation::migrate(2, &payload).is_err());
+    }
 }
@@ -1,4 +1,7 @@
-use crate::asset::Asset;
+use crate::asset::{decode_payload, Asset};
+use crate::error::Result;
+use crate::mesh_lod::MeshLod;
+use crate::morph_target::MorphTarget;
 use crate::resource_type::EResourceType;
 use serde::Deserialize;
 use serde::Serialize;
@@ -23,9 +26,36 @@ pub struct SkinMesh {
     pub vertexes: Vec<SkinMeshVertex>,
     pub indexes: Vec<u32>,
     pub bone_paths: Vec<String>,
+    pub lods: Vec<MeshLod>,
+    pub morph_targets: Vec<MorphTarget>,
+}
+
+/// The layout of `SkinMesh` before morph targets were added.
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+struct SkinMeshV1 {
+    name: String,
+    url: url::Url,
+    vertexes: Vec<SkinMeshVertex>,
+    indexes: Vec<u32>,
+    bone_paths: Vec<String>,
+    lods: Vec<MeshLod>,
+}
+
+/// The layout of `SkinMesh` before levels of detail were added.
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+struct SkinMeshV0 {
+    name: String,
+    url: url::Url,
+    vertexes: Vec<SkinMeshVertex>,
+    indexes: Vec<u32>,
+    bone_paths: Vec<String>,
 }
 
 impl Asset for SkinMesh {
+    const VERSION: u32 = 2;
+
     fn get_url(&self) -> url::Url {
         self.url.clone()
     }
@@ -33,4 +63,89 @@ impl Asset for SkinMesh {
     fn get_resource_type(&self) -> EResourceType {
         EResourceType::SkinMesh
     }
+
+    fn migrate(version: u32, payload: &[u8]) -> Result<Self> {
+        match version {
+            0 => {
+                let skin_mesh = decode_payload::<SkinMeshV0>(payload)?;
+                Ok(SkinMesh {
+                    name: skin_mesh.name,
+                    url: skin_mesh.url,
+                    vertexes: skin_mesh.vertexes,
+                    indexes: skin_mesh.indexes,
+                    bone_paths: skin_mesh.bone_paths,
+                    lods: vec![],
+                    morph_targets: vec![],
+                })
+            }
+            1 => {
+                let skin_mesh = decode_payload::<SkinMeshV1>(payload)?;
+                Ok(SkinMesh {
+                    name: skin_mesh.name,
+                    url: skin_mesh.url,
+                    vertexes: skin_mesh.vertexes,
+                    indexes: skin_mesh.indexes,
+                    bone_paths: skin_mesh.bone_paths,
+                    lods: skin_mesh.lods,
+                    morph_targets: vec![],
+                })
+            }
+            _ => Err(crate::error::Error::VersionNotSupported(Some(format!(
+                "No migration of skin mesh from version {}.",
+                version
+            )))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{SkinMesh, SkinMeshV0, SkinMeshV1, SkinMeshVertex};
+    use crate::{asset::Asset, mesh_lod::MeshLod};
+
+    #[test]
+    fn test_case_migrate() {
+        let url = url::Url::parse("asset://skin_mesh").unwrap();
+        let vertex = SkinMeshVertex {
+            bones: [1, 0, 0, 0],
+            weights: [1.0, 0.0, 0.0, 0.0],
+            ..Default::default()
+        };
+
+        let payload = bincode::serialize(&SkinMeshV0 {
+            name: String::from("skin_mesh"),
+            url: url.clone(),
+            vertexes: vec![vertex.clone()],
+            indexes: vec![0, 0, 0],
+            bone_paths: vec![String::from("root/bone")],
+        })
+        .unwrap();
+        let skin_mesh = SkinMesh::migrate(0, &payload).unwrap();
+        assert_eq!(skin_mesh.name, "skin_mesh");
+        assert_eq!(skin_mesh.url, url);
+        assert_eq!(skin_mesh.vertexes[0].bones, [1, 0, 0, 0]);
+        assert_eq!(skin_mesh.indexes, vec![0, 0, 0]);
+        assert_eq!(skin_mesh.bone_paths, vec![String::from("root/bone")]);
+        assert!(skin_mesh.lods.is_empty());
+        assert!(skin_mesh.morph_targets.is_empty());
+
+        let payload = bincode::serialize(&SkinMeshV1 {
+            name: String::from("skin_mesh"),
+            url: url.clone(),
+            vertexes: vec![vertex],
+            indexes: vec![0, 0, 0],
+            bone_paths: vec![String::from("root/bone")],
+            lods: vec![MeshLod {
+                screen_size: 0.25,
+                indexes: vec![0, 0, 0],
+            }],
+        })
+        .unwrap();
+        let skin_mesh = SkinMesh::migrate(1, &payload).unwrap();
+        assert_eq!(skin_mesh.lods.len(), 1);
+        assert_eq!(skin_mesh.lods[0].screen_size, 0.25);
+        assert!(skin_mesh.morph_targets.is_empty());
+
+        assert!(SkinMesh::migrate(2, &payload).is_err());
+    }
 }
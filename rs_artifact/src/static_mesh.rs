@@ -1,16 +1,127 @@
-use crate::{asset::Asset, default_url, mesh_vertex::MeshVertex, resource_type::EResourceType};
+use crate::{
+    asset::{decode_payload, Asset},
+    cluster_lod::ClusterLod,
+    default_url,
+    error::Result,
+    mesh_lod::MeshLod,
+    mesh_vertex::MeshVertex,
+    morph_target::MorphTarget,
+    quantized_mesh_vertex::{EEncodedMeshVertexes, EVertexEncoding},
+    resource_type::EResourceType,
+};
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize)]
+#[serde(from = "StaticMeshData<'static>")]
 pub struct StaticMesh {
     pub name: String,
     // pub id: uuid::Uuid,
     pub url: url::Url,
     pub vertexes: Vec<MeshVertex>,
     pub indexes: Vec<u32>,
+    /// How `vertexes` are stored in the artifact, they are always decoded to `MeshVertex`.
+    pub vertex_encoding: EVertexEncoding,
+    pub lods: Vec<MeshLod>,
+    pub morph_targets: Vec<MorphTarget>,
+    /// Built at cook time for dense meshes, selected per frame instead of `lods`.
+    pub cluster_lod: Option<ClusterLod>,
+}
+
+/// The serialized layout of `StaticMesh`. The borrowed variants only avoid copies when
+/// serializing, deserialization always produces owned data because the arrays of a mapped
+/// artifact are not aligned for their element types.
+#[derive(Deserialize, Serialize)]
+struct StaticMeshData<'a> {
+    name: Cow<'a, str>,
+    url: Cow<'a, url::Url>,
+    vertexes: EEncodedMeshVertexes<'a>,
+    indexes: Cow<'a, [u32]>,
+    lods: Cow<'a, [MeshLod]>,
+    morph_targets: Cow<'a, [MorphTarget]>,
+    cluster_lod: Option<Cow<'a, ClusterLod>>,
+}
+
+impl From<StaticMeshData<'_>> for StaticMesh {
+    fn from(value: StaticMeshData<'_>) -> Self {
+        let (vertexes, vertex_encoding) = value.vertexes.decode();
+        StaticMesh {
+            name: value.name.into_owned(),
+            url: value.url.into_owned(),
+            vertexes,
+            indexes: value.indexes.into_owned(),
+            vertex_encoding,
+            lods: value.lods.into_owned(),
+            morph_targets: value.morph_targets.into_owned(),
+            cluster_lod: value.cluster_lod.map(|x| x.into_owned()),
+        }
+    }
+}
+
+impl Serialize for StaticMesh {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        StaticMeshData {
+            name: Cow::Borrowed(&self.name),
+            url: Cow::Borrowed(&self.url),
+            vertexes: EEncodedMeshVertexes::encode(&self.vertexes, self.vertex_encoding),
+            indexes: Cow::Borrowed(&self.indexes),
+            lods: Cow::Borrowed(&self.lods),
+            morph_targets: Cow::Borrowed(&self.morph_targets),
+            cluster_lod: self.cluster_lod.as_ref().map(Cow::Borrowed),
+        }
+        .serialize(serializer)
+    }
+}
+
+/// The layout of `StaticMesh` before cluster levels of detail were added.
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+struct StaticMeshDataV3 {
+    name: String,
+    url: url::Url,
+    vertexes: EEncodedMeshVertexes<'static>,
+    indexes: Vec<u32>,
+    lods: Vec<MeshLod>,
+    morph_targets: Vec<MorphTarget>,
+}
+
+/// The layout of `StaticMesh` before morph targets were added.
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+struct StaticMeshDataV2 {
+    name: String,
+    url: url::Url,
+    vertexes: EEncodedMeshVertexes<'static>,
+    indexes: Vec<u32>,
+    lods: Vec<MeshLod>,
+}
+
+/// The layout of `StaticMesh` before levels of detail were added.
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+struct StaticMeshDataV1 {
+    name: String,
+    url: url::Url,
+    vertexes: EEncodedMeshVertexes<'static>,
+    indexes: Vec<u32>,
+}
+
+/// The layout of `StaticMesh` before vertexes could be quantized.
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+struct StaticMeshV0 {
+    name: String,
+    url: url::Url,
+    vertexes: Vec<MeshVertex>,
+    indexes: Vec<u32>,
 }
 
 impl Asset for StaticMesh {
+    const VERSION: u32 = 4;
+
     fn get_url(&self) -> url::Url {
         self.url.clone()
     }
@@ -18,6 +129,70 @@ impl Asset for StaticMesh {
     fn get_resource_type(&self) -> EResourceType {
         EResourceType::StaticMesh
     }
+
+    fn migrate(version: u32, payload: &[u8]) -> Result<Self> {
+        match version {
+            0 => {
+                let static_mesh = decode_payload::<StaticMeshV0>(payload)?;
+                Ok(StaticMesh {
+                    name: static_mesh.name,
+                    url: static_mesh.url,
+                    vertexes: static_mesh.vertexes,
+                    indexes: static_mesh.indexes,
+                    vertex_encoding: EVertexEncoding::Full,
+                    lods: vec![],
+                    morph_targets: vec![],
+                    cluster_lod: None,
+                })
+            }
+            1 => {
+                let static_mesh = decode_payload::<StaticMeshDataV1>(payload)?;
+                let (vertexes, vertex_encoding) = static_mesh.vertexes.decode();
+                Ok(StaticMesh {
+                    name: static_mesh.name,
+                    url: static_mesh.url,
+                    vertexes,
+                    indexes: static_mesh.indexes,
+                    vertex_encoding,
+                    lods: vec![],
+                    morph_targets: vec![],
+                    cluster_lod: None,
+                })
+            }
+            2 => {
+                let static_mesh = decode_payload::<StaticMeshDataV2>(payload)?;
+                let (vertexes, vertex_encoding) = static_mesh.vertexes.decode();
+                Ok(StaticMesh {
+                    name: static_mesh.name,
+                    url: static_mesh.url,
+                    vertexes,
+                    indexes: static_mesh.indexes,
+                    vertex_encoding,
+                    lods: static_mesh.lods,
+                    morph_targets: vec![],
+                    cluster_lod: None,
+                })
+            }
+            3 => {
+                let static_mesh = decode_payload::<StaticMeshDataV3>(payload)?;
+                let (vertexes, vertex_encoding) = static_mesh.vertexes.decode();
+                Ok(StaticMesh {
+                    name: static_mesh.name,
+                    url: static_mesh.url,
+                    vertexes,
+                    indexes: static_mesh.indexes,
+                    vertex_encoding,
+                    lods: static_mesh.lods,
+                    morph_targets: static_mesh.morph_targets,
+                    cluster_lod: None,
+                })
+            }
+            _ => Err(crate::error::Error::VersionNotSupported(Some(format!(
+                "No migration of static mesh from version {}.",
+                version
+            )))),
+        }
+    }
 }
 
 impl Default for StaticMesh {
@@ -28,14 +203,25 @@ impl Default for StaticMesh {
             url: default_url().clone(),
             vertexes: Default::default(),
             indexes: Default::default(),
+            vertex_encoding: Default::default(),
+            lods: Default::default(),
+            morph_targets: Default::default(),
+            cluster_lod: Default::default(),
         }
     }
 }
 
 #[cfg(test)]
 mod test {
-    use super::StaticMesh;
-    use crate::mesh_vertex::MeshVertex;
+    use super::{StaticMesh, StaticMeshDataV1, StaticMeshDataV2, StaticMeshDataV3, StaticMeshV0};
+    use crate::{
+        asset::Asset,
+        mesh_lod::MeshLod,
+        mesh_vertex::MeshVertex,
+        morph_target::MorphTarget,
+        quantized_mesh_vertex::{EEncodedMeshVertexes, EVertexEncoding, QuantizedMeshVertex},
+    };
+    use std::borrow::Cow;
 
     #[test]
     fn test_case_static_mesh() {
@@ -49,4 +235,99 @@ mod test {
         assert_eq!(decoded.vertexes.len(), 1);
         assert_eq!(decoded.vertexes[0].position.x, 10.0);
     }
+
+    #[test]
+    fn test_case_quantized_static_mesh() {
+        let mut mesh = StaticMesh::default();
+        mesh.vertex_encoding = EVertexEncoding::Quantized;
+        mesh.vertexes.push(MeshVertex::default());
+        mesh.vertexes[0].position.x = 10.0;
+        mesh.vertexes[0].normal = glam::Vec3::Y;
+        mesh.indexes = vec![0, 0, 0];
+        let encoded: Vec<u8> = bincode::serialize(&mesh).unwrap();
+        let decoded: StaticMesh = bincode::deserialize(&encoded[..]).unwrap();
+        assert_eq!(decoded.vertex_encoding, EVertexEncoding::Quantized);
+        assert_eq!(decoded.indexes, mesh.indexes);
+        assert_eq!(decoded.vertexes[0].position.x, 10.0);
+        assert!(decoded.vertexes[0].normal.abs_diff_eq(glam::Vec3::Y, 1e-3));
+    }
+
+    #[test]
+    fn test_case_migrate() {
+        let url = url::Url::parse("asset://mesh").unwrap();
+        let mut vertex = MeshVertex::default();
+        vertex.position.x = 10.0;
+        let lods = vec![MeshLod {
+            screen_size: 0.5,
+            indexes: vec![0, 0, 0],
+        }];
+        let morph_targets = vec![MorphTarget {
+            name: String::from("smile"),
+            position_deltas: vec![glam::Vec3::Y],
+            normal_deltas: vec![],
+            default_weight: 0.0,
+        }];
+
+        let payload = bincode::serialize(&StaticMeshV0 {
+            name: String::from("mesh"),
+            url: url.clone(),
+            vertexes: vec![vertex],
+            indexes: vec![0, 0, 0],
+        })
+        .unwrap();
+        let mesh = StaticMesh::migrate(0, &payload).unwrap();
+        assert_eq!(mesh.name, "mesh");
+        assert_eq!(mesh.url, url);
+        assert_eq!(mesh.vertexes[0].position.x, 10.0);
+        assert_eq!(mesh.indexes, vec![0, 0, 0]);
+        assert_eq!(mesh.vertex_encoding, EVertexEncoding::Full);
+        assert!(mesh.lods.is_empty());
+        assert!(mesh.morph_targets.is_empty());
+        assert!(mesh.cluster_lod.is_none());
+
+        let payload = bincode::serialize(&StaticMeshDataV1 {
+            name: String::from("mesh"),
+            url: url.clone(),
+            vertexes: EEncodedMeshVertexes::Quantized(vec![QuantizedMeshVertex::from_mesh_vertex(
+                &vertex,
+            )]),
+            indexes: vec![0, 0, 0],
+        })
+        .unwrap();
+        let mesh = StaticMesh::migrate(1, &payload).unwrap();
+        assert_eq!(mesh.vertex_encoding, EVertexEncoding::Quantized);
+        assert_eq!(mesh.vertexes[0].position.x, 10.0);
+        assert!(mesh.lods.is_empty());
+
+        let payload = bincode::serialize(&StaticMeshDataV2 {
+            name: String::from("mesh"),
+            url: url.clone(),
+            vertexes: EEncodedMeshVertexes::Full(Cow::Owned(vec![vertex])),
+            indexes: vec![0, 0, 0],
+            lods: lods.clone(),
+        })
+        .unwrap();
+        let mesh = StaticMesh::migrate(2, &payload).unwrap();
+        assert_eq!(mesh.vertex_encoding, EVertexEncoding::Full);
+        assert_eq!(mesh.lods.len(), 1);
+        assert_eq!(mesh.lods[0].screen_size, 0.5);
+        assert!(mesh.morph_targets.is_empty());
+
+        let payload = bincode::serialize(&StaticMeshDataV3 {
+            name: String::from("mesh"),
+            url: url.clone(),
+            vertexes: EEncodedMeshVertexes::Full(Cow::Owned(vec![vertex])),
+            indexes: vec![0, 0, 0],
+            lods,
+            morph_targets,
+        })
+        .unwrap();
+        let mesh = StaticMesh::migrate(3, &payload).unwrap();
+        assert_eq!(mesh.lods.len(), 1);
+        assert_eq!(mesh.morph_targets.len(), 1);
+        assert_eq!(mesh.morph_targets[0].name, "smile");
+        assert!(mesh.cluster_lod.is_none());
+
+        assert!(StaticMesh::migrate(4, &payload).is_err());
+    }
 }
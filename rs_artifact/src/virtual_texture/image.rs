@@ -1,3 +1,4 @@
+use crate::compression::ECompressionType;
 use crate::error::Result;
 use crate::file_header::HEADER_OFFSET;
 use crate::{file_header::IDENTIFICATION_SIZE, EEndianType};
@@ -30,10 +31,34 @@ pub struct ImageInfo {
     color_type: crate::image::ColorType,
 }
 
+/// Every stored tile is `tile_size + 2 * border_size` texels wide, the border repeats the texels
+/// of the neighboring tiles so that filtering does not bleed across pages.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct TileSettings {
+    pub tile_size: u32,
+    pub border_size: u32,
+    pub compression_type: ECompressionType,
+}
+
+impl TileSettings {
+    pub fn get_stored_tile_size(&self) -> u32 {
+        self.tile_size + 2 * self.border_size
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ImageFileHeader {
     lod_sizes: Vec<glam::UVec2>,
     lod_images: Vec<HashMap<glam::UVec2, ImageInfo>>,
+    tile_settings: TileSettings,
+}
+
+/// The layout of `ImageFileHeader` before tile settings were added, tiles are raw pixels
+/// without borders.
+#[derive(Debug, Deserialize)]
+struct ImageFileHeaderV0 {
+    lod_sizes: Vec<glam::UVec2>,
+    lod_images: Vec<HashMap<glam::UVec2, ImageInfo>>,
 }
 
 #[derive(Debug)]
@@ -54,6 +79,10 @@ where
         self.file_header.lod_sizes.get(0).copied().unwrap()
     }
 
+    pub fn get_tile_settings(&self) -> &TileSettings {
+        &self.file_header.tile_settings
+    }
+
     pub fn get_dynamic_image(&mut self, tile_index: &TileIndex) -> Result<image::DynamicImage> {
         let lod = tile_index.mipmap_level;
         let tile_index = glam::uvec2(tile_index.x, tile_index.y);
@@ -86,6 +115,14 @@ where
                 None,
             ))
         } else {
+            let uncompressed_length = image_info.width as u64
+                * image_info.height as u64
+                * image_info.color_type.to_external_format().bytes_per_pixel() as u64;
+            let buffer = crate::compression::decompress(
+                self.file_header.tile_settings.compression_type,
+                &buffer,
+                uncompressed_length,
+            )?;
             let image = new_dynamic_image(
                 buffer,
                 image_info.color_type.to_external_format(),
@@ -108,7 +145,29 @@ where
             endian_type,
         )?;
         let file_header: ImageFileHeader =
-            crate::file_header::FileHeader::get_header2(&mut reader, endian_type)?;
+            match crate::file_header::FileHeader::get_header2(&mut reader, endian_type) {
+                Ok(file_header) => file_header,
+                Err(_) => {
+                    let file_header: ImageFileHeaderV0 =
+                        crate::file_header::FileHeader::get_header2(&mut reader, endian_type)?;
+                    let tile_size = file_header
+                        .lod_images
+                        .iter()
+                        .flat_map(|x| x.values())
+                        .map(|x| x.width)
+                        .next()
+                        .unwrap_or_default();
+                    ImageFileHeader {
+                        lod_sizes: file_header.lod_sizes,
+                        lod_images: file_header.lod_images,
+                        tile_settings: TileSettings {
+                            tile_size,
+                            border_size: 0,
+                            compression_type: ECompressionType::None,
+                        },
+                    }
+                }
+            };
         let body_offset: u64 = HEADER_OFFSET as u64 + header_bytes_length;
 
         let image = Image {
@@ -144,6 +203,7 @@ pub fn encode_to_writer<W>(
     endian_type: Option<EEndianType>,
     lod_sizes: Vec<glam::UVec2>,
     lod_tiles: Vec<HashMap<glam::UVec2, image::DynamicImage>>,
+    tile_settings: TileSettings,
 ) -> Result<()>
 where
     W: Write,
@@ -151,6 +211,7 @@ where
     let mut file_header = ImageFileHeader {
         lod_sizes,
         lod_images: vec![],
+        tile_settings,
     };
     let mut start: u64 = 0;
     let mut body: Vec<u8> = vec![];
@@ -158,12 +219,13 @@ where
     for tiles in lod_tiles {
         let mut infos: HashMap<glam::UVec2, ImageInfo> = HashMap::new();
         for (index, tile) in tiles {
-            let data = tile.as_bytes();
+            let data =
+                crate::compression::compress(tile_settings.compression_type, tile.as_bytes())?;
             let span = Span {
                 start,
                 end: start + (data.len() as u64),
             };
-            body.extend_from_slice(data);
+            body.extend_from_slice(&data);
             let info = ImageInfo {
                 span,
                 width: tile.width(),
@@ -196,14 +258,22 @@ pub fn encode_to_file<P: AsRef<Path>>(
     endian_type: Option<EEndianType>,
     lod_sizes: Vec<glam::UVec2>,
     lod_tiles: Vec<HashMap<glam::UVec2, image::DynamicImage>>,
+    tile_settings: TileSettings,
 ) -> Result<()> {
     let mut writer = OpenOptions::new()
         .write(true)
         .create(true)
+        .truncate(true)
         .open(path)
         .map_err(|err| crate::error::Error::IO(err, None))?;
 
-    encode_to_writer(&mut writer, endian_type, lod_sizes, lod_tiles)
+    encode_to_writer(
+        &mut writer,
+        endian_type,
+        lod_sizes,
+        lod_tiles,
+        tile_settings,
+    )
 }
 
 fn new_dynamic_image(
@@ -256,3 +326,43 @@ fn new_dynamic_image(
         _ => todo!(),
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{encode_to_writer, Image, TileIndex, TileSettings};
+    use crate::compression::ECompressionType;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_case_compressed_tiles() {
+        let tile_settings = TileSettings {
+            tile_size: 4,
+            border_size: 1,
+            compression_type: ECompressionType::Zstd(3),
+        };
+        let tile = image::RgbaImage::from_fn(6, 6, |x, y| image::Rgba([x as u8, y as u8, 0, 255]));
+        let lod_tiles = vec![HashMap::from([(
+            glam::uvec2(0, 0),
+            image::DynamicImage::ImageRgba8(tile.clone()),
+        )])];
+        let mut data: Vec<u8> = vec![];
+        encode_to_writer(
+            &mut data,
+            None,
+            vec![glam::uvec2(4, 4)],
+            lod_tiles,
+            tile_settings,
+        )
+        .unwrap();
+        let mut image = Image::decode_from_reader(std::io::Cursor::new(data), None).unwrap();
+        assert_eq!(image.get_tile_settings(), &tile_settings);
+        let decoded = image
+            .get_dynamic_image(&TileIndex {
+                x: 0,
+                y: 0,
+                mipmap_level: 0,
+            })
+            .unwrap();
+        assert_eq!(decoded.to_rgba8(), tile);
+    }
+}
@@ -0,0 +1,95 @@
+use rs_artifact::image::{CompressedTexture, ECompressedTextureFormat};
+use std::io::Write;
+
+const IDENTIFIER: [u8; 12] = [
+    0xAB, 0x4B, 0x54, 0x58, 0x20, 0x31, 0x31, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A,
+];
+const ENDIANNESS: u32 = 0x04030201;
+
+const GL_RGB: u32 = 0x1907;
+const GL_RGBA: u32 = 0x1908;
+const GL_RG: u32 = 0x8227;
+
+/// Returns the internal format and the base internal format of OpenGL.
+fn gl_formats(format: ECompressedTextureFormat) -> (u32, u32) {
+    match format {
+        ECompressedTextureFormat::BC1RgbaUnorm => (0x83F1, GL_RGBA),
+        ECompressedTextureFormat::BC3RgbaUnorm => (0x83F3, GL_RGBA),
+        ECompressedTextureFormat::BC5RgUnorm => (0x8DBD, GL_RG),
+        ECompressedTextureFormat::BC7RgbaUnorm => (0x8E8C, GL_RGBA),
+        ECompressedTextureFormat::Etc2Rgb8Unorm => (0x9274, GL_RGB),
+        ECompressedTextureFormat::Astc4x4Unorm => (0x93B0, GL_RGBA),
+    }
+}
+
+/// Writes a KTX 1.1 file with every mip level of the texture.
+pub fn write_compressed_texture(
+    writer: &mut impl Write,
+    texture: &CompressedTexture,
+) -> std::io::Result<()> {
+    let (gl_internal_format, gl_base_internal_format) = gl_formats(texture.format);
+    writer.write_all(&IDENTIFIER)?;
+    let header = [
+        ENDIANNESS,
+        // glType, glTypeSize and glFormat of compressed textures.
+        0,
+        1,
+        0,
+        gl_internal_format,
+        gl_base_internal_format,
+        texture.width,
+        texture.height,
+        // pixelDepth, numberOfArrayElements and numberOfFaces.
+        0,
+        0,
+        1,
+        texture.mip_level_count,
+        // bytesOfKeyValueData.
+        0,
+    ];
+    for value in header {
+        writer.write_all(&value.to_le_bytes())?;
+    }
+    for mip_level in 0..texture.mip_level_count {
+        let data = texture.get_mip_level_data(mip_level).ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("Mip level {} is out of bounds of the data", mip_level),
+            )
+        })?;
+        writer.write_all(&(data.len() as u32).to_le_bytes())?;
+        writer.write_all(data)?;
+        let padding = (4 - data.len() % 4) % 4;
+        writer.write_all(&[0; 3][..padding])?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::write_compressed_texture;
+    use rs_artifact::image::{CompressedTexture, ECompressedTextureFormat};
+
+    #[test]
+    fn test_case_write_compressed_texture() {
+        let texture = CompressedTexture {
+            format: ECompressedTextureFormat::BC1RgbaUnorm,
+            width: 8,
+            height: 8,
+            mip_level_count: 2,
+            data: vec![0; 32 + 8],
+        };
+        let mut data: Vec<u8> = vec![];
+        write_compressed_texture(&mut data, &texture).unwrap();
+        assert_eq!(data.len(), 64 + 4 + 32 + 4 + 8);
+        assert_eq!(&data[0..12], &super::IDENTIFIER);
+        assert_eq!(&data[28..32], &0x83F1_u32.to_le_bytes());
+        assert_eq!(&data[64..68], &32_u32.to_le_bytes());
+
+        let texture = CompressedTexture {
+            mip_level_count: 3,
+            ..texture
+        };
+        assert!(write_compressed_texture(&mut vec![], &texture).is_err());
+    }
+}
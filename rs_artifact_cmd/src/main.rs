@@ -0,0 +1,414 @@
+mod ktx;
+mod obj;
+
+use anyhow::anyhow;
+use clap::{Args, Parser};
+use rs_artifact::{
+    artifact::ArtifactReader, image::Image, resource_info::ResourceInfo,
+    resource_type::EResourceType, shader_source_code::ShaderSourceCode, skin_mesh::SkinMesh,
+    static_mesh::StaticMesh, EEndianType,
+};
+use std::{
+    collections::BTreeSet,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Clone, Args)]
+struct ListArgs {
+    #[arg(short, long)]
+    input_file: PathBuf,
+}
+
+#[derive(Debug, Clone, Args)]
+struct HeaderArgs {
+    #[arg(short, long)]
+    input_file: PathBuf,
+}
+
+#[derive(Debug, Clone, Args)]
+struct ExtractArgs {
+    #[arg(short, long)]
+    input_file: PathBuf,
+    /// Urls of the resources to extract, all resources are extracted when empty.
+    #[arg(short, long)]
+    url: Vec<String>,
+    #[arg(short, long, default_value = "./extract")]
+    output_folder: PathBuf,
+}
+
+#[derive(Debug, Clone, Args)]
+struct DiffArgs {
+    #[arg(long)]
+    old_file: PathBuf,
+    #[arg(long)]
+    new_file: PathBuf,
+}
+
+#[derive(Parser, Debug, Clone)]
+#[command(author, version, about, long_about = None)]
+#[command(propagate_version = true)]
+enum Cli {
+    /// List url, resource type, offset and length of every resource.
+    List(ListArgs),
+    /// Print the settings of the artifact file header as json.
+    Header(HeaderArgs),
+    /// Extract resources, images keep their format or are written as ktx when they are cooked to
+    /// compressed textures, meshes are written as obj and shaders as wgsl.
+    Extract(ExtractArgs),
+    /// Print resources that are added, removed or changed between two artifacts.
+    Diff(DiffArgs),
+}
+
+fn open_artifact(path: &Path) -> anyhow::Result<ArtifactReader> {
+    ArtifactReader::new(path, Some(EEndianType::Little))
+        .map_err(|err| anyhow!("Failed to open {:?}, {}", path, err))
+}
+
+fn sorted_resource_infos(reader: &ArtifactReader) -> Vec<ResourceInfo> {
+    let mut resource_infos: Vec<ResourceInfo> = reader
+        .get_artifact_file_header()
+        .resource_map
+        .values()
+        .cloned()
+        .collect();
+    resource_infos.sort_by(|lhs, rhs| lhs.url.as_str().cmp(rhs.url.as_str()));
+    resource_infos
+}
+
+fn list(args: ListArgs, writer: &mut impl Write) -> anyhow::Result<()> {
+    let reader = open_artifact(&args.input_file)?;
+    for resource_info in sorted_resource_infos(&reader) {
+        writeln!(
+            writer,
+            "{}\t{:?}\t{}\t{}\t{:?}\t{}",
+            resource_info.url,
+            resource_info.resource_type,
+            resource_info.offset,
+            resource_info.length,
+            resource_info.compression_type,
+            resource_info.uncompressed_length
+        )?;
+    }
+    Ok(())
+}
+
+fn header(args: HeaderArgs, writer: &mut impl Write) -> anyhow::Result<()> {
+    let reader = open_artifact(&args.input_file)?;
+    let json = serde_json::to_string_pretty(&reader.get_artifact_file_header().settings)?;
+    writeln!(writer, "{}", json)?;
+    Ok(())
+}
+
+/// Different urls can map to the same readable name, so the name ends with a hash of the url.
+fn make_file_stem(url: &url::Url) -> String {
+    let name = format!(
+        "{}{}",
+        url.host_str().unwrap_or_default(),
+        url.path().replace('/', "_")
+    );
+    let name: String = name
+        .chars()
+        .map(|x| match x {
+            '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            x => x,
+        })
+        .collect();
+    let url_hash = rs_artifact::hash::content_hash(url.as_str().as_bytes());
+    format!("{}_{:016x}", name, url_hash)
+}
+
+fn extract_resource(
+    reader: &mut ArtifactReader,
+    resource_info: &ResourceInfo,
+    output_folder: &Path,
+) -> anyhow::Result<PathBuf> {
+    let url = &resource_info.url;
+    let resource_type = Some(resource_info.resource_type);
+    let file_stem = make_file_stem(url);
+    match resource_info.resource_type {
+        EResourceType::Image => {
+            let image = reader.get_resource::<Image>(url, resource_type)?;
+            if let Some(compressed_texture) = &image.compressed_texture {
+                let path = output_folder.join(format!("{}.ktx", file_stem));
+                let mut writer = BufWriter::new(std::fs::File::create(&path)?);
+                ktx::write_compressed_texture(&mut writer, compressed_texture)?;
+                return Ok(path);
+            }
+            let extension = image
+                .image_format
+                .to_external_format()
+                .extensions_str()
+                .first()
+                .cloned()
+                .unwrap_or("bin");
+            let path = output_folder.join(format!("{}.{}", file_stem, extension));
+            std::fs::write(&path, &image.data)?;
+            Ok(path)
+        }
+        EResourceType::StaticMesh => {
+            let mesh = reader.get_resource::<StaticMesh>(url, resource_type)?;
+            let path = output_folder.join(format!("{}.obj", file_stem));
+            let mut writer = BufWriter::new(std::fs::File::create(&path)?);
+            obj::write_static_mesh(&mut writer, &mesh)?;
+            Ok(path)
+        }
+        EResourceType::SkinMesh => {
+            let mesh = reader.get_resource::<SkinMesh>(url, resource_type)?;
+            let path = output_folder.join(format!("{}.obj", file_stem));
+            let mut writer = BufWriter::new(std::fs::File::create(&path)?);
+            obj::write_skin_mesh(&mut writer, &mesh)?;
+            Ok(path)
+        }
+        EResourceType::ShaderSourceCode => {
+            let shader = reader.get_resource::<ShaderSourceCode>(url, resource_type)?;
+            let path = output_folder.join(format!("{}.wgsl", file_stem));
+            std::fs::write(&path, shader.code)?;
+            Ok(path)
+        }
+        _ => {
+            let data = reader.get_resource_data(url, resource_type)?;
+            let path = output_folder.join(format!("{}.bin", file_stem));
+            std::fs::write(&path, &data)?;
+            Ok(path)
+        }
+    }
+}
+
+fn extract(args: ExtractArgs) -> anyhow::Result<()> {
+    let mut reader = open_artifact(&args.input_file)?;
+    std::fs::create_dir_all(&args.output_folder)?;
+    let resource_infos = sorted_resource_infos(&reader);
+    let mut failed_urls: Vec<String> = vec![];
+    let resource_infos: Vec<ResourceInfo> = if args.url.is_empty() {
+        resource_infos
+    } else {
+        let mut selected = vec![];
+        for url in &args.url {
+            let resource_info = url::Url::parse(url)
+                .ok()
+                .and_then(|url| resource_infos.iter().find(|x| x.url == url));
+            match resource_info {
+                Some(resource_info) => selected.push(resource_info.clone()),
+                None => {
+                    log::warn!("{} is not found", url);
+                    failed_urls.push(url.clone());
+                }
+            }
+        }
+        selected
+    };
+    for resource_info in resource_infos {
+        match extract_resource(&mut reader, &resource_info, &args.output_folder) {
+            Ok(path) => log::trace!("{} -> {:?}", resource_info.url, path),
+            Err(err) => {
+                log::warn!("Failed to extract {}, {}", resource_info.url, err);
+                failed_urls.push(resource_info.url.to_string());
+            }
+        }
+    }
+    if !failed_urls.is_empty() {
+        return Err(anyhow!(
+            "Failed to extract {} resources: {}",
+            failed_urls.len(),
+            failed_urls.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// Content hashes are computed from the stored data, so they are only compared when both
+/// resources are stored the same way, otherwise the decompressed data is compared.
+fn is_resource_changed(
+    old_reader: &mut ArtifactReader,
+    old: &ResourceInfo,
+    new_reader: &mut ArtifactReader,
+    new: &ResourceInfo,
+) -> anyhow::Result<bool> {
+    if old.resource_type != new.resource_type {
+        return Ok(true);
+    }
+    match (old.content_hash, new.content_hash) {
+        (Some(old_hash), Some(new_hash)) if old.compression_type == new.compression_type => {
+            Ok(old_hash != new_hash)
+        }
+        _ => {
+            if old.uncompressed_length != new.uncompressed_length {
+                return Ok(true);
+            }
+            let old_data = old_reader.get_resource_data(&old.url, None)?;
+            let new_data = new_reader.get_resource_data(&new.url, None)?;
+            Ok(old_data != new_data)
+        }
+    }
+}
+
+fn diff(args: DiffArgs, writer: &mut impl Write) -> anyhow::Result<()> {
+    let mut old_reader = open_artifact(&args.old_file)?;
+    let mut new_reader = open_artifact(&args.new_file)?;
+    let old_resource_map = old_reader.get_artifact_file_header().resource_map.clone();
+    let new_resource_map = new_reader.get_artifact_file_header().resource_map.clone();
+    let urls: BTreeSet<&str> = old_resource_map
+        .keys()
+        .chain(new_resource_map.keys())
+        .map(|x| x.as_str())
+        .collect();
+    for url in urls {
+        let url = url::Url::parse(url)?;
+        match (old_resource_map.get(&url), new_resource_map.get(&url)) {
+            (None, Some(new)) => {
+                writeln!(writer, "+ {}\t{:?}\t{}", url, new.resource_type, new.length)?;
+            }
+            (Some(old), None) => {
+                writeln!(writer, "- {}\t{:?}\t{}", url, old.resource_type, old.length)?;
+            }
+            (Some(old), Some(new)) => {
+                if is_resource_changed(&mut old_reader, old, &mut new_reader, new)? {
+                    writeln!(
+                        writer,
+                        "~ {}\t{:?}\t{} -> {}",
+                        url, new.resource_type, old.length, new.length
+                    )?;
+                }
+            }
+            (None, None) => unreachable!(),
+        }
+    }
+    let old_settings = serde_json::to_value(&old_reader.get_artifact_file_header().settings)?;
+    let new_settings = serde_json::to_value(&new_reader.get_artifact_file_header().settings)?;
+    if old_settings != new_settings {
+        writeln!(writer, "~ settings")?;
+    }
+    Ok(())
+}
+
+fn main() {
+    let mut builder = env_logger::Builder::new();
+    builder.write_style(env_logger::WriteStyle::Auto);
+    builder.filter_level(log::LevelFilter::Trace);
+    builder.init();
+
+    let mut stdout = std::io::stdout().lock();
+    let result = match Cli::parse() {
+        Cli::List(args) => list(args, &mut stdout),
+        Cli::Header(args) => header(args, &mut stdout),
+        Cli::Extract(args) => extract(args),
+        Cli::Diff(args) => diff(args, &mut stdout),
+    };
+    if let Err(err) = result {
+        log::error!("{}", err);
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{diff, extract, header, list, DiffArgs, ExtractArgs, HeaderArgs, ListArgs};
+    use rs_artifact::{
+        artifact::ArtifactAssetEncoder,
+        compression::{CompressionPolicy, ECompressionType},
+        image::{Image, ImageFormat},
+        resource_type::EResourceType,
+        EEndianType,
+    };
+    use std::path::{Path, PathBuf};
+
+    fn make_test_folder(name: &str) -> PathBuf {
+        let folder =
+            std::env::temp_dir().join(format!("rs_artifact_cmd_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&folder);
+        std::fs::create_dir_all(&folder).unwrap();
+        folder
+    }
+
+    fn write_artifact(path: &Path, images: &[(&str, &[u8])], compression_type: ECompressionType) {
+        let mut encoder =
+            ArtifactAssetEncoder::new(Some(EEndianType::Little), Default::default(), path);
+        let mut compression_policy = CompressionPolicy::none();
+        compression_policy.set_compression_type(EResourceType::Image, compression_type);
+        encoder.set_compression_policy(compression_policy);
+        for (url, data) in images {
+            encoder.encode(&Image {
+                url: url::Url::parse(url).unwrap(),
+                image_format: ImageFormat::Png,
+                data: data.to_vec(),
+                compressed_texture: None,
+            });
+        }
+        encoder.finish().unwrap();
+    }
+
+    #[test]
+    fn test_case_list_header_extract() {
+        let folder = make_test_folder("extract");
+        let input_file = folder.join("main.rs");
+        let images: [(&str, &[u8]); 2] = [("asset://image/a", &[1, 2]), ("asset://image_a", &[3])];
+        write_artifact(&input_file, &images, ECompressionType::None);
+
+        let mut output: Vec<u8> = vec![];
+        list(
+            ListArgs {
+                input_file: input_file.clone(),
+            },
+            &mut output,
+        )
+        .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.lines().count(), 2);
+        assert!(output.starts_with("asset://image/a\tImage\t"));
+
+        let mut output: Vec<u8> = vec![];
+        header(
+            HeaderArgs {
+                input_file: input_file.clone(),
+            },
+            &mut output,
+        )
+        .unwrap();
+        assert!(serde_json::from_slice::<serde_json::Value>(&output).is_ok());
+
+        let output_folder = folder.join("extract");
+        extract(ExtractArgs {
+            input_file,
+            url: vec![],
+            output_folder: output_folder.clone(),
+        })
+        .unwrap();
+        let mut datas: Vec<Vec<u8>> = std::fs::read_dir(&output_folder)
+            .unwrap()
+            .map(|x| std::fs::read(x.unwrap().path()).unwrap())
+            .collect();
+        datas.sort();
+        assert_eq!(datas, vec![vec![1, 2], vec![3]]);
+        let _ = std::fs::remove_dir_all(&folder);
+    }
+
+    #[test]
+    fn test_case_diff() {
+        let folder = make_test_folder("diff");
+        let old_file = folder.join("old.rs");
+        let new_file = folder.join("new.rs");
+        let old_images: [(&str, &[u8]); 3] = [
+            ("asset://image/changed", &[1, 2, 3]),
+            ("asset://image/removed", &[4]),
+            ("asset://image/same", &[6, 6]),
+        ];
+        let new_images: [(&str, &[u8]); 3] = [
+            ("asset://image/added", &[5]),
+            ("asset://image/changed", &[1, 2, 4]),
+            ("asset://image/same", &[6, 6]),
+        ];
+        write_artifact(&old_file, &old_images, ECompressionType::None);
+        write_artifact(&new_file, &new_images, ECompressionType::Lz4);
+
+        let mut output: Vec<u8> = vec![];
+        diff(DiffArgs { old_file, new_file }, &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("+ asset://image/added\t"));
+        assert!(lines[1].starts_with("~ asset://image/changed\t"));
+        assert!(lines[2].starts_with("- asset://image/removed\t"));
+        let _ = std::fs::remove_dir_all(&folder);
+    }
+}
@@ -0,0 +1,49 @@
+use rs_artifact::{skin_mesh::SkinMesh, static_mesh::StaticMesh};
+use std::io::Write;
+
+fn write_obj(
+    writer: &mut impl Write,
+    name: &str,
+    positions: impl Iterator<Item = [f32; 3]>,
+    normals: impl Iterator<Item = [f32; 3]>,
+    tex_coords: impl Iterator<Item = [f32; 2]>,
+    indexes: &[u32],
+) -> std::io::Result<()> {
+    writeln!(writer, "o {}", name)?;
+    for [x, y, z] in positions {
+        writeln!(writer, "v {} {} {}", x, y, z)?;
+    }
+    for [u, v] in tex_coords {
+        writeln!(writer, "vt {} {}", u, 1.0 - v)?;
+    }
+    for [x, y, z] in normals {
+        writeln!(writer, "vn {} {} {}", x, y, z)?;
+    }
+    for triangle in indexes.chunks_exact(3) {
+        let [a, b, c] = [triangle[0] + 1, triangle[1] + 1, triangle[2] + 1];
+        writeln!(writer, "f {a}/{a}/{a} {b}/{b}/{b} {c}/{c}/{c}")?;
+    }
+    Ok(())
+}
+
+pub fn write_static_mesh(writer: &mut impl Write, mesh: &StaticMesh) -> std::io::Result<()> {
+    write_obj(
+        writer,
+        &mesh.name,
+        mesh.vertexes.iter().map(|x| x.position.to_array()),
+        mesh.vertexes.iter().map(|x| x.normal.to_array()),
+        mesh.vertexes.iter().map(|x| x.tex_coord.to_array()),
+        &mesh.indexes,
+    )
+}
+
+pub fn write_skin_mesh(writer: &mut impl Write, mesh: &SkinMesh) -> std::io::Result<()> {
+    write_obj(
+        writer,
+        &mesh.name,
+        mesh.vertexes.iter().map(|x| x.position.to_array()),
+        mesh.vertexes.iter().map(|x| x.normal.to_array()),
+        mesh.vertexes.iter().map(|x| x.tex_coord.to_array()),
+        &mesh.indexes,
+    )
+}
@@ -0,0 +1,54 @@
+use crate::convert::ConvertToVec3;
+use std::marker::PhantomData;
+
+pub struct AnimMesh<'a> {
+    _ai_anim_mesh: &'a mut russimp_sys::aiAnimMesh,
+    pub name: String,
+    /// Replacements of the vertices of the mesh, empty if they are not replaced.
+    pub vertices: Vec<glam::Vec3>,
+    /// Replacements of the normals of the mesh, empty if they are not replaced.
+    pub normals: Vec<glam::Vec3>,
+    pub weight: f32,
+    marker: PhantomData<&'a ()>,
+}
+
+impl<'a> AnimMesh<'a> {
+    pub fn borrow_from(ai_anim_mesh: &'a mut russimp_sys::aiAnimMesh) -> AnimMesh<'a> {
+        let name = ai_anim_mesh.mName.into();
+        let vertices = if ai_anim_mesh.mVertices == std::ptr::null_mut() {
+            vec![]
+        } else {
+            unsafe {
+                std::slice::from_raw_parts_mut(
+                    ai_anim_mesh.mVertices,
+                    ai_anim_mesh.mNumVertices as usize,
+                )
+            }
+            .iter_mut()
+            .map(|x| x.to_vec3())
+            .collect()
+        };
+        let normals = if ai_anim_mesh.mNormals == std::ptr::null_mut() {
+            vec![]
+        } else {
+            unsafe {
+                std::slice::from_raw_parts_mut(
+                    ai_anim_mesh.mNormals,
+                    ai_anim_mesh.mNumVertices as usize,
+                )
+            }
+            .iter_mut()
+            .map(|x| x.to_vec3())
+            .collect()
+        };
+        let weight = ai_anim_mesh.mWeight;
+        AnimMesh {
+            _ai_anim_mesh: ai_anim_mesh,
+            name,
+            vertices,
+            normals,
+            weight,
+            marker: PhantomData,
+        }
+    }
+}
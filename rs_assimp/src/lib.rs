@@ -1,4 +1,5 @@
 pub mod anim_behaviour;
+pub mod anim_mesh;
 pub mod animation;
 pub mod bone;
 pub mod config;
@@ -1,6 +1,6 @@
 use crate::{convert::ConvertToString, texture_type::TextureType};
 use russimp_sys::*;
-use std::marker::PhantomData;
+use std::{collections::HashMap, marker::PhantomData};
 use strum::IntoEnumIterator;
 
 #[derive(Debug, Clone, Copy)]
@@ -155,11 +155,14 @@ pub struct Material<'a> {
     _ai_material: &'a mut russimp_sys::aiMaterial,
     pub num_allocated: u32,
     pub material_properties: Vec<MaterialProperty<'a>>,
+    /// The file paths of textures as they are written in the model file.
+    pub textures: HashMap<TextureType, Vec<String>>,
     marker: PhantomData<&'a ()>,
 }
 
 impl<'a> Material<'a> {
     pub fn borrow_from(ai_material: &'a mut russimp_sys::aiMaterial) -> Material<'a> {
+        let mut textures: HashMap<TextureType, Vec<String>> = HashMap::new();
         for texture_type in TextureType::iter() {
             unsafe {
                 for index in 0..aiGetMaterialTextureCount(ai_material, texture_type as _) {
@@ -180,8 +183,10 @@ impl<'a> Material<'a> {
                         std::ptr::null_mut(),
                     );
                     assert_eq!(aiReturn_aiReturn_SUCCESS, status);
-                    // aiGetMaterialProperty(c, pKey, texture_type as _, index, pPropOut);
-                    // println!("{}", path.to_string());
+                    textures
+                        .entry(texture_type)
+                        .or_default()
+                        .push(path.to_string());
                 }
             }
         }
@@ -201,6 +206,40 @@ impl<'a> Material<'a> {
             num_allocated,
             marker: PhantomData,
             material_properties,
+            textures,
         }
     }
+
+    /// Finds a property which is not bound to a texture, such as `$clr.base`.
+    pub fn get_property_value(&self, key: &str) -> Option<&EPropertyTypeValue> {
+        self.material_properties
+            .iter()
+            .find(|x| x.key == key && x._semantic == 0)
+            .map(|x| &x.value)
+    }
+
+    pub fn get_float_array(&self, key: &str) -> Option<&[f32]> {
+        match self.get_property_value(key) {
+            Some(EPropertyTypeValue::FloatArray(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn get_integer_array(&self, key: &str) -> Option<&[i32]> {
+        match self.get_property_value(key) {
+            Some(EPropertyTypeValue::IntegerArray(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn get_name(&self) -> Option<String> {
+        match self.get_property_value("?mat.name") {
+            Some(EPropertyTypeValue::String(name)) => Some(name.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn get_texture(&self, texture_type: TextureType) -> Option<&String> {
+        self.textures.get(&texture_type).and_then(|x| x.first())
+    }
 }
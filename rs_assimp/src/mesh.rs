@@ -1,4 +1,5 @@
 use crate::{
+    anim_mesh::AnimMesh,
     bone::Bone,
     convert::{ConvertToVec3, ConvertToVec4},
     face::Face,
@@ -21,6 +22,10 @@ pub struct Mesh<'a> {
     pub texture_coords: Vec<Vec<glam::Vec3>>,
     pub colors: Vec<Vec<glam::Vec4>>,
     pub faces: Vec<Face<'a>>,
+    /// The index of the material in the scene.
+    pub material_index: usize,
+    /// Morph targets of the mesh.
+    pub anim_meshes: Vec<AnimMesh<'a>>,
     marker: PhantomData<&'a ()>,
 }
 
@@ -127,6 +132,20 @@ impl<'a> Mesh<'a> {
                 .map(|x| Face::borrow_from(x))
                 .collect();
 
+        let material_index = ai_mesh.mMaterialIndex as usize;
+
+        let mut anim_meshes = Vec::new();
+        if ai_mesh.mAnimMeshes.is_null() == false {
+            let slice = unsafe {
+                std::slice::from_raw_parts_mut(ai_mesh.mAnimMeshes, ai_mesh.mNumAnimMeshes as usize)
+            };
+            for anim_mesh in slice {
+                anim_meshes.push(AnimMesh::borrow_from(unsafe {
+                    anim_mesh.as_mut().unwrap()
+                }));
+            }
+        }
+
         Mesh {
             _ai_mesh: ai_mesh,
             name,
@@ -140,6 +159,8 @@ impl<'a> Mesh<'a> {
             texture_coords,
             colors,
             faces,
+            material_index,
+            anim_meshes,
         }
     }
 }
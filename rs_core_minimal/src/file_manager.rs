@@ -47,11 +47,6 @@ pub fn create_editor_tmp_folder() -> PathBuf {
     }
 }
 
-#[cfg(feature = "editor")]
-pub fn get_gpmetis_program_path() -> PathBuf {
-    get_engine_root_dir().join("build/windows/x64/release/gpmetis.exe")
-}
-
 thread_local! {
     pub static GLOBAL_CURRENT_PROJECT_DIR: std::cell::RefCell<PathBuf>  = std::cell::RefCell::new(PathBuf::new()) ;
 }
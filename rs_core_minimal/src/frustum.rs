@@ -76,6 +76,30 @@ pub struct Frustum {
 }
 
 impl Frustum {
+    /// Creates the frustum of the view from the corners of the normalized device coordinates,
+    /// whose depth is in [0, 1]. Returns None if the matrix is not invertible.
+    pub fn from_view_projection(view_projection: &glam::Mat4) -> Option<Frustum> {
+        let determinant = view_projection.determinant();
+        if determinant == 0.0 || !determinant.is_finite() {
+            return None;
+        }
+        let inverse = view_projection.inverse();
+        // The corners are ordered like `frustum_from_perspective`, whose z axis points forward,
+        // so the x axis is mirrored for right-handed matrices.
+        let right = determinant.signum();
+        let corner = |x: f32, y: f32, z: f32| inverse.project_point3(glam::vec3(x, y, z));
+        Some(Frustum {
+            near_0: corner(right, 1.0, 0.0),
+            near_1: corner(right, -1.0, 0.0),
+            near_2: corner(-right, -1.0, 0.0),
+            near_3: corner(-right, 1.0, 0.0),
+            far_0: corner(right, 1.0, 1.0),
+            far_1: corner(right, -1.0, 1.0),
+            far_2: corner(-right, -1.0, 1.0),
+            far_3: corner(-right, 1.0, 1.0),
+        })
+    }
+
     pub fn transform(&self, transform: &glam::Mat4) -> Frustum {
         Frustum {
             near_0: transform.transform_point3(self.near_0),
@@ -286,7 +286,7 @@ mod test {
         frustum_from_perspective, is_sphere_visible_to_frustum, point_light_radius, split_frustum,
         subdivide_four_points, subdivide_two_points,
     };
-    use crate::{misc::is_valid_name, sphere_3d::Sphere3D};
+    use crate::{frustum::Frustum, misc::is_valid_name, sphere_3d::Sphere3D};
 
     #[test]
     fn is_valid_name_test() {
@@ -327,6 +327,24 @@ mod test {
         assert_eq!(is_sphere_visible_to_frustum(&sphere, &frustum), false);
     }
 
+    #[test]
+    fn frustum_from_view_projection_test() {
+        let view = glam::Mat4::look_to_rh(glam::vec3(0.0, 0.0, 5.0), -glam::Vec3::Z, glam::Vec3::Y);
+        for projection in [
+            glam::Mat4::perspective_rh(39.6_f32.to_radians(), 1280.0 / 720.0, 0.01, 1000.0),
+            glam::Mat4::orthographic_rh(-10.0, 10.0, -10.0, 10.0, 0.01, 1000.0),
+        ] {
+            let frustum = Frustum::from_view_projection(&(projection * view)).unwrap();
+            let sphere = Sphere3D::new(glam::vec3(0.0, 0.0, -10.0), 1.0);
+            assert_eq!(is_sphere_visible_to_frustum(&sphere, &frustum), true);
+            let sphere = Sphere3D::new(glam::vec3(0.0, 0.0, 20.0), 1.0);
+            assert_eq!(is_sphere_visible_to_frustum(&sphere, &frustum), false);
+            let sphere = Sphere3D::new(glam::vec3(-300.0, 0.0, -10.0), 1.0);
+            assert_eq!(is_sphere_visible_to_frustum(&sphere, &frustum), false);
+        }
+        assert!(Frustum::from_view_projection(&glam::Mat4::ZERO).is_none());
+    }
+
     #[test]
     fn split_frustum_test() {
         let frustum = frustum_from_perspective(39.6_f32.to_radians(), 1280.0 / 720.0, 0.01, 1000.0);
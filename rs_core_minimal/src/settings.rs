@@ -2,6 +2,22 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct VirtualTextureSetting {
+    pub is_enable: bool,
+    pub tile_size: u32,
+    /// The number of texels around every tile in the physical texture for filtering.
+    #[serde(default)]
+    pub tile_border_size: u32,
+    pub physical_texture_size: u32,
+    pub virtual_texture_size: u32,
+    pub feed_back_texture_div: u32,
+    pub mipmap_level_bias: f32,
+    pub mipmap_level_scale: f32,
+    pub feedback_bias: f32,
+}
+
+/// The layout of `VirtualTextureSetting` before tiles had borders.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VirtualTextureSettingV0 {
     pub is_enable: bool,
     pub tile_size: u32,
     pub physical_texture_size: u32,
@@ -12,6 +28,22 @@ pub struct VirtualTextureSetting {
     pub feedback_bias: f32,
 }
 
+impl From<VirtualTextureSettingV0> for VirtualTextureSetting {
+    fn from(value: VirtualTextureSettingV0) -> Self {
+        Self {
+            is_enable: value.is_enable,
+            tile_size: value.tile_size,
+            tile_border_size: 0,
+            physical_texture_size: value.physical_texture_size,
+            virtual_texture_size: value.virtual_texture_size,
+            feed_back_texture_div: value.feed_back_texture_div,
+            mipmap_level_bias: value.mipmap_level_bias,
+            mipmap_level_scale: value.mipmap_level_scale,
+            feedback_bias: value.feedback_bias,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub enum PowerPreference {
     #[default]
@@ -37,6 +69,24 @@ pub enum EAntialiasType {
     MSAA,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ClusterLodSetting {
+    /// Clusters are refined until their simplification error projects to fewer pixels.
+    pub max_pixel_error: f32,
+    /// The maximum number of triangles drawn for one mesh, the error is relaxed until the
+    /// selected clusters fit.
+    pub triangle_budget: u32,
+}
+
+impl Default for ClusterLodSetting {
+    fn default() -> Self {
+        Self {
+            max_pixel_error: 1.0,
+            triangle_budget: 1 << 20,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RenderSettings {
     pub power_preference: PowerPreference,
@@ -48,6 +98,65 @@ pub struct RenderSettings {
     pub is_enable_multithread_rendering: bool,
     pub is_enable_debugging: bool,
     pub is_enable_dump_material_shader_code: bool,
+    #[serde(default)]
+    pub cluster_lod_setting: ClusterLodSetting,
+}
+
+/// The layout of `RenderSettings` before virtual texture tiles had borders.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RenderSettingsV0 {
+    pub power_preference: PowerPreference,
+    pub backends: Backends,
+    pub android_backends: Backends,
+    pub virtual_texture_setting: VirtualTextureSettingV0,
+    pub antialias_type: EAntialiasType,
+    pub is_enable_multithread_rendering: bool,
+    pub is_enable_debugging: bool,
+    pub is_enable_dump_material_shader_code: bool,
+}
+
+impl From<RenderSettingsV0> for RenderSettingsV1 {
+    fn from(value: RenderSettingsV0) -> Self {
+        Self {
+            power_preference: value.power_preference,
+            backends: value.backends,
+            android_backends: value.android_backends,
+            virtual_texture_setting: value.virtual_texture_setting.into(),
+            antialias_type: value.antialias_type,
+            is_enable_multithread_rendering: value.is_enable_multithread_rendering,
+            is_enable_debugging: value.is_enable_debugging,
+            is_enable_dump_material_shader_code: value.is_enable_dump_material_shader_code,
+        }
+    }
+}
+
+/// The layout of `RenderSettings` before cluster levels of detail were added.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RenderSettingsV1 {
+    pub power_preference: PowerPreference,
+    pub backends: Backends,
+    pub android_backends: Backends,
+    pub virtual_texture_setting: VirtualTextureSetting,
+    pub antialias_type: EAntialiasType,
+    pub is_enable_multithread_rendering: bool,
+    pub is_enable_debugging: bool,
+    pub is_enable_dump_material_shader_code: bool,
+}
+
+impl From<RenderSettingsV1> for RenderSettings {
+    fn from(value: RenderSettingsV1) -> Self {
+        Self {
+            power_preference: value.power_preference,
+            backends: value.backends,
+            android_backends: value.android_backends,
+            virtual_texture_setting: value.virtual_texture_setting,
+            antialias_type: value.antialias_type,
+            is_enable_multithread_rendering: value.is_enable_multithread_rendering,
+            is_enable_debugging: value.is_enable_debugging,
+            is_enable_dump_material_shader_code: value.is_enable_dump_material_shader_code,
+            cluster_lod_setting: ClusterLodSetting::default(),
+        }
+    }
 }
 
 impl RenderSettings {
@@ -71,12 +180,53 @@ pub struct Settings {
     pub render_setting: RenderSettings,
 }
 
+/// The layout of `Settings` before virtual texture tiles had borders,
+/// it is still embedded in the headers of older artifacts.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SettingsV0 {
+    pub editor_settings: EditorSettings,
+    pub render_setting: RenderSettingsV0,
+}
+
+impl From<SettingsV0> for SettingsV1 {
+    fn from(value: SettingsV0) -> Self {
+        Self {
+            editor_settings: value.editor_settings,
+            render_setting: value.render_setting.into(),
+        }
+    }
+}
+
+impl From<SettingsV0> for Settings {
+    fn from(value: SettingsV0) -> Self {
+        SettingsV1::from(value).into()
+    }
+}
+
+/// The layout of `Settings` before cluster levels of detail were added,
+/// it is still embedded in the headers of older artifacts.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SettingsV1 {
+    pub editor_settings: EditorSettings,
+    pub render_setting: RenderSettingsV1,
+}
+
+impl From<SettingsV1> for Settings {
+    fn from(value: SettingsV1) -> Self {
+        Self {
+            editor_settings: value.editor_settings,
+            render_setting: value.render_setting.into(),
+        }
+    }
+}
+
 impl Default for Settings {
     fn default() -> Self {
         Self {
             render_setting: RenderSettings {
                 virtual_texture_setting: VirtualTextureSetting {
                     tile_size: 256,
+                    tile_border_size: 4,
                     physical_texture_size: 4096,
                     virtual_texture_size: 512 * 1000,
                     feed_back_texture_div: 10,
@@ -95,6 +245,7 @@ impl Default for Settings {
                 is_enable_multithread_rendering: false,
                 is_enable_debugging: true,
                 is_enable_dump_material_shader_code: true,
+                cluster_lod_setting: ClusterLodSetting::default(),
             },
             editor_settings: EditorSettings {
                 is_auto_open_last_project: true,
@@ -10,6 +10,9 @@ use winit::{
 struct Args {
     #[arg(short, long)]
     input_file: Option<std::path::PathBuf>,
+    /// Patch artifacts mounted on top of the input file, later patches take precedence.
+    #[arg(short, long)]
+    patch_files: Vec<std::path::PathBuf>,
 }
 
 pub struct Application {
@@ -62,7 +65,8 @@ impl ApplicationHandler<ECustomEventType> for Application {
                     .create_window(window_attributes)
                     .expect("Should not be null");
                 window.set_ime_allowed(true);
-                let application_context = ApplicationContext::new(&window, args.input_file);
+                let application_context =
+                    ApplicationContext::new(&window, args.input_file, args.patch_files);
                 self.window = Some(window);
                 self.application_context = Some(application_context);
             }
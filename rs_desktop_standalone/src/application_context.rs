@@ -6,9 +6,13 @@ use rs_engine::{
     input_mode::EInputMode,
     input_type::EInputType,
     logger::{Logger, LoggerConfiguration, SlotFlags},
+    resource_manager::ResourceManager,
 };
 use rs_render::{command::RenderCommand, egui_render::EGUIRenderOutput};
-use std::{collections::HashMap, path::Path};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 use winit::event::{Event, WindowEvent};
 
 include!("../../build/target/generated/rs_desktop_standalone/load_plugins.generated.rs");
@@ -25,6 +29,7 @@ impl ApplicationContext {
     pub fn new(
         window: &winit::window::Window,
         input_file: Option<impl AsRef<Path>>,
+        patch_files: Vec<PathBuf>,
     ) -> ApplicationContext {
         let window_id = u64::from(window.id()) as isize;
         rs_foundation::change_working_directory();
@@ -54,7 +59,21 @@ impl ApplicationContext {
             None => Path::new("main.rs").to_path_buf(),
         };
         let artifact_reader =
-            ArtifactReader::new(&artifact_filepath, Some(EEndianType::Little)).ok();
+            ArtifactReader::new_mmap(&artifact_filepath, Some(EEndianType::Little)).ok();
+        for (index, patch_file) in patch_files.iter().enumerate() {
+            match ArtifactReader::new_mmap(patch_file, Some(EEndianType::Little)) {
+                Ok(reader) => {
+                    ResourceManager::default().mount_artifact(
+                        patch_file.to_string_lossy().to_string(),
+                        reader,
+                        index as i32 + 1,
+                    );
+                }
+                Err(err) => {
+                    log::warn!("Failed to mount patch {:?}, {}", patch_file, err);
+                }
+            }
+        }
         let mut engine = rs_engine::engine::Engine::new(
             window_id,
             window,
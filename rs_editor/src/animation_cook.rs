@@ -0,0 +1,218 @@
+use crate::project::AnimationCookSettings;
+use rs_artifact::{
+    node_anim::{ERotationTrack, EVectorTrack, NodeAnim, QuantizedQuat},
+    skeleton_animation::SkeletonAnimation,
+};
+
+/// The relative deviation of the intervals between keys for them to be treated as uniform.
+const UNIFORM_INTERVAL_TOLERANCE: f64 = 0.001;
+
+pub fn cook_skeleton_animation(
+    skeleton_animation: &mut SkeletonAnimation,
+    animation_cook_settings: &AnimationCookSettings,
+) {
+    if !animation_cook_settings.is_compress {
+        return;
+    }
+    for channel in skeleton_animation.channels.iter_mut() {
+        cook_node_anim(channel, animation_cook_settings);
+    }
+}
+
+pub fn cook_node_anim(node_anim: &mut NodeAnim, animation_cook_settings: &AnimationCookSettings) {
+    if let EVectorTrack::Keys(keys) = &node_anim.position_track {
+        let times: Vec<f64> = keys.iter().map(|x| x.time).collect();
+        let values: Vec<glam::Vec3> = keys.iter().map(|x| x.value).collect();
+        node_anim.position_track =
+            compress_vector_track(&times, &values, animation_cook_settings.position_tolerance);
+    }
+    if let EVectorTrack::Keys(keys) = &node_anim.scaling_track {
+        let times: Vec<f64> = keys.iter().map(|x| x.time).collect();
+        let values: Vec<glam::Vec3> = keys.iter().map(|x| x.value).collect();
+        node_anim.scaling_track =
+            compress_vector_track(&times, &values, animation_cook_settings.scaling_tolerance);
+    }
+    if let ERotationTrack::Keys(keys) = &node_anim.rotation_track {
+        let times: Vec<f64> = keys.iter().map(|x| x.time).collect();
+        let values: Vec<glam::Quat> = keys.iter().map(|x| x.value).collect();
+        node_anim.rotation_track =
+            compress_rotation_track(&times, &values, animation_cook_settings.rotation_tolerance);
+    }
+}
+
+/// Returns the indexes of the keys to keep. A key is removed when interpolating the keys around
+/// it reproduces every removed key within the tolerance.
+pub fn reduce_keys<T>(
+    times: &[f64],
+    values: &[T],
+    interpolate: impl Fn(&T, &T, f32) -> T,
+    error: impl Fn(&T, &T) -> f32,
+    tolerance: f32,
+) -> Vec<usize> {
+    let len = times.len().min(values.len());
+    if len == 0 {
+        return vec![];
+    }
+    if values[1..len]
+        .iter()
+        .all(|x| error(&values[0], x) <= tolerance)
+    {
+        return vec![0];
+    }
+    let mut kept = vec![0];
+    let mut start = 0;
+    for end in 2..len {
+        let is_reducible = (start + 1..end).all(|i| {
+            let alpha = ((times[i] - times[start]) / (times[end] - times[start])) as f32;
+            let interpolated = interpolate(&values[start], &values[end], alpha);
+            error(&interpolated, &values[i]) <= tolerance
+        });
+        if !is_reducible {
+            start = end - 1;
+            kept.push(start);
+        }
+    }
+    if len > 1 {
+        kept.push(len - 1);
+    }
+    kept
+}
+
+/// Returns the start time and the interval if keys are evenly spaced.
+fn find_uniform_interval(times: &[f64]) -> Option<(f64, f64)> {
+    if times.len() < 2 {
+        return None;
+    }
+    let interval = times[1] - times[0];
+    if interval <= 0.0 {
+        return None;
+    }
+    let is_uniform = times
+        .windows(2)
+        .all(|x| ((x[1] - x[0]) - interval).abs() <= interval * UNIFORM_INTERVAL_TOLERANCE);
+    if is_uniform {
+        Some((times[0], interval))
+    } else {
+        None
+    }
+}
+
+/// Uniform sampling is chosen when it is smaller than the reduced keys, it does not store times.
+fn is_uniform_smaller(key_count: usize, reduced_key_count: usize, value_size: usize) -> bool {
+    let uniform_size = key_count * value_size;
+    let sparse_size = reduced_key_count * (value_size + std::mem::size_of::<f64>());
+    uniform_size < sparse_size
+}
+
+pub fn compress_vector_track(times: &[f64], values: &[glam::Vec3], tolerance: f32) -> EVectorTrack {
+    let kept = reduce_keys(
+        times,
+        values,
+        |lhs, rhs, alpha| lhs.lerp(*rhs, alpha),
+        |lhs, rhs| lhs.distance(*rhs),
+        tolerance,
+    );
+    if let Some((start_time, interval)) = find_uniform_interval(times) {
+        if is_uniform_smaller(values.len(), kept.len(), std::mem::size_of::<glam::Vec3>()) {
+            return EVectorTrack::Uniform {
+                start_time,
+                interval,
+                values: values.to_vec(),
+            };
+        }
+    }
+    EVectorTrack::SparseKeys {
+        times: kept.iter().map(|x| times[*x]).collect(),
+        values: kept.iter().map(|x| values[*x]).collect(),
+    }
+}
+
+pub fn compress_rotation_track(
+    times: &[f64],
+    values: &[glam::Quat],
+    tolerance: f32,
+) -> ERotationTrack {
+    let kept = reduce_keys(
+        times,
+        values,
+        |lhs, rhs, alpha| lhs.slerp(*rhs, alpha),
+        |lhs, rhs| lhs.angle_between(*rhs),
+        tolerance,
+    );
+    if let Some((start_time, interval)) = find_uniform_interval(times) {
+        if is_uniform_smaller(
+            values.len(),
+            kept.len(),
+            std::mem::size_of::<QuantizedQuat>(),
+        ) {
+            return ERotationTrack::Uniform {
+                start_time,
+                interval,
+                values: values.iter().map(|x| QuantizedQuat::encode(*x)).collect(),
+            };
+        }
+    }
+    ERotationTrack::SparseKeys {
+        times: kept.iter().map(|x| times[*x]).collect(),
+        values: kept
+            .iter()
+            .map(|x| QuantizedQuat::encode(values[*x]))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{compress_vector_track, reduce_keys};
+    use rs_artifact::node_anim::EVectorTrack;
+
+    #[test]
+    fn test_case_reduce_keys() {
+        let times: Vec<f64> = (0..10).map(|x| x as f64).collect();
+        let mut values: Vec<f32> = (0..10).map(|x| x as f32).collect();
+        values[9] = 0.0;
+        let kept = reduce_keys(
+            &times,
+            &values,
+            |lhs, rhs, alpha| lhs + (rhs - lhs) * alpha,
+            |lhs, rhs| (lhs - rhs).abs(),
+            0.001,
+        );
+        assert_eq!(kept, vec![0, 8, 9]);
+
+        let kept = reduce_keys(
+            &times,
+            &[1.0_f32; 10],
+            |lhs, rhs, alpha| lhs + (rhs - lhs) * alpha,
+            |lhs, rhs| (lhs - rhs).abs(),
+            0.001,
+        );
+        assert_eq!(kept, vec![0]);
+    }
+
+    #[test]
+    fn test_case_compress_vector_track() {
+        let times: Vec<f64> = (0..100).map(|x| x as f64 * 0.5).collect();
+        let values: Vec<glam::Vec3> = times
+            .iter()
+            .map(|x| glam::vec3(*x as f32, 0.0, 0.0))
+            .collect();
+        let track = compress_vector_track(&times, &values, 0.001);
+        assert!(matches!(track, EVectorTrack::SparseKeys { .. }));
+        assert_eq!(track.len(), 2);
+        assert!(
+            track
+                .sample(10.25)
+                .unwrap()
+                .distance(glam::vec3(10.25, 0.0, 0.0))
+                < 0.001
+        );
+
+        let values: Vec<glam::Vec3> = times
+            .iter()
+            .map(|x| glam::vec3((*x as f32).sin(), 0.0, 0.0))
+            .collect();
+        let track = compress_vector_track(&times, &values, 0.0001);
+        assert!(matches!(track, EVectorTrack::Uniform { .. }));
+    }
+}
@@ -0,0 +1,572 @@
+use rs_artifact::cluster_lod::{BoundingSphere, ClusterLod, MeshCluster};
+use rs_metis::{
+    bindings::idx_t,
+    graph::CsrGraph,
+    metis::{Metis, PartitionOptions},
+};
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BinaryHeap, HashMap, HashSet},
+};
+
+#[derive(Debug, Clone)]
+pub struct ClusterLodSettings {
+    pub max_cluster_triangle_count: usize,
+    /// The number of clusters which are simplified together.
+    pub group_cluster_count: usize,
+    pub max_level_count: usize,
+    /// A group is not simplified further when its triangle count drops by less than this ratio.
+    pub min_reduction: f32,
+}
+
+impl Default for ClusterLodSettings {
+    fn default() -> Self {
+        Self {
+            max_cluster_triangle_count: 128,
+            group_cluster_count: 4,
+            max_level_count: 16,
+            min_reduction: 0.15,
+        }
+    }
+}
+
+type WeldedEdge = (u32, u32);
+
+fn make_welded_edge(welded: &[u32], v0: u32, v1: u32) -> WeldedEdge {
+    let (v0, v1) = (welded[v0 as usize], welded[v1 as usize]);
+    (v0.min(v1), v0.max(v1))
+}
+
+fn triangle_edges(triangle: &[u32]) -> [(u32, u32); 3] {
+    [
+        (triangle[0], triangle[1]),
+        (triangle[1], triangle[2]),
+        (triangle[2], triangle[0]),
+    ]
+}
+
+/// Maps every vertex to the first vertex with the same position, so triangles which are split
+/// by texture coordinate or normal seams are still adjacent.
+fn weld_vertexes(positions: &[glam::Vec3]) -> Vec<u32> {
+    let mut first_vertexes: HashMap<[u32; 3], u32> = HashMap::new();
+    positions
+        .iter()
+        .enumerate()
+        .map(|(vertex_index, position)| {
+            let key = position.to_array().map(|x| x.to_bits());
+            *first_vertexes.entry(key).or_insert(vertex_index as u32)
+        })
+        .collect()
+}
+
+/// Splits triangles into clusters of connected triangles with METIS.
+fn partition_triangles(
+    indexes: &[u32],
+    welded: &[u32],
+    max_triangle_count: usize,
+) -> anyhow::Result<Vec<Vec<u32>>> {
+    let triangle_count = indexes.len() / 3;
+    if triangle_count <= max_triangle_count {
+        return Ok(vec![indexes.to_vec()]);
+    }
+    let mut edge_triangles: HashMap<WeldedEdge, Vec<u32>> = HashMap::new();
+    for (triangle_index, triangle) in indexes.chunks_exact(3).enumerate() {
+        for (v0, v1) in triangle_edges(triangle) {
+            edge_triangles
+                .entry(make_welded_edge(welded, v0, v1))
+                .or_default()
+                .push(triangle_index as u32);
+        }
+    }
+    let mut adjoin_indices: Vec<HashSet<u32>> = vec![HashSet::new(); triangle_count];
+    for triangles in edge_triangles.values() {
+        for triangle in triangles {
+            adjoin_indices[*triangle as usize].extend(triangles.iter().copied());
+        }
+    }
+    let num_parts = triangle_count.div_ceil(max_triangle_count) as u32;
+    let partition = Metis::partition_graph(
+        &CsrGraph::from_adjoin_indices(&adjoin_indices),
+        num_parts,
+        &PartitionOptions::default(),
+    )?;
+    let mut parts: Vec<Vec<u32>> = vec![vec![]; num_parts as usize];
+    for (triangle, part) in indexes.chunks_exact(3).zip(partition) {
+        parts[part as usize].extend_from_slice(triangle);
+    }
+    parts.retain(|x| !x.is_empty());
+    Ok(parts)
+}
+
+/// Groups neighboring clusters, clusters are weighted by the number of edges they share so
+/// groups have short borders.
+fn group_clusters(
+    cluster_indexes: &[&[u32]],
+    welded: &[u32],
+    group_cluster_count: usize,
+) -> anyhow::Result<Vec<Vec<usize>>> {
+    if cluster_indexes.len() <= group_cluster_count {
+        return Ok(vec![(0..cluster_indexes.len()).collect()]);
+    }
+    let mut edge_clusters: HashMap<WeldedEdge, Vec<usize>> = HashMap::new();
+    for (cluster, indexes) in cluster_indexes.iter().enumerate() {
+        for triangle in indexes.chunks_exact(3) {
+            for (v0, v1) in triangle_edges(triangle) {
+                let clusters = edge_clusters
+                    .entry(make_welded_edge(welded, v0, v1))
+                    .or_default();
+                if !clusters.contains(&cluster) {
+                    clusters.push(cluster);
+                }
+            }
+        }
+    }
+    let mut shared_edge_counts: Vec<BTreeMap<usize, idx_t>> =
+        vec![BTreeMap::new(); cluster_indexes.len()];
+    for clusters in edge_clusters.values() {
+        for cluster in clusters {
+            for other in clusters.iter().filter(|x| *x != cluster) {
+                *shared_edge_counts[*cluster].entry(*other).or_default() += 1;
+            }
+        }
+    }
+    let mut graph = CsrGraph::default();
+    let mut edge_weights: Vec<idx_t> = vec![];
+    graph.xadj.push(0);
+    for shared_edge_count in &shared_edge_counts {
+        for (other, count) in shared_edge_count {
+            graph.adjncy.push(*other as idx_t);
+            edge_weights.push(*count);
+        }
+        graph.xadj.push(graph.adjncy.len() as idx_t);
+    }
+    graph.edge_weights = Some(edge_weights);
+
+    let num_groups = cluster_indexes.len().div_ceil(group_cluster_count) as u32;
+    let partition = Metis::partition_graph(&graph, num_groups, &PartitionOptions::default())?;
+    let mut groups: Vec<Vec<usize>> = vec![vec![]; num_groups as usize];
+    for (cluster, group) in partition.iter().enumerate() {
+        groups[*group as usize].push(cluster);
+    }
+    groups.retain(|x| !x.is_empty());
+    Ok(groups)
+}
+
+/// Error quadric of the planes around a vertex.
+#[derive(Clone, Copy, Default)]
+struct Quadric {
+    a: [f64; 10],
+}
+
+impl Quadric {
+    fn from_plane(normal: glam::DVec3, d: f64) -> Quadric {
+        let (a, b, c) = (normal.x, normal.y, normal.z);
+        Quadric {
+            a: [
+                a * a,
+                a * b,
+                a * c,
+                a * d,
+                b * b,
+                b * c,
+                b * d,
+                c * c,
+                c * d,
+                d * d,
+            ],
+        }
+    }
+
+    fn add(&mut self, other: &Quadric) {
+        for (x, y) in self.a.iter_mut().zip(other.a.iter()) {
+            *x += y;
+        }
+    }
+
+    fn evaluate(&self, p: glam::DVec3) -> f64 {
+        let a = &self.a;
+        let value = a[0] * p.x * p.x
+            + 2.0 * a[1] * p.x * p.y
+            + 2.0 * a[2] * p.x * p.z
+            + 2.0 * a[3] * p.x
+            + a[4] * p.y * p.y
+            + 2.0 * a[5] * p.y * p.z
+            + 2.0 * a[6] * p.y
+            + a[7] * p.z * p.z
+            + 2.0 * a[8] * p.z
+            + a[9];
+        value.max(0.0)
+    }
+}
+
+/// Simplifies triangles by collapsing vertexes into their neighbors, locked vertexes never
+/// move. Returns the indexes and the largest distance error of the collapses.
+fn simplify(
+    positions: &[glam::Vec3],
+    indexes: &[u32],
+    locked: &HashSet<u32>,
+    target_index_count: usize,
+) -> (Vec<u32>, f32) {
+    let position = |vertex: u32| positions[vertex as usize].as_dvec3();
+    let mut triangles: Vec<Option<[u32; 3]>> = indexes
+        .chunks_exact(3)
+        .map(|x| Some([x[0], x[1], x[2]]))
+        .collect();
+    let mut live_triangle_count = triangles.len();
+    let mut vertex_triangles: HashMap<u32, Vec<usize>> = HashMap::new();
+    let mut quadrics: HashMap<u32, Quadric> = HashMap::new();
+    for (triangle_index, triangle) in triangles.iter().enumerate() {
+        let Some(triangle) = triangle else {
+            continue;
+        };
+        let [p0, p1, p2] = triangle.map(position);
+        let normal = (p1 - p0).cross(p2 - p0).normalize_or_zero();
+        let quadric = Quadric::from_plane(normal, -normal.dot(p0));
+        for vertex in triangle {
+            vertex_triangles
+                .entry(*vertex)
+                .or_default()
+                .push(triangle_index);
+            quadrics.entry(*vertex).or_default().add(&quadric);
+        }
+    }
+    let mut versions: HashMap<u32, u32> = HashMap::new();
+    let mut heap: BinaryHeap<Reverse<(u64, u32, u32, u32, u32)>> = BinaryHeap::new();
+
+    let neighbors = |vertex_triangles: &HashMap<u32, Vec<usize>>,
+                     triangles: &[Option<[u32; 3]>],
+                     vertex: u32|
+     -> HashSet<u32> {
+        vertex_triangles
+            .get(&vertex)
+            .map(|x| x.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|x| triangles[*x])
+            .flatten()
+            .filter(|x| *x != vertex)
+            .collect()
+    };
+    let push_candidates = |heap: &mut BinaryHeap<Reverse<(u64, u32, u32, u32, u32)>>,
+                           quadrics: &HashMap<u32, Quadric>,
+                           versions: &HashMap<u32, u32>,
+                           from: u32,
+                           to: u32| {
+        if locked.contains(&from) {
+            return;
+        }
+        let mut quadric = quadrics[&from];
+        quadric.add(&quadrics[&to]);
+        let cost = quadric.evaluate(position(to));
+        let version = |x: u32| versions.get(&x).copied().unwrap_or(0);
+        heap.push(Reverse((
+            cost.to_bits(),
+            from,
+            to,
+            version(from),
+            version(to),
+        )));
+    };
+
+    let vertexes: Vec<u32> = vertex_triangles.keys().copied().collect();
+    for vertex in vertexes {
+        for neighbor in neighbors(&vertex_triangles, &triangles, vertex) {
+            push_candidates(&mut heap, &quadrics, &versions, vertex, neighbor);
+        }
+    }
+
+    let mut max_error: f64 = 0.0;
+    while live_triangle_count * 3 > target_index_count {
+        let Some(Reverse((cost, from, to, from_version, to_version))) = heap.pop() else {
+            break;
+        };
+        let version = |x: u32| versions.get(&x).copied().unwrap_or(0);
+        if version(from) != from_version || version(to) != to_version {
+            continue;
+        }
+        let Some(from_triangles) = vertex_triangles.get(&from).cloned() else {
+            continue;
+        };
+        let shared_triangles: Vec<usize> = from_triangles
+            .iter()
+            .copied()
+            .filter(|x| triangles[*x].is_some_and(|x| x.contains(&to)))
+            .collect();
+        if shared_triangles.is_empty() {
+            continue;
+        }
+
+        // Only the opposite vertexes of the collapsed triangles may be neighbors of both, or
+        // the collapse makes the surface non-manifold.
+        let opposite_vertexes: HashSet<u32> = shared_triangles
+            .iter()
+            .filter_map(|x| triangles[*x])
+            .flatten()
+            .filter(|x| *x != from && *x != to)
+            .collect();
+        let from_neighbors = neighbors(&vertex_triangles, &triangles, from);
+        let to_neighbors = neighbors(&vertex_triangles, &triangles, to);
+        if from_neighbors
+            .intersection(&to_neighbors)
+            .any(|x| !opposite_vertexes.contains(x))
+        {
+            continue;
+        }
+
+        let is_flipped = from_triangles
+            .iter()
+            .filter(|x| !shared_triangles.contains(x))
+            .filter_map(|x| triangles[*x])
+            .any(|triangle| {
+                let [p0, p1, p2] = triangle.map(position);
+                let old_normal = (p1 - p0).cross(p2 - p0);
+                let [p0, p1, p2] = triangle.map(|x| position(if x == from { to } else { x }));
+                let new_normal = (p1 - p0).cross(p2 - p0);
+                new_normal.length_squared() <= f64::EPSILON
+                    || old_normal.normalize_or_zero().dot(new_normal.normalize()) < 0.2
+            });
+        if is_flipped {
+            continue;
+        }
+
+        for triangle_index in from_triangles {
+            let Some(triangle) = triangles[triangle_index].as_mut() else {
+                continue;
+            };
+            if shared_triangles.contains(&triangle_index) {
+                for vertex in *triangle {
+                    if let Some(x) = vertex_triangles.get_mut(&vertex) {
+                        x.retain(|x| *x != triangle_index);
+                    }
+                }
+                triangles[triangle_index] = None;
+                live_triangle_count -= 1;
+            } else {
+                triangle
+                    .iter_mut()
+                    .filter(|x| **x == from)
+                    .for_each(|x| *x = to);
+                vertex_triangles.entry(to).or_default().push(triangle_index);
+            }
+        }
+        vertex_triangles.remove(&from);
+        let from_quadric = quadrics[&from];
+        quadrics.get_mut(&to).unwrap().add(&from_quadric);
+        *versions.entry(from).or_default() += 1;
+        *versions.entry(to).or_default() += 1;
+        max_error = max_error.max(f64::from_bits(cost).sqrt());
+
+        for neighbor in neighbors(&vertex_triangles, &triangles, to) {
+            push_candidates(&mut heap, &quadrics, &versions, neighbor, to);
+            push_candidates(&mut heap, &quadrics, &versions, to, neighbor);
+        }
+    }
+
+    let indexes = triangles.iter().flatten().flatten().copied().collect();
+    (indexes, max_error as f32)
+}
+
+fn make_cluster(
+    cluster_lod: &mut ClusterLod,
+    positions: &[glam::Vec3],
+    mut indexes: Vec<u32>,
+    level: u32,
+    error: f32,
+    error_bounds: BoundingSphere,
+) -> usize {
+    meshopt::optimize_vertex_cache_in_place(&mut indexes, positions.len());
+    let bounds = BoundingSphere::from_points(indexes.iter().map(|x| positions[*x as usize]));
+    cluster_lod.clusters.push(MeshCluster {
+        index_offset: cluster_lod.indexes.len() as u32,
+        index_count: indexes.len() as u32,
+        level,
+        bounds,
+        error,
+        error_bounds: if level == 0 { bounds } else { error_bounds },
+        parent_error: f32::MAX,
+        parent_error_bounds: BoundingSphere::default(),
+    });
+    cluster_lod.indexes.append(&mut indexes);
+    cluster_lod.clusters.len() - 1
+}
+
+fn get_cluster_indexes<'a>(cluster_lod: &'a ClusterLod, cluster: usize) -> &'a [u32] {
+    let cluster = &cluster_lod.clusters[cluster];
+    let start = cluster.index_offset as usize;
+    &cluster_lod.indexes[start..start + cluster.index_count as usize]
+}
+
+/// Splits the mesh into clusters, then repeatedly groups neighboring clusters, simplifies
+/// every group to half of its triangles with its border locked and splits it into clusters
+/// again, until the mesh can not be reduced further.
+pub fn build_cluster_lod(
+    positions: &[glam::Vec3],
+    indexes: &[u32],
+    settings: &ClusterLodSettings,
+) -> anyhow::Result<ClusterLod> {
+    let mut cluster_lod = ClusterLod::default();
+    if indexes.is_empty() || indexes.len() % 3 != 0 {
+        return Ok(cluster_lod);
+    }
+    let welded = weld_vertexes(positions);
+    let mut level_clusters: Vec<usize> = vec![];
+    for cluster_indexes in
+        partition_triangles(indexes, &welded, settings.max_cluster_triangle_count)?
+    {
+        level_clusters.push(make_cluster(
+            &mut cluster_lod,
+            positions,
+            cluster_indexes,
+            0,
+            0.0,
+            BoundingSphere::default(),
+        ));
+    }
+
+    for level in 1..settings.max_level_count as u32 {
+        if level_clusters.len() <= 1 {
+            break;
+        }
+        let cluster_indexes: Vec<&[u32]> = level_clusters
+            .iter()
+            .map(|x| get_cluster_indexes(&cluster_lod, *x))
+            .collect();
+        let groups = group_clusters(&cluster_indexes, &welded, settings.group_cluster_count)?;
+        let mut next_level_clusters: Vec<usize> = vec![];
+        for group in groups {
+            let group_clusters: Vec<usize> = group.iter().map(|x| level_clusters[*x]).collect();
+            let group_indexes: Vec<u32> = group_clusters
+                .iter()
+                .flat_map(|x| get_cluster_indexes(&cluster_lod, *x).to_vec())
+                .collect();
+
+            // Edges used by one triangle of the group are on the border of the group, of the
+            // mesh or of a seam, moving them would open cracks.
+            let mut edge_counts: HashMap<(u32, u32), u32> = HashMap::new();
+            for triangle in group_indexes.chunks_exact(3) {
+                for (v0, v1) in triangle_edges(triangle) {
+                    *edge_counts.entry((v0.min(v1), v0.max(v1))).or_default() += 1;
+                }
+            }
+            let locked: HashSet<u32> = edge_counts
+                .iter()
+                .filter(|(_, count)| **count == 1)
+                .flat_map(|(edge, _)| [edge.0, edge.1])
+                .collect();
+
+            let target_index_count = group_indexes.len() / 6 * 3;
+            let (simplified_indexes, simplify_error) =
+                simplify(positions, &group_indexes, &locked, target_index_count);
+            if simplified_indexes.is_empty()
+                || simplified_indexes.len() as f32
+                    > group_indexes.len() as f32 * (1.0 - settings.min_reduction)
+            {
+                continue;
+            }
+
+            let children = group_clusters
+                .iter()
+                .map(|x| &cluster_lod.clusters[*x])
+                .collect::<Vec<&MeshCluster>>();
+            let error = children
+                .iter()
+                .map(|x| x.error)
+                .fold(simplify_error, f32::max);
+            let error_bounds = BoundingSphere::merge(
+                &children
+                    .iter()
+                    .map(|x| x.error_bounds)
+                    .collect::<Vec<BoundingSphere>>(),
+            );
+            for child in &group_clusters {
+                let child = &mut cluster_lod.clusters[*child];
+                child.parent_error = error;
+                child.parent_error_bounds = error_bounds;
+            }
+            for cluster_indexes in partition_triangles(
+                &simplified_indexes,
+                &welded,
+                settings.max_cluster_triangle_count,
+            )? {
+                next_level_clusters.push(make_cluster(
+                    &mut cluster_lod,
+                    positions,
+                    cluster_indexes,
+                    level,
+                    error,
+                    error_bounds,
+                ));
+            }
+        }
+        if next_level_clusters.is_empty() {
+            break;
+        }
+        level_clusters = next_level_clusters;
+    }
+    Ok(cluster_lod)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{build_cluster_lod, simplify, ClusterLodSettings};
+    use std::collections::HashSet;
+
+    fn make_grid(size: u32) -> (Vec<glam::Vec3>, Vec<u32>) {
+        let mut positions = vec![];
+        for y in 0..=size {
+            for x in 0..=size {
+                positions.push(glam::vec3(x as f32, y as f32, 0.0));
+            }
+        }
+        let mut indexes = vec![];
+        for y in 0..size {
+            for x in 0..size {
+                let i = y * (size + 1) + x;
+                indexes.extend([i, i + 1, i + size + 1]);
+                indexes.extend([i + 1, i + size + 2, i + size + 1]);
+            }
+        }
+        (positions, indexes)
+    }
+
+    #[test]
+    fn test_case_simplify_keeps_locked_vertexes() {
+        let (positions, indexes) = make_grid(8);
+        let locked: HashSet<u32> = (0..positions.len() as u32)
+            .filter(|x| {
+                let p = positions[*x as usize];
+                p.x == 0.0 || p.y == 0.0 || p.x == 8.0 || p.y == 8.0
+            })
+            .collect();
+        let (simplified, error) = simplify(&positions, &indexes, &locked, indexes.len() / 2);
+        assert!(simplified.len() < indexes.len());
+        assert!(error < 1e-3);
+        let referenced: HashSet<u32> = simplified.iter().copied().collect();
+        assert!(locked.is_subset(&referenced));
+    }
+
+    #[test]
+    fn test_case_build_cluster_lod() {
+        let (positions, indexes) = make_grid(32);
+        let settings = ClusterLodSettings::default();
+        let cluster_lod = build_cluster_lod(&positions, &indexes, &settings).unwrap();
+        assert!(cluster_lod.get_max_level() > 0);
+
+        let level_0_index_count: u32 = cluster_lod
+            .clusters
+            .iter()
+            .filter(|x| x.level == 0)
+            .map(|x| x.index_count)
+            .sum();
+        assert_eq!(level_0_index_count as usize, indexes.len());
+        for cluster in &cluster_lod.clusters {
+            assert!(cluster.parent_error >= cluster.error);
+        }
+        let coarsest = cluster_lod.select_clusters(|_, _| true);
+        let coarsest_index_count: u32 = coarsest
+            .iter()
+            .map(|x| cluster_lod.clusters[*x].index_count)
+            .sum();
+        assert!((coarsest_index_count as usize) < indexes.len());
+    }
+}
@@ -2,6 +2,7 @@
 pub enum EFileDialogType {
     NewProject(String),
     OpenProject,
+    ExportPatch,
 }
 
 #[derive(Debug, Clone)]
@@ -5,7 +5,7 @@ use crate::{
     data_source::{AssetFile, AssetFolder, DataSource},
     editor_ui::{EditorUI, GizmoEvent},
     material_resolve,
-    model_loader::ModelLoader,
+    model_loader::{LoadResult, ModelLoader},
     project::Project,
     project_context::{EFolderUpdateType, ProjectContext},
     ui::{
@@ -31,7 +31,9 @@ use anyhow::{anyhow, Context};
 use lazy_static::lazy_static;
 use rs_artifact::{material::MaterialInfo, sound::ESoundFileType};
 use rs_core_minimal::{
-    file_manager, name_generator::make_unique_name, path_ext::CanonicalizeSlashExt,
+    file_manager,
+    name_generator::{make_unique_name, NameGenerator},
+    path_ext::CanonicalizeSlashExt,
 };
 #[cfg(any(feature = "plugin_shared_crate"))]
 use rs_engine::plugin::plugin_crate::Plugin;
@@ -44,8 +46,9 @@ use rs_engine::{
         spot_light_component::SpotLightComponent,
     },
     content::{
-        blend_animations::BlendAnimations, content_file_type::EContentFileType,
-        texture::TextureFile,
+        blend_animations::BlendAnimations,
+        content_file_type::EContentFileType,
+        texture::{ETextureColorSpace, TextureFile},
     },
     directional_light::DirectionalLight,
     frame_sync::{EOptions, FrameSync},
@@ -96,6 +99,7 @@ lazy_static! {
         let mut m = HashSet::new();
         m.insert(EFileType::Fbx);
         m.insert(EFileType::Glb);
+        m.insert(EFileType::Gltf);
         m.insert(EFileType::Blend);
         m.insert(EFileType::Dae);
         m
@@ -513,7 +517,7 @@ impl EditorContext {
                         match self.try_create_plugin() {
                             Ok(plugin) => {
                                 if let Some(window) = &mut self.standalone_ui_window {
-                                    window.reload_plugins(vec![plugin]);
+                                    window.reload_plugins(&mut self.engine, vec![plugin]);
                                 }
                             }
                             Err(err) => log::warn!("{}", err),
@@ -527,7 +531,7 @@ impl EditorContext {
                 if let Some(project_context) = &mut self.project_context {
                     if let Some(folder_update_type) = project_context.check_folder_notification() {
                         match folder_update_type {
-                            EFolderUpdateType::Asset => {
+                            EFolderUpdateType::Asset(changed_paths) => {
                                 let asset_folder = Self::build_asset_folder(
                                     &project_context.get_asset_folder_path(),
                                 );
@@ -535,6 +539,7 @@ impl EditorContext {
                                 self.data_source.asset_folder = Some(asset_folder.clone());
                                 self.data_source.current_asset_folder = Some(asset_folder);
                                 self.post_build_asset_folder();
+                                self.reimport_changed_asset_files(&changed_paths);
                             }
                         }
                     }
@@ -768,19 +773,53 @@ impl EditorContext {
 
     fn try_create_plugin(&mut self) -> anyhow::Result<Box<dyn Plugin>> {
         #[cfg(feature = "plugin_shared_crate")]
-        if let Some(project_context) = self.project_context.as_mut() {
-            project_context.reload()?;
-            let lib = project_context.hot_reload.get_library_reload();
-            let lib = lib.lock().unwrap();
-            let func = lib.load_symbol::<rs_engine::plugin::signature::CreatePlugin>(
-                rs_engine::plugin::symbol_name::CREATE_PLUGIN,
-            )?;
-            let plugin = func();
+        if self.project_context.is_some() {
+            self.unload_custom_components();
+            let mut plugin = {
+                let project_context = self.project_context.as_mut().expect("Not null");
+                project_context.reload()?;
+                let lib = project_context.hot_reload.get_library_reload();
+                let lib = lib.lock().unwrap();
+                let func = lib.load_symbol::<rs_engine::plugin::signature::CreatePlugin>(
+                    rs_engine::plugin::symbol_name::CREATE_PLUGIN,
+                )?;
+                func()
+            };
+            plugin.register_components(self.engine.get_component_registry_mut());
+            self.load_custom_components();
             return Ok(plugin);
         }
         return Err(anyhow!("Can not create plugin"));
     }
 
+    #[cfg(feature = "plugin_shared_crate")]
+    fn unload_custom_components(&mut self) {
+        if let Some(level) = &self.data_source.level {
+            level.borrow().unload_custom_components();
+        }
+        if let Some(window) = &self.standalone_ui_window {
+            window.unload_custom_components();
+        }
+        self.engine.get_component_registry_mut().clear();
+    }
+
+    #[cfg(feature = "plugin_shared_crate")]
+    fn load_custom_components(&mut self) {
+        let Some(level) = self.data_source.level.clone() else {
+            return;
+        };
+        let files = if let Some(folder) = &self.data_source.content_data_source.current_folder {
+            folder.borrow().files.clone()
+        } else {
+            vec![]
+        };
+        level.borrow_mut().load_custom_components(
+            &mut self.engine,
+            &files,
+            &mut self.player_viewport,
+        );
+    }
+
     fn try_load_dotnet_plugin(&mut self) -> anyhow::Result<()> {
         #[cfg(feature = "plugin_dotnet")]
         if let Some(project_context) = self.project_context.as_mut() {
@@ -1207,7 +1246,11 @@ impl EditorContext {
                         continue;
                     };
                     let abs_path = project_folder_path.join(image_reference);
-                    let _ = engine.create_texture_from_path(&abs_path, &texture_file.url);
+                    let _ = engine.create_texture_from_path(
+                        &abs_path,
+                        &texture_file.url,
+                        &texture_file.import_settings,
+                    );
 
                     {
                         let url = texture_file.url.clone();
@@ -1312,10 +1355,34 @@ impl EditorContext {
                         material_paramenters_collection.borrow_mut();
                     material_paramenters_collection.initialize(engine);
                 }
+                EContentFileType::Prefab(_) => {}
             }
         }
     }
 
+    fn add_prefab_instance(
+        &mut self,
+        prefab: SingleThreadMutType<rs_engine::content::prefab::Prefab>,
+    ) {
+        let Some(active_level) = self.data_source.level.clone() else {
+            return;
+        };
+        let Some(project_context) = self.project_context.as_ref() else {
+            return;
+        };
+        let content = project_context.project.content.clone();
+        let content = content.borrow();
+        let result = active_level.borrow_mut().add_prefab_instance(
+            prefab,
+            &mut self.engine,
+            &content.files,
+            &mut self.player_viewport,
+        );
+        if let Err(err) = result {
+            log::warn!("{}", err);
+        }
+    }
+
     fn add_new_actors(
         level: &mut rs_engine::content::level::Level,
         engine: &mut rs_engine::engine::Engine,
@@ -1348,6 +1415,10 @@ impl EditorContext {
 
         self.data_source.content_data_source.current_folder =
             Some(project_context.project.content.clone());
+        self.model_loader.set_import_settings(
+            project_context.project.model_import_settings.clone(),
+            &asset_folder_path,
+        );
         Self::content_load_resources(
             &mut self.engine,
             &mut self.model_loader,
@@ -1477,6 +1548,20 @@ impl EditorContext {
                     let result = self.open_project(&file_path, window);
                     log::trace!("{:?}", result);
                 }
+                EFileDialogType::ExportPatch => {
+                    let Some(project_context) = self.project_context.as_mut() else {
+                        return;
+                    };
+                    let dialog = rfd::FileDialog::new()
+                        .set_directory(project_context.get_build_dir())
+                        .add_filter("Artifact", &["rs"]);
+                    let Some(file_path) = dialog.pick_file() else {
+                        return;
+                    };
+                    log::trace!("Selected base artifact: {:?}", file_path);
+                    let result = project_context.export_patch(&mut self.model_loader, &file_path);
+                    log::trace!("{:?}", result);
+                }
             },
         }
     }
@@ -1542,7 +1627,7 @@ impl EditorContext {
         let load_result = self.model_loader.load_from_file_as_actor(
             &file_path,
             asset_reference.to_string(),
-            exist_names,
+            exist_names.clone(),
             actor_names,
         )?;
 
@@ -1564,6 +1649,22 @@ impl EditorContext {
         if let Some(skeleton) = &load_result.skeleton {
             add_files.push(EContentFileType::Skeleton(skeleton.clone()));
         }
+        let exist_content_names = exist_names
+            .into_iter()
+            .chain(add_files.iter().map(|x| x.get_name()))
+            .collect();
+        let mut material_files =
+            Self::create_imported_materials(project_context, &load_result, exist_content_names)?;
+        for file in &material_files {
+            if let EContentFileType::Material(material) = file {
+                self.editor_ui
+                    .object_property_view
+                    .materials
+                    .borrow_mut()
+                    .push(material.borrow().url.clone());
+            }
+        }
+        add_files.append(&mut material_files);
         let content = project_context.project.content.clone();
         let mut content = content.borrow_mut();
         Self::content_load_resources(
@@ -1603,6 +1704,479 @@ impl EditorContext {
         Ok(())
     }
 
+    /// Creates textures and materials from the materials of the imported model, and assigns the
+    /// materials to the mesh components of the actor.
+    fn create_imported_materials(
+        project_context: &mut ProjectContext,
+        load_result: &LoadResult,
+        exist_content_names: Vec<String>,
+    ) -> anyhow::Result<Vec<EContentFileType>> {
+        let mut name_generator = NameGenerator::new(exist_content_names);
+        let asset_folder_path = project_context.get_asset_folder_path();
+        let mut used_materials: Vec<usize> = load_result
+            .mesh_materials
+            .values()
+            .copied()
+            .collect::<HashSet<usize>>()
+            .into_iter()
+            .collect();
+        used_materials.sort();
+        let mut files: Vec<EContentFileType> = vec![];
+
+        let mut texture_urls: HashMap<PathBuf, url::Url> = HashMap::new();
+        for material in used_materials.iter().map(|x| &load_result.materials[*x]) {
+            for texture in material.get_textures() {
+                if texture_urls.contains_key(texture) {
+                    continue;
+                }
+                let name = texture
+                    .file_stem()
+                    .map(|x| x.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                let url = build_content_file_url(name_generator.next(&name))?;
+                let mut texture_file = TextureFile::new(url.clone());
+                texture_file.set_image_reference_path(
+                    texture
+                        .strip_prefix(&asset_folder_path)
+                        .unwrap_or(texture.as_path()),
+                );
+                if material.normal_texture.as_ref() == Some(texture) {
+                    texture_file.import_settings.is_normal_map = true;
+                } else if material.is_linear_texture(texture) {
+                    texture_file.import_settings.color_space = ETextureColorSpace::Linear;
+                }
+                texture_urls.insert(texture.clone(), url);
+                files.push(EContentFileType::Texture(SingleThreadMut::new(
+                    texture_file,
+                )));
+            }
+        }
+
+        let mut material_urls: HashMap<usize, url::Url> = HashMap::new();
+        for index in used_materials {
+            let material = &load_result.materials[index];
+            let name = name_generator.next(&material.name);
+            let material_content = rs_engine::content::material::Material::new(
+                build_content_file_url(&name)?,
+                build_asset_url(format!("material/{}", &name))?,
+            );
+            let material_editor = crate::material::Material::new(
+                material_content.asset_url.clone(),
+                material.to_snarl(&texture_urls),
+            );
+            project_context
+                .project
+                .materials
+                .push(SingleThreadMut::new(material_editor));
+            material_urls.insert(index, material_content.url.clone());
+            files.push(EContentFileType::Material(SingleThreadMut::new(
+                material_content,
+            )));
+        }
+
+        let mesh_material_urls: HashMap<url::Url, url::Url> = load_result
+            .mesh_materials
+            .iter()
+            .filter_map(|(mesh_url, index)| {
+                Some((mesh_url.clone(), material_urls.get(index)?.clone()))
+            })
+            .collect();
+        Self::assign_imported_materials(
+            &load_result.actor.borrow().scene_node,
+            &mesh_material_urls,
+        );
+        Ok(files)
+    }
+
+    fn assign_imported_materials(
+        scene_node: &SingleThreadMutType<SceneNode>,
+        mesh_material_urls: &HashMap<url::Url, url::Url>,
+    ) {
+        let scene_node = scene_node.borrow();
+        match &scene_node.component {
+            rs_engine::scene_node::EComponentType::StaticMeshComponent(component) => {
+                let mut component = component.borrow_mut();
+                if let Some(material_url) = component
+                    .static_mesh
+                    .as_ref()
+                    .and_then(|x| mesh_material_urls.get(x))
+                {
+                    component.material_url = Some(material_url.clone());
+                }
+            }
+            rs_engine::scene_node::EComponentType::SkeletonMeshComponent(component) => {
+                let mut component = component.borrow_mut();
+                // The skeleton meshes of the component share one material.
+                if let Some(material_url) = component
+                    .skeleton_mesh_urls
+                    .iter()
+                    .find_map(|x| mesh_material_urls.get(x))
+                {
+                    component.material_url = Some(material_url.clone());
+                }
+            }
+            _ => {}
+        }
+        for child in &scene_node.childs {
+            Self::assign_imported_materials(child, mesh_material_urls);
+        }
+    }
+
+    fn create_virtual_texture_cache(
+        engine: &rs_engine::engine::Engine,
+        project_context: &ProjectContext,
+        texture_file: &Rc<RefCell<TextureFile>>,
+    ) -> anyhow::Result<()> {
+        let virtual_texture_cache_dir = project_context.try_create_virtual_texture_cache_dir()?;
+        let project_folder_path = &project_context.get_project_folder_path();
+
+        let virtual_cache_name = texture_file
+            .borrow()
+            .get_pref_virtual_cache_name(project_folder_path)?;
+        let virtual_texture_setting = engine
+            .get_settings()
+            .render_setting
+            .virtual_texture_setting
+            .clone();
+        texture_file.borrow_mut().create_virtual_texture_cache(
+            project_folder_path,
+            &virtual_texture_cache_dir.join(virtual_cache_name.clone()),
+            Some(rs_artifact::EEndianType::Little),
+            rs_artifact::virtual_texture::image::TileSettings {
+                tile_size: virtual_texture_setting.tile_size,
+                border_size: virtual_texture_setting.tile_border_size,
+                compression_type: rs_artifact::compression::ECompressionType::Zstd(3),
+            },
+        )?;
+        log::trace!("virtual_cache_name: {}", virtual_cache_name);
+        texture_file.borrow_mut().virtual_image_reference = Some(virtual_cache_name);
+        Ok(())
+    }
+
+    /// Imports the content files again whose source files in the asset folder are modified.
+    fn reimport_changed_asset_files(&mut self, changed_paths: &[PathBuf]) {
+        let Some(project_context) = self.project_context.as_ref() else {
+            return;
+        };
+        let project_folder_path = project_context.get_project_folder_path();
+        let asset_folder_path = project_context.get_asset_folder_path();
+        let files = project_context.project.content.borrow().files.clone();
+
+        let mut model_file_paths: Vec<PathBuf> = vec![];
+        let mut reload_files: Vec<EContentFileType> = vec![];
+        for changed_path in changed_paths.iter().filter(|x| x.is_file()) {
+            self.editor_ui.invalidate_thumbnail(changed_path);
+            for file in files.iter() {
+                let source_path = match file {
+                    EContentFileType::StaticMesh(_)
+                    | EContentFileType::SkeletonMesh(_)
+                    | EContentFileType::SkeletonAnimation(_)
+                    | EContentFileType::Skeleton(_) => {
+                        if Self::get_model_file_path(project_context, file).as_ref()
+                            == Some(changed_path)
+                            && !model_file_paths.contains(changed_path)
+                        {
+                            model_file_paths.push(changed_path.clone());
+                        }
+                        continue;
+                    }
+                    EContentFileType::Texture(texture_file) => texture_file
+                        .borrow()
+                        .get_image_reference_path()
+                        .map(|x| project_folder_path.join(x)),
+                    EContentFileType::Sound(sound) => {
+                        Some(asset_folder_path.join(&sound.borrow().asset_info.relative_path))
+                    }
+                    _ => None,
+                };
+                if source_path.as_ref() == Some(changed_path) {
+                    reload_files.push(file.clone());
+                }
+            }
+        }
+        if model_file_paths.is_empty() && reload_files.is_empty() {
+            return;
+        }
+
+        for file in reload_files.iter() {
+            let EContentFileType::Texture(texture_file) = file else {
+                continue;
+            };
+            let Some(old_virtual_image_reference) =
+                texture_file.borrow().virtual_image_reference.clone()
+            else {
+                continue;
+            };
+            let _ = std::fs::remove_file(
+                project_context
+                    .get_virtual_texture_cache_dir()
+                    .join(old_virtual_image_reference),
+            );
+            texture_file.borrow_mut().virtual_image_reference = None;
+            if let Err(err) =
+                Self::create_virtual_texture_cache(&self.engine, project_context, texture_file)
+            {
+                log::warn!("{}", err);
+            }
+        }
+        let mut changed_urls: HashSet<url::Url> =
+            reload_files.iter().map(|x| x.get_url()).collect();
+        changed_urls.extend(Self::collect_materials_referencing(&files, &changed_urls));
+        Self::content_load_resources(
+            &mut self.engine,
+            &mut self.model_loader,
+            project_context,
+            reload_files,
+        );
+
+        for model_file_path in model_file_paths {
+            log::trace!("Reimport model file: {:?}", model_file_path);
+            if let Err(err) = self.reimport_model_file(model_file_path) {
+                log::warn!("{}", err);
+            }
+        }
+
+        let Some(project_context) = self.project_context.as_ref() else {
+            return;
+        };
+        let files = project_context.project.content.borrow().files.clone();
+        self.engine
+            .on_content_files_changed(project_context.project.content.borrow().files_to_map(true));
+        if let Some(active_level) = self.data_source.level.clone() {
+            active_level.borrow_mut().reinitialize_actors_referencing(
+                &changed_urls,
+                &mut self.engine,
+                &files,
+                &mut self.player_viewport,
+            );
+        }
+    }
+
+    /// The materials which bind any of the textures, the actors using them are initialized
+    /// again to bind the reloaded textures.
+    fn collect_materials_referencing(
+        files: &[EContentFileType],
+        texture_urls: &HashSet<url::Url>,
+    ) -> Vec<url::Url> {
+        files
+            .iter()
+            .filter_map(|file| {
+                let EContentFileType::Material(material) = file else {
+                    return None;
+                };
+                let material = material.borrow();
+                material.get_pipeline_handle()?;
+                let is_referencing = material.get_material_info().values().any(|x| {
+                    x.map_textures
+                        .iter()
+                        .any(|x| texture_urls.contains(&x.texture_url))
+                        || x.virtual_textures.iter().any(|x| texture_urls.contains(x))
+                });
+                is_referencing.then(|| material.url.clone())
+            })
+            .collect()
+    }
+
+    /// The model file which the content file is imported from.
+    fn get_model_file_path(
+        project_context: &ProjectContext,
+        file: &EContentFileType,
+    ) -> Option<PathBuf> {
+        let project_folder_path = project_context.get_project_folder_path();
+        match file {
+            EContentFileType::StaticMesh(static_mesh) => Some(
+                project_context
+                    .get_asset_folder_path()
+                    .join(&static_mesh.borrow().asset_info.relative_path),
+            ),
+            EContentFileType::SkeletonMesh(skeleton_mesh) => {
+                Some(project_folder_path.join(skeleton_mesh.borrow().get_relative_path()))
+            }
+            EContentFileType::SkeletonAnimation(skeleton_animation) => {
+                Some(project_folder_path.join(skeleton_animation.borrow().get_relative_path()))
+            }
+            EContentFileType::Skeleton(skeleton) => {
+                Some(project_folder_path.join(skeleton.borrow().get_relative_path()))
+            }
+            _ => None,
+        }
+    }
+
+    /// The url of the imported asset, it is the same after importing the model file again.
+    fn get_model_asset_url(file: &EContentFileType) -> Option<url::Url> {
+        match file {
+            EContentFileType::StaticMesh(static_mesh) => {
+                Some(static_mesh.borrow().asset_info.get_url())
+            }
+            EContentFileType::SkeletonMesh(skeleton_mesh) => {
+                Some(skeleton_mesh.borrow().asset_url.clone())
+            }
+            EContentFileType::SkeletonAnimation(skeleton_animation) => {
+                Some(skeleton_animation.borrow().asset_url.clone())
+            }
+            EContentFileType::Skeleton(skeleton) => Some(skeleton.borrow().asset_url.clone()),
+            _ => None,
+        }
+    }
+
+    /// Imports the model file again with its import settings, the content files of the assets
+    /// which are still in the model file are updated in place and the others are removed.
+    fn reimport_model_file(&mut self, file_path: PathBuf) -> anyhow::Result<()> {
+        let exist_names = self.get_all_content_names();
+        let project_context = self
+            .project_context
+            .as_mut()
+            .ok_or(anyhow!("Project context is null"))?;
+        let asset_folder_path = project_context.get_asset_folder_path();
+        let asset_reference = file_path
+            .strip_prefix(&asset_folder_path)?
+            .to_str()
+            .ok_or(anyhow!("Incorrect path: {:?}", file_path))?
+            .to_string();
+
+        self.model_loader.set_import_settings(
+            project_context.project.model_import_settings.clone(),
+            &asset_folder_path,
+        );
+        self.model_loader.unload(&file_path);
+        let load_result = self.model_loader.load_from_file_as_actor(
+            &file_path,
+            asset_reference,
+            exist_names,
+            vec![],
+        )?;
+
+        let content = project_context.project.content.clone();
+        let mut content = content.borrow_mut();
+        let mut exist_files: HashMap<url::Url, EContentFileType> = HashMap::new();
+        for file in content.files.iter() {
+            if Self::get_model_file_path(project_context, file).as_ref() != Some(&file_path) {
+                continue;
+            }
+            if let Some(asset_url) = Self::get_model_asset_url(file) {
+                exist_files.insert(asset_url, file.clone());
+            }
+        }
+
+        let mut new_files: Vec<EContentFileType> = vec![];
+        new_files.extend(
+            load_result
+                .static_meshes
+                .iter()
+                .map(|x| EContentFileType::StaticMesh(x.clone())),
+        );
+        new_files.extend(
+            load_result
+                .skeleton_meshes
+                .iter()
+                .map(|x| EContentFileType::SkeletonMesh(x.clone())),
+        );
+        new_files.extend(
+            load_result
+                .node_animations
+                .iter()
+                .map(|x| EContentFileType::SkeletonAnimation(x.clone())),
+        );
+        new_files.extend(load_result.skeleton.clone().map(EContentFileType::Skeleton));
+
+        let mut reload_files: Vec<EContentFileType> = vec![];
+        let mut add_files: Vec<EContentFileType> = vec![];
+        for new_file in new_files {
+            let Some(asset_url) = Self::get_model_asset_url(&new_file) else {
+                continue;
+            };
+            match exist_files.remove(&asset_url) {
+                Some(exist_file) => reload_files.push(exist_file),
+                None => add_files.push(new_file),
+            }
+        }
+        // The assets of the previous import which are gone, for example after meshes are merged.
+        let removed_files: Vec<EContentFileType> = exist_files.into_values().collect();
+        for file in removed_files.iter() {
+            log::warn!(
+                "{} is no longer in {:?}, it is removed",
+                file.get_name(),
+                file_path
+            );
+        }
+
+        let skeleton_url = reload_files
+            .iter()
+            .chain(add_files.iter())
+            .find_map(|x| match x {
+                EContentFileType::Skeleton(skeleton) => Some(skeleton.borrow().url.clone()),
+                _ => None,
+            });
+        let resource_manager = ResourceManager::default();
+        for file in reload_files
+            .iter()
+            .chain(add_files.iter())
+            .chain(removed_files.iter())
+        {
+            match file {
+                EContentFileType::StaticMesh(static_mesh) => {
+                    let static_mesh = static_mesh.borrow();
+                    resource_manager.remove_static_mesh(&static_mesh.url);
+                    resource_manager.remove_static_mesh(&static_mesh.asset_info.get_url());
+                }
+                EContentFileType::SkeletonMesh(skeleton_mesh) => {
+                    let mut skeleton_mesh = skeleton_mesh.borrow_mut();
+                    if let Some(skeleton_url) = &skeleton_url {
+                        skeleton_mesh.skeleton_url = skeleton_url.clone();
+                    }
+                    resource_manager.remove_skin_mesh(&skeleton_mesh.asset_url);
+                }
+                EContentFileType::SkeletonAnimation(skeleton_animation) => {
+                    resource_manager
+                        .remove_skeleton_animation(&skeleton_animation.borrow().asset_url);
+                }
+                EContentFileType::Skeleton(skeleton) => {
+                    resource_manager.remove_skeleton(&skeleton.borrow().asset_url);
+                }
+                _ => {}
+            }
+        }
+        for file in add_files.iter() {
+            if let EContentFileType::SkeletonAnimation(node_animation) = file {
+                self.editor_ui
+                    .object_property_view
+                    .animations
+                    .borrow_mut()
+                    .push(node_animation.borrow().url.clone());
+            }
+        }
+        let removed_urls: HashSet<url::Url> = removed_files.iter().map(|x| x.get_url()).collect();
+        self.editor_ui
+            .object_property_view
+            .animations
+            .borrow_mut()
+            .retain(|x| !removed_urls.contains(x));
+        content
+            .files
+            .retain(|x| !removed_urls.contains(&x.get_url()));
+
+        let mut changed_urls: HashSet<url::Url> =
+            reload_files.iter().map(|x| x.get_url()).collect();
+        changed_urls.extend(removed_urls);
+        Self::content_load_resources(
+            &mut self.engine,
+            &mut self.model_loader,
+            project_context,
+            reload_files.into_iter().chain(add_files.clone()).collect(),
+        );
+        content.files.append(&mut add_files);
+
+        if let Some(active_level) = self.data_source.level.clone() {
+            active_level.borrow_mut().reinitialize_actors_referencing(
+                &changed_urls,
+                &mut self.engine,
+                &content.files,
+                &mut self.player_viewport,
+            );
+        }
+        Ok(())
+    }
+
     #[cfg(feature = "plugin_v8")]
     fn process_v8_plugin_tick(&mut self) -> anyhow::Result<()> {
         if let (Some(v8_plugin), Some(level)) =
@@ -1636,34 +2210,43 @@ impl EditorContext {
         if let Some(active_level) = self.data_source.level.clone() {
             let mut active_level = active_level.borrow_mut();
             active_level.set_physics_simulate(self.data_source.is_simulate_real_time);
+            if let Some(folder) = &self.data_source.content_data_source.current_folder {
+                active_level.update_streaming(
+                    &mut self.engine,
+                    &folder.borrow().files,
+                    &mut self.player_viewport,
+                );
+            }
             active_level.tick(
                 self.engine.get_game_time(),
                 &mut self.engine,
                 &mut self.player_viewport,
             );
 
-            let mut draw_objects = active_level.collect_draw_objects();
-
             for camera_componenet in active_level.collect_camera_componenets() {
                 let camera_componenet = camera_componenet.borrow();
                 if let Some(player_viewport) = camera_componenet.get_player_viewport() {
                     let mut player_viewport = player_viewport.borrow_mut();
                     player_viewport.update_global_constants(&mut self.engine);
-                    for draw_object in draw_objects.iter_mut() {
-                        player_viewport.update_draw_object(&mut self.engine, draw_object);
-                        draw_object.switch_player_viewport(&player_viewport);
+                    let mut draw_objects =
+                        active_level.collect_visible_draw_objects(&player_viewport);
+                    for x in draw_objects.iter_mut() {
+                        player_viewport.update_draw_object(&mut self.engine, &mut x.draw_object);
+                        x.draw_object.switch_player_viewport(&player_viewport);
                     }
-                    player_viewport.append_to_draw_list(&draw_objects);
+                    player_viewport.append_visible_to_draw_list(&draw_objects);
                     self.engine.present_player_viewport(&mut player_viewport);
                 }
             }
 
-            for draw_object in draw_objects.iter_mut() {
+            let mut draw_objects = active_level.collect_visible_draw_objects(&self.player_viewport);
+            for x in draw_objects.iter_mut() {
                 self.player_viewport
-                    .update_draw_object(&mut self.engine, draw_object);
-                draw_object.switch_player_viewport(&self.player_viewport);
+                    .update_draw_object(&mut self.engine, &mut x.draw_object);
+                x.draw_object.switch_player_viewport(&self.player_viewport);
             }
-            self.player_viewport.append_to_draw_list(&draw_objects);
+            self.player_viewport
+                .append_visible_to_draw_list(&draw_objects);
 
             if let Some(physics) = active_level.get_physics_mut() {
                 self.player_viewport.physics_debug(
@@ -2099,6 +2682,15 @@ impl EditorContext {
                     log::trace!("{:?}", result);
                 }
             }
+            top_menu::EClickEventType::ExportPatch => {
+                if self.project_context.is_some() {
+                    let _ = self
+                        .event_loop_proxy
+                        .send_event(ECustomEventType::OpenFileDialog(
+                            EFileDialogType::ExportPatch,
+                        ));
+                }
+            }
             top_menu::EClickEventType::OpenVisualStudioCode => {
                 if let Some(project_context) = &self.project_context {
                     let path = project_context.get_project_folder_path();
@@ -2471,6 +3063,77 @@ impl EditorContext {
                     &mut self.player_viewport,
                 );
             }
+            crate::ui::level_view::EClickEventType::CreatePrefab(actor) => {
+                let names = self.get_all_content_names();
+                let name = make_unique_name(names, &actor.borrow().name);
+                let Some(project_context) = &mut self.project_context else {
+                    return;
+                };
+                let Ok(content_url) = build_content_file_url(&name) else {
+                    return;
+                };
+                let prefab = match rs_engine::content::prefab::Prefab::new(
+                    content_url,
+                    &actor.borrow().scene_node.borrow(),
+                ) {
+                    Ok(prefab) => SingleThreadMut::new(prefab),
+                    Err(err) => {
+                        log::warn!("{}", err);
+                        return;
+                    }
+                };
+                actor.borrow_mut().prefab = Some(rs_engine::content::prefab::PrefabInstance::new(
+                    prefab.clone(),
+                ));
+                project_context
+                    .project
+                    .content
+                    .borrow_mut()
+                    .files
+                    .push(EContentFileType::Prefab(prefab));
+            }
+            crate::ui::level_view::EClickEventType::ApplyPrefabOverrides(actor) => {
+                let Some(active_level) = self.data_source.level.as_mut() else {
+                    return;
+                };
+                let Some(project_context) = self.project_context.as_mut() else {
+                    return;
+                };
+
+                let mut active_level = active_level.borrow_mut();
+                let content = project_context.project.content.clone();
+                let content = content.borrow();
+                let result = active_level.apply_prefab_overrides(
+                    actor,
+                    &mut self.engine,
+                    &content.files,
+                    &mut self.player_viewport,
+                );
+                if let Err(err) = result {
+                    log::warn!("{}", err);
+                }
+            }
+            crate::ui::level_view::EClickEventType::RevertPrefabOverrides(actor) => {
+                let Some(active_level) = self.data_source.level.as_mut() else {
+                    return;
+                };
+                let Some(project_context) = self.project_context.as_mut() else {
+                    return;
+                };
+
+                let mut active_level = active_level.borrow_mut();
+                let content = project_context.project.content.clone();
+                let content = content.borrow();
+                let result = active_level.revert_prefab_overrides(
+                    actor,
+                    &mut self.engine,
+                    &content.files,
+                    &mut self.player_viewport,
+                );
+                if let Err(err) = result {
+                    log::warn!("{}", err);
+                }
+            }
             crate::ui::level_view::EClickEventType::CreateSpotLightComponent(parent_node) => {
                 let Some(project_context) = self.project_context.as_mut() else {
                     return;
@@ -2565,6 +3228,22 @@ impl EditorContext {
             }
             content_browser::EClickEventType::OpenFile(file) => {
                 self.editor_ui.content_item_property_view.content = Some(file.clone());
+                self.editor_ui
+                    .content_item_property_view
+                    .model_import_settings =
+                    self.project_context.as_ref().and_then(|project_context| {
+                        let file_path = Self::get_model_file_path(project_context, &file)?;
+                        let asset_reference = file_path
+                            .strip_prefix(project_context.get_asset_folder_path())
+                            .ok()?
+                            .to_str()?
+                            .replace('\\', "/");
+                        let settings = project_context
+                            .project
+                            .model_import_settings
+                            .get_asset_settings(&asset_reference);
+                        Some((asset_reference, settings))
+                    });
                 self.data_source.is_content_item_property_view_open = true;
                 match file {
                     EContentFileType::StaticMesh(_) => {}
@@ -2597,6 +3276,9 @@ impl EditorContext {
                         );
                     }
                     EContentFileType::MaterialParamentersCollection(_) => {}
+                    EContentFileType::Prefab(prefab) => {
+                        self.add_prefab_instance(prefab);
+                    }
                 }
             }
             content_browser::EClickEventType::SingleClickFile(file) => {
@@ -2780,6 +3462,20 @@ impl EditorContext {
                     ),
                 );
             }
+            content_browser::EClickEventType::ExportGltf(content_file) => {
+                let Some(project_context) = &self.project_context else {
+                    return;
+                };
+                let dialog = rfd::FileDialog::new()
+                    .set_file_name(format!("{}.glb", content_file.get_name()))
+                    .add_filter("glTF", &["glb", "gltf"]);
+                let Some(file_path) = dialog.save_file() else {
+                    return;
+                };
+                let result =
+                    project_context.export_gltf(&mut self.model_loader, &content_file, &file_path);
+                log::trace!("Export glTF {:?}, {:?}", file_path, result);
+            }
         }
     }
 
@@ -2946,22 +3642,7 @@ impl EditorContext {
                         .project_context
                         .as_ref()
                         .ok_or(anyhow!("No project context"))?;
-                    let virtual_texture_cache_dir =
-                        project_context.try_create_virtual_texture_cache_dir()?;
-                    let project_folder_path = &project_context.get_project_folder_path();
-
-                    let virtual_cache_name = texture_file
-                        .borrow()
-                        .get_pref_virtual_cache_name(project_folder_path)?;
-                    texture_file.borrow_mut().create_virtual_texture_cache(
-                        project_folder_path,
-                        &virtual_texture_cache_dir.join(virtual_cache_name.clone()),
-                        Some(rs_artifact::EEndianType::Little),
-                        256,
-                    )?;
-                    log::trace!("virtual_cache_name: {}", virtual_cache_name);
-                    texture_file.borrow_mut().virtual_image_reference = Some(virtual_cache_name);
-                    Ok(())
+                    Self::create_virtual_texture_cache(&self.engine, project_context, texture_file)
                 })();
                 log::trace!("{:?}", result);
             }
@@ -2978,6 +3659,28 @@ impl EditorContext {
                 })();
                 log::trace!("{:?}", result);
             }
+            content_item_property_view::EEventType::UpdateTextureImportSettings(texture_file) => {
+                let Some(project_context) = self.project_context.as_ref() else {
+                    return;
+                };
+                Self::content_load_resources(
+                    &mut self.engine,
+                    &mut self.model_loader,
+                    project_context,
+                    vec![EContentFileType::Texture(texture_file)],
+                );
+                let files = project_context.project.content.borrow().files.clone();
+                if let Some(active_level) = self.data_source.level.clone() {
+                    let mut active_level = active_level.borrow_mut();
+                    let actors = active_level.actors.clone();
+                    active_level.init_actors(
+                        &mut self.engine,
+                        actors,
+                        &files,
+                        &mut self.player_viewport,
+                    );
+                }
+            }
             content_item_property_view::EEventType::UpdateMaterialParamentersCollection(
                 update_info,
             ) => {
@@ -2985,6 +3688,29 @@ impl EditorContext {
                 material_paramenters_collection.fields = update_info.1.fields.clone();
                 material_paramenters_collection.initialize(&mut self.engine);
             }
+            content_item_property_view::EEventType::UpdateModelImportSettings(
+                asset_reference,
+                settings,
+            ) => {
+                let Some(project_context) = self.project_context.as_mut() else {
+                    return;
+                };
+                project_context
+                    .project
+                    .model_import_settings
+                    .set_asset_settings(asset_reference, settings.clone());
+            }
+            content_item_property_view::EEventType::Reimport(asset_reference) => {
+                let Some(project_context) = self.project_context.as_ref() else {
+                    return;
+                };
+                let file_path = project_context
+                    .get_asset_folder_path()
+                    .join(asset_reference);
+                if let Err(err) = self.reimport_model_file(file_path) {
+                    log::warn!("{}", err);
+                }
+            }
         }
     }
 
@@ -3060,6 +3786,9 @@ impl EditorContext {
                             rs_engine::scene_node::EComponentType::PointLightComponent(_) => {
                                 unimplemented!()
                             }
+                            rs_engine::scene_node::EComponentType::CustomComponent(_) => {
+                                log::warn!("Custom components have no material to update");
+                            }
                         }
                     }
                 }
@@ -3269,6 +3998,16 @@ impl EditorContext {
                             component.set_transformation(model_matrix);
                         }
                     }
+                    rs_engine::scene_node::EComponentType::CustomComponent(component) => {
+                        let mut component = component.borrow_mut();
+                        if let Some(gizmo_final_transformation) = gizmo_final_transformation {
+                            let parent_final_transformation =
+                                component.get_parent_final_transformation();
+                            let model_matrix =
+                                parent_final_transformation.inverse() * gizmo_final_transformation;
+                            component.set_transformation(model_matrix);
+                        }
+                    }
                 }
                 let level_physics = active_level.get_physics_mut();
                 secne_node.notify_transformation_updated(level_physics);
@@ -15,7 +15,10 @@ use egui::*;
 use rs_engine::components::component::Component;
 use rs_engine::input_mode::EInputMode;
 use std::sync::Arc;
-use std::{path::PathBuf, rc::Rc};
+use std::{
+    path::{Path, PathBuf},
+    rc::Rc,
+};
 use transform_gizmo_egui::math::Transform;
 use transform_gizmo_egui::GizmoResult;
 
@@ -91,6 +94,13 @@ impl EditorUI {
         self.project_folder_path = project_folder_path;
     }
 
+    pub fn invalidate_thumbnail(&mut self, image_path: &Path) {
+        if let Some(uri) = self.thumbnail_cache.get_image_file_uri(image_path) {
+            self.egui_context.forget_image(&uri);
+        }
+        self.thumbnail_cache.invalidate(image_path);
+    }
+
     pub fn build(
         &mut self,
         context: &Context,
@@ -153,6 +163,10 @@ impl EditorUI {
                             let component = component.borrow();
                             Some(component.get_final_transformation())
                         }
+                        rs_engine::scene_node::EComponentType::CustomComponent(component) => {
+                            let component = component.borrow();
+                            Some(component.get_final_transformation())
+                        }
                     }
                 }
                 ESelectedObjectType::DirectionalLight(component) => {
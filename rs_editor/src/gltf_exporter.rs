@@ -0,0 +1,984 @@
+use anyhow::anyhow;
+use rs_artifact::{
+    skeleton::Skeleton,
+    skeleton_animation::SkeletonAnimation,
+    skin_mesh::{SkinMesh, SkinMeshVertex},
+    static_mesh::StaticMesh,
+};
+use rs_engine::{
+    content::level::Level,
+    scene_node::{EComponentType, SceneNode},
+};
+use rs_foundation::new::SingleThreadMutType;
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::Write,
+    path::Path,
+    sync::Arc,
+};
+
+const COMPONENT_TYPE_UNSIGNED_SHORT: u32 = 5123;
+const COMPONENT_TYPE_UNSIGNED_INT: u32 = 5125;
+const COMPONENT_TYPE_FLOAT: u32 = 5126;
+
+const TARGET_ARRAY_BUFFER: u32 = 34962;
+const TARGET_ELEMENT_ARRAY_BUFFER: u32 = 34963;
+
+const MODE_TRIANGLES: u32 = 4;
+
+const GLB_MAGIC: u32 = 0x46546C67;
+const GLB_VERSION: u32 = 2;
+const GLB_CHUNK_TYPE_JSON: u32 = 0x4E4F534A;
+const GLB_CHUNK_TYPE_BIN: u32 = 0x004E4942;
+
+/// Assimp uses the same value when a file does not specify it.
+const DEFAULT_TICKS_PER_SECOND: f64 = 25.0;
+
+#[derive(Serialize)]
+struct GltfAsset {
+    version: String,
+    generator: String,
+}
+
+#[derive(Serialize)]
+struct GltfScene {
+    nodes: Vec<usize>,
+}
+
+#[derive(Serialize, Default)]
+struct GltfNode {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    children: Vec<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mesh: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    skin: Option<usize>,
+    translation: [f32; 3],
+    rotation: [f32; 4],
+    scale: [f32; 3],
+}
+
+#[derive(Serialize)]
+struct GltfPrimitive {
+    attributes: BTreeMap<&'static str, usize>,
+    indices: usize,
+    mode: u32,
+}
+
+#[derive(Serialize)]
+struct GltfMesh {
+    name: String,
+    primitives: Vec<GltfPrimitive>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GltfSkin {
+    name: String,
+    inverse_bind_matrices: usize,
+    skeleton: usize,
+    joints: Vec<usize>,
+}
+
+#[derive(Serialize)]
+struct GltfChannelTarget {
+    node: usize,
+    path: &'static str,
+}
+
+#[derive(Serialize)]
+struct GltfChannel {
+    sampler: usize,
+    target: GltfChannelTarget,
+}
+
+#[derive(Serialize)]
+struct GltfSampler {
+    input: usize,
+    output: usize,
+    interpolation: &'static str,
+}
+
+#[derive(Serialize)]
+struct GltfAnimation {
+    name: String,
+    channels: Vec<GltfChannel>,
+    samplers: Vec<GltfSampler>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GltfAccessor {
+    buffer_view: usize,
+    component_type: u32,
+    count: usize,
+    #[serde(rename = "type")]
+    accessor_type: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min: Option<Vec<f32>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max: Option<Vec<f32>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GltfBufferView {
+    buffer: usize,
+    byte_offset: usize,
+    byte_length: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target: Option<u32>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GltfBuffer {
+    byte_length: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    uri: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GltfDocument {
+    asset: GltfAsset,
+    scene: usize,
+    scenes: Vec<GltfScene>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    nodes: Vec<GltfNode>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    meshes: Vec<GltfMesh>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    skins: Vec<GltfSkin>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    animations: Vec<GltfAnimation>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    accessors: Vec<GltfAccessor>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    buffer_views: Vec<GltfBufferView>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    buffers: Vec<GltfBuffer>,
+}
+
+/// The nodes which are created for the bones of a skeleton.
+#[derive(Debug, Clone)]
+pub struct SkeletonNodes {
+    pub root_node: usize,
+    pub bone_nodes: HashMap<String, usize>,
+    inverse_bind_matrices: HashMap<String, glam::Mat4>,
+}
+
+/// Resources referenced by the components of a level, keyed by the urls of the contents.
+#[derive(Default)]
+pub struct LevelResources {
+    pub static_meshes: HashMap<url::Url, Arc<StaticMesh>>,
+    pub skin_meshes: HashMap<url::Url, Arc<SkinMesh>>,
+    pub skeletons: HashMap<url::Url, Arc<Skeleton>>,
+    pub skeleton_animations: HashMap<url::Url, Arc<SkeletonAnimation>>,
+}
+
+#[derive(Default)]
+struct PrimitiveVertexes {
+    positions: Vec<glam::Vec3>,
+    normals: Vec<glam::Vec3>,
+    tangents: Vec<glam::Vec4>,
+    tex_coords: Vec<glam::Vec2>,
+    colors: Vec<glam::Vec4>,
+}
+
+impl PrimitiveVertexes {
+    fn push(
+        &mut self,
+        position: glam::Vec3,
+        normal: glam::Vec3,
+        tangent: glam::Vec3,
+        bitangent: glam::Vec3,
+        tex_coord: glam::Vec2,
+        color: glam::Vec4,
+    ) {
+        // glTF requires unit normals and tangents which are orthogonal to the normals.
+        let normal = normal.try_normalize().unwrap_or(glam::Vec3::Z);
+        let tangent = (tangent - normal * normal.dot(tangent))
+            .try_normalize()
+            .unwrap_or_else(|| normal.any_orthonormal_vector());
+        let handedness = if normal.cross(tangent).dot(bitangent) < 0.0 {
+            -1.0
+        } else {
+            1.0
+        };
+        self.positions.push(position);
+        self.normals.push(normal);
+        self.tangents.push(tangent.extend(handedness));
+        self.tex_coords.push(tex_coord);
+        self.colors.push(color);
+    }
+}
+
+/// Builds a glTF 2.0 document, all binary data is stored in a single buffer.
+pub struct GltfExporter {
+    nodes: Vec<GltfNode>,
+    root_nodes: Vec<usize>,
+    meshes: Vec<GltfMesh>,
+    skins: Vec<GltfSkin>,
+    animations: Vec<GltfAnimation>,
+    accessors: Vec<GltfAccessor>,
+    buffer_views: Vec<GltfBufferView>,
+    buffer: Vec<u8>,
+    static_mesh_cache: HashMap<url::Url, usize>,
+}
+
+impl GltfExporter {
+    pub fn new() -> GltfExporter {
+        GltfExporter {
+            nodes: vec![],
+            root_nodes: vec![],
+            meshes: vec![],
+            skins: vec![],
+            animations: vec![],
+            accessors: vec![],
+            buffer_views: vec![],
+            buffer: vec![],
+            static_mesh_cache: HashMap::new(),
+        }
+    }
+
+    fn add_buffer_view(&mut self, data: &[u8], target: Option<u32>) -> usize {
+        while self.buffer.len() % 4 != 0 {
+            self.buffer.push(0);
+        }
+        self.buffer_views.push(GltfBufferView {
+            buffer: 0,
+            byte_offset: self.buffer.len(),
+            byte_length: data.len(),
+            target,
+        });
+        self.buffer.extend_from_slice(data);
+        self.buffer_views.len() - 1
+    }
+
+    fn add_accessor<T>(
+        &mut self,
+        values: &[T],
+        component_type: u32,
+        accessor_type: &'static str,
+        target: Option<u32>,
+        bounds: Option<(Vec<f32>, Vec<f32>)>,
+    ) -> usize {
+        let buffer_view = self.add_buffer_view(rs_foundation::cast_to_raw_buffer(values), target);
+        let (min, max) = match bounds {
+            Some((min, max)) => (Some(min), Some(max)),
+            None => (None, None),
+        };
+        self.accessors.push(GltfAccessor {
+            buffer_view,
+            component_type,
+            count: values.len(),
+            accessor_type,
+            min,
+            max,
+        });
+        self.accessors.len() - 1
+    }
+
+    fn add_primitive(
+        &mut self,
+        vertexes: &PrimitiveVertexes,
+        indexes: &[u32],
+    ) -> anyhow::Result<GltfPrimitive> {
+        // The bounds of the positions would be infinite and the buffer views would be empty.
+        if vertexes.positions.is_empty() || indexes.is_empty() {
+            return Err(anyhow!("The primitive has no vertexes or indexes"));
+        }
+        if let Some(index) = indexes
+            .iter()
+            .find(|x| **x as usize >= vertexes.positions.len())
+        {
+            return Err(anyhow!("Index {} is out of range", index));
+        }
+        let min = vertexes
+            .positions
+            .iter()
+            .fold(glam::Vec3::INFINITY, |acc, x| acc.min(*x));
+        let max = vertexes
+            .positions
+            .iter()
+            .fold(glam::Vec3::NEG_INFINITY, |acc, x| acc.max(*x));
+        let mut attributes = BTreeMap::new();
+        attributes.insert(
+            "POSITION",
+            self.add_accessor(
+                &vertexes.positions,
+                COMPONENT_TYPE_FLOAT,
+                "VEC3",
+                Some(TARGET_ARRAY_BUFFER),
+                Some((min.to_array().to_vec(), max.to_array().to_vec())),
+            ),
+        );
+        attributes.insert(
+            "NORMAL",
+            self.add_accessor(
+                &vertexes.normals,
+                COMPONENT_TYPE_FLOAT,
+                "VEC3",
+                Some(TARGET_ARRAY_BUFFER),
+                None,
+            ),
+        );
+        attributes.insert(
+            "TANGENT",
+            self.add_accessor(
+                &vertexes.tangents,
+                COMPONENT_TYPE_FLOAT,
+                "VEC4",
+                Some(TARGET_ARRAY_BUFFER),
+                None,
+            ),
+        );
+        attributes.insert(
+            "TEXCOORD_0",
+            self.add_accessor(
+                &vertexes.tex_coords,
+                COMPONENT_TYPE_FLOAT,
+                "VEC2",
+                Some(TARGET_ARRAY_BUFFER),
+                None,
+            ),
+        );
+        // Imported meshes without vertex colors have zero colors, which would turn them black.
+        if vertexes.colors.iter().any(|x| *x != glam::Vec4::ZERO) {
+            attributes.insert(
+                "COLOR_0",
+                self.add_accessor(
+                    &vertexes.colors,
+                    COMPONENT_TYPE_FLOAT,
+                    "VEC4",
+                    Some(TARGET_ARRAY_BUFFER),
+                    None,
+                ),
+            );
+        }
+        let indices = self.add_accessor(
+            indexes,
+            COMPONENT_TYPE_UNSIGNED_INT,
+            "SCALAR",
+            Some(TARGET_ELEMENT_ARRAY_BUFFER),
+            None,
+        );
+        Ok(GltfPrimitive {
+            attributes,
+            indices,
+            mode: MODE_TRIANGLES,
+        })
+    }
+
+    /// Adds a node with the transformation relative to the parent, nodes without a parent are
+    /// the roots of the scene.
+    pub fn add_node(
+        &mut self,
+        name: &str,
+        transformation: glam::Mat4,
+        parent: Option<usize>,
+    ) -> usize {
+        let (scale, rotation, translation) = transformation.to_scale_rotation_translation();
+        self.nodes.push(GltfNode {
+            name: Some(name.to_string()),
+            translation: translation.to_array(),
+            rotation: rotation.normalize().to_array(),
+            scale: scale.to_array(),
+            ..Default::default()
+        });
+        let node = self.nodes.len() - 1;
+        match parent.and_then(|x| self.nodes.get_mut(x)) {
+            Some(parent) => parent.children.push(node),
+            None => self.root_nodes.push(node),
+        }
+        node
+    }
+
+    pub fn set_node_mesh(&mut self, node: usize, mesh: usize, skin: Option<usize>) {
+        if let Some(node) = self.nodes.get_mut(node) {
+            node.mesh = Some(mesh);
+            node.skin = skin;
+        }
+    }
+
+    /// Adds the mesh with the indexes of the first level of detail, a mesh is only added once.
+    pub fn add_static_mesh(&mut self, static_mesh: &StaticMesh) -> anyhow::Result<usize> {
+        if let Some(mesh) = self.static_mesh_cache.get(&static_mesh.url) {
+            return Ok(*mesh);
+        }
+        let mut vertexes = PrimitiveVertexes::default();
+        for vertex in &static_mesh.vertexes {
+            vertexes.push(
+                vertex.position,
+                vertex.normal,
+                vertex.tangent,
+                vertex.bitangent,
+                vertex.tex_coord,
+                vertex.vertex_color,
+            );
+        }
+        let primitive = self.add_primitive(&vertexes, &static_mesh.indexes)?;
+        self.meshes.push(GltfMesh {
+            name: static_mesh.name.clone(),
+            primitives: vec![primitive],
+        });
+        let mesh = self.meshes.len() - 1;
+        self.static_mesh_cache.insert(static_mesh.url.clone(), mesh);
+        Ok(mesh)
+    }
+
+    /// Adds a node for every bone, the transformations of the nodes are the bind pose.
+    pub fn add_skeleton(
+        &mut self,
+        skeleton: &Skeleton,
+        parent: Option<usize>,
+    ) -> anyhow::Result<SkeletonNodes> {
+        let root_bone = skeleton
+            .bones
+            .get(&skeleton.root_bone)
+            .ok_or(anyhow!("Root bone {} is not found", skeleton.root_bone))?;
+        let mut skeleton_nodes = SkeletonNodes {
+            root_node: 0,
+            bone_nodes: HashMap::new(),
+            inverse_bind_matrices: HashMap::new(),
+        };
+        let mut stack = vec![(root_bone, parent)];
+        while let Some((bone, parent)) = stack.pop() {
+            let transformation = skeleton
+                .skeleton_mesh_hierarchy
+                .get(&bone.path)
+                .map(|x| x.transformation)
+                .unwrap_or(glam::Mat4::IDENTITY);
+            let name = bone.path.rsplit('/').next().unwrap_or(&bone.path);
+            let node = self.add_node(name, transformation, parent);
+            if bone.path == skeleton.root_bone {
+                skeleton_nodes.root_node = node;
+            }
+            skeleton_nodes.bone_nodes.insert(bone.path.clone(), node);
+            skeleton_nodes
+                .inverse_bind_matrices
+                .insert(bone.path.clone(), bone.offset_matrix);
+            for child in bone.childs.iter().rev() {
+                if let Some(child) = skeleton.bones.get(child) {
+                    stack.push((child, Some(node)));
+                }
+            }
+        }
+        Ok(skeleton_nodes)
+    }
+
+    /// Adds the mesh and the skin which binds it to the nodes of the skeleton. Returns the index
+    /// of the mesh and the index of the skin.
+    pub fn add_skin_mesh(
+        &mut self,
+        skin_mesh: &SkinMesh,
+        skeleton_nodes: &SkeletonNodes,
+    ) -> anyhow::Result<(usize, Option<usize>)> {
+        let mut vertexes = PrimitiveVertexes::default();
+        for vertex in &skin_mesh.vertexes {
+            vertexes.push(
+                vertex.position,
+                vertex.normal,
+                vertex.tangent,
+                vertex.bitangent,
+                vertex.tex_coord,
+                vertex.vertex_color,
+            );
+        }
+        let mut primitive = self.add_primitive(&vertexes, &skin_mesh.indexes)?;
+
+        let mut skin = None;
+        if !skin_mesh.bone_paths.is_empty() {
+            let mut joints = Vec::with_capacity(skin_mesh.bone_paths.len());
+            let mut inverse_bind_matrices = Vec::with_capacity(skin_mesh.bone_paths.len());
+            for bone_path in &skin_mesh.bone_paths {
+                match skeleton_nodes.bone_nodes.get(bone_path) {
+                    Some(node) => {
+                        joints.push(*node);
+                        inverse_bind_matrices.push(
+                            skeleton_nodes
+                                .inverse_bind_matrices
+                                .get(bone_path)
+                                .copied()
+                                .unwrap_or(glam::Mat4::IDENTITY),
+                        );
+                    }
+                    None => {
+                        log::warn!("Bone {} is not found in the skeleton", bone_path);
+                        joints.push(skeleton_nodes.root_node);
+                        inverse_bind_matrices.push(glam::Mat4::IDENTITY);
+                    }
+                }
+            }
+            let (vertex_joints, vertex_weights) =
+                Self::make_joints_and_weights(&skin_mesh.vertexes, skin_mesh.bone_paths.len());
+            primitive.attributes.insert(
+                "JOINTS_0",
+                self.add_accessor(
+                    &vertex_joints,
+                    COMPONENT_TYPE_UNSIGNED_SHORT,
+                    "VEC4",
+                    Some(TARGET_ARRAY_BUFFER),
+                    None,
+                ),
+            );
+            primitive.attributes.insert(
+                "WEIGHTS_0",
+                self.add_accessor(
+                    &vertex_weights,
+                    COMPONENT_TYPE_FLOAT,
+                    "VEC4",
+                    Some(TARGET_ARRAY_BUFFER),
+                    None,
+                ),
+            );
+            let inverse_bind_matrices = self.add_accessor(
+                &inverse_bind_matrices,
+                COMPONENT_TYPE_FLOAT,
+                "MAT4",
+                None,
+                None,
+            );
+            self.skins.push(GltfSkin {
+                name: skin_mesh.name.clone(),
+                inverse_bind_matrices,
+                skeleton: skeleton_nodes.root_node,
+                joints,
+            });
+            skin = Some(self.skins.len() - 1);
+        }
+
+        self.meshes.push(GltfMesh {
+            name: skin_mesh.name.clone(),
+            primitives: vec![primitive],
+        });
+        Ok((self.meshes.len() - 1, skin))
+    }
+
+    /// Invalid bones get a zero weight and the weights are normalized, a vertex without any
+    /// bone is bound to the first joint.
+    fn make_joints_and_weights(
+        vertexes: &[SkinMeshVertex],
+        joint_count: usize,
+    ) -> (Vec<[u16; 4]>, Vec<[f32; 4]>) {
+        let mut vertex_joints = Vec::with_capacity(vertexes.len());
+        let mut vertex_weights = Vec::with_capacity(vertexes.len());
+        for vertex in vertexes {
+            let mut joints = [0_u16; 4];
+            let mut weights = [0.0_f32; 4];
+            for i in 0..4 {
+                let bone = vertex.bones[i];
+                if bone >= 0 && (bone as usize) < joint_count && vertex.weights[i] > 0.0 {
+                    joints[i] = bone as u16;
+                    weights[i] = vertex.weights[i];
+                }
+            }
+            let sum: f32 = weights.iter().sum();
+            if sum > 0.0 {
+                weights.iter_mut().for_each(|x| *x /= sum);
+            } else {
+                weights = [1.0, 0.0, 0.0, 0.0];
+            }
+            vertex_joints.push(joints);
+            vertex_weights.push(weights);
+        }
+        (vertex_joints, vertex_weights)
+    }
+
+    /// Adds the animation of the nodes of the skeleton, tracks are written with their own key
+    /// times and linear interpolation.
+    pub fn add_skeleton_animation(
+        &mut self,
+        skeleton_animation: &SkeletonAnimation,
+        skeleton_nodes: &SkeletonNodes,
+    ) -> Option<usize> {
+        let ticks_per_second = if skeleton_animation.ticks_per_second > 0.0 {
+            skeleton_animation.ticks_per_second
+        } else {
+            DEFAULT_TICKS_PER_SECOND
+        };
+        let mut animation = GltfAnimation {
+            name: skeleton_animation.name.clone(),
+            channels: vec![],
+            samplers: vec![],
+        };
+        for channel in &skeleton_animation.channels {
+            let Some(node) = skeleton_nodes.bone_nodes.get(&channel.node).copied() else {
+                continue;
+            };
+            let times = Self::make_increasing(channel.position_track.key_times());
+            if !times.is_empty() {
+                let values: Vec<glam::Vec3> = times
+                    .iter()
+                    .map(|x| channel.position_track.sample(*x).unwrap_or_default())
+                    .collect();
+                self.add_animation_channel(
+                    &mut animation,
+                    node,
+                    "translation",
+                    &Self::to_seconds(&times, ticks_per_second),
+                    &values,
+                    "VEC3",
+                );
+            }
+            let times = Self::make_increasing(channel.rotation_track.key_times());
+            if !times.is_empty() {
+                let values: Vec<glam::Quat> = times
+                    .iter()
+                    .map(|x| {
+                        channel
+                            .rotation_track
+                            .sample(*x)
+                            .unwrap_or_default()
+                            .normalize()
+                    })
+                    .collect();
+                self.add_animation_channel(
+                    &mut animation,
+                    node,
+                    "rotation",
+                    &Self::to_seconds(&times, ticks_per_second),
+                    &values,
+                    "VEC4",
+                );
+            }
+            let times = Self::make_increasing(channel.scaling_track.key_times());
+            if !times.is_empty() {
+                let values: Vec<glam::Vec3> = times
+                    .iter()
+                    .map(|x| channel.scaling_track.sample(*x).unwrap_or(glam::Vec3::ONE))
+                    .collect();
+                self.add_animation_channel(
+                    &mut animation,
+                    node,
+                    "scale",
+                    &Self::to_seconds(&times, ticks_per_second),
+                    &values,
+                    "VEC3",
+                );
+            }
+        }
+        if animation.channels.is_empty() {
+            return None;
+        }
+        self.animations.push(animation);
+        Some(self.animations.len() - 1)
+    }
+
+    /// glTF requires strictly increasing key times.
+    fn make_increasing(times: Vec<f64>) -> Vec<f64> {
+        let mut increasing: Vec<f64> = Vec::with_capacity(times.len());
+        for time in times {
+            if increasing.last().map_or(true, |last| time > *last) {
+                increasing.push(time);
+            }
+        }
+        increasing
+    }
+
+    fn to_seconds(times: &[f64], ticks_per_second: f64) -> Vec<f32> {
+        times
+            .iter()
+            .map(|x| (*x / ticks_per_second) as f32)
+            .collect()
+    }
+
+    fn add_animation_channel<T>(
+        &mut self,
+        animation: &mut GltfAnimation,
+        node: usize,
+        path: &'static str,
+        seconds: &[f32],
+        values: &[T],
+        accessor_type: &'static str,
+    ) {
+        let bounds = (
+            vec![seconds.first().copied().unwrap_or_default()],
+            vec![seconds.last().copied().unwrap_or_default()],
+        );
+        let input = self.add_accessor(seconds, COMPONENT_TYPE_FLOAT, "SCALAR", None, Some(bounds));
+        let output = self.add_accessor(values, COMPONENT_TYPE_FLOAT, accessor_type, None, None);
+        animation.samplers.push(GltfSampler {
+            input,
+            output,
+            interpolation: "LINEAR",
+        });
+        animation.channels.push(GltfChannel {
+            sampler: animation.samplers.len() - 1,
+            target: GltfChannelTarget { node, path },
+        });
+    }
+
+    /// Adds a node for every actor and every component of the level, components which reference
+    /// resources that are not in `level_resources` are added without meshes.
+    pub fn add_level(
+        &mut self,
+        level: &Level,
+        level_resources: &LevelResources,
+    ) -> anyhow::Result<()> {
+        for actor in &level.actors {
+            let actor = actor.borrow();
+            let node = self.add_node(&actor.name, glam::Mat4::IDENTITY, None);
+            self.add_scene_node(&actor.scene_node, node, level_resources)?;
+        }
+        Ok(())
+    }
+
+    fn add_scene_node(
+        &mut self,
+        scene_node: &SingleThreadMutType<SceneNode>,
+        parent: usize,
+        level_resources: &LevelResources,
+    ) -> anyhow::Result<()> {
+        let scene_node = scene_node.borrow();
+        let node = self.add_node(
+            &scene_node.get_name(),
+            scene_node.get_transformation(),
+            Some(parent),
+        );
+        match &scene_node.component {
+            EComponentType::StaticMeshComponent(component) => {
+                let component = component.borrow();
+                if let Some(static_mesh) = component
+                    .static_mesh
+                    .as_ref()
+                    .and_then(|x| level_resources.static_meshes.get(x))
+                {
+                    if static_mesh.indexes.is_empty() {
+                        log::warn!("{} is empty, it is not exported", static_mesh.url);
+                    } else {
+                        let mesh = self.add_static_mesh(static_mesh)?;
+                        self.set_node_mesh(node, mesh, None);
+                    }
+                }
+            }
+            EComponentType::SkeletonMeshComponent(component) => {
+                let component = component.borrow();
+                if let Some(skeleton) = component
+                    .skeleton_url
+                    .as_ref()
+                    .and_then(|x| level_resources.skeletons.get(x))
+                {
+                    let skeleton_nodes = self.add_skeleton(skeleton, Some(node))?;
+                    for skin_mesh in component
+                        .skeleton_mesh_urls
+                        .iter()
+                        .filter_map(|x| level_resources.skin_meshes.get(x))
+                    {
+                        if skin_mesh.indexes.is_empty() {
+                            log::warn!("{} is empty, it is not exported", skin_mesh.url);
+                            continue;
+                        }
+                        let (mesh, skin) = self.add_skin_mesh(skin_mesh, &skeleton_nodes)?;
+                        let mesh_node =
+                            self.add_node(&skin_mesh.name, glam::Mat4::IDENTITY, Some(node));
+                        self.set_node_mesh(mesh_node, mesh, skin);
+                    }
+                    if let Some(skeleton_animation) = component
+                        .animation_url
+                        .as_ref()
+                        .and_then(|x| level_resources.skeleton_animations.get(x))
+                    {
+                        self.add_skeleton_animation(skeleton_animation, &skeleton_nodes);
+                    }
+                }
+            }
+            _ => {}
+        }
+        for child in &scene_node.childs {
+            self.add_scene_node(child, node, level_resources)?;
+        }
+        Ok(())
+    }
+
+    fn into_document(self, buffer_uri: Option<String>) -> (GltfDocument, Vec<u8>) {
+        let mut buffer = self.buffer;
+        while buffer.len() % 4 != 0 {
+            buffer.push(0);
+        }
+        let buffers = if buffer.is_empty() {
+            vec![]
+        } else {
+            vec![GltfBuffer {
+                byte_length: buffer.len(),
+                uri: buffer_uri,
+            }]
+        };
+        let document = GltfDocument {
+            asset: GltfAsset {
+                version: "2.0".to_string(),
+                generator: "rs_editor".to_string(),
+            },
+            scene: 0,
+            scenes: vec![GltfScene {
+                nodes: self.root_nodes,
+            }],
+            nodes: self.nodes,
+            meshes: self.meshes,
+            skins: self.skins,
+            animations: self.animations,
+            accessors: self.accessors,
+            buffer_views: self.buffer_views,
+            buffers,
+        };
+        (document, buffer)
+    }
+
+    /// Returns the binary glTF file.
+    pub fn to_glb(self) -> anyhow::Result<Vec<u8>> {
+        let (document, buffer) = self.into_document(None);
+        let mut json = serde_json::to_vec(&document)?;
+        while json.len() % 4 != 0 {
+            json.push(b' ');
+        }
+        let mut length = 12 + 8 + json.len();
+        if !buffer.is_empty() {
+            length += 8 + buffer.len();
+        }
+        let mut glb = Vec::with_capacity(length);
+        for value in [GLB_MAGIC, GLB_VERSION, length as u32] {
+            glb.write_all(&value.to_le_bytes())?;
+        }
+        glb.write_all(&(json.len() as u32).to_le_bytes())?;
+        glb.write_all(&GLB_CHUNK_TYPE_JSON.to_le_bytes())?;
+        glb.write_all(&json)?;
+        if !buffer.is_empty() {
+            glb.write_all(&(buffer.len() as u32).to_le_bytes())?;
+            glb.write_all(&GLB_CHUNK_TYPE_BIN.to_le_bytes())?;
+            glb.write_all(&buffer)?;
+        }
+        Ok(glb)
+    }
+
+    /// Returns the json of the document and the content of the buffer which is referenced by
+    /// `buffer_uri`.
+    pub fn to_gltf(self, buffer_uri: &str) -> anyhow::Result<(String, Vec<u8>)> {
+        let (document, buffer) = self.into_document(Some(buffer_uri.to_string()));
+        Ok((serde_json::to_string_pretty(&document)?, buffer))
+    }
+
+    /// Writes a .glb file, or a .gltf file and a .bin file next to it.
+    pub fn write_to_file(self, path: &Path) -> anyhow::Result<()> {
+        let extension = path
+            .extension()
+            .map(|x| x.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        match extension.as_str() {
+            "glb" => {
+                std::fs::write(path, self.to_glb()?)?;
+            }
+            "gltf" => {
+                let buffer_path = path.with_extension("bin");
+                let buffer_uri = buffer_path
+                    .file_name()
+                    .ok_or(anyhow!("Not a valid file path, {:?}", path))?
+                    .to_string_lossy()
+                    .to_string();
+                let (json, buffer) = self.to_gltf(&buffer_uri)?;
+                if !buffer.is_empty() {
+                    std::fs::write(&buffer_path, buffer)?;
+                }
+                std::fs::write(path, json)?;
+            }
+            _ => {
+                return Err(anyhow!("Not a glTF file extension, {:?}", path));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the urls of the contents which are referenced by the components of the level.
+pub fn collect_level_references(level: &Level) -> Vec<url::Url> {
+    fn walk(scene_node: &SingleThreadMutType<SceneNode>, urls: &mut Vec<url::Url>) {
+        let scene_node = scene_node.borrow();
+        match &scene_node.component {
+            EComponentType::StaticMeshComponent(component) => {
+                urls.extend(component.borrow().static_mesh.clone());
+            }
+            EComponentType::SkeletonMeshComponent(component) => {
+                let component = component.borrow();
+                urls.extend(component.skeleton_url.clone());
+                urls.extend(component.skeleton_mesh_urls.iter().cloned());
+                urls.extend(component.animation_url.clone());
+            }
+            _ => {}
+        }
+        for child in &scene_node.childs {
+            walk(child, urls);
+        }
+    }
+    let mut urls = vec![];
+    for actor in &level.actors {
+        walk(&actor.borrow().scene_node, &mut urls);
+    }
+    urls.sort_by(|lhs, rhs| lhs.as_str().cmp(rhs.as_str()));
+    urls.dedup();
+    urls
+}
+
+#[cfg(test)]
+mod test {
+    use super::GltfExporter;
+    use rs_artifact::{
+        mesh_vertex::MeshVertex, quantized_mesh_vertex::EVertexEncoding, static_mesh::StaticMesh,
+    };
+
+    #[test]
+    fn test_case_glb() {
+        let vertexes = [glam::Vec3::ZERO, glam::Vec3::X, glam::Vec3::Y]
+            .map(|position| MeshVertex {
+                vertex_color: glam::Vec4::ZERO,
+                position,
+                normal: glam::Vec3::Z,
+                tangent: glam::Vec3::X,
+                bitangent: glam::Vec3::Y,
+                tex_coord: glam::Vec2::ZERO,
+            })
+            .to_vec();
+        let static_mesh = StaticMesh {
+            name: "Triangle".to_string(),
+            url: url::Url::parse("asset://Triangle").unwrap(),
+            vertexes,
+            indexes: vec![0, 1, 2],
+            vertex_encoding: EVertexEncoding::Full,
+            lods: vec![],
+            morph_targets: vec![],
+            cluster_lod: None,
+        };
+        let mut exporter = GltfExporter::new();
+        let mesh = exporter.add_static_mesh(&static_mesh).unwrap();
+        assert_eq!(exporter.add_static_mesh(&static_mesh).unwrap(), mesh);
+        let node = exporter.add_node("Triangle", glam::Mat4::IDENTITY, None);
+        exporter.set_node_mesh(node, mesh, None);
+        let glb = exporter.to_glb().unwrap();
+
+        let read_u32 =
+            |offset: usize| u32::from_le_bytes(glb[offset..offset + 4].try_into().unwrap());
+        assert_eq!(read_u32(0), super::GLB_MAGIC);
+        assert_eq!(read_u32(8) as usize, glb.len());
+        let json_length = read_u32(12) as usize;
+        assert_eq!(read_u32(16), super::GLB_CHUNK_TYPE_JSON);
+        let json: serde_json::Value = serde_json::from_slice(&glb[20..20 + json_length]).unwrap();
+        assert_eq!(json["asset"]["version"], "2.0");
+        assert_eq!(json["meshes"].as_array().unwrap().len(), 1);
+        assert_eq!(json["accessors"][0]["max"][0], 1.0);
+        let bin_length = read_u32(20 + json_length) as usize;
+        assert_eq!(json["buffers"][0]["byteLength"], bin_length);
+        assert_eq!(20 + json_length + 8 + bin_length, glb.len());
+
+        let empty_static_mesh = StaticMesh {
+            url: url::Url::parse("asset://Empty").unwrap(),
+            vertexes: vec![],
+            indexes: vec![],
+            ..static_mesh
+        };
+        assert!(GltfExporter::new()
+            .add_static_mesh(&empty_static_mesh)
+            .is_err());
+    }
+}
@@ -0,0 +1,1370 @@
+use crate::{
+    import_transformation::ImportTransformation,
+    mesh_cook::MeshLodSettings,
+    model_loader::{EAlphaMode, ImportedMaterial, LoadResult},
+    project::ModelAssetImportSettings,
+};
+use anyhow::{anyhow, Context};
+use rs_artifact::{
+    mesh_vertex::MeshVertex,
+    morph_target::{MorphAnim, MorphTarget, MorphWeightKey},
+    node_anim::{ERotationTrack, EVectorTrack, NodeAnim, QuatKey, VectorKey},
+    quantized_mesh_vertex::EVertexEncoding,
+    skeleton::{Skeleton, SkeletonBone, SkeletonMeshHierarchyNode},
+    skeleton_animation::SkeletonAnimation,
+    skin_mesh::{SkinMesh, SkinMeshVertex},
+    static_mesh::StaticMesh,
+};
+use rs_core_minimal::name_generator::NameGenerator;
+use rs_engine::{
+    build_content_file_url,
+    scene_node::{EComponentType, SceneComponent, SceneNode},
+    static_mesh_component::StaticMeshComponent,
+};
+use rs_foundation::new::{SingleThreadMut, SingleThreadMutType};
+use rs_render::vertex_data_type::skin_mesh_vertex::INVALID_BONE;
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap, HashSet},
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+/// Every node of the scene is a descendant of this node, glTF scenes can have several roots.
+const ROOT_NODE_NAME: &str = "Root";
+
+/// Names end up in the query of asset urls.
+fn sanitize_name(name: &str) -> String {
+    name.chars()
+        .map(|x| match x {
+            '|' | '/' | '?' | '&' | '=' | '#' | '%' => '_',
+            x => x,
+        })
+        .collect()
+}
+
+fn make_unique_name(name: String, names: &mut HashSet<String>) -> String {
+    let mut unique_name = name.clone();
+    let mut number = 1;
+    while names.contains(&unique_name) {
+        unique_name = format!("{}_{}", name, number);
+        number += 1;
+    }
+    names.insert(unique_name.clone());
+    unique_name
+}
+
+struct GltfMesh {
+    name: String,
+    vertexes: Vec<SkinMeshVertex>,
+    indexes: Vec<u32>,
+    /// Paths of the joints of the skin, empty if the mesh is not skinned.
+    bone_paths: Vec<String>,
+    material: Option<usize>,
+    morph_targets: Vec<MorphTarget>,
+}
+
+struct GltfSceneNode {
+    name: String,
+    path: String,
+    transformation: glam::Mat4,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    meshes: Vec<usize>,
+}
+
+struct GltfSkeleton {
+    name: String,
+    root_bone: String,
+    bones: HashMap<String, SkeletonBone>,
+    skeleton_mesh_hierarchy: HashMap<String, SkeletonMeshHierarchyNode>,
+}
+
+struct GltfAnimation {
+    name: String,
+    duration: f64,
+    channels: Vec<NodeAnim>,
+    morph_channels: Vec<MorphAnim>,
+}
+
+enum EGltfImage {
+    File(PathBuf),
+    Embedded {
+        data: Vec<u8>,
+        extension: &'static str,
+    },
+}
+
+struct GltfMaterial {
+    name: String,
+    base_color_factor: glam::Vec4,
+    base_color_texture: Option<usize>,
+    metallic_factor: f32,
+    roughness_factor: f32,
+    metallic_roughness_texture: Option<usize>,
+    normal_texture: Option<usize>,
+    occlusion_texture: Option<usize>,
+    emissive_factor: glam::Vec3,
+    emissive_texture: Option<usize>,
+    alpha_mode: EAlphaMode,
+    is_double_sided: bool,
+}
+
+/// A glTF 2.0 file which is converted to the layout of the imported assets, the meshes,
+/// skeleton and animations are looked up by name like the ones of assimp scenes.
+pub struct GltfScene {
+    name: String,
+    /// The first node is the root node.
+    nodes: Vec<GltfSceneNode>,
+    meshes: Vec<GltfMesh>,
+    skeleton: Option<GltfSkeleton>,
+    animations: Vec<GltfAnimation>,
+    materials: Vec<GltfMaterial>,
+    images: Vec<EGltfImage>,
+}
+
+impl GltfScene {
+    pub fn from_file(file_path: &Path) -> anyhow::Result<GltfScene> {
+        let gltf = gltf::Gltf::open(file_path)?;
+        let base_folder = file_path.parent();
+        let buffers = gltf::import_buffers(&gltf.document, base_folder, gltf.blob.clone())?;
+        Self::from_document(&gltf.document, &buffers, base_folder)
+    }
+
+    pub fn from_slice(data: &[u8], base_folder: Option<&Path>) -> anyhow::Result<GltfScene> {
+        let gltf = gltf::Gltf::from_slice(data)?;
+        let buffers = gltf::import_buffers(&gltf.document, base_folder, gltf.blob.clone())?;
+        Self::from_document(&gltf.document, &buffers, base_folder)
+    }
+
+    fn from_document(
+        document: &gltf::Document,
+        buffers: &[gltf::buffer::Data],
+        base_folder: Option<&Path>,
+    ) -> anyhow::Result<GltfScene> {
+        let gltf_scene = document
+            .default_scene()
+            .or_else(|| document.scenes().next());
+        let mut scene = GltfScene {
+            name: gltf_scene
+                .as_ref()
+                .and_then(|x| x.name())
+                .map(sanitize_name)
+                .unwrap_or_default(),
+            nodes: vec![GltfSceneNode {
+                name: ROOT_NODE_NAME.to_string(),
+                path: format!("/{}", ROOT_NODE_NAME),
+                transformation: glam::Mat4::IDENTITY,
+                parent: None,
+                children: vec![],
+                meshes: vec![],
+            }],
+            meshes: vec![],
+            skeleton: None,
+            animations: vec![],
+            materials: vec![],
+            images: vec![],
+        };
+
+        let mut node_map: HashMap<usize, usize> = HashMap::new();
+        for root_node in scene_roots(document, gltf_scene) {
+            scene.add_node(&root_node, 0, &mut node_map);
+        }
+
+        scene.add_meshes(document, buffers, &node_map)?;
+        scene.add_skeleton(document, buffers, &node_map)?;
+        scene.add_animations(document, buffers, &node_map)?;
+        scene.add_materials(document, buffers, base_folder);
+        Ok(scene)
+    }
+
+    /// Applies the settings which change the meshes, the scale and the up axis are baked into
+    /// the meshes, the nodes, the skeleton and the animations.
+    pub fn apply_import_settings(&mut self, import_settings: &ModelAssetImportSettings) {
+        if import_settings.is_merge_meshes {
+            self.merge_meshes();
+        }
+        if import_settings.is_generate_tangents {
+            for mesh in self.meshes.iter_mut() {
+                compute_tangents(&mut mesh.vertexes, &mesh.indexes);
+            }
+        }
+        self.apply_import_transformation(&import_settings.get_import_transformation());
+    }
+
+    fn apply_import_transformation(&mut self, import_transformation: &ImportTransformation) {
+        if import_transformation.is_identity() {
+            return;
+        }
+        for mesh in self.meshes.iter_mut() {
+            import_transformation.transform_skin_mesh_vertexes(&mut mesh.vertexes);
+            import_transformation.transform_morph_targets(&mut mesh.morph_targets);
+        }
+        for node in self.nodes.iter_mut() {
+            node.transformation = import_transformation.transform_matrix(node.transformation);
+        }
+        if let Some(skeleton) = self.skeleton.as_mut() {
+            import_transformation
+                .transform_skeleton(&mut skeleton.bones, &mut skeleton.skeleton_mesh_hierarchy);
+        }
+        for channel in self
+            .animations
+            .iter_mut()
+            .flat_map(|x| x.channels.iter_mut())
+        {
+            import_transformation.transform_node_anim(channel);
+        }
+    }
+
+    /// Merges the primitives of every mesh into the first one, which keeps its material.
+    fn merge_meshes(&mut self) {
+        let mut old_meshes: Vec<Option<GltfMesh>> = std::mem::take(&mut self.meshes)
+            .into_iter()
+            .map(Some)
+            .collect();
+        let mut merged_meshes: HashMap<Vec<usize>, usize> = HashMap::new();
+        for node in self.nodes.iter_mut() {
+            if node.meshes.is_empty() {
+                continue;
+            }
+            if let Some(merged_mesh) = merged_meshes.get(&node.meshes) {
+                node.meshes = vec![*merged_mesh];
+                continue;
+            }
+            let mut parts = node.meshes.iter().filter_map(|x| old_meshes[*x].take());
+            let Some(mut merged_mesh) = parts.next() else {
+                continue;
+            };
+            for part in parts {
+                let base_vertex = merged_mesh.vertexes.len();
+                let vertex_count = part.vertexes.len();
+                for (morph_target, part_morph_target) in merged_mesh
+                    .morph_targets
+                    .iter_mut()
+                    .zip(part.morph_targets.iter())
+                {
+                    let mut part_normal_deltas = part_morph_target.normal_deltas.clone();
+                    if morph_target.normal_deltas.is_empty() != part_normal_deltas.is_empty() {
+                        morph_target
+                            .normal_deltas
+                            .resize(base_vertex, glam::Vec3::ZERO);
+                        part_normal_deltas.resize(vertex_count, glam::Vec3::ZERO);
+                    }
+                    morph_target
+                        .position_deltas
+                        .extend_from_slice(&part_morph_target.position_deltas);
+                    morph_target.normal_deltas.extend(part_normal_deltas);
+                }
+                merged_mesh.vertexes.extend(part.vertexes);
+                merged_mesh
+                    .indexes
+                    .extend(part.indexes.iter().map(|x| x + base_vertex as u32));
+            }
+            self.meshes.push(merged_mesh);
+            merged_meshes.insert(node.meshes.clone(), self.meshes.len() - 1);
+            node.meshes = vec![self.meshes.len() - 1];
+        }
+    }
+
+    fn add_node(&mut self, node: &gltf::Node, parent: usize, node_map: &mut HashMap<usize, usize>) {
+        if node_map.contains_key(&node.index()) {
+            return;
+        }
+        let name = node
+            .name()
+            .map(sanitize_name)
+            .unwrap_or(format!("Node{}", node.index()));
+        let path = format!("{}/{}", self.nodes[parent].path, name);
+        self.nodes.push(GltfSceneNode {
+            name,
+            path,
+            transformation: glam::Mat4::from_cols_array_2d(&node.transform().matrix()),
+            parent: Some(parent),
+            children: vec![],
+            meshes: vec![],
+        });
+        let index = self.nodes.len() - 1;
+        self.nodes[parent].children.push(index);
+        node_map.insert(node.index(), index);
+        for child in node.children() {
+            self.add_node(&child, index, node_map);
+        }
+    }
+
+    fn add_meshes(
+        &mut self,
+        document: &gltf::Document,
+        buffers: &[gltf::buffer::Data],
+        node_map: &HashMap<usize, usize>,
+    ) -> anyhow::Result<()> {
+        let mut names: HashSet<String> = HashSet::new();
+        let mut mesh_cache: HashMap<(usize, Option<usize>), Vec<usize>> = HashMap::new();
+        for node in document.nodes() {
+            let (Some(mesh), Some(scene_node)) = (node.mesh(), node_map.get(&node.index())) else {
+                continue;
+            };
+            let skin = node.skin();
+            let key = (mesh.index(), skin.as_ref().map(|x| x.index()));
+            if let Some(meshes) = mesh_cache.get(&key) {
+                self.nodes[*scene_node].meshes = meshes.clone();
+                continue;
+            }
+            let bone_paths: Vec<String> = match &skin {
+                Some(skin) => skin
+                    .joints()
+                    .filter_map(|x| node_map.get(&x.index()))
+                    .map(|x| self.nodes[*x].path.clone())
+                    .collect(),
+                None => vec![],
+            };
+            let base_name = mesh
+                .name()
+                .map(sanitize_name)
+                .unwrap_or(format!("Mesh{}", mesh.index()));
+            let default_weights = node.weights().or(mesh.weights()).unwrap_or_default();
+            let primitive_count = mesh.primitives().len();
+            let mut meshes = vec![];
+            for primitive in mesh.primitives() {
+                let name = if primitive_count > 1 {
+                    format!("{}_{}", base_name, primitive.index())
+                } else {
+                    base_name.clone()
+                };
+                let Some((vertexes, indexes)) =
+                    read_primitive(&primitive, buffers, !bone_paths.is_empty())
+                        .context(format!("Failed to read {}", name))?
+                else {
+                    log::warn!("Primitive {} is not made of triangles, skipped", name);
+                    continue;
+                };
+                let morph_targets = read_morph_targets(&primitive, buffers, default_weights);
+                self.meshes.push(GltfMesh {
+                    name: make_unique_name(name, &mut names),
+                    vertexes,
+                    indexes,
+                    bone_paths: bone_paths.clone(),
+                    material: primitive.material().index(),
+                    morph_targets,
+                });
+                meshes.push(self.meshes.len() - 1);
+            }
+            self.nodes[*scene_node].meshes = meshes.clone();
+            mesh_cache.insert(key, meshes);
+        }
+        Ok(())
+    }
+
+    fn add_skeleton(
+        &mut self,
+        document: &gltf::Document,
+        buffers: &[gltf::buffer::Data],
+        node_map: &HashMap<usize, usize>,
+    ) -> anyhow::Result<()> {
+        if document.skins().len() > 1 {
+            log::warn!("Too many skins, only the first skeleton is imported");
+        }
+        let Some(skin) = document.skins().next() else {
+            return Ok(());
+        };
+        let mut inverse_bind_matrices: HashMap<usize, glam::Mat4> = HashMap::new();
+        for skin in document.skins() {
+            let reader = skin.reader(|buffer| buffers.get(buffer.index()).map(|x| x.0.as_slice()));
+            let matrixes: Vec<glam::Mat4> = match reader.read_inverse_bind_matrices() {
+                Some(matrixes) => matrixes
+                    .map(|x| glam::Mat4::from_cols_array_2d(&x))
+                    .collect(),
+                None => vec![],
+            };
+            for (i, joint) in skin.joints().enumerate() {
+                let Some(node) = node_map.get(&joint.index()) else {
+                    continue;
+                };
+                inverse_bind_matrices
+                    .entry(*node)
+                    .or_insert(matrixes.get(i).copied().unwrap_or(glam::Mat4::IDENTITY));
+            }
+        }
+
+        let joints: Vec<usize> = skin
+            .joints()
+            .filter_map(|x| node_map.get(&x.index()).copied())
+            .collect();
+        let root_bone = match skin.skeleton().and_then(|x| node_map.get(&x.index())) {
+            Some(root_bone) => *root_bone,
+            None => self.find_common_ancestor(&joints),
+        };
+
+        let mut bones = HashMap::new();
+        let mut stack = vec![root_bone];
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            bones.insert(
+                node.path.clone(),
+                SkeletonBone {
+                    path: node.path.clone(),
+                    parent: match node.parent {
+                        Some(parent) if index != root_bone => Some(self.nodes[parent].path.clone()),
+                        _ => None,
+                    },
+                    childs: node
+                        .children
+                        .iter()
+                        .map(|x| self.nodes[*x].path.clone())
+                        .collect(),
+                    offset_matrix: inverse_bind_matrices
+                        .get(&index)
+                        .copied()
+                        .unwrap_or(glam::Mat4::IDENTITY),
+                },
+            );
+            stack.extend(node.children.iter().copied());
+        }
+
+        let skeleton_mesh_hierarchy = self
+            .nodes
+            .iter()
+            .map(|node| {
+                (
+                    node.path.clone(),
+                    SkeletonMeshHierarchyNode {
+                        path: node.path.clone(),
+                        transformation: node.transformation,
+                        parent: node.parent.map(|x| self.nodes[x].path.clone()),
+                        childs: node
+                            .children
+                            .iter()
+                            .map(|x| self.nodes[*x].path.clone())
+                            .collect(),
+                    },
+                )
+            })
+            .collect();
+
+        self.skeleton = Some(GltfSkeleton {
+            name: self.nodes[root_bone].name.clone(),
+            root_bone: self.nodes[root_bone].path.clone(),
+            bones,
+            skeleton_mesh_hierarchy,
+        });
+        Ok(())
+    }
+
+    /// Returns the deepest node which is the ancestor of all the nodes or one of them.
+    fn find_common_ancestor(&self, nodes: &[usize]) -> usize {
+        let ancestors = |mut index: usize| {
+            let mut ancestors = vec![index];
+            while let Some(parent) = self.nodes[index].parent {
+                ancestors.push(parent);
+                index = parent;
+            }
+            ancestors.reverse();
+            ancestors
+        };
+        let Some(first) = nodes.first() else {
+            return 0;
+        };
+        let mut common = ancestors(*first);
+        for node in &nodes[1..] {
+            let other = ancestors(*node);
+            let length = common
+                .iter()
+                .zip(other.iter())
+                .take_while(|(lhs, rhs)| lhs == rhs)
+                .count();
+            common.truncate(length);
+        }
+        common.last().copied().unwrap_or(0)
+    }
+
+    fn add_animations(
+        &mut self,
+        document: &gltf::Document,
+        buffers: &[gltf::buffer::Data],
+        node_map: &HashMap<usize, usize>,
+    ) -> anyhow::Result<()> {
+        let mut names: HashSet<String> = HashSet::new();
+        for animation in document.animations() {
+            let name = animation
+                .name()
+                .map(sanitize_name)
+                .unwrap_or(format!("Animation{}", animation.index()));
+            let mut duration: f64 = 0.0;
+            let mut tracks: BTreeMap<
+                usize,
+                (
+                    Option<Vec<VectorKey>>,
+                    Option<Vec<QuatKey>>,
+                    Option<Vec<VectorKey>>,
+                ),
+            > = BTreeMap::new();
+            let mut morph_channels: Vec<MorphAnim> = vec![];
+            for channel in animation.channels() {
+                let Some(node) = node_map.get(&channel.target().node().index()) else {
+                    continue;
+                };
+                let reader =
+                    channel.reader(|buffer| buffers.get(buffer.index()).map(|x| x.0.as_slice()));
+                let (Some(inputs), Some(outputs)) = (reader.read_inputs(), reader.read_outputs())
+                else {
+                    continue;
+                };
+                let times: Vec<f64> = inputs.map(|x| x as f64).collect();
+                duration = times.iter().copied().fold(duration, f64::max);
+                let interpolation = channel.sampler().interpolation();
+                if let gltf::animation::util::ReadOutputs::MorphTargetWeights(values) = outputs {
+                    let values: Vec<f32> = values.into_f32().collect();
+                    morph_channels.extend(self.make_morph_channels(
+                        *node,
+                        &times,
+                        &values,
+                        interpolation,
+                    ));
+                    continue;
+                }
+                let track = tracks.entry(*node).or_default();
+                match outputs {
+                    gltf::animation::util::ReadOutputs::Translations(values) => {
+                        let values: Vec<glam::Vec3> = values.map(glam::Vec3::from_array).collect();
+                        track.0 = Some(
+                            make_keys(&times, &values, interpolation)
+                                .map(|(time, value)| VectorKey { time, value })
+                                .collect(),
+                        );
+                    }
+                    gltf::animation::util::ReadOutputs::Rotations(values) => {
+                        let values: Vec<glam::Quat> = values
+                            .into_f32()
+                            .map(|x| glam::Quat::from_array(x).normalize())
+                            .collect();
+                        track.1 = Some(
+                            make_keys(&times, &values, interpolation)
+                                .map(|(time, value)| QuatKey { time, value })
+                                .collect(),
+                        );
+                    }
+                    gltf::animation::util::ReadOutputs::Scales(values) => {
+                        let values: Vec<glam::Vec3> = values.map(glam::Vec3::from_array).collect();
+                        track.2 = Some(
+                            make_keys(&times, &values, interpolation)
+                                .map(|(time, value)| VectorKey { time, value })
+                                .collect(),
+                        );
+                    }
+                    gltf::animation::util::ReadOutputs::MorphTargetWeights(_) => {}
+                }
+            }
+            if tracks.is_empty() && morph_channels.is_empty() {
+                continue;
+            }
+            // Like assimp, a property without a channel keeps the value of the bind pose.
+            let channels = tracks
+                .into_iter()
+                .map(|(node, (positions, rotations, scalings))| {
+                    let node = &self.nodes[node];
+                    let (scale, rotation, translation) =
+                        node.transformation.to_scale_rotation_translation();
+                    NodeAnim {
+                        node: node.path.clone(),
+                        position_track: EVectorTrack::Keys(positions.unwrap_or(vec![VectorKey {
+                            time: 0.0,
+                            value: translation,
+                        }])),
+                        scaling_track: EVectorTrack::Keys(scalings.unwrap_or(vec![VectorKey {
+                            time: 0.0,
+                            value: scale,
+                        }])),
+                        rotation_track: ERotationTrack::Keys(rotations.unwrap_or(vec![QuatKey {
+                            time: 0.0,
+                            value: rotation,
+                        }])),
+                    }
+                })
+                .collect();
+            self.animations.push(GltfAnimation {
+                name: make_unique_name(name, &mut names),
+                duration,
+                channels,
+                morph_channels,
+            });
+        }
+        Ok(())
+    }
+
+    /// Weights of all the targets are in one sampler, every primitive of the mesh of the node
+    /// gets a channel.
+    fn make_morph_channels(
+        &self,
+        node: usize,
+        times: &[f64],
+        values: &[f32],
+        interpolation: gltf::animation::Interpolation,
+    ) -> Vec<MorphAnim> {
+        let values_per_key = match interpolation {
+            gltf::animation::Interpolation::CubicSpline => times.len() * 3,
+            _ => times.len(),
+        };
+        if values_per_key == 0 {
+            return vec![];
+        }
+        let target_count = values.len() / values_per_key;
+        let weights: Vec<&[f32]> = values.chunks_exact(target_count.max(1)).collect();
+        let keys: Vec<MorphWeightKey> = make_keys(times, &weights, interpolation)
+            .map(|(time, weights)| MorphWeightKey {
+                time,
+                targets: (0..target_count as u32).collect(),
+                weights: weights.to_vec(),
+            })
+            .collect();
+        self.nodes[node]
+            .meshes
+            .iter()
+            .filter(|x| !self.meshes[**x].morph_targets.is_empty())
+            .map(|x| MorphAnim {
+                mesh: self.meshes[*x].name.clone(),
+                keys: keys.clone(),
+            })
+            .collect()
+    }
+
+    fn add_materials(
+        &mut self,
+        document: &gltf::Document,
+        buffers: &[gltf::buffer::Data],
+        base_folder: Option<&Path>,
+    ) {
+        for image in document.images() {
+            let index = image.index();
+            let image = match image.source() {
+                gltf::image::Source::Uri { uri, .. } => {
+                    if let Some(data) = uri.strip_prefix("data:") {
+                        read_data_uri(data)
+                    } else {
+                        let uri = percent_encoding::percent_decode_str(uri)
+                            .decode_utf8_lossy()
+                            .to_string();
+                        let path = match base_folder {
+                            Some(base_folder) => base_folder.join(uri),
+                            None => PathBuf::from(uri),
+                        };
+                        Some(EGltfImage::File(path))
+                    }
+                }
+                gltf::image::Source::View { view, mime_type } => buffers
+                    .get(view.buffer().index())
+                    .and_then(|x| x.0.get(view.offset()..view.offset() + view.length()))
+                    .map(|data| EGltfImage::Embedded {
+                        data: data.to_vec(),
+                        extension: image_extension(mime_type),
+                    }),
+            };
+            self.images.push(image.unwrap_or_else(|| {
+                log::warn!("Failed to read image {}", index);
+                EGltfImage::Embedded {
+                    data: vec![],
+                    extension: "bin",
+                }
+            }));
+        }
+
+        let image_index = |info: Option<gltf::texture::Texture>| info.map(|x| x.source().index());
+        for material in document.materials() {
+            let pbr_metallic_roughness = material.pbr_metallic_roughness();
+            self.materials.push(GltfMaterial {
+                name: material
+                    .name()
+                    .map(sanitize_name)
+                    .unwrap_or(format!("Material{}", self.materials.len())),
+                base_color_factor: glam::Vec4::from_array(
+                    pbr_metallic_roughness.base_color_factor(),
+                ),
+                base_color_texture: image_index(
+                    pbr_metallic_roughness
+                        .base_color_texture()
+                        .map(|x| x.texture()),
+                ),
+                metallic_factor: pbr_metallic_roughness.metallic_factor(),
+                roughness_factor: pbr_metallic_roughness.roughness_factor(),
+                metallic_roughness_texture: image_index(
+                    pbr_metallic_roughness
+                        .metallic_roughness_texture()
+                        .map(|x| x.texture()),
+                ),
+                normal_texture: image_index(material.normal_texture().map(|x| x.texture())),
+                occlusion_texture: image_index(material.occlusion_texture().map(|x| x.texture())),
+                emissive_factor: glam::Vec3::from_array(material.emissive_factor()),
+                emissive_texture: image_index(material.emissive_texture().map(|x| x.texture())),
+                alpha_mode: match material.alpha_mode() {
+                    gltf::material::AlphaMode::Opaque => EAlphaMode::Opaque,
+                    gltf::material::AlphaMode::Mask => {
+                        EAlphaMode::Mask(material.alpha_cutoff().unwrap_or(0.5))
+                    }
+                    gltf::material::AlphaMode::Blend => EAlphaMode::Blend,
+                },
+                is_double_sided: material.double_sided(),
+            });
+        }
+    }
+
+    fn find_mesh(&self, name: &str) -> anyhow::Result<&GltfMesh> {
+        self.meshes
+            .iter()
+            .find(|x| x.name == name)
+            .ok_or(anyhow!("Mesh {} is not found", name))
+    }
+
+    pub fn to_static_mesh(&self, name: &str, url: url::Url) -> anyhow::Result<StaticMesh> {
+        let mesh = self.find_mesh(name)?;
+        let vertexes: Vec<MeshVertex> = mesh
+            .vertexes
+            .iter()
+            .map(|x| MeshVertex {
+                vertex_color: x.vertex_color,
+                position: x.position,
+                normal: x.normal,
+                tangent: x.tangent,
+                bitangent: x.bitangent,
+                tex_coord: x.tex_coord,
+            })
+            .collect();
+        let lods = crate::mesh_cook::generate_lods(
+            &vertexes,
+            std::mem::offset_of!(MeshVertex, position),
+            &mesh.indexes,
+            &MeshLodSettings::default(),
+        )
+        .unwrap_or_else(|err| {
+            log::warn!("Failed to generate lods, {}", err);
+            vec![]
+        });
+        Ok(StaticMesh {
+            name: mesh.name.clone(),
+            url,
+            vertexes,
+            indexes: mesh.indexes.clone(),
+            vertex_encoding: EVertexEncoding::Full,
+            lods,
+            morph_targets: mesh.morph_targets.clone(),
+            cluster_lod: None,
+        })
+    }
+
+    pub fn to_skin_mesh(&self, name: &str, url: url::Url) -> anyhow::Result<SkinMesh> {
+        let mesh = self.find_mesh(name)?;
+        let lods = crate::mesh_cook::generate_lods(
+            &mesh.vertexes,
+            std::mem::offset_of!(SkinMeshVertex, position),
+            &mesh.indexes,
+            &MeshLodSettings::default(),
+        )
+        .unwrap_or_else(|err| {
+            log::warn!("Failed to generate lods, {}", err);
+            vec![]
+        });
+        Ok(SkinMesh {
+            name: mesh.name.clone(),
+            url,
+            vertexes: mesh.vertexes.clone(),
+            indexes: mesh.indexes.clone(),
+            bone_paths: mesh.bone_paths.clone(),
+            lods,
+            morph_targets: mesh.morph_targets.clone(),
+            cluster_lod: None,
+        })
+    }
+
+    pub fn to_skeleton(&self, url: url::Url) -> anyhow::Result<Skeleton> {
+        let skeleton = self.skeleton.as_ref().ok_or(anyhow!("No skeleton"))?;
+        Ok(Skeleton {
+            name: skeleton.name.clone(),
+            url,
+            root_bone: skeleton.root_bone.clone(),
+            root_node: self.nodes[0].path.clone(),
+            bones: skeleton.bones.clone(),
+            skeleton_mesh_hierarchy: skeleton.skeleton_mesh_hierarchy.clone(),
+        })
+    }
+
+    /// Time of the animation is in seconds.
+    pub fn to_skeleton_animation(
+        &self,
+        name: &str,
+        url: url::Url,
+    ) -> anyhow::Result<SkeletonAnimation> {
+        let animation = self
+            .animations
+            .iter()
+            .find(|x| x.name == name)
+            .ok_or(anyhow!("Animation {} is not found", name))?;
+        Ok(SkeletonAnimation {
+            name: animation.name.clone(),
+            url,
+            duration: animation.duration,
+            ticks_per_second: 1.0,
+            channels: animation.channels.clone(),
+            morph_channels: animation.morph_channels.clone(),
+        })
+    }
+
+    /// Embedded images are written next to the model file, so they can be imported as textures.
+    fn resolve_images(&self, file_path: &Path) -> anyhow::Result<Vec<Option<PathBuf>>> {
+        let folder = file_path
+            .parent()
+            .ok_or(anyhow!("Incorrect file path: {:?}", file_path))?;
+        let stem = file_path
+            .file_stem()
+            .map(|x| x.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let mut paths = Vec::with_capacity(self.images.len());
+        for (index, image) in self.images.iter().enumerate() {
+            let path = match image {
+                EGltfImage::File(path) => Some(path.clone()),
+                EGltfImage::Embedded { data, .. } if data.is_empty() => None,
+                EGltfImage::Embedded { data, extension } => {
+                    let path = folder.join(format!("{}_image{}.{}", stem, index, extension));
+                    if !path.exists() {
+                        std::fs::write(&path, data)
+                            .context(format!("Failed to write {:?}", path))?;
+                    }
+                    Some(path)
+                }
+            };
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+
+    fn to_imported_materials(&self, image_paths: &[Option<PathBuf>]) -> Vec<ImportedMaterial> {
+        let path = |image: Option<usize>| image.and_then(|x| image_paths.get(x).cloned().flatten());
+        self.materials
+            .iter()
+            .map(|material| ImportedMaterial {
+                name: material.name.clone(),
+                base_color_factor: material.base_color_factor,
+                base_color_texture: path(material.base_color_texture),
+                metallic_factor: material.metallic_factor,
+                roughness_factor: material.roughness_factor,
+                metallic_roughness_texture: path(material.metallic_roughness_texture),
+                metallic_texture: None,
+                roughness_texture: None,
+                normal_texture: path(material.normal_texture),
+                occlusion_texture: path(material.occlusion_texture),
+                emissive_factor: material.emissive_factor,
+                emissive_texture: path(material.emissive_texture),
+                opacity_texture: None,
+                alpha_mode: material.alpha_mode,
+                is_double_sided: material.is_double_sided,
+            })
+            .collect()
+    }
+
+    fn node_to_scene_node(
+        &self,
+        index: usize,
+        static_mesh_urls: &HashMap<usize, url::Url>,
+    ) -> SingleThreadMutType<SceneNode> {
+        let node = &self.nodes[index];
+        let mut meshes = node.meshes.iter().filter_map(|x| static_mesh_urls.get(x));
+        let component = match meshes.next() {
+            Some(url) => {
+                EComponentType::StaticMeshComponent(SingleThreadMut::new(StaticMeshComponent::new(
+                    node.name.clone(),
+                    Some(url.clone()),
+                    None,
+                    node.transformation,
+                )))
+            }
+            None => EComponentType::SceneComponent(SingleThreadMut::new(SceneComponent::new(
+                node.name.clone(),
+                node.transformation,
+            ))),
+        };
+        let mut childs = vec![];
+        // The other primitives of the mesh.
+        for (i, url) in meshes.enumerate() {
+            childs.push(SingleThreadMut::new(SceneNode {
+                component: EComponentType::StaticMeshComponent(SingleThreadMut::new(
+                    StaticMeshComponent::new(
+                        format!("{}_{}", node.name, i + 1),
+                        Some(url.clone()),
+                        None,
+                        glam::Mat4::IDENTITY,
+                    ),
+                )),
+                childs: vec![],
+            }));
+        }
+        for child in &node.children {
+            childs.push(self.node_to_scene_node(*child, static_mesh_urls));
+        }
+        SingleThreadMut::new(SceneNode { component, childs })
+    }
+
+    pub fn to_load_result(
+        &self,
+        file_path: &Path,
+        asset_reference: String,
+        name_generator: &mut NameGenerator,
+        actor_name_generator: &mut NameGenerator,
+    ) -> anyhow::Result<LoadResult> {
+        let image_paths = self.resolve_images(file_path)?;
+        let materials = self.to_imported_materials(&image_paths);
+
+        let mut skeleton: Option<Rc<RefCell<rs_engine::content::skeleton::Skeleton>>> = None;
+        if let Some(gltf_skeleton) = &self.skeleton {
+            let name = name_generator.next(&gltf_skeleton.name);
+            let url = build_content_file_url(name).context(gltf_skeleton.name.clone())?;
+            let asset_url = rs_engine::content::skeleton::Skeleton::make_asset_url(
+                &asset_reference,
+                &gltf_skeleton.root_bone,
+            );
+            skeleton = Some(SingleThreadMut::new(
+                rs_engine::content::skeleton::Skeleton { url, asset_url },
+            ));
+        }
+
+        let mut node_animations = vec![];
+        for animation in &self.animations {
+            let name = name_generator.next(&animation.name);
+            let url = build_content_file_url(&name).context(animation.name.clone())?;
+            let asset_url =
+                rs_engine::content::skeleton_animation::SkeletonAnimation::make_asset_url(
+                    &asset_reference,
+                    &animation.name,
+                );
+            node_animations.push(SingleThreadMut::new(
+                rs_engine::content::skeleton_animation::SkeletonAnimation { url, asset_url },
+            ));
+        }
+
+        let mut static_meshes = vec![];
+        let mut skeleton_meshes = vec![];
+        let mut static_mesh_urls: HashMap<usize, url::Url> = HashMap::new();
+        let mut mesh_materials: HashMap<url::Url, usize> = HashMap::new();
+        for (index, mesh) in self.meshes.iter().enumerate() {
+            let name = name_generator.next(&mesh.name);
+            let url = build_content_file_url(&name).context(mesh.name.clone())?;
+            if let Some(material) = mesh.material {
+                mesh_materials.insert(url.clone(), material);
+            }
+            match (&skeleton, mesh.bone_paths.is_empty()) {
+                (Some(skeleton), false) => {
+                    let asset_url = rs_engine::content::skeleton_mesh::SkeletonMesh::make_asset_url(
+                        &asset_reference,
+                        &mesh.name,
+                    );
+                    skeleton_meshes.push(SingleThreadMut::new(
+                        rs_engine::content::skeleton_mesh::SkeletonMesh {
+                            url,
+                            skeleton_url: skeleton.borrow().url.clone(),
+                            asset_url,
+                        },
+                    ));
+                }
+                _ => {
+                    static_mesh_urls.insert(index, url.clone());
+                    static_meshes.push(SingleThreadMut::new(
+                        rs_engine::content::static_mesh::StaticMesh {
+                            url,
+                            asset_info: rs_engine::content::static_mesh::AssetInfo {
+                                relative_path: Path::new(&asset_reference).to_path_buf(),
+                                path: mesh.name.clone(),
+                            },
+                        },
+                    ));
+                }
+            }
+        }
+
+        let file_name = file_path
+            .file_name()
+            .and_then(|x| x.to_str())
+            .map(|x| x.to_string())
+            .ok_or(anyhow!("Incorrect file path: {:?}", file_path))?;
+        let actor_name = actor_name_generator.next(if self.name.is_empty() {
+            &file_name
+        } else {
+            &self.name
+        });
+        let scene_node = match &skeleton {
+            Some(skeleton) => {
+                let skeleton_mesh_component =
+                    rs_engine::skeleton_mesh_component::SkeletonMeshComponent::new(
+                        skeleton.borrow().get_name().clone(),
+                        Some(skeleton.borrow().url.clone()),
+                        skeleton_meshes
+                            .iter()
+                            .map(|x| x.borrow().url.clone())
+                            .collect(),
+                        node_animations.first().map(|x| x.borrow().url.clone()),
+                        None,
+                        glam::Mat4::IDENTITY,
+                    );
+                SingleThreadMut::new(SceneNode {
+                    component: EComponentType::SkeletonMeshComponent(SingleThreadMut::new(
+                        skeleton_mesh_component,
+                    )),
+                    childs: vec![],
+                })
+            }
+            None => self.node_to_scene_node(0, &static_mesh_urls),
+        };
+
+        Ok(LoadResult {
+            asset_reference,
+            static_meshes,
+            skeleton_meshes,
+            skeleton,
+            node_animations,
+            actor: SingleThreadMut::new(rs_engine::actor::Actor {
+                name: actor_name,
+                scene_node,
+                prefab: None,
+            }),
+            materials,
+            mesh_materials,
+        })
+    }
+}
+
+fn scene_roots<'a>(
+    document: &'a gltf::Document,
+    scene: Option<gltf::Scene<'a>>,
+) -> Vec<gltf::Node<'a>> {
+    if let Some(scene) = scene {
+        return scene.nodes().collect();
+    }
+    // Without scenes every node which is not a child is a root.
+    let children: HashSet<usize> = document
+        .nodes()
+        .flat_map(|x| x.children().map(|x| x.index()).collect::<Vec<_>>())
+        .collect();
+    document
+        .nodes()
+        .filter(|x| !children.contains(&x.index()))
+        .collect()
+}
+
+fn image_extension(mime_type: &str) -> &'static str {
+    match mime_type {
+        "image/png" => "png",
+        "image/jpeg" => "jpg",
+        "image/webp" => "webp",
+        "image/ktx2" => "ktx2",
+        _ => "bin",
+    }
+}
+
+/// Reads `<mime type>;base64,<data>`.
+fn read_data_uri(data: &str) -> Option<EGltfImage> {
+    let (header, data) = data.split_once(',')?;
+    let mime_type = header.strip_suffix(";base64")?;
+    let data = decode_base64(data)?;
+    Some(EGltfImage::Embedded {
+        data,
+        extension: image_extension(mime_type),
+    })
+}
+
+fn decode_base64(data: &str) -> Option<Vec<u8>> {
+    let mut decoded = Vec::with_capacity(data.len() / 4 * 3);
+    let mut bits: u32 = 0;
+    let mut bit_count = 0;
+    for byte in data.bytes() {
+        let value = match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            b'=' => break,
+            b'\r' | b'\n' | b' ' => continue,
+            _ => return None,
+        };
+        bits = (bits << 6) | value as u32;
+        bit_count += 6;
+        if bit_count >= 8 {
+            bit_count -= 8;
+            decoded.push((bits >> bit_count) as u8);
+        }
+    }
+    Some(decoded)
+}
+
+/// Returns the value of every key, the in and out tangents of cubic splines are dropped and
+/// steps are made of two keys at the same time.
+fn make_keys<'a, T: Copy + 'a>(
+    times: &'a [f64],
+    values: &'a [T],
+    interpolation: gltf::animation::Interpolation,
+) -> Box<dyn Iterator<Item = (f64, T)> + 'a> {
+    match interpolation {
+        gltf::animation::Interpolation::Linear => {
+            Box::new(times.iter().copied().zip(values.iter().copied()))
+        }
+        gltf::animation::Interpolation::CubicSpline => Box::new(
+            times
+                .iter()
+                .copied()
+                .zip(values.chunks_exact(3).map(|x| x[1])),
+        ),
+        gltf::animation::Interpolation::Step => Box::new(
+            times
+                .iter()
+                .copied()
+                .zip(values.iter().copied())
+                .enumerate()
+                .flat_map(move |(i, (time, value))| {
+                    let previous = i.checked_sub(1).and_then(|x| values.get(x)).copied();
+                    previous
+                        .map(|previous| (time, previous))
+                        .into_iter()
+                        .chain(std::iter::once((time, value)))
+                }),
+        ),
+    }
+}
+
+/// The displacements of morph targets of glTF are the deltas of the engine.
+fn read_morph_targets(
+    primitive: &gltf::Primitive,
+    buffers: &[gltf::buffer::Data],
+    default_weights: &[f32],
+) -> Vec<MorphTarget> {
+    let reader = primitive.reader(|buffer| buffers.get(buffer.index()).map(|x| x.0.as_slice()));
+    reader
+        .read_morph_targets()
+        .enumerate()
+        .map(|(i, (positions, normals, _))| MorphTarget {
+            name: format!("Target{}", i),
+            position_deltas: positions
+                .map(|x| x.map(glam::Vec3::from_array).collect())
+                .unwrap_or_default(),
+            normal_deltas: normals
+                .map(|x| x.map(glam::Vec3::from_array).collect())
+                .unwrap_or_default(),
+            default_weight: default_weights.get(i).copied().unwrap_or(0.0),
+        })
+        .collect()
+}
+
+/// Returns `None` if the primitive is not made of triangles.
+fn read_primitive(
+    primitive: &gltf::Primitive,
+    buffers: &[gltf::buffer::Data],
+    is_skinned: bool,
+) -> anyhow::Result<Option<(Vec<SkinMeshVertex>, Vec<u32>)>> {
+    let reader = primitive.reader(|buffer| buffers.get(buffer.index()).map(|x| x.0.as_slice()));
+    let positions: Vec<glam::Vec3> = reader
+        .read_positions()
+        .ok_or(anyhow!("No positions"))?
+        .map(glam::Vec3::from_array)
+        .collect();
+    let vertex_count = positions.len();
+    let indexes: Vec<u32> = match reader.read_indices() {
+        Some(indexes) => indexes.into_u32().collect(),
+        None => (0..vertex_count as u32).collect(),
+    };
+    let indexes = match primitive.mode() {
+        gltf::mesh::Mode::Triangles => {
+            let mut indexes = indexes;
+            indexes.truncate(indexes.len() / 3 * 3);
+            indexes
+        }
+        gltf::mesh::Mode::TriangleStrip => (0..indexes.len().saturating_sub(2))
+            .flat_map(|i| {
+                if i % 2 == 0 {
+                    [indexes[i], indexes[i + 1], indexes[i + 2]]
+                } else {
+                    [indexes[i + 1], indexes[i], indexes[i + 2]]
+                }
+            })
+            .collect(),
+        gltf::mesh::Mode::TriangleFan => (1..indexes.len().saturating_sub(1))
+            .flat_map(|i| [indexes[0], indexes[i], indexes[i + 1]])
+            .collect(),
+        _ => return Ok(None),
+    };
+    if let Some(index) = indexes.iter().find(|x| **x as usize >= vertex_count) {
+        return Err(anyhow!("Index {} is out of range", index));
+    }
+
+    let normals: Vec<glam::Vec3> = match reader.read_normals() {
+        Some(normals) => normals.map(glam::Vec3::from_array).collect(),
+        None => compute_normals(&positions, &indexes),
+    };
+    let tangents: Option<Vec<glam::Vec4>> = reader
+        .read_tangents()
+        .map(|x| x.map(glam::Vec4::from_array).collect());
+    let tex_coords: Option<Vec<glam::Vec2>> = reader
+        .read_tex_coords(0)
+        .map(|x| x.into_f32().map(glam::Vec2::from_array).collect());
+    let colors: Option<Vec<glam::Vec4>> = reader
+        .read_colors(0)
+        .map(|x| x.into_rgba_f32().map(glam::Vec4::from_array).collect());
+    let joints: Option<Vec<[u16; 4]>> = if is_skinned {
+        reader.read_joints(0).map(|x| x.into_u16().collect())
+    } else {
+        None
+    };
+    let weights: Option<Vec<[f32; 4]>> = if is_skinned {
+        reader.read_weights(0).map(|x| x.into_f32().collect())
+    } else {
+        None
+    };
+
+    let mut vertexes = Vec::with_capacity(vertex_count);
+    for (i, position) in positions.iter().enumerate() {
+        let normal = normals.get(i).copied().unwrap_or(glam::Vec3::Z);
+        let (tangent, bitangent) = match tangents.as_ref().and_then(|x| x.get(i)) {
+            Some(tangent) => (
+                tangent.truncate(),
+                normal.cross(tangent.truncate()) * tangent.w,
+            ),
+            None => (glam::Vec3::X, glam::Vec3::Y),
+        };
+        // Texture coordinates of assimp have the origin at the bottom left corner.
+        let tex_coord = tex_coords
+            .as_ref()
+            .and_then(|x| x.get(i))
+            .map(|x| glam::vec2(x.x, 1.0 - x.y))
+            .unwrap_or(glam::Vec2::ZERO);
+        let mut bones = [INVALID_BONE; 4];
+        let mut bone_weights = [0.0_f32; 4];
+        if let (Some(joints), Some(weights)) = (
+            joints.as_ref().and_then(|x| x.get(i)),
+            weights.as_ref().and_then(|x| x.get(i)),
+        ) {
+            for j in 0..4 {
+                if weights[j] > 0.0 {
+                    bones[j] = joints[j] as i32;
+                    bone_weights[j] = weights[j];
+                }
+            }
+        }
+        vertexes.push(SkinMeshVertex {
+            vertex_color: colors
+                .as_ref()
+                .and_then(|x| x.get(i))
+                .copied()
+                .unwrap_or(glam::Vec4::ZERO),
+            position: *position,
+            normal,
+            tangent,
+            bitangent,
+            tex_coord,
+            bones,
+            weights: bone_weights,
+        });
+    }
+    Ok(Some((vertexes, indexes)))
+}
+
+/// Smooth normals weighted by the area of the triangles.
+fn compute_normals(positions: &[glam::Vec3], indexes: &[u32]) -> Vec<glam::Vec3> {
+    let mut normals = vec![glam::Vec3::ZERO; positions.len()];
+    for triangle in indexes.chunks_exact(3) {
+        let [a, b, c] = [triangle[0], triangle[1], triangle[2]].map(|x| x as usize);
+        let normal = (positions[b] - positions[a]).cross(positions[c] - positions[a]);
+        normals[a] += normal;
+        normals[b] += normal;
+        normals[c] += normal;
+    }
+    normals
+        .into_iter()
+        .map(|x| x.try_normalize().unwrap_or(glam::Vec3::Z))
+        .collect()
+}
+
+/// Tangents along the direction of increasing texture coordinates, orthogonalized against the
+/// normals.
+fn compute_tangents(vertexes: &mut [SkinMeshVertex], indexes: &[u32]) {
+    let mut tangents = vec![glam::Vec3::ZERO; vertexes.len()];
+    let mut bitangents = vec![glam::Vec3::ZERO; vertexes.len()];
+    for triangle in indexes.chunks_exact(3) {
+        let [a, b, c] = [triangle[0], triangle[1], triangle[2]].map(|x| x as usize);
+        let edge_0 = vertexes[b].position - vertexes[a].position;
+        let edge_1 = vertexes[c].position - vertexes[a].position;
+        let delta_uv_0 = vertexes[b].tex_coord - vertexes[a].tex_coord;
+        let delta_uv_1 = vertexes[c].tex_coord - vertexes[a].tex_coord;
+        let determinant = delta_uv_0.perp_dot(delta_uv_1);
+        if determinant.abs() <= f32::EPSILON {
+            continue;
+        }
+        let tangent = (edge_0 * delta_uv_1.y - edge_1 * delta_uv_0.y) / determinant;
+        let bitangent = (edge_1 * delta_uv_0.x - edge_0 * delta_uv_1.x) / determinant;
+        for index in [a, b, c] {
+            tangents[index] += tangent;
+            bitangents[index] += bitangent;
+        }
+    }
+    for ((vertex, tangent), bitangent) in vertexes.iter_mut().zip(tangents).zip(bitangents) {
+        let normal = vertex.normal;
+        let Some(tangent) = (tangent - normal * normal.dot(tangent)).try_normalize() else {
+            continue;
+        };
+        let handedness = if normal.cross(tangent).dot(bitangent) < 0.0 {
+            -1.0
+        } else {
+            1.0
+        };
+        vertex.tangent = tangent;
+        vertex.bitangent = normal.cross(tangent) * handedness;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::GltfScene;
+    use crate::gltf_exporter::GltfExporter;
+    use rs_artifact::{
+        mesh_vertex::MeshVertex, quantized_mesh_vertex::EVertexEncoding, static_mesh::StaticMesh,
+    };
+
+    #[test]
+    fn test_case_round_trip() {
+        let vertexes = [glam::Vec3::ZERO, glam::Vec3::X, glam::Vec3::Y]
+            .map(|position| MeshVertex {
+                vertex_color: glam::Vec4::ZERO,
+                position,
+                normal: glam::Vec3::Z,
+                tangent: glam::Vec3::X,
+                bitangent: glam::Vec3::Y,
+                tex_coord: glam::vec2(position.x, position.y),
+            })
+            .to_vec();
+        let static_mesh = StaticMesh {
+            name: "Triangle".to_string(),
+            url: url::Url::parse("asset://Triangle").unwrap(),
+            vertexes,
+            indexes: vec![0, 1, 2],
+            vertex_encoding: EVertexEncoding::Full,
+            lods: vec![],
+            morph_targets: vec![],
+            cluster_lod: None,
+        };
+        let mut exporter = GltfExporter::new();
+        let mesh = exporter.add_static_mesh(&static_mesh).unwrap();
+        let node = exporter.add_node("Node", glam::Mat4::from_translation(glam::Vec3::Z), None);
+        exporter.set_node_mesh(node, mesh, None);
+        let glb = exporter.to_glb().unwrap();
+
+        let scene = GltfScene::from_slice(&glb, None).unwrap();
+        assert_eq!(scene.nodes[1].path, "/Root/Node");
+        assert_eq!(
+            scene.nodes[1].transformation,
+            glam::Mat4::from_translation(glam::Vec3::Z)
+        );
+        let imported = scene
+            .to_static_mesh("Triangle", static_mesh.url.clone())
+            .unwrap();
+        assert_eq!(imported.indexes, static_mesh.indexes);
+        for (lhs, rhs) in imported.vertexes.iter().zip(static_mesh.vertexes.iter()) {
+            assert_eq!(lhs.position, rhs.position);
+            assert_eq!(lhs.normal, rhs.normal);
+            assert_eq!(
+                lhs.tex_coord,
+                glam::vec2(rhs.tex_coord.x, 1.0 - rhs.tex_coord.y)
+            );
+        }
+    }
+}
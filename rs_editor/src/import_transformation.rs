@@ -0,0 +1,181 @@
+use rs_artifact::{
+    mesh_vertex::MeshVertex,
+    morph_target::MorphTarget,
+    node_anim::{ERotationTrack, EVectorTrack, NodeAnim, QuantizedQuat},
+    skeleton::{SkeletonBone, SkeletonMeshHierarchyNode},
+    skin_mesh::SkinMeshVertex,
+};
+use rs_engine::scene_node::SceneNode;
+use rs_foundation::new::SingleThreadMutType;
+use std::collections::HashMap;
+
+/// Converts the data of a model file to the space of the engine. The scale is uniform and the
+/// rotation maps axes to axes, so a transformation of the file is converted by conjugating it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImportTransformation {
+    pub rotation: glam::Quat,
+    pub scale: f32,
+}
+
+impl ImportTransformation {
+    pub fn is_identity(&self) -> bool {
+        self.rotation == glam::Quat::IDENTITY && self.scale == 1.0
+    }
+
+    pub fn to_matrix(&self) -> glam::Mat4 {
+        glam::Mat4::from_scale_rotation_translation(
+            glam::Vec3::splat(self.scale),
+            self.rotation,
+            glam::Vec3::ZERO,
+        )
+    }
+
+    pub fn transform_point(&self, point: glam::Vec3) -> glam::Vec3 {
+        self.rotation * point * self.scale
+    }
+
+    pub fn transform_direction(&self, direction: glam::Vec3) -> glam::Vec3 {
+        self.rotation * direction
+    }
+
+    pub fn transform_matrix(&self, matrix: glam::Mat4) -> glam::Mat4 {
+        let transformation = self.to_matrix();
+        transformation * matrix * transformation.inverse()
+    }
+
+    pub fn transform_mesh_vertexes(&self, vertexes: &mut [MeshVertex]) {
+        for vertex in vertexes {
+            vertex.position = self.transform_point(vertex.position);
+            vertex.normal = self.transform_direction(vertex.normal);
+            vertex.tangent = self.transform_direction(vertex.tangent);
+            vertex.bitangent = self.transform_direction(vertex.bitangent);
+        }
+    }
+
+    pub fn transform_skin_mesh_vertexes(&self, vertexes: &mut [SkinMeshVertex]) {
+        for vertex in vertexes {
+            vertex.position = self.transform_point(vertex.position);
+            vertex.normal = self.transform_direction(vertex.normal);
+            vertex.tangent = self.transform_direction(vertex.tangent);
+            vertex.bitangent = self.transform_direction(vertex.bitangent);
+        }
+    }
+
+    pub fn transform_morph_targets(&self, morph_targets: &mut [MorphTarget]) {
+        for morph_target in morph_targets {
+            for delta in morph_target.position_deltas.iter_mut() {
+                *delta = self.transform_point(*delta);
+            }
+            for delta in morph_target.normal_deltas.iter_mut() {
+                *delta = self.transform_direction(*delta);
+            }
+        }
+    }
+
+    pub fn transform_skeleton(
+        &self,
+        bones: &mut HashMap<String, SkeletonBone>,
+        skeleton_mesh_hierarchy: &mut HashMap<String, SkeletonMeshHierarchyNode>,
+    ) {
+        for bone in bones.values_mut() {
+            bone.offset_matrix = self.transform_matrix(bone.offset_matrix);
+        }
+        for node in skeleton_mesh_hierarchy.values_mut() {
+            node.transformation = self.transform_matrix(node.transformation);
+        }
+    }
+
+    /// The keys are the local transformations of the node, which are conjugated like matrices.
+    pub fn transform_node_anim(&self, node_anim: &mut NodeAnim) {
+        self.transform_vector_track(&mut node_anim.position_track, |x| self.transform_point(x));
+        // Swaps the components of the scale like the rotation swaps the axes.
+        self.transform_vector_track(&mut node_anim.scaling_track, |x| {
+            self.transform_direction(x).abs()
+        });
+        let inverse_rotation = self.rotation.inverse();
+        let transform_rotation = |x: glam::Quat| self.rotation * x * inverse_rotation;
+        match &mut node_anim.rotation_track {
+            ERotationTrack::Keys(keys) => {
+                for key in keys {
+                    key.value = transform_rotation(key.value);
+                }
+            }
+            ERotationTrack::SparseKeys { values, .. } | ERotationTrack::Uniform { values, .. } => {
+                for value in values {
+                    *value = QuantizedQuat::encode(transform_rotation(value.decode()));
+                }
+            }
+        }
+    }
+
+    fn transform_vector_track(
+        &self,
+        track: &mut EVectorTrack,
+        transform: impl Fn(glam::Vec3) -> glam::Vec3,
+    ) {
+        match track {
+            EVectorTrack::Keys(keys) => {
+                for key in keys {
+                    key.value = transform(key.value);
+                }
+            }
+            EVectorTrack::SparseKeys { values, .. } | EVectorTrack::Uniform { values, .. } => {
+                for value in values {
+                    *value = transform(*value);
+                }
+            }
+        }
+    }
+
+    pub fn transform_scene_node(&self, scene_node: &SingleThreadMutType<SceneNode>) {
+        let mut scene_node = scene_node.borrow_mut();
+        let transformation = self.transform_matrix(scene_node.get_transformation());
+        scene_node.set_transformation(transformation);
+        for child in &scene_node.childs {
+            self.transform_scene_node(child);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ImportTransformation;
+    use rs_artifact::node_anim::{ERotationTrack, EVectorTrack, NodeAnim, QuatKey, VectorKey};
+
+    #[test]
+    fn test_case_transform_node_anim() {
+        let import_transformation = ImportTransformation {
+            rotation: glam::Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2),
+            scale: 0.01,
+        };
+        let local_transformation = glam::Mat4::from_scale_rotation_translation(
+            glam::vec3(1.0, 2.0, 3.0),
+            glam::Quat::from_rotation_z(0.5),
+            glam::vec3(100.0, 200.0, 300.0),
+        );
+        let (scale, rotation, translation) = local_transformation.to_scale_rotation_translation();
+        let mut node_anim = NodeAnim {
+            node: String::from("Root"),
+            position_track: EVectorTrack::Keys(vec![VectorKey {
+                time: 0.0,
+                value: translation,
+            }]),
+            scaling_track: EVectorTrack::Keys(vec![VectorKey {
+                time: 0.0,
+                value: scale,
+            }]),
+            rotation_track: ERotationTrack::Keys(vec![QuatKey {
+                time: 0.0,
+                value: rotation,
+            }]),
+        };
+        import_transformation.transform_node_anim(&mut node_anim);
+        let animated_transformation = glam::Mat4::from_scale_rotation_translation(
+            node_anim.scaling_track.sample(0.0).unwrap(),
+            node_anim.rotation_track.sample(0.0).unwrap(),
+            node_anim.position_track.sample(0.0).unwrap(),
+        );
+        let expected = import_transformation.transform_matrix(local_transformation);
+        assert!(animated_transformation.abs_diff_eq(expected, 1e-4));
+    }
+}
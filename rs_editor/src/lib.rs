@@ -1,4 +1,6 @@
+pub mod animation_cook;
 pub mod build_config;
+pub mod cluster_lod_cook;
 pub mod content_folder;
 pub mod custom_event;
 pub mod data_source;
@@ -6,11 +8,16 @@ pub mod editor;
 pub mod editor_context;
 pub mod editor_ui;
 pub mod error;
+pub mod gltf_exporter;
+pub mod gltf_loader;
+pub mod import_transformation;
 pub mod material;
 pub mod material_resolve;
+pub mod mesh_cook;
 pub mod model_loader;
 pub mod project;
 pub mod project_context;
+pub mod texture_cook;
 pub mod thumbnail_cache;
 pub mod ui;
 pub mod watch_shader;
@@ -39,6 +39,8 @@ struct ResolveContext<'a> {
     current_group: usize,
     current_binding: usize,
     used_material_paramenters_collections: Vec<SingleThreadMutType<MaterialParamentersCollection>>,
+    /// Nodes whose variables are already declared, nodes can be shared by several attributes.
+    resolved_nodes: HashSet<NodeId>,
 }
 
 impl<'a> ResolveContext<'a> {
@@ -74,6 +76,7 @@ impl<'a> ResolveContext<'a> {
             current_group: 0,
             current_binding: 0,
             used_material_paramenters_collections: vec![],
+            resolved_nodes: HashSet::new(),
         }
     }
 
@@ -129,6 +132,7 @@ impl<'a> ResolveContext<'a> {
         }
         if options.is_skin {
             group_binding!(skin_constants_binding, "SKIN_CONSTANTS");
+            group_binding!(morph_target_deltas_binding, "MORPH_TARGET_DELTAS");
         }
     }
 
@@ -152,6 +156,7 @@ impl<'a> ResolveContext<'a> {
             cluster_light_binding: None,
             cluster_light_index_binding: None,
             material_paramenters_collection_bindings: HashSet::new(),
+            morph_target_deltas_binding: None,
         };
         let is_support_cluster_light = true;
         let mut definitions: Vec<String> = vec![
@@ -195,6 +200,10 @@ impl<'a> ResolveContext<'a> {
                 "SKELETON_MAX_BONES={}",
                 rs_render::global_shaders::skeleton_shading::NUM_MAX_BONE
             ));
+            definitions.push(format!(
+                "MORPH_WEIGHT_VECTORS={}",
+                rs_render::global_shaders::skeleton_shading::NUM_MAX_MORPH_TARGET / 4
+            ));
         }
         let shader_code = rs_shader_compiler::pre_process::pre_process(
             &shader_path,
@@ -212,6 +221,15 @@ impl<'a> ResolveContext<'a> {
                 map_texture.group, map_texture.binding, name
             );
             texture_uniform_code += &line;
+            if let Some(sampler_binding) = map_texture.sampler_binding {
+                let line = format!(
+                    "@group({}) @binding({}) var {}: sampler;\n",
+                    map_texture.group,
+                    sampler_binding,
+                    map_texture.get_sampler_bind_name()
+                );
+                texture_uniform_code += &line;
+            }
         }
         let shader_code = shader_code.replace("@USER_TEXTURES@", &texture_uniform_code);
 
@@ -295,6 +313,8 @@ impl<'a> ResolveContext<'a> {
         resolve_attribute!(opacity, 4, convert_to_f32);
         resolve_attribute!(clear_coat, 5, convert_to_f32);
         resolve_attribute!(clear_coat_roughness, 6, convert_to_f32);
+        resolve_attribute!(ambient_occlusion, 7, convert_to_f32);
+        resolve_attribute!(emissive, 8, convert_to_vec3);
 
         Ok(result)
     }
@@ -319,10 +339,7 @@ impl<'a> ResolveContext<'a> {
             node_io_info
                 .inputs
                 .get(&input)
-                .and_then(|x| {
-                    let var_name = node_var_name(x.node);
-                    Some(var_name)
-                })
+                .map(out_pin_var_name)
                 .unwrap_or_else(|| attribute_value_literal.to_string())
         };
 
@@ -343,7 +360,6 @@ impl<'a> ResolveContext<'a> {
         // let Some(input) = node_io_info.inputs.get(&input) else {
         //     panic!()
         // };
-        lines.reverse();
         // Some(lines)
 
         // let mut lines: Vec<String> = node_io_info
@@ -371,21 +387,20 @@ impl<'a> ResolveContext<'a> {
         material_info: &mut MaterialInfo,
         // user_texture_binding_start: usize,
     ) {
-        let node = self.snarl.get_node(node_id).expect("Not null");
-        let line = self.resolve_node(
-            node_id,
-            node,
-            // resolve_context,
-            material_info,
-            // snarl,
-            // user_texture_binding_start,
-        );
-        lines.push(line);
-        let out_pin_ids: Vec<_> = {
+        // Inputs are declared before the node which reads them.
+        if !self.resolved_nodes.insert(node_id) {
+            return;
+        }
+        let mut inputs: Vec<(usize, OutPinId)> = {
             let node_io_info = self.nodes.get(&node_id).unwrap();
-            node_io_info.inputs.iter().map(|x| x.1.clone()).collect()
+            node_io_info
+                .inputs
+                .iter()
+                .map(|(input, out_pin_id)| (*input, *out_pin_id))
+                .collect()
         };
-        for out_pin_id in out_pin_ids {
+        inputs.sort_by_key(|x| x.0);
+        for (_, out_pin_id) in inputs {
             self.walk_resolve_node(
                 out_pin_id.node,
                 // resolve_context,
@@ -395,17 +410,16 @@ impl<'a> ResolveContext<'a> {
                 // user_texture_binding_start,
             );
         }
-
-        // for (_, out_pin_id) in &node_io_info.inputs {
-        //     self.walk_resolve_node(
-        //         out_pin_id.node,
-        //         // resolve_context,
-        //         // snarl,
-        //         lines,
-        //         material_info,
-        //         // user_texture_binding_start,
-        //     );
-        // }
+        let node = self.snarl.get_node(node_id).expect("Not null");
+        let line = self.resolve_node(
+            node_id,
+            node,
+            // resolve_context,
+            material_info,
+            // snarl,
+            // user_texture_binding_start,
+        );
+        lines.push(line);
     }
 
     fn resolve_node(
@@ -420,7 +434,7 @@ impl<'a> ResolveContext<'a> {
         // let _ = snarl;
         let var_name = node_var_name(node_id);
         match &node.node_type {
-            EMaterialNodeType::Add(v1, v2) => {
+            EMaterialNodeType::Add(v1, v2) | EMaterialNodeType::Multiply(v1, v2) => {
                 let inputs = &self
                     .nodes
                     .get(&node_id)
@@ -428,50 +442,65 @@ impl<'a> ResolveContext<'a> {
                     .inputs;
                 let part_1 = inputs
                     .get(&0)
-                    .and_then(|out_pin_id| Some(node_var_name(out_pin_id.node)))
+                    .map(out_pin_var_name)
                     .unwrap_or_else(|| v1.literal());
                 let part_2 = inputs
                     .get(&1)
-                    .and_then(|out_pin_id| Some(node_var_name(out_pin_id.node)))
+                    .map(out_pin_var_name)
                     .unwrap_or_else(|| v2.literal());
-                format!("var {} = {} + {};", var_name, part_1, part_2)
+                let operator = match &node.node_type {
+                    EMaterialNodeType::Multiply(..) => "*",
+                    _ => "+",
+                };
+                format!("var {} = {} {} {};", var_name, part_1, operator, part_2)
             }
             EMaterialNodeType::Texture(texture_url) => {
                 if let Some(texture_url) = texture_url {
-                    let binding = self.next_binding();
+                    // The bindings of user textures are in order, textures which are used by
+                    // several nodes are bound once.
+                    let texture_binding = match material_info
+                        .map_textures
+                        .iter()
+                        .find(|x| &x.texture_url == texture_url)
+                    {
+                        Some(exist) => exist.clone(),
+                        None => {
+                            let binding = self.next_binding();
+                            let sampler_binding = self.next_binding();
+                            let texture_binding = TextureBinding {
+                                group: self.current_group,
+                                binding,
+                                texture_url: texture_url.clone(),
+                                sampler_binding: Some(sampler_binding),
+                            };
+                            material_info.map_textures.insert(texture_binding.clone());
+                            texture_binding
+                        }
+                    };
+                    let texture_var_name = texture_binding.get_texture_bind_name();
+                    let sampler_var_name = texture_binding.get_sampler_bind_name();
                     let inputs = &self
                         .nodes
                         .get(&node_id)
                         .expect("This node should not be null")
                         .inputs;
-                    let binding = TextureBinding {
-                        group: self.current_group,
-                        binding, //user_texture_binding_start + material_info.map_textures.len(),
-                        texture_url: texture_url.clone(),
-                    };
-                    let texture_var_name: String;
-                    if let Some(exist) = material_info
-                        .map_textures
-                        .iter()
-                        .find(|x| &x.texture_url == texture_url)
-                    {
-                        texture_var_name = exist.get_texture_bind_name();
-                    } else {
-                        texture_var_name = binding.get_texture_bind_name();
-                        material_info.map_textures.insert(binding);
-                    }
-                    inputs.get(&0).and_then(|out_pin_id| {
-                    Some(format!(
-                        "var {} = textureSample({}, base_color_sampler, {}).xyz;",
-                        var_name,
+                    let tex_coord = inputs
+                        .get(&0)
+                        .map(out_pin_var_name)
+                        .unwrap_or_else(|| "vertex_output.tex_coord0".to_string());
+                    let color = format!("{}_rgba", var_name);
+                    format!(
+                        "var {} = textureSample({}, {}, {});\n{}",
+                        color,
                         texture_var_name,
-                        node_var_name(out_pin_id.node)
-                    ))
-                }).unwrap_or_else(|| {
-                    format!("var {} = textureSample({}, base_color_sampler, vertex_output.tex_coord0).xyz;", var_name, texture_var_name)
-                })
+                        sampler_var_name,
+                        tex_coord,
+                        texture_output_lines(node_id, &format!("{}.xyz", color), |channel| {
+                            format!("{}.{}", color, channel)
+                        })
+                    )
                 } else {
-                    format!("var {} = vec4<f32>(0.0);", var_name)
+                    texture_output_lines(node_id, "vec3<f32>(0.0)", |_| "0.0".to_string())
                 }
             }
             EMaterialNodeType::TexCoord(tex_coord_index) => {
@@ -501,7 +530,7 @@ impl<'a> ResolveContext<'a> {
                     .inputs;
                 let part_1 = inputs
                     .get(&0)
-                    .and_then(|out_pin_id| Some(node_var_name(out_pin_id.node)))
+                    .map(out_pin_var_name)
                     .unwrap_or_else(|| v1.literal());
                 format!("var {} = {}", var_name, part_1)
             }
@@ -555,6 +584,32 @@ fn node_var_name(node_id: NodeId) -> String {
     format!("v{}", node_id.0)
 }
 
+/// The first output of a node is the variable of the node.
+fn out_pin_var_name(out_pin_id: &OutPinId) -> String {
+    if out_pin_id.output == 0 {
+        node_var_name(out_pin_id.node)
+    } else {
+        format!("{}_{}", node_var_name(out_pin_id.node), out_pin_id.output)
+    }
+}
+
+/// Declares the color and the channels of a texture node.
+fn texture_output_lines(node_id: NodeId, color: &str, channel: impl Fn(&str) -> String) -> String {
+    let mut lines = vec![format!("var {} = {};", node_var_name(node_id), color)];
+    for (output, name) in ["x", "y", "z", "w"].iter().enumerate() {
+        let out_pin_id = OutPinId {
+            node: node_id,
+            output: output + 1,
+        };
+        lines.push(format!(
+            "var {} = {};",
+            out_pin_var_name(&out_pin_id),
+            channel(name)
+        ));
+    }
+    lines.join("\n")
+}
+
 fn material_paramenters_collection_to_struct_string(
     material_paramenters_collection: &MaterialParamentersCollection,
 ) -> String {
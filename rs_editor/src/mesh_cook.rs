@@ -0,0 +1,218 @@
+use crate::project::MeshCookSettings;
+use rs_artifact::{
+    mesh_lod::MeshLod, mesh_vertex::MeshVertex, morph_target::MorphTarget,
+    quantized_mesh_vertex::EVertexEncoding, static_mesh::StaticMesh,
+};
+
+/// Slightly worse vertex cache efficiency is accepted for less overdraw.
+const OVERDRAW_THRESHOLD: f32 = 1.05;
+
+#[derive(Debug, Clone)]
+pub struct MeshLodSettings {
+    pub max_lod_count: usize,
+    /// The ratio of the index count of a level to the index count of the previous level.
+    pub reduction: f32,
+    /// The error relative to the mesh extents that the simplification is allowed to introduce.
+    pub target_error: f32,
+}
+
+impl Default for MeshLodSettings {
+    fn default() -> Self {
+        Self {
+            max_lod_count: 3,
+            reduction: 0.5,
+            target_error: 0.05,
+        }
+    }
+}
+
+pub fn cook_static_mesh(
+    static_mesh: &mut StaticMesh,
+    mesh_cook_settings: &MeshCookSettings,
+) -> anyhow::Result<()> {
+    if mesh_cook_settings.is_optimize_indexes {
+        optimize_indexes(
+            &mut static_mesh.indexes,
+            &mut static_mesh.lods,
+            &mut static_mesh.vertexes,
+            &mut static_mesh.morph_targets,
+        )?;
+    }
+    if mesh_cook_settings.is_build_cluster_lod
+        && static_mesh.indexes.len() / 3 >= mesh_cook_settings.cluster_lod_min_triangle_count
+    {
+        let positions = static_mesh
+            .vertexes
+            .iter()
+            .map(|x| x.position)
+            .collect::<Vec<glam::Vec3>>();
+        static_mesh.cluster_lod = Some(crate::cluster_lod_cook::build_cluster_lod(
+            &positions,
+            &static_mesh.indexes,
+            &crate::cluster_lod_cook::ClusterLodSettings::default(),
+        )?);
+    }
+    static_mesh.vertex_encoding = if mesh_cook_settings.is_quantize_vertexes {
+        EVertexEncoding::Quantized
+    } else {
+        EVertexEncoding::Full
+    };
+    Ok(())
+}
+
+fn make_vertex_data_adapter<T>(
+    vertexes: &[T],
+    position_offset: usize,
+) -> anyhow::Result<meshopt::VertexDataAdapter<'_>> {
+    meshopt::VertexDataAdapter::new(
+        rs_foundation::cast_to_raw_buffer(vertexes),
+        std::mem::size_of::<T>(),
+        position_offset,
+    )
+    .map_err(|err| anyhow::anyhow!("{}", err))
+}
+
+/// Simplifies the mesh into levels with fewer triangles, every level is simplified from the
+/// original indexes and references the original vertexes.
+pub fn generate_lods<T>(
+    vertexes: &[T],
+    position_offset: usize,
+    indexes: &[u32],
+    mesh_lod_settings: &MeshLodSettings,
+) -> anyhow::Result<Vec<MeshLod>> {
+    let mut lods = vec![];
+    if indexes.is_empty() || indexes.len() % 3 != 0 {
+        return Ok(lods);
+    }
+    let vertex_data_adapter = make_vertex_data_adapter(vertexes, position_offset)?;
+    let mut index_count = indexes.len();
+    let mut screen_size = 1.0;
+    for _ in 0..mesh_lod_settings.max_lod_count {
+        let target_index_count =
+            (index_count as f32 * mesh_lod_settings.reduction) as usize / 3 * 3;
+        if target_index_count < 3 {
+            break;
+        }
+        let simplified = meshopt::simplify(
+            indexes,
+            &vertex_data_adapter,
+            target_index_count,
+            mesh_lod_settings.target_error,
+        );
+        // Stop when the error limit prevents any meaningful reduction.
+        if simplified.is_empty() || simplified.len() as f32 > index_count as f32 * 0.9 {
+            break;
+        }
+        index_count = simplified.len();
+        screen_size *= 0.5;
+        lods.push(MeshLod {
+            screen_size,
+            indexes: simplified,
+        });
+    }
+    Ok(lods)
+}
+
+/// Reorders triangles for the post transform vertex cache and overdraw, then reorders vertexes
+/// in the order they are first referenced and drops unreferenced vertexes. Vertexes which are
+/// only referenced by levels of detail are placed after the others. Deltas of morph targets
+/// follow their vertexes.
+fn optimize_indexes(
+    indexes: &mut Vec<u32>,
+    lods: &mut [MeshLod],
+    vertexes: &mut Vec<MeshVertex>,
+    morph_targets: &mut [MorphTarget],
+) -> anyhow::Result<()> {
+    if indexes.is_empty() || indexes.len() % 3 != 0 {
+        return Ok(());
+    }
+    let vertex_count = vertexes.len();
+    let all_indexes = indexes
+        .iter()
+        .chain(lods.iter().flat_map(|x| x.indexes.iter()));
+    if let Some(index) = all_indexes.max().filter(|x| **x as usize >= vertex_count) {
+        return Err(anyhow::anyhow!(
+            "Index {} is out of range of {} vertexes",
+            index,
+            vertex_count
+        ));
+    }
+    meshopt::optimize_vertex_cache_in_place(indexes, vertexes.len());
+    let vertex_data_adapter =
+        make_vertex_data_adapter(vertexes, std::mem::offset_of!(MeshVertex, position))?;
+    meshopt::optimize_overdraw_in_place(indexes, &vertex_data_adapter, OVERDRAW_THRESHOLD);
+    for lod in lods.iter_mut() {
+        meshopt::optimize_vertex_cache_in_place(&mut lod.indexes, vertexes.len());
+    }
+
+    let mut remap: Vec<Option<u32>> = vec![None; vertexes.len()];
+    let mut remapped_vertexes: Vec<MeshVertex> = Vec::with_capacity(vertexes.len());
+    let mut old_indexes: Vec<usize> = Vec::with_capacity(vertexes.len());
+    let mut remap_index = |index: &mut u32| {
+        *index = *remap[*index as usize].get_or_insert_with(|| {
+            remapped_vertexes.push(vertexes[*index as usize]);
+            old_indexes.push(*index as usize);
+            (remapped_vertexes.len() - 1) as u32
+        });
+    };
+    indexes.iter_mut().for_each(&mut remap_index);
+    for lod in lods.iter_mut() {
+        lod.indexes.iter_mut().for_each(&mut remap_index);
+    }
+    for morph_target in morph_targets.iter_mut() {
+        let remap_deltas = |deltas: &[glam::Vec3]| -> Vec<glam::Vec3> {
+            if deltas.is_empty() {
+                return vec![];
+            }
+            old_indexes
+                .iter()
+                .map(|x| deltas.get(*x).copied().unwrap_or(glam::Vec3::ZERO))
+                .collect()
+        };
+        morph_target.position_deltas = remap_deltas(&morph_target.position_deltas);
+        morph_target.normal_deltas = remap_deltas(&morph_target.normal_deltas);
+    }
+    *vertexes = remapped_vertexes;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::optimize_indexes;
+    use rs_artifact::{mesh_lod::MeshLod, mesh_vertex::MeshVertex};
+
+    fn make_vertexes(count: usize) -> Vec<MeshVertex> {
+        (0..count)
+            .map(|x| MeshVertex {
+                position: glam::vec3(x as f32, (x % 2) as f32, 0.0),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_case_optimize_indexes() {
+        let mut vertexes = make_vertexes(5);
+        let mut indexes = vec![0, 1, 2];
+        let mut lods = vec![MeshLod {
+            screen_size: 0.5,
+            indexes: vec![0, 1, 4],
+        }];
+        optimize_indexes(&mut indexes, &mut lods, &mut vertexes, &mut []).unwrap();
+        assert_eq!(vertexes.len(), 4);
+        assert!(indexes.iter().all(|x| *x < 3));
+        assert_eq!(vertexes[lods[0].indexes[2] as usize].position.x, 4.0);
+
+        let mut lods = vec![MeshLod {
+            screen_size: 0.5,
+            indexes: vec![0, 1, 4],
+        }];
+        let result = optimize_indexes(
+            &mut vec![0, 1, 2],
+            &mut lods,
+            &mut make_vertexes(3),
+            &mut [],
+        );
+        assert!(result.is_err());
+    }
+}
@@ -1,7 +1,16 @@
+use crate::{
+    gltf_loader::GltfScene,
+    mesh_cook::MeshLodSettings,
+    project::{EModelImporterType, ModelAssetImportSettings, ModelImportSettings},
+    ui::material_view::{Attribute, EMaterialNodeType, EValueType, MaterialNode},
+};
 use anyhow::{anyhow, Context};
+use egui_snarl::{InPinId, NodeId, OutPinId, Snarl};
 use glam::Vec3Swizzles;
 use rs_artifact::{
     mesh_vertex::MeshVertex,
+    morph_target::{MorphAnim, MorphTarget, MorphWeightKey},
+    quantized_mesh_vertex::EVertexEncoding,
     skin_mesh::{SkinMesh, SkinMeshVertex},
 };
 use rs_core_minimal::name_generator::NameGenerator;
@@ -30,6 +39,199 @@ pub struct MeshCluster {
     pub textures_dic: HashMap<TextureType, String>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EAlphaMode {
+    Opaque,
+    /// Fragments with an alpha below the cutoff are discarded.
+    Mask(f32),
+    Blend,
+}
+
+/// A metallic roughness material of an imported model, textures are absolute file paths.
+#[derive(Debug, Clone)]
+pub struct ImportedMaterial {
+    pub name: String,
+    pub base_color_factor: glam::Vec4,
+    pub base_color_texture: Option<PathBuf>,
+    pub metallic_factor: f32,
+    pub roughness_factor: f32,
+    /// Roughness is in the green channel and metallic is in the blue channel.
+    pub metallic_roughness_texture: Option<PathBuf>,
+    /// Separate grayscale maps, they are used if there is no metallic roughness texture.
+    pub metallic_texture: Option<PathBuf>,
+    pub roughness_texture: Option<PathBuf>,
+    pub normal_texture: Option<PathBuf>,
+    pub occlusion_texture: Option<PathBuf>,
+    pub emissive_factor: glam::Vec3,
+    pub emissive_texture: Option<PathBuf>,
+    /// A grayscale map, the alpha of the base color texture is used if there is none.
+    pub opacity_texture: Option<PathBuf>,
+    pub alpha_mode: EAlphaMode,
+    pub is_double_sided: bool,
+}
+
+impl ImportedMaterial {
+    /// The constant inputs of the material graph, textures are multiplied with them.
+    pub fn to_attribute(&self) -> Attribute {
+        Attribute {
+            base_color: EValueType::Vec3(self.base_color_factor.truncate()),
+            metallic: EValueType::F32(self.metallic_factor),
+            roughness: EValueType::F32(self.roughness_factor),
+            opacity: EValueType::F32(match self.alpha_mode {
+                EAlphaMode::Opaque => 1.0,
+                EAlphaMode::Mask(_) | EAlphaMode::Blend => self.base_color_factor.w,
+            }),
+            emissive: EValueType::Vec3(self.emissive_factor),
+            ..Default::default()
+        }
+    }
+
+    /// Every texture which is referenced by the material.
+    pub fn get_textures(&self) -> Vec<&PathBuf> {
+        [
+            &self.base_color_texture,
+            &self.metallic_roughness_texture,
+            &self.metallic_texture,
+            &self.roughness_texture,
+            &self.normal_texture,
+            &self.occlusion_texture,
+            &self.emissive_texture,
+            &self.opacity_texture,
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    /// Textures which store data instead of colors.
+    pub fn is_linear_texture(&self, texture: &Path) -> bool {
+        [
+            &self.metallic_roughness_texture,
+            &self.metallic_texture,
+            &self.roughness_texture,
+            &self.normal_texture,
+            &self.occlusion_texture,
+            &self.opacity_texture,
+        ]
+        .into_iter()
+        .flatten()
+        .any(|x| x == texture)
+    }
+
+    /// Builds a material graph, textures are multiplied with the constant factors. Textures
+    /// which are not found in `texture_urls` are skipped.
+    pub fn to_snarl(&self, texture_urls: &HashMap<PathBuf, url::Url>) -> Snarl<MaterialNode> {
+        let mut snarl = Snarl::new();
+        // The resolver looks for the sink node by the first id.
+        let sink_node_id = snarl.insert_node(
+            egui::pos2(0.0, 0.0),
+            MaterialNode {
+                node_type: EMaterialNodeType::Sink(self.to_attribute()),
+            },
+        );
+        let mut texture_node_ids: HashMap<url::Url, NodeId> = HashMap::new();
+        let mut row = 0;
+        let mut texture_output = |snarl: &mut Snarl<MaterialNode>,
+                                  texture: &Option<PathBuf>,
+                                  output: usize|
+         -> Option<OutPinId> {
+            let url = texture_urls.get(texture.as_ref()?)?;
+            let node_id = *texture_node_ids.entry(url.clone()).or_insert_with(|| {
+                row += 1;
+                snarl.insert_node(
+                    egui::pos2(-600.0, row as f32 * 200.0),
+                    MaterialNode {
+                        node_type: EMaterialNodeType::Texture(Some(url.clone())),
+                    },
+                )
+            });
+            Some(OutPinId {
+                node: node_id,
+                output,
+            })
+        };
+        // The attribute inputs of the sink node, see `TEXTURE_OUTPUT_NAMES` for the outputs.
+        let mut inputs: Vec<(usize, OutPinId, Option<EValueType>)> = vec![];
+        if let Some(out_pin_id) = texture_output(&mut snarl, &self.base_color_texture, 0) {
+            let factor = self.base_color_factor.truncate();
+            let factor = (factor != glam::Vec3::ONE).then_some(EValueType::Vec3(factor));
+            inputs.push((0, out_pin_id, factor));
+        }
+        let metallic = texture_output(&mut snarl, &self.metallic_roughness_texture, 3)
+            .or_else(|| texture_output(&mut snarl, &self.metallic_texture, 1));
+        if let Some(out_pin_id) = metallic {
+            let factor =
+                (self.metallic_factor != 1.0).then_some(EValueType::F32(self.metallic_factor));
+            inputs.push((1, out_pin_id, factor));
+        }
+        let roughness = texture_output(&mut snarl, &self.metallic_roughness_texture, 2)
+            .or_else(|| texture_output(&mut snarl, &self.roughness_texture, 1));
+        if let Some(out_pin_id) = roughness {
+            let factor =
+                (self.roughness_factor != 1.0).then_some(EValueType::F32(self.roughness_factor));
+            inputs.push((2, out_pin_id, factor));
+        }
+        if let Some(out_pin_id) = texture_output(&mut snarl, &self.normal_texture, 0) {
+            inputs.push((3, out_pin_id, None));
+        }
+        // The occlusion is in the red channel.
+        if let Some(out_pin_id) = texture_output(&mut snarl, &self.occlusion_texture, 1) {
+            inputs.push((7, out_pin_id, None));
+        }
+        if let Some(out_pin_id) = texture_output(&mut snarl, &self.emissive_texture, 0) {
+            let factor = self.emissive_factor;
+            let factor = (factor != glam::Vec3::ONE).then_some(EValueType::Vec3(factor));
+            inputs.push((8, out_pin_id, factor));
+        }
+        if self.alpha_mode != EAlphaMode::Opaque {
+            let opacity = texture_output(&mut snarl, &self.opacity_texture, 1)
+                .or_else(|| texture_output(&mut snarl, &self.base_color_texture, 4));
+            if let Some(out_pin_id) = opacity {
+                let factor = self.base_color_factor.w;
+                let factor = (factor != 1.0).then_some(EValueType::F32(factor));
+                inputs.push((4, out_pin_id, factor));
+            }
+        }
+
+        for (input, out_pin_id, factor) in inputs {
+            let out_pin_id = match factor {
+                Some(factor) => {
+                    let one = match factor {
+                        EValueType::Vec3(_) => EValueType::Vec3(glam::Vec3::ONE),
+                        _ => EValueType::F32(1.0),
+                    };
+                    let node_id = snarl.insert_node(
+                        egui::pos2(-300.0, input as f32 * 100.0),
+                        MaterialNode {
+                            node_type: EMaterialNodeType::Multiply(one, factor),
+                        },
+                    );
+                    snarl.connect(
+                        out_pin_id,
+                        InPinId {
+                            node: node_id,
+                            input: 0,
+                        },
+                    );
+                    OutPinId {
+                        node: node_id,
+                        output: 0,
+                    }
+                }
+                None => out_pin_id,
+            };
+            snarl.connect(
+                out_pin_id,
+                InPinId {
+                    node: sink_node_id,
+                    input,
+                },
+            );
+        }
+        snarl
+    }
+}
+
 pub struct LoadResult {
     pub asset_reference: String,
     pub static_meshes: Vec<Rc<RefCell<rs_engine::content::static_mesh::StaticMesh>>>,
@@ -38,19 +240,68 @@ pub struct LoadResult {
     pub node_animations:
         Vec<Rc<RefCell<rs_engine::content::skeleton_animation::SkeletonAnimation>>>,
     pub actor: SingleThreadMutType<rs_engine::actor::Actor>,
+    pub materials: Vec<ImportedMaterial>,
+    /// The index of the material of static meshes and skeleton meshes.
+    pub mesh_materials: HashMap<url::Url, usize>,
 }
 
 pub struct ModelLoader {
     scene_cache: HashMap<PathBuf, Rc<rs_assimp::scene::Scene<'static>>>,
+    gltf_scene_cache: HashMap<PathBuf, Rc<GltfScene>>,
+    import_settings: ModelImportSettings,
+    /// Settings of imported files are looked up by the path relative to this folder.
+    asset_folder: PathBuf,
 }
 
 impl ModelLoader {
     pub fn new() -> ModelLoader {
         ModelLoader {
             scene_cache: HashMap::new(),
+            gltf_scene_cache: HashMap::new(),
+            import_settings: ModelImportSettings::default(),
+            asset_folder: PathBuf::new(),
         }
     }
 
+    pub fn set_import_settings(
+        &mut self,
+        import_settings: ModelImportSettings,
+        asset_folder: &Path,
+    ) {
+        self.import_settings = import_settings;
+        self.asset_folder = asset_folder.to_path_buf();
+    }
+
+    pub fn get_asset_import_settings(&self, file_path: &Path) -> ModelAssetImportSettings {
+        match file_path
+            .strip_prefix(&self.asset_folder)
+            .ok()
+            .and_then(|x| x.to_str())
+        {
+            Some(asset_reference) => self.import_settings.get_asset_settings(asset_reference),
+            None => ModelAssetImportSettings::default(),
+        }
+    }
+
+    /// Drops the cached scene of the file, the next load reads the file again.
+    pub fn unload(&mut self, file_path: &Path) {
+        self.scene_cache.remove(file_path);
+        self.gltf_scene_cache.remove(file_path);
+    }
+
+    fn load_gltf(&mut self, file_path: &Path) -> anyhow::Result<Rc<GltfScene>> {
+        if let Some(gltf_scene) = self.gltf_scene_cache.get(file_path) {
+            return Ok(gltf_scene.clone());
+        }
+        let mut gltf_scene = GltfScene::from_file(file_path)
+            .context(format!("Failed to load glTF file: {:?}", file_path))?;
+        gltf_scene.apply_import_settings(&self.get_asset_import_settings(file_path));
+        let gltf_scene = Rc::new(gltf_scene);
+        self.gltf_scene_cache
+            .insert(file_path.to_path_buf(), gltf_scene.clone());
+        Ok(gltf_scene)
+    }
+
     fn get_texture_absolute_path(
         model_file_path: &Path,
         texture: &russimp::material::Texture,
@@ -319,7 +570,12 @@ impl ModelLoader {
     }
 
     pub fn load(&mut self, file_path: &Path) -> anyhow::Result<()> {
+        if self.import_settings.get_importer(file_path) == EModelImporterType::Gltf {
+            self.load_gltf(file_path)?;
+            return Ok(());
+        }
         if !self.scene_cache.contains_key(file_path) {
+            let import_settings = self.get_asset_import_settings(file_path);
             let mut props = rs_assimp::property_store::PropertyStore::new();
             props.set_property_integer(
                 &rs_assimp::config::AI_CONFIG_FBX_USE_SKELETON_BONE_CONTAINER,
@@ -329,8 +585,7 @@ impl ModelLoader {
                 file_path.to_path_buf(),
                 Rc::new(rs_assimp::scene::Scene::from_file_with_properties(
                     file_path,
-                    rs_assimp::post_process_steps::PostProcessSteps::Triangulate
-                        | rs_assimp::post_process_steps::PostProcessSteps::PopulateArmatureData,
+                    Self::make_post_process_steps(&import_settings),
                     props,
                 )?),
             );
@@ -338,6 +593,20 @@ impl ModelLoader {
         Ok(())
     }
 
+    fn make_post_process_steps(
+        import_settings: &ModelAssetImportSettings,
+    ) -> rs_assimp::post_process_steps::PostProcessSteps {
+        use rs_assimp::post_process_steps::PostProcessSteps;
+        let mut steps = PostProcessSteps::Triangulate | PostProcessSteps::PopulateArmatureData;
+        if import_settings.is_generate_tangents {
+            steps |= PostProcessSteps::CalcTangentSpace;
+        }
+        if import_settings.is_merge_meshes {
+            steps |= PostProcessSteps::OptimizeMeshes | PostProcessSteps::OptimizeGraph;
+        }
+        steps
+    }
+
     pub fn get(&self, file_path: &Path) -> anyhow::Result<Rc<rs_assimp::scene::Scene<'static>>> {
         let cache_scene = self.scene_cache.get(file_path);
         match cache_scene {
@@ -360,6 +629,15 @@ impl ModelLoader {
                 let relative_path = &static_mesh.asset_info.relative_path;
                 let path = asset_folder.join(relative_path);
 
+                if let Some(gltf_scene) = self.gltf_scene_cache.get(&path) {
+                    let static_mesh = Arc::new(gltf_scene.to_static_mesh(
+                        &static_mesh.asset_info.path,
+                        static_mesh.asset_info.get_url(),
+                    )?);
+                    resource_manager.add_static_mesh(static_mesh.url.clone(), static_mesh.clone());
+                    return Ok(static_mesh);
+                }
+
                 let scene_cache = self
                     .scene_cache
                     .get(&path)
@@ -420,11 +698,32 @@ impl ModelLoader {
                     }
                 }
 
+                let import_transformation = self
+                    .get_asset_import_settings(&path)
+                    .get_import_transformation();
+                import_transformation.transform_mesh_vertexes(&mut vertex_buffer);
+                let lods = crate::mesh_cook::generate_lods(
+                    &vertex_buffer,
+                    std::mem::offset_of!(MeshVertex, position),
+                    &index_buffer,
+                    &MeshLodSettings::default(),
+                )
+                .unwrap_or_else(|err| {
+                    log::warn!("Failed to generate lods, {}", err);
+                    vec![]
+                });
+                let mut morph_targets =
+                    Self::make_morph_targets(&imported_mesh.borrow(), vertex_buffer.len());
+                import_transformation.transform_morph_targets(&mut morph_targets);
                 let static_mesh = rs_artifact::static_mesh::StaticMesh {
                     vertexes: vertex_buffer,
                     indexes: index_buffer,
                     name: static_mesh.asset_info.path.clone(),
                     url: static_mesh.asset_info.get_url(),
+                    vertex_encoding: EVertexEncoding::Full,
+                    lods,
+                    morph_targets,
+                    cluster_lod: None,
                 };
                 let static_mesh = Arc::new(static_mesh);
                 resource_manager.add_static_mesh(static_mesh.url.clone(), static_mesh.clone());
@@ -449,6 +748,15 @@ impl ModelLoader {
             Some(loaded_mesh) => loaded_mesh.clone(),
             None => {
                 let path = asset_folder.join(skeleton_mesh.get_relative_path());
+                if let Some(gltf_scene) = self.gltf_scene_cache.get(&path) {
+                    let skin_mesh = Arc::new(
+                        gltf_scene
+                            .to_skin_mesh(&skeleton_mesh.get_skeleton_mesh_name(), url.clone())
+                            .expect("Find matching mesh."),
+                    );
+                    resource_manager.add_skin_mesh(url, skin_mesh.clone());
+                    return skin_mesh;
+                }
                 let scene_cache = self
                     .scene_cache
                     .get(&path)
@@ -531,12 +839,31 @@ impl ModelLoader {
                     .iter()
                     .map(|x| x.borrow().node.clone().unwrap().borrow().path.clone())
                     .collect();
+                let import_transformation = self
+                    .get_asset_import_settings(&path)
+                    .get_import_transformation();
+                import_transformation.transform_skin_mesh_vertexes(&mut vertex_buffer);
+                let lods = crate::mesh_cook::generate_lods(
+                    &vertex_buffer,
+                    std::mem::offset_of!(SkinMeshVertex, position),
+                    &index_buffer,
+                    &MeshLodSettings::default(),
+                )
+                .unwrap_or_else(|err| {
+                    log::warn!("Failed to generate lods, {}", err);
+                    vec![]
+                });
+                let mut morph_targets =
+                    Self::make_morph_targets(&imported_mesh.borrow(), vertex_buffer.len());
+                import_transformation.transform_morph_targets(&mut morph_targets);
                 let skin_mesh = SkinMesh {
                     name: skeleton_mesh.get_skeleton_mesh_name().clone(),
                     url: skeleton_mesh.asset_url.clone(),
                     vertexes: vertex_buffer,
                     indexes: index_buffer,
                     bone_paths,
+                    lods,
+                    morph_targets,
                 };
                 let skin_mesh = Arc::new(skin_mesh);
                 resource_manager.add_skin_mesh(skeleton_mesh.asset_url.clone(), skin_mesh.clone());
@@ -561,6 +888,18 @@ impl ModelLoader {
             Some(loaded_animation) => loaded_animation.clone(),
             None => {
                 let path = asset_folder.join(skeleton_animation.borrow().get_relative_path());
+                if let Some(gltf_scene) = self.gltf_scene_cache.get(&path) {
+                    let skeleton_animation = Arc::new(
+                        gltf_scene
+                            .to_skeleton_animation(
+                                &skeleton_animation.borrow().get_animation_name(),
+                                url.clone(),
+                            )
+                            .expect("Find matching animation."),
+                    );
+                    resource_manager.add_skeleton_animation(url, skeleton_animation.clone());
+                    return skeleton_animation;
+                }
                 let scene_cache = self
                     .scene_cache
                     .get(&path)
@@ -570,35 +909,45 @@ impl ModelLoader {
                     .iter()
                     .find(|x| x.name == skeleton_animation.borrow().get_animation_name())
                     .expect("Find matching animation.");
+                let import_transformation = self
+                    .get_asset_import_settings(&path)
+                    .get_import_transformation();
                 let mut channels: Vec<rs_artifact::node_anim::NodeAnim> = vec![];
                 for channel in &animation.channels {
-                    let node_anim = rs_artifact::node_anim::NodeAnim {
+                    let mut node_anim = rs_artifact::node_anim::NodeAnim {
                         node: channel.node.as_ref().unwrap().borrow().path.clone(),
-                        position_keys: channel
-                            .position_keys
-                            .iter()
-                            .map(|x| rs_artifact::node_anim::VectorKey {
-                                time: x.time,
-                                value: x.value,
-                            })
-                            .collect(),
-                        scaling_keys: channel
-                            .scaling_keys
-                            .iter()
-                            .map(|x| rs_artifact::node_anim::VectorKey {
-                                time: x.time,
-                                value: x.value,
-                            })
-                            .collect(),
-                        rotation_keys: channel
-                            .rotation_keys
-                            .iter()
-                            .map(|x| rs_artifact::node_anim::QuatKey {
-                                time: x.time,
-                                value: x.value,
-                            })
-                            .collect(),
+                        position_track: rs_artifact::node_anim::EVectorTrack::Keys(
+                            channel
+                                .position_keys
+                                .iter()
+                                .map(|x| rs_artifact::node_anim::VectorKey {
+                                    time: x.time,
+                                    value: x.value,
+                                })
+                                .collect(),
+                        ),
+                        scaling_track: rs_artifact::node_anim::EVectorTrack::Keys(
+                            channel
+                                .scaling_keys
+                                .iter()
+                                .map(|x| rs_artifact::node_anim::VectorKey {
+                                    time: x.time,
+                                    value: x.value,
+                                })
+                                .collect(),
+                        ),
+                        rotation_track: rs_artifact::node_anim::ERotationTrack::Keys(
+                            channel
+                                .rotation_keys
+                                .iter()
+                                .map(|x| rs_artifact::node_anim::QuatKey {
+                                    time: x.time,
+                                    value: x.value,
+                                })
+                                .collect(),
+                        ),
                     };
+                    import_transformation.transform_node_anim(&mut node_anim);
                     channels.push(node_anim);
                 }
                 let skeleton_animation = rs_artifact::skeleton_animation::SkeletonAnimation {
@@ -607,6 +956,7 @@ impl ModelLoader {
                     duration: animation.duration,
                     ticks_per_second: animation.ticks_per_second,
                     channels,
+                    morph_channels: Self::make_morph_channels(scene_cache, animation),
                 };
                 let skeleton_animation = Arc::new(skeleton_animation);
                 resource_manager.add_skeleton_animation(
@@ -623,6 +973,84 @@ impl ModelLoader {
         }
     }
 
+    /// Assimp replaces the vertexes of the mesh, morph targets store the offsets to them.
+    fn make_morph_targets(
+        imported_mesh: &rs_assimp::mesh::Mesh,
+        vertex_count: usize,
+    ) -> Vec<MorphTarget> {
+        let make_deltas = |replaced: &[glam::Vec3], base: &[glam::Vec3]| -> Vec<glam::Vec3> {
+            if replaced.is_empty() || base.is_empty() {
+                return vec![];
+            }
+            (0..vertex_count)
+                .map(|x| match (replaced.get(x), base.get(x)) {
+                    (Some(replaced), Some(base)) => *replaced - *base,
+                    _ => glam::Vec3::ZERO,
+                })
+                .collect()
+        };
+        imported_mesh
+            .anim_meshes
+            .iter()
+            .enumerate()
+            .map(|(i, anim_mesh)| MorphTarget {
+                name: if anim_mesh.name.is_empty() {
+                    format!("Target{}", i)
+                } else {
+                    anim_mesh.name.clone()
+                },
+                position_deltas: make_deltas(&anim_mesh.vertices, &imported_mesh.vertices),
+                normal_deltas: make_deltas(&anim_mesh.normals, &imported_mesh.normals),
+                default_weight: anim_mesh.weight,
+            })
+            .collect()
+    }
+
+    /// Some importers name the channels after the node which holds the meshes, every mesh of the
+    /// node gets its own channel.
+    fn make_morph_channels(
+        scene: &rs_assimp::scene::Scene,
+        animation: &rs_assimp::animation::Animation,
+    ) -> Vec<MorphAnim> {
+        let mut morph_channels = vec![];
+        for channel in &animation.morph_mesh_channels {
+            let keys: Vec<MorphWeightKey> = channel
+                .keys
+                .iter()
+                .map(|x| MorphWeightKey {
+                    time: x.time,
+                    targets: x.values.clone(),
+                    weights: x.weights.iter().map(|x| *x as f32).collect(),
+                })
+                .collect();
+            let mut mesh_names: Vec<String> = scene
+                .all_nodes
+                .values()
+                .filter(|x| x.borrow().name == channel.name)
+                .flat_map(|x| {
+                    x.borrow()
+                        .meshes
+                        .iter()
+                        .map(|x| x.borrow().name.clone())
+                        .collect::<Vec<String>>()
+                })
+                .collect();
+            if mesh_names.is_empty() || scene.meshes.iter().any(|x| x.borrow().name == channel.name)
+            {
+                mesh_names = vec![channel.name.clone()];
+            }
+            mesh_names.sort();
+            mesh_names.dedup();
+            for mesh in mesh_names {
+                morph_channels.push(MorphAnim {
+                    mesh,
+                    keys: keys.clone(),
+                });
+            }
+        }
+        morph_channels
+    }
+
     fn make_bones<'a>(
         node: Rc<RefCell<rs_assimp::node::Node<'a>>>,
         parent: Option<String>,
@@ -689,6 +1117,12 @@ impl ModelLoader {
             Some(loaded_skeleton) => loaded_skeleton.clone(),
             None => {
                 let path = asset_folder.join(skeleton.borrow().get_relative_path());
+                if let Some(gltf_scene) = self.gltf_scene_cache.get(&path) {
+                    let skeleton =
+                        Arc::new(gltf_scene.to_skeleton(url.clone()).expect("Find skeleton."));
+                    resource_manager.add_skeleton(url, skeleton.clone());
+                    return skeleton;
+                }
                 let scene = self
                     .scene_cache
                     .get(&path)
@@ -707,6 +1141,9 @@ impl ModelLoader {
                     &mut skeleton_mesh_hierarchy,
                 );
                 Self::make_bones(armature.clone(), None, &mut bones);
+                self.get_asset_import_settings(&path)
+                    .get_import_transformation()
+                    .transform_skeleton(&mut bones, &mut skeleton_mesh_hierarchy);
                 let skeleton = rs_artifact::skeleton::Skeleton {
                     name: armature.borrow().name.clone(),
                     url: skeleton.borrow().asset_url.clone(),
@@ -793,22 +1230,39 @@ impl ModelLoader {
         let mut name_generator = NameGenerator::new(exist_content_names);
         let mut actor_name_generator = NameGenerator::new(exist_actors_names);
 
-        let mut props = rs_assimp::property_store::PropertyStore::new();
-        props.set_property_bool(
-            &rs_assimp::config::AI_CONFIG_FBX_USE_SKELETON_BONE_CONTAINER,
-            true,
-        );
-        if !self.scene_cache.contains_key(file_path) {
-            self.scene_cache.insert(
-                file_path.to_path_buf(),
-                Rc::new(rs_assimp::scene::Scene::from_file_with_properties(
-                    file_path,
-                    rs_assimp::post_process_steps::PostProcessSteps::Triangulate
-                        | rs_assimp::post_process_steps::PostProcessSteps::PopulateArmatureData,
-                    props,
-                )?),
+        if self.import_settings.get_importer(file_path) == EModelImporterType::Gltf {
+            // The import settings are applied to the cached scene.
+            let gltf_scene = self.load_gltf(file_path)?;
+            return gltf_scene.to_load_result(
+                file_path,
+                asset_reference,
+                &mut name_generator,
+                &mut actor_name_generator,
             );
         }
+        self.load(file_path)?;
+        let load_result = self.assimp_scene_to_load_result(
+            file_path,
+            asset_reference,
+            &mut name_generator,
+            &mut actor_name_generator,
+        )?;
+        let import_transformation = self
+            .get_asset_import_settings(file_path)
+            .get_import_transformation();
+        if !import_transformation.is_identity() {
+            import_transformation.transform_scene_node(&load_result.actor.borrow().scene_node);
+        }
+        Ok(load_result)
+    }
+
+    fn assimp_scene_to_load_result(
+        &self,
+        file_path: &Path,
+        asset_reference: String,
+        name_generator: &mut NameGenerator,
+        actor_name_generator: &mut NameGenerator,
+    ) -> anyhow::Result<LoadResult> {
         let scene = self
             .scene_cache
             .get(file_path)
@@ -862,12 +1316,23 @@ impl ModelLoader {
             node_animations.push(SingleThreadMut::new(node_animation));
         }
 
+        let materials: Vec<ImportedMaterial> = scene
+            .materials
+            .iter()
+            .enumerate()
+            .map(|(index, material)| Self::to_imported_material(file_path, index, material))
+            .collect();
+        let mut mesh_materials: HashMap<url::Url, usize> = HashMap::new();
+
         for imported_mesh in &scene.meshes {
             let imported_mesh = imported_mesh.clone();
             let imported_mesh = imported_mesh.borrow();
             let name = imported_mesh.name.clone().replace("|", "_");
             let name = name_generator.next(&name);
             let url = build_content_file_url(&name).context(imported_mesh.name.clone())?;
+            if imported_mesh.material_index < materials.len() {
+                mesh_materials.insert(url.clone(), imported_mesh.material_index);
+            }
             if imported_mesh.bones.is_empty() {
                 let static_mesh = rs_engine::content::static_mesh::StaticMesh {
                     // asset_reference_name: imported_mesh.name.clone(),
@@ -928,6 +1393,7 @@ impl ModelLoader {
                     ),
                     childs: vec![],
                 }),
+                prefab: None,
             };
         } else {
             let scene_node = Self::node_to_scene_node_recursion(scene_root_node, &static_meshes);
@@ -944,6 +1410,7 @@ impl ModelLoader {
                     &scene.name
                 }),
                 scene_node,
+                prefab: None,
             };
         }
 
@@ -954,6 +1421,160 @@ impl ModelLoader {
             skeleton,
             node_animations,
             actor: SingleThreadMut::new(actor),
+            materials,
+            mesh_materials,
         })
     }
+
+    fn to_imported_material(
+        file_path: &Path,
+        index: usize,
+        material: &rs_assimp::material::Material,
+    ) -> ImportedMaterial {
+        use rs_assimp::texture_type::TextureType;
+
+        // Embedded textures are named like `*0`, they are not supported.
+        let texture = |texture_types: &[TextureType]| -> Option<PathBuf> {
+            texture_types.iter().find_map(|texture_type| {
+                let path = material.get_texture(*texture_type)?;
+                let path = file_path.parent()?.join(path.replace("\\", "/"));
+                path.is_file().then_some(path)
+            })
+        };
+        let factor = |key: &str| {
+            material
+                .get_float_array(key)
+                .and_then(|x| x.first().copied())
+        };
+        let base_color_factor = material
+            .get_float_array("$clr.base")
+            .or_else(|| material.get_float_array("$clr.diffuse"))
+            .map(|x| match x {
+                [r, g, b, a, ..] => glam::vec4(*r, *g, *b, *a),
+                [r, g, b] => glam::vec4(*r, *g, *b, 1.0),
+                _ => glam::Vec4::ONE,
+            })
+            .unwrap_or(glam::Vec4::ONE);
+        let opacity = factor("$mat.opacity").unwrap_or(1.0);
+        let base_color_factor = base_color_factor
+            .truncate()
+            .extend(base_color_factor.w * opacity);
+        let opacity_texture = texture(&[TextureType::Opacity]);
+        // Assimp reads the metallic roughness texture of glTF files as an unknown texture, other
+        // formats use the type for textures which are not known by assimp.
+        let is_gltf = file_path
+            .extension()
+            .is_some_and(|x| x.eq_ignore_ascii_case("gltf") || x.eq_ignore_ascii_case("glb"));
+        let metallic_roughness_texture = if is_gltf {
+            texture(&[TextureType::Unknown])
+        } else {
+            None
+        };
+        let metallic_texture = texture(&[TextureType::Metalness]);
+        // Textures are not scaled down if the factor is missing.
+        let default_metallic_factor =
+            if metallic_roughness_texture.is_some() || metallic_texture.is_some() {
+                1.0
+            } else {
+                0.0
+            };
+        let alpha_mode = if opacity_texture.is_some() || base_color_factor.w < 1.0 {
+            EAlphaMode::Blend
+        } else {
+            EAlphaMode::Opaque
+        };
+        ImportedMaterial {
+            name: material
+                .get_name()
+                .filter(|x| !x.is_empty())
+                .unwrap_or(format!("Material{}", index)),
+            base_color_factor,
+            base_color_texture: texture(&[TextureType::BaseColor, TextureType::Diffuse]),
+            metallic_factor: factor("$mat.metallicFactor").unwrap_or(default_metallic_factor),
+            roughness_factor: factor("$mat.roughnessFactor").unwrap_or(1.0),
+            metallic_roughness_texture,
+            metallic_texture,
+            roughness_texture: texture(&[TextureType::Roughness]),
+            normal_texture: texture(&[TextureType::Normals, TextureType::NormalCamera]),
+            occlusion_texture: texture(&[TextureType::AmbientOcclusion, TextureType::LightMap]),
+            emissive_factor: material
+                .get_float_array("$clr.emissive")
+                .and_then(|x| match x {
+                    [r, g, b, ..] => Some(glam::vec3(*r, *g, *b)),
+                    _ => None,
+                })
+                .unwrap_or(glam::Vec3::ZERO),
+            emissive_texture: texture(&[TextureType::EmissionColor, TextureType::Emissive]),
+            opacity_texture,
+            alpha_mode,
+            is_double_sided: material
+                .get_integer_array("$mat.twosided")
+                .is_some_and(|x| x.first().is_some_and(|x| *x != 0)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{EAlphaMode, ImportedMaterial};
+    use crate::ui::material_view::EMaterialNodeType;
+    use egui_snarl::NodeId;
+    use std::{collections::HashMap, path::PathBuf};
+
+    #[test]
+    fn test_case_to_snarl() {
+        let metallic_roughness_texture = PathBuf::from("metallic_roughness.png");
+        let occlusion_texture = PathBuf::from("occlusion.png");
+        let emissive_texture = PathBuf::from("emissive.png");
+        let material = ImportedMaterial {
+            name: "Material".to_string(),
+            base_color_factor: glam::Vec4::ONE,
+            base_color_texture: None,
+            metallic_factor: 0.5,
+            roughness_factor: 1.0,
+            metallic_roughness_texture: Some(metallic_roughness_texture.clone()),
+            metallic_texture: None,
+            roughness_texture: None,
+            normal_texture: None,
+            occlusion_texture: Some(occlusion_texture.clone()),
+            emissive_factor: glam::Vec3::ONE,
+            emissive_texture: Some(emissive_texture.clone()),
+            opacity_texture: None,
+            alpha_mode: EAlphaMode::Opaque,
+            is_double_sided: false,
+        };
+        let texture_urls = HashMap::from([
+            (
+                metallic_roughness_texture,
+                url::Url::parse("content://MetallicRoughness").unwrap(),
+            ),
+            (
+                occlusion_texture,
+                url::Url::parse("content://Occlusion").unwrap(),
+            ),
+            (
+                emissive_texture,
+                url::Url::parse("content://Emissive").unwrap(),
+            ),
+        ]);
+        let snarl = material.to_snarl(&texture_urls);
+        assert!(matches!(
+            snarl.get_node(NodeId(0)).unwrap().node_type,
+            EMaterialNodeType::Sink(_)
+        ));
+        let texture_nodes = snarl
+            .node_ids()
+            .filter(|(_, x)| matches!(x.node_type, EMaterialNodeType::Texture(_)))
+            .count();
+        assert_eq!(texture_nodes, 3);
+        let mut sink_inputs: Vec<(usize, usize)> = snarl
+            .wires()
+            .filter(|(_, in_pin_id)| in_pin_id.node == NodeId(0))
+            .map(|(out_pin_id, in_pin_id)| (in_pin_id.input, out_pin_id.output))
+            .collect();
+        sink_inputs.sort();
+        // Metallic is scaled by a multiply node, roughness is read from the green channel and
+        // occlusion from the red channel.
+        assert_eq!(sink_inputs, vec![(1, 0), (2, 2), (7, 1), (8, 0)]);
+    }
 }
@@ -1,3 +1,4 @@
+use crate::import_transformation::ImportTransformation;
 use anyhow::anyhow;
 use path_slash::PathBufExt;
 use rs_artifact::EEndianType;
@@ -6,6 +7,7 @@ use rs_engine::content::content_file_type::EContentFileType;
 use serde::{Deserialize, Serialize};
 use std::{
     cell::RefCell,
+    collections::HashMap,
     io::Write,
     path::{Path, PathBuf},
     rc::Rc,
@@ -17,18 +19,234 @@ pub const BUILD_FOLDER_NAME: &str = "build";
 pub const SHADER_FOLDER_NAME: &str = "shader";
 pub const SRC_FOLDER_NAME: &str = "src";
 pub const VERSION_STR: &str = "0.0.1";
+/// Version of the project file layout, bump it and append a migration whenever the layout changes.
+pub const PROJECT_FORMAT_VERSION: u32 = 1;
+
+/// Upgrades a project file from version `index` to version `index + 1`.
+const PROJECT_MIGRATIONS: [fn(&mut serde_json::Value) -> anyhow::Result<()>;
+    PROJECT_FORMAT_VERSION as usize] = [migrate_project_v0];
+
+fn migrate_project_v0(_: &mut serde_json::Value) -> anyhow::Result<()> {
+    // Version 0 only lacks `format_version`.
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct MeshCookSettings {
+    pub is_quantize_vertexes: bool,
+    pub is_optimize_indexes: bool,
+    /// Builds a cluster level of detail hierarchy for static meshes with at least
+    /// `cluster_lod_min_triangle_count` triangles.
+    pub is_build_cluster_lod: bool,
+    pub cluster_lod_min_triangle_count: usize,
+}
+
+impl Default for MeshCookSettings {
+    fn default() -> Self {
+        Self {
+            is_quantize_vertexes: false,
+            is_optimize_indexes: true,
+            is_build_cluster_lod: false,
+            cluster_lod_min_triangle_count: 16384,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AnimationCookSettings {
+    /// Removes redundant keys and quantizes rotations.
+    pub is_compress: bool,
+    pub position_tolerance: f32,
+    pub scaling_tolerance: f32,
+    /// In radians.
+    pub rotation_tolerance: f32,
+}
+
+impl Default for AnimationCookSettings {
+    fn default() -> Self {
+        Self {
+            is_compress: true,
+            position_tolerance: 0.0001,
+            scaling_tolerance: 0.0001,
+            rotation_tolerance: 0.0005,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ETextureCookPlatform {
+    /// BC1, BC3 and BC7.
+    Desktop,
+    /// ETC2 and ASTC.
+    Android,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TextureCookSettings {
+    pub is_compress: bool,
+    pub platform: ETextureCookPlatform,
+    /// Prefers formats with better quality over formats with smaller size.
+    pub is_high_quality: bool,
+}
+
+impl Default for TextureCookSettings {
+    fn default() -> Self {
+        Self {
+            is_compress: true,
+            platform: ETextureCookPlatform::Desktop,
+            is_high_quality: false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EModelImporterType {
+    Assimp,
+    /// The native glTF 2.0 importer.
+    Gltf,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EUpAxis {
+    Y,
+    Z,
+}
+
+/// Options of importing one model file.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ModelAssetImportSettings {
+    /// Scales the imported data, for example 0.01 converts centimeters to meters.
+    pub scale_factor: f32,
+    /// The up axis of the file, models are converted to Y up.
+    pub up_axis: EUpAxis,
+    /// Computes tangents from texture coordinates instead of reading them from the file.
+    pub is_generate_tangents: bool,
+    /// Merges meshes to reduce draw calls, assimp merges meshes which share a material and the
+    /// glTF importer merges the primitives of every mesh.
+    pub is_merge_meshes: bool,
+}
+
+impl ModelAssetImportSettings {
+    /// The transformation which is baked into the imported meshes, skeletons and animations.
+    pub fn get_import_transformation(&self) -> ImportTransformation {
+        let rotation = match self.up_axis {
+            EUpAxis::Y => glam::Quat::IDENTITY,
+            EUpAxis::Z => glam::Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2),
+        };
+        ImportTransformation {
+            rotation,
+            scale: self.scale_factor,
+        }
+    }
+}
+
+impl Default for ModelAssetImportSettings {
+    fn default() -> Self {
+        Self {
+            scale_factor: 1.0,
+            up_axis: EUpAxis::Y,
+            is_generate_tangents: false,
+            is_merge_meshes: false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ModelImportSettings {
+    /// Importers of lower case file extensions, files with other extensions use assimp.
+    pub importers: HashMap<String, EModelImporterType>,
+    /// Settings of imported files by the path relative to the asset folder.
+    #[serde(default)]
+    pub assets: HashMap<String, ModelAssetImportSettings>,
+}
+
+impl ModelImportSettings {
+    pub fn get_asset_settings(&self, asset_reference: &str) -> ModelAssetImportSettings {
+        self.assets
+            .get(&asset_reference.replace('\\', "/"))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn set_asset_settings(
+        &mut self,
+        asset_reference: &str,
+        settings: ModelAssetImportSettings,
+    ) {
+        self.assets
+            .insert(asset_reference.replace('\\', "/"), settings);
+    }
+
+    pub fn get_importer(&self, file_path: &Path) -> EModelImporterType {
+        file_path
+            .extension()
+            .and_then(|x| {
+                self.importers
+                    .get(&x.to_string_lossy().to_lowercase())
+                    .copied()
+            })
+            .unwrap_or(EModelImporterType::Assimp)
+    }
+}
+
+impl Default for ModelImportSettings {
+    fn default() -> Self {
+        Self {
+            importers: HashMap::from([
+                ("gltf".to_string(), EModelImporterType::Gltf),
+                ("glb".to_string(), EModelImporterType::Gltf),
+            ]),
+            assets: HashMap::new(),
+        }
+    }
+}
 
 #[derive(Serialize, Deserialize)]
 pub struct Project {
     pub version_str: String,
+    #[serde(default)]
+    pub format_version: u32,
     pub project_name: String,
     pub settings: Rc<RefCell<Settings>>,
     pub endian_type: EEndianType,
     pub materials: Vec<Rc<RefCell<crate::material::Material>>>,
     pub content: Rc<RefCell<crate::content_folder::ContentFolder>>,
+    #[serde(default)]
+    pub mesh_cook_settings: MeshCookSettings,
+    #[serde(default)]
+    pub texture_cook_settings: TextureCookSettings,
+    #[serde(default)]
+    pub animation_cook_settings: AnimationCookSettings,
+    #[serde(default)]
+    pub model_import_settings: ModelImportSettings,
 }
 
 impl Project {
+    pub fn from_json_value(mut value: serde_json::Value) -> anyhow::Result<Project> {
+        let format_version = value
+            .get("format_version")
+            .and_then(|x| x.as_u64())
+            .unwrap_or(0) as u32;
+        if format_version > PROJECT_FORMAT_VERSION {
+            return Err(anyhow!(
+                "Project format version {} is newer than the supported version {}, please update the editor.",
+                format_version,
+                PROJECT_FORMAT_VERSION
+            ));
+        }
+        for version in format_version..PROJECT_FORMAT_VERSION {
+            log::trace!(
+                "Migrate project from version {} to version {}.",
+                version,
+                version + 1
+            );
+            PROJECT_MIGRATIONS[version as usize](&mut value)?;
+            value["format_version"] = serde_json::Value::from(version + 1);
+        }
+        Ok(serde_json::from_value(value)?)
+    }
+
     pub fn create_empty_project(
         project_parent_folder: &Path,
         project_name: &str,
@@ -69,11 +287,16 @@ impl Project {
             ))));
         let empty_project = Project {
             version_str: VERSION_STR.to_string(),
+            format_version: PROJECT_FORMAT_VERSION,
             project_name: project_name.to_string(),
             endian_type: EEndianType::Little,
             settings: Rc::new(RefCell::new(Settings::default())),
             content,
             materials: vec![],
+            mesh_cook_settings: MeshCookSettings::default(),
+            texture_cook_settings: TextureCookSettings::default(),
+            animation_cook_settings: AnimationCookSettings::default(),
+            model_import_settings: ModelImportSettings::default(),
         };
         let json_str = serde_json::ser::to_string_pretty(&empty_project)?;
         let mut file = std::fs::File::create(project_file_path)?;
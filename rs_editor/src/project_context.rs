@@ -1,5 +1,6 @@
 use crate::{
     build_config::{BuildConfig, EArchType, EBuildPlatformType, EBuildType},
+    gltf_exporter::{GltfExporter, LevelResources},
     model_loader::ModelLoader,
     project::{Project, ASSET_FOLDER_NAME},
 };
@@ -7,8 +8,11 @@ use anyhow::{anyhow, Context};
 use notify::ReadDirectoryChangesWatcher;
 use notify_debouncer_mini::{DebouncedEvent, Debouncer};
 use rs_artifact::{
-    artifact::ArtifactAssetEncoder, material::MaterialInfo, shader_source_code::ShaderSourceCode,
-    sound::ESoundFileType, EEndianType,
+    artifact::{ArtifactAssetEncoder, ArtifactFileHeader, ArtifactReader},
+    material::MaterialInfo,
+    shader_source_code::ShaderSourceCode,
+    sound::ESoundFileType,
+    EEndianType,
 };
 use rs_engine::{
     content::content_file_type::EContentFileType, resource_manager::ResourceManager,
@@ -25,7 +29,9 @@ use std::{
 };
 
 pub enum EFolderUpdateType {
-    Asset,
+    /// The paths of the files and folders of the asset folder which are created, modified or
+    /// removed.
+    Asset(Vec<PathBuf>),
 }
 
 #[derive(Serialize, Deserialize)]
@@ -79,7 +85,11 @@ impl ProjectContext {
         let file = std::fs::File::open(project_file_path)
             .context(format!("Can not open file: {:?}", project_file_path))?;
         let reader = std::io::BufReader::new(file);
-        let project: Project = serde_json::de::from_reader(reader)
+        let value: serde_json::Value = serde_json::de::from_reader(reader).context(format!(
+            "Failed to parse JSON file: {:?}",
+            project_file_path
+        ))?;
+        let project = Project::from_json_value(value)
             .context("Failed to deserialize JSON data to a project data structure.")?;
         // #[cfg(debug_assertions)]
         // let lib_folder = project_folder_path.join("target").join("debug");
@@ -124,26 +134,24 @@ impl ProjectContext {
         let Some(receiver) = self.folder_receiver.as_mut() else {
             return None;
         };
-        let mut is_need_update = false;
+        let mut changed_paths: Vec<PathBuf> = vec![];
         for events in receiver.try_iter() {
-            if is_need_update {
-                break;
-            }
             let Ok(events) = events else {
                 continue;
             };
             for event in events {
-                if event.path.starts_with(asset_folder_path.clone()) {
-                    is_need_update = true;
-                    break;
+                if event.path.starts_with(&asset_folder_path)
+                    && !changed_paths.contains(&event.path)
+                {
+                    changed_paths.push(event.path);
                 }
             }
         }
 
-        if is_need_update {
-            return Some(EFolderUpdateType::Asset);
+        if changed_paths.is_empty() {
+            return None;
         }
-        None
+        Some(EFolderUpdateType::Asset(changed_paths))
     }
 
     pub fn is_need_reload_plugin(&self) -> bool {
@@ -285,13 +293,205 @@ impl ProjectContext {
     }
 
     pub fn export(&mut self, model_loader: &mut ModelLoader) -> anyhow::Result<PathBuf> {
+        self.export_artifact(model_loader, "main.rs", None)
+    }
+
+    /// Export an artifact that only contains resources which are new or changed relative to the
+    /// artifact at `base_artifact_path`. Resources removed from the project stay in the base
+    /// artifact, a full export is needed to remove them.
+    pub fn export_patch(
+        &mut self,
+        model_loader: &mut ModelLoader,
+        base_artifact_path: &Path,
+    ) -> anyhow::Result<PathBuf> {
+        let base_artifact_reader =
+            ArtifactReader::new(base_artifact_path, Some(EEndianType::Little))
+                .context(format!("Failed to read {:?}", base_artifact_path))?;
+        let base_artifact_file_header = base_artifact_reader.get_artifact_file_header().clone();
+        self.export_artifact(model_loader, "patch.rs", Some(base_artifact_file_header))
+    }
+
+    /// Export a static mesh, skeleton mesh, skeleton, skeleton animation or level as a .gltf or
+    /// .glb file.
+    pub fn export_gltf(
+        &self,
+        model_loader: &mut ModelLoader,
+        file: &EContentFileType,
+        path: &Path,
+    ) -> anyhow::Result<()> {
+        let mut exporter = GltfExporter::new();
+        match file {
+            EContentFileType::StaticMesh(static_mesh) => {
+                let url = static_mesh.borrow().url.clone();
+                let resources = self.load_gltf_export_resources(model_loader, &[url.clone()])?;
+                let static_mesh = resources
+                    .static_meshes
+                    .get(&url)
+                    .ok_or(anyhow!("{} is not loaded", url))?;
+                let mesh = exporter.add_static_mesh(static_mesh)?;
+                let node = exporter.add_node(&static_mesh.name, glam::Mat4::IDENTITY, None);
+                exporter.set_node_mesh(node, mesh, None);
+            }
+            EContentFileType::SkeletonMesh(skeleton_mesh) => {
+                let skeleton_mesh = skeleton_mesh.borrow();
+                let resources = self.load_gltf_export_resources(
+                    model_loader,
+                    &[
+                        skeleton_mesh.url.clone(),
+                        skeleton_mesh.skeleton_url.clone(),
+                    ],
+                )?;
+                let skeleton = resources
+                    .skeletons
+                    .get(&skeleton_mesh.skeleton_url)
+                    .ok_or(anyhow!("{} is not loaded", skeleton_mesh.skeleton_url))?;
+                let skin_mesh = resources
+                    .skin_meshes
+                    .get(&skeleton_mesh.url)
+                    .ok_or(anyhow!("{} is not loaded", skeleton_mesh.url))?;
+                let skeleton_nodes = exporter.add_skeleton(skeleton, None)?;
+                let (mesh, skin) = exporter.add_skin_mesh(skin_mesh, &skeleton_nodes)?;
+                let node = exporter.add_node(&skin_mesh.name, glam::Mat4::IDENTITY, None);
+                exporter.set_node_mesh(node, mesh, skin);
+            }
+            EContentFileType::Skeleton(skeleton) => {
+                let url = skeleton.borrow().url.clone();
+                let resources = self.load_gltf_export_resources(model_loader, &[url.clone()])?;
+                let skeleton = resources
+                    .skeletons
+                    .get(&url)
+                    .ok_or(anyhow!("{} is not loaded", url))?;
+                exporter.add_skeleton(skeleton, None)?;
+            }
+            EContentFileType::SkeletonAnimation(skeleton_animation) => {
+                let skeleton_animation = skeleton_animation.borrow();
+                // Animations are bound to the skeleton which is imported from the same file.
+                let relative_path = skeleton_animation.get_relative_path();
+                let skeleton_url = self
+                    .project
+                    .content
+                    .borrow()
+                    .files
+                    .iter()
+                    .find_map(|x| match x {
+                        EContentFileType::Skeleton(skeleton)
+                            if skeleton.borrow().get_relative_path() == relative_path =>
+                        {
+                            Some(skeleton.borrow().url.clone())
+                        }
+                        _ => None,
+                    })
+                    .ok_or(anyhow!("No skeleton is imported from {}", relative_path))?;
+                let resources = self.load_gltf_export_resources(
+                    model_loader,
+                    &[skeleton_animation.url.clone(), skeleton_url.clone()],
+                )?;
+                let skeleton = resources
+                    .skeletons
+                    .get(&skeleton_url)
+                    .ok_or(anyhow!("{} is not loaded", skeleton_url))?;
+                let animation = resources
+                    .skeleton_animations
+                    .get(&skeleton_animation.url)
+                    .ok_or(anyhow!("{} is not loaded", skeleton_animation.url))?;
+                let skeleton_nodes = exporter.add_skeleton(skeleton, None)?;
+                exporter.add_skeleton_animation(animation, &skeleton_nodes);
+            }
+            EContentFileType::Level(level) => {
+                let level = level.borrow();
+                let urls = crate::gltf_exporter::collect_level_references(&level);
+                let resources = self.load_gltf_export_resources(model_loader, &urls)?;
+                exporter.add_level(&level, &resources)?;
+            }
+            _ => {
+                return Err(anyhow!("{} can not be exported as glTF", file.get_url()));
+            }
+        }
+        exporter.write_to_file(path)
+    }
+
+    /// Load the meshes, skeletons and animations of the contents with the urls.
+    fn load_gltf_export_resources(
+        &self,
+        model_loader: &mut ModelLoader,
+        urls: &[url::Url],
+    ) -> anyhow::Result<LevelResources> {
+        let project_folder_path = self.get_project_folder_path();
+        let mut resources = LevelResources::default();
+        for file in &self.project.content.borrow().files {
+            if !urls.contains(&file.get_url()) {
+                continue;
+            }
+            match file {
+                EContentFileType::StaticMesh(asset) => {
+                    let asset = asset.borrow();
+                    let file_path = self
+                        .get_asset_folder_path()
+                        .join(&asset.asset_info.relative_path);
+                    model_loader.load(&file_path)?;
+                    let static_mesh = model_loader.to_runtime_static_mesh(
+                        &asset,
+                        &self.get_asset_folder_path(),
+                        ResourceManager::default(),
+                    )?;
+                    resources
+                        .static_meshes
+                        .insert(asset.url.clone(), static_mesh);
+                }
+                EContentFileType::SkeletonMesh(asset) => {
+                    let file_path = project_folder_path.join(&asset.borrow().get_relative_path());
+                    model_loader.load(&file_path)?;
+                    let skin_mesh = model_loader.to_runtime_skin_mesh(
+                        &asset.borrow(),
+                        &project_folder_path,
+                        ResourceManager::default(),
+                    );
+                    resources
+                        .skin_meshes
+                        .insert(asset.borrow().url.clone(), skin_mesh);
+                }
+                EContentFileType::SkeletonAnimation(asset) => {
+                    let file_path = project_folder_path.join(&asset.borrow().get_relative_path());
+                    model_loader.load(&file_path)?;
+                    let skeleton_animation = model_loader.to_runtime_skeleton_animation(
+                        asset.clone(),
+                        &project_folder_path,
+                        ResourceManager::default(),
+                    );
+                    resources
+                        .skeleton_animations
+                        .insert(asset.borrow().url.clone(), skeleton_animation);
+                }
+                EContentFileType::Skeleton(asset) => {
+                    let file_path = project_folder_path.join(&asset.borrow().get_relative_path());
+                    model_loader.load(&file_path)?;
+                    let skeleton = model_loader.to_runtime_skeleton(
+                        asset.clone(),
+                        &project_folder_path,
+                        ResourceManager::default(),
+                    );
+                    resources
+                        .skeletons
+                        .insert(asset.borrow().url.clone(), skeleton);
+                }
+                _ => {}
+            }
+        }
+        Ok(resources)
+    }
+
+    fn export_artifact(
+        &mut self,
+        model_loader: &mut ModelLoader,
+        output_filename: &str,
+        base_artifact_file_header: Option<ArtifactFileHeader>,
+    ) -> anyhow::Result<PathBuf> {
         let _span = tracy_client::span!();
 
         let output_folder_path = self.try_create_build_dir()?;
         if !output_folder_path.exists() {
             std::fs::create_dir(output_folder_path.clone())?;
         }
-        let output_filename = "main.rs";
         let project_folder_path = self.get_project_folder_path();
 
         let mut artifact_asset_encoder = ArtifactAssetEncoder::new(
@@ -299,6 +499,7 @@ impl ProjectContext {
             self.project.settings.borrow().clone(),
             &output_folder_path.join(output_filename),
         );
+        artifact_asset_encoder.set_base_artifact_file_header(base_artifact_file_header);
 
         let mut images: HashMap<url::Url, rs_artifact::image::Image> = HashMap::new();
         let mut shader_source_codes: HashMap<
@@ -335,6 +536,8 @@ impl ProjectContext {
             rs_engine::content::material_paramenters_collection::MaterialParamentersCollection,
         > = HashMap::new();
 
+        let mut prefabs: HashMap<url::Url, rs_engine::content::prefab::Prefab> = HashMap::new();
+
         for file in &self.project.content.borrow().files {
             match file {
                 EContentFileType::StaticMesh(asset) => {
@@ -351,10 +554,12 @@ impl ProjectContext {
                                 ResourceManager::default(),
                             )
                             .expect("Loaded");
-                        static_meshes.insert(
-                            loaded_static_mesh.url.clone(),
-                            loaded_static_mesh.deref().clone(),
-                        );
+                        let mut static_mesh = loaded_static_mesh.deref().clone();
+                        crate::mesh_cook::cook_static_mesh(
+                            &mut static_mesh,
+                            &self.project.mesh_cook_settings,
+                        )?;
+                        static_meshes.insert(static_mesh.url.clone(), static_mesh);
                     }
                     artifact_asset_encoder.encode(&*asset.borrow());
                 }
@@ -381,10 +586,12 @@ impl ProjectContext {
                         &project_folder_path,
                         ResourceManager::default(),
                     );
-                    skeleton_animations.insert(
-                        loaded_skeleton_animation.url.clone(),
-                        loaded_skeleton_animation.deref().clone(),
+                    let mut skeleton_animation = loaded_skeleton_animation.deref().clone();
+                    crate::animation_cook::cook_skeleton_animation(
+                        &mut skeleton_animation,
+                        &self.project.animation_cook_settings,
                     );
+                    skeleton_animations.insert(skeleton_animation.url.clone(), skeleton_animation);
 
                     artifact_asset_encoder.encode(&*asset.borrow());
                 }
@@ -413,13 +620,20 @@ impl ProjectContext {
                             absolute_image_file_path
                         ))?;
                         let format = image::guess_format(&buffer)?;
-                        let image = rs_artifact::image::Image {
+                        let mut image = rs_artifact::image::Image {
                             url: image_reference.clone(),
                             image_format: rs_artifact::image::ImageFormat::from_external_format(
                                 format,
                             ),
                             data: buffer,
+                            compressed_texture: None,
                         };
+                        crate::texture_cook::cook_image(
+                            &mut image,
+                            &self.project.texture_cook_settings,
+                            &asset.import_settings,
+                        )
+                        .context(format!("Failed to cook {:?}", absolute_image_file_path))?;
                         images.insert(image_reference.clone(), image);
                     }
                     artifact_asset_encoder.encode(&*asset);
@@ -538,6 +752,10 @@ impl ProjectContext {
                         material_paramenters_collection.clone(),
                     );
                 }
+                EContentFileType::Prefab(prefab) => {
+                    let prefab = prefab.borrow();
+                    prefabs.insert(prefab.url.clone(), prefab.clone());
+                }
             }
         }
 
@@ -598,6 +816,9 @@ impl ProjectContext {
         for asset in material_paramenters_collections.values() {
             artifact_asset_encoder.encode(asset);
         }
+        for asset in prefabs.values() {
+            artifact_asset_encoder.encode(asset);
+        }
         let _ = artifact_asset_encoder.finish()?;
         Ok(output_folder_path.join(output_filename))
     }
@@ -0,0 +1,221 @@
+use crate::project::{ETextureCookPlatform, TextureCookSettings};
+use rs_artifact::image::{CompressedTexture, ECompressedTextureFormat, Image};
+use rs_engine::content::texture::TextureImportSettings;
+
+/// Normal maps only need two channels, the z is reconstructed in the shader.
+pub fn select_format(
+    texture_cook_settings: &TextureCookSettings,
+    import_settings: &TextureImportSettings,
+    has_alpha: bool,
+) -> ECompressedTextureFormat {
+    match texture_cook_settings.platform {
+        ETextureCookPlatform::Desktop => {
+            if import_settings.is_normal_map {
+                ECompressedTextureFormat::BC5RgUnorm
+            } else if texture_cook_settings.is_high_quality {
+                ECompressedTextureFormat::BC7RgbaUnorm
+            } else if has_alpha {
+                ECompressedTextureFormat::BC3RgbaUnorm
+            } else {
+                ECompressedTextureFormat::BC1RgbaUnorm
+            }
+        }
+        ETextureCookPlatform::Android => {
+            if texture_cook_settings.is_high_quality || has_alpha {
+                ECompressedTextureFormat::Astc4x4Unorm
+            } else {
+                ECompressedTextureFormat::Etc2Rgb8Unorm
+            }
+        }
+    }
+}
+
+/// The number of mip levels of a full chain down to 1x1.
+pub fn full_mip_level_count(width: u32, height: u32) -> u32 {
+    u32::BITS - width.max(height).max(1).leading_zeros()
+}
+
+/// Transcodes the image to a block compressed texture with a full mip chain, the source file is
+/// dropped from the image.
+pub fn cook_image(
+    image: &mut Image,
+    texture_cook_settings: &TextureCookSettings,
+    import_settings: &TextureImportSettings,
+) -> anyhow::Result<()> {
+    if !texture_cook_settings.is_compress || image.compressed_texture.is_some() {
+        return Ok(());
+    }
+    let rgba_image = import_settings.prepare_image(image.decode()?);
+    let has_alpha = rgba_image.pixels().any(|x| x.0[3] != u8::MAX);
+    let format = select_format(texture_cook_settings, import_settings, has_alpha);
+    image.compressed_texture = Some(compress(&rgba_image, format, import_settings)?);
+    image.data = vec![];
+    Ok(())
+}
+
+pub fn compress(
+    rgba_image: &image::RgbaImage,
+    format: ECompressedTextureFormat,
+    import_settings: &TextureImportSettings,
+) -> anyhow::Result<CompressedTexture> {
+    // The size of the largest level of block compressed textures must be a multiple of the
+    // block size.
+    let (block_width, block_height) = format.block_dimensions();
+    let width = rgba_image.width().div_ceil(block_width) * block_width;
+    let height = rgba_image.height().div_ceil(block_height) * block_height;
+    let rgba_image = if width == rgba_image.width() && height == rgba_image.height() {
+        rgba_image.clone()
+    } else {
+        image::imageops::resize(
+            rgba_image,
+            width,
+            height,
+            import_settings.mipmap_filter.to_filter_type(),
+        )
+    };
+    let mip_levels = import_settings.generate_mip_levels(rgba_image);
+    let mip_level_count = full_mip_level_count(width, height);
+    if mip_levels.len() != mip_level_count as usize {
+        return Err(anyhow::anyhow!(
+            "The number of mip levels {} != expected number: {}",
+            mip_levels.len(),
+            mip_level_count
+        ));
+    }
+
+    let data = match format {
+        ECompressedTextureFormat::BC1RgbaUnorm
+        | ECompressedTextureFormat::BC3RgbaUnorm
+        | ECompressedTextureFormat::BC5RgUnorm
+        | ECompressedTextureFormat::BC7RgbaUnorm => {
+            let image_format = match format {
+                ECompressedTextureFormat::BC1RgbaUnorm => image_dds::ImageFormat::BC1RgbaUnorm,
+                ECompressedTextureFormat::BC3RgbaUnorm => image_dds::ImageFormat::BC3RgbaUnorm,
+                ECompressedTextureFormat::BC5RgUnorm => image_dds::ImageFormat::BC5RgUnorm,
+                ECompressedTextureFormat::BC7RgbaUnorm => image_dds::ImageFormat::BC7RgbaUnorm,
+                _ => unreachable!(),
+            };
+            let surface = image_dds::SurfaceRgba8 {
+                width,
+                height,
+                depth: 1,
+                layers: 1,
+                mipmaps: mip_level_count,
+                data: mip_levels
+                    .iter()
+                    .flat_map(|x| x.as_raw().iter().copied())
+                    .collect::<Vec<u8>>(),
+            }
+            .encode(
+                image_format,
+                image_dds::Quality::Normal,
+                image_dds::Mipmaps::FromSurface,
+            )?;
+            surface.data
+        }
+        ECompressedTextureFormat::Etc2Rgb8Unorm | ECompressedTextureFormat::Astc4x4Unorm => {
+            let mut data = vec![];
+            for mip_level_image in mip_levels.iter() {
+                let mip_level_image = pad_to_block_size(mip_level_image, block_width, block_height);
+                let surface = intel_tex_2::RgbaSurface {
+                    data: mip_level_image.as_raw(),
+                    width: mip_level_image.width(),
+                    height: mip_level_image.height(),
+                    stride: mip_level_image.width() * 4,
+                };
+                let mut blocks = match format {
+                    // ETC2 is backward compatible with ETC1.
+                    ECompressedTextureFormat::Etc2Rgb8Unorm => intel_tex_2::etc1::compress_blocks(
+                        &intel_tex_2::etc1::slow_settings(),
+                        &surface,
+                    ),
+                    ECompressedTextureFormat::Astc4x4Unorm => intel_tex_2::astc::compress_blocks(
+                        &intel_tex_2::astc::alpha_fast_settings(block_width, block_height),
+                        &surface,
+                    ),
+                    _ => unreachable!(),
+                };
+                data.append(&mut blocks);
+            }
+            data
+        }
+    };
+
+    let compressed_texture = CompressedTexture {
+        format,
+        width,
+        height,
+        mip_level_count,
+        data,
+    };
+    let expected_length: usize = (0..mip_level_count)
+        .map(|x| format.mip_level_length(width, height, x))
+        .sum();
+    if compressed_texture.data.len() != expected_length {
+        return Err(anyhow::anyhow!(
+            "The length of the compressed data {} != expected length: {}",
+            compressed_texture.data.len(),
+            expected_length
+        ));
+    }
+    Ok(compressed_texture)
+}
+
+/// Repeats the pixels of the last row and column until the size is a multiple of the block size.
+fn pad_to_block_size(
+    rgba_image: &image::RgbaImage,
+    block_width: u32,
+    block_height: u32,
+) -> image::RgbaImage {
+    let width = rgba_image.width().div_ceil(block_width) * block_width;
+    let height = rgba_image.height().div_ceil(block_height) * block_height;
+    if width == rgba_image.width() && height == rgba_image.height() {
+        return rgba_image.clone();
+    }
+    image::RgbaImage::from_fn(width, height, |x, y| {
+        *rgba_image.get_pixel(
+            x.min(rgba_image.width() - 1),
+            y.min(rgba_image.height() - 1),
+        )
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{full_mip_level_count, select_format};
+    use crate::project::{ETextureCookPlatform, TextureCookSettings};
+    use rs_artifact::image::ECompressedTextureFormat;
+    use rs_engine::content::texture::TextureImportSettings;
+
+    #[test]
+    fn test_case_full_mip_level_count() {
+        assert_eq!(full_mip_level_count(1, 1), 1);
+        assert_eq!(full_mip_level_count(4, 4), 3);
+        assert_eq!(full_mip_level_count(256, 128), 9);
+        assert_eq!(full_mip_level_count(100, 60), 7);
+    }
+
+    #[test]
+    fn test_case_select_format() {
+        let mut texture_cook_settings = TextureCookSettings::default();
+        let mut import_settings = TextureImportSettings::default();
+        assert_eq!(
+            select_format(&texture_cook_settings, &import_settings, false),
+            ECompressedTextureFormat::BC1RgbaUnorm
+        );
+        assert_eq!(
+            select_format(&texture_cook_settings, &import_settings, true),
+            ECompressedTextureFormat::BC3RgbaUnorm
+        );
+        import_settings.is_normal_map = true;
+        assert_eq!(
+            select_format(&texture_cook_settings, &import_settings, true),
+            ECompressedTextureFormat::BC5RgUnorm
+        );
+        texture_cook_settings.platform = ETextureCookPlatform::Android;
+        assert_eq!(
+            select_format(&texture_cook_settings, &import_settings, false),
+            ECompressedTextureFormat::Etc2Rgb8Unorm
+        );
+    }
+}
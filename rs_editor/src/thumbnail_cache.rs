@@ -57,6 +57,14 @@ impl ThumbnailCache {
         });
     }
 
+    /// The thumbnail is created again the next time it is requested.
+    pub fn invalidate(&mut self, image_path: &Path) {
+        let mut cache = self.cache.lock().unwrap();
+        if let Some(thumbnail_path) = cache.remove(image_path) {
+            let _ = std::fs::remove_file(thumbnail_path);
+        }
+    }
+
     pub fn get_image_file_uri(&self, image_path: &Path) -> Option<String> {
         let cache = self.cache.lock().unwrap();
         let path = match cache.get(image_path) {
@@ -133,6 +133,7 @@ fn draw_content(
                                 match file.get_file_type() {
                                     EFileType::Fbx
                                     | EFileType::Glb
+                                    | EFileType::Gltf
                                     | EFileType::Blend
                                     | EFileType::Dae => {
                                         response.context_menu(|ui| {
@@ -201,7 +202,7 @@ fn draw_content(
 fn render_thumbnail(file: &AssetFile, thumbnail_cache: &mut ThumbnailCache, ui: &mut Ui) {
     let thumbnail_render_szie = egui::vec2(50.0, 50.0);
     match file.get_file_type() {
-        EFileType::Fbx | EFileType::Glb | EFileType::Blend | EFileType::Dae => {
+        EFileType::Fbx | EFileType::Glb | EFileType::Gltf | EFileType::Blend | EFileType::Dae => {
             ui.image(egui::include_image!("../../../Resource/Editor/model.svg"));
         }
         EFileType::Jpeg | EFileType::Jpg | EFileType::Png | EFileType::Exr | EFileType::Hdr => {
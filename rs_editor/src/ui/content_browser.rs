@@ -41,6 +41,7 @@ pub enum EClickEventType {
     Back,
     Rename(EContentFileType, String),
     CreateMaterialParametersCollection,
+    ExportGltf(EContentFileType),
 }
 
 enum EItemType {
@@ -230,6 +231,18 @@ fn draw_content(
                                     click = Some(EClickEventType::DeleteFile(file.clone()));
                                     ui.close_menu();
                                 }
+                                let is_gltf_exportable = match file {
+                                    EContentFileType::StaticMesh(_)
+                                    | EContentFileType::SkeletonMesh(_)
+                                    | EContentFileType::Skeleton(_)
+                                    | EContentFileType::SkeletonAnimation(_)
+                                    | EContentFileType::Level(_) => true,
+                                    _ => false,
+                                };
+                                if is_gltf_exportable && ui.button("Export glTF").clicked() {
+                                    click = Some(EClickEventType::ExportGltf(file.clone()));
+                                    ui.close_menu();
+                                }
                             });
                             let mut edit_name = name.clone();
                             if ui.text_edit_multiline(&mut edit_name).changed() {
@@ -334,5 +347,8 @@ fn render_thumbnail(
         EContentFileType::MaterialParamentersCollection(_) => {
             ui.image(egui::include_image!("../../../Resource/Editor/file.svg"));
         }
+        EContentFileType::Prefab(_) => {
+            ui.image(egui::include_image!("../../../Resource/Editor/file.svg"));
+        }
     }
 }
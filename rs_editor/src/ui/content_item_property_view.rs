@@ -1,10 +1,18 @@
+use crate::project::{EUpAxis, ModelAssetImportSettings};
 use rs_core_minimal::name_generator::NameGenerator;
 use rs_engine::{
     content::{
-        content_file_type::EContentFileType, ibl::IBL,
-        material_paramenters_collection::MaterialParamentersCollection, texture::TextureFile,
+        content_file_type::EContentFileType,
+        ibl::IBL,
+        material_paramenters_collection::MaterialParamentersCollection,
+        sub_level::{StreamingVolume, SubLevelReference},
+        texture::{
+            EMipmapFilter, ETextureAddressMode, ETextureColorSpace, ETextureFilterMode,
+            TextureFile, TextureImportSettings,
+        },
     },
     uniform_map::{BaseDataValueType, StructField},
+    url_extension::UrlExtension,
 };
 use rs_foundation::new::SingleThreadMutType;
 use std::{cell::RefCell, path::PathBuf, rc::Rc};
@@ -13,17 +21,26 @@ pub enum EEventType {
     IBL(Rc<RefCell<IBL>>, Option<PathBuf>, Option<PathBuf>),
     IsVirtualTexture(Rc<RefCell<TextureFile>>, bool),
     SDF2D(Rc<RefCell<TextureFile>>),
+    /// The import settings of the texture file are already changed.
+    UpdateTextureImportSettings(Rc<RefCell<TextureFile>>),
     UpdateMaterialParamentersCollection(
         (
             SingleThreadMutType<MaterialParamentersCollection>,
             MaterialParamentersCollection,
         ),
     ),
+    /// The asset reference of the model file and the new settings.
+    UpdateModelImportSettings(String, ModelAssetImportSettings),
+    Reimport(String),
 }
 
 pub struct ContentItemPropertyView {
     pub content: Option<EContentFileType>,
     pub image_asset_files: Vec<PathBuf>,
+    /// The asset reference and the import settings of the model file of the content.
+    pub model_import_settings: Option<(String, ModelAssetImportSettings)>,
+    /// The reference of the level which will be added as a sub-level.
+    pub sub_level_reference: String,
     pub click: Option<EEventType>,
 }
 
@@ -32,6 +49,8 @@ impl ContentItemPropertyView {
         ContentItemPropertyView {
             content: None,
             image_asset_files: Vec::new(),
+            model_import_settings: None,
+            sub_level_reference: String::new(),
             click: None,
         }
     }
@@ -54,10 +73,12 @@ impl ContentItemPropertyView {
         ui.label(format!("url: {}", content.get_url().to_string()));
 
         match content {
-            EContentFileType::StaticMesh(_) => {}
-            EContentFileType::SkeletonMesh(_) => {}
-            EContentFileType::SkeletonAnimation(_) => {}
-            EContentFileType::Skeleton(_) => {}
+            EContentFileType::StaticMesh(_)
+            | EContentFileType::SkeletonMesh(_)
+            | EContentFileType::SkeletonAnimation(_)
+            | EContentFileType::Skeleton(_) => {
+                self.render_model_import_settings(ui);
+            }
             EContentFileType::Texture(texture_file) => {
                 let texture_file_clone = texture_file.clone();
                 let mut texture_file = texture_file.borrow_mut();
@@ -71,10 +92,16 @@ impl ContentItemPropertyView {
                     ));
                 }
                 if ui.button("SDF 2D").clicked() {
-                    self.click = Some(EEventType::SDF2D(texture_file_clone));
+                    self.click = Some(EEventType::SDF2D(texture_file_clone.clone()));
+                }
+                if render_texture_import_settings(&mut texture_file.import_settings, ui) {
+                    self.click = Some(EEventType::UpdateTextureImportSettings(texture_file_clone));
                 }
             }
-            EContentFileType::Level(_) => {}
+            EContentFileType::Level(level) => {
+                let mut level = level.borrow_mut();
+                render_sub_levels(&mut level.sub_levels, &mut self.sub_level_reference, ui);
+            }
             EContentFileType::Material(_) => {}
             EContentFileType::IBL(ibl) => {
                 let ibl_clone = ibl.clone();
@@ -320,8 +347,210 @@ impl ContentItemPropertyView {
                     )));
                 }
             }
+            EContentFileType::Prefab(_) => {}
+        }
+    }
+
+    fn render_model_import_settings(&mut self, ui: &mut egui::Ui) {
+        let Some((asset_reference, settings)) = &mut self.model_import_settings else {
+            return;
+        };
+        ui.separator();
+        ui.label(format!("Import Settings: {}", asset_reference));
+        let mut is_changed = false;
+        is_changed |= ui
+            .add(
+                egui::DragValue::new(&mut settings.scale_factor)
+                    .speed(0.01)
+                    .prefix("Scale Factor: ")
+                    .range(0.0001..=10000.0),
+            )
+            .changed();
+        egui::ComboBox::from_label("Up Axis")
+            .selected_text(format!("{:?}", settings.up_axis))
+            .show_ui(ui, |ui| {
+                for up_axis in [EUpAxis::Y, EUpAxis::Z] {
+                    is_changed |= ui
+                        .selectable_value(&mut settings.up_axis, up_axis, format!("{:?}", up_axis))
+                        .changed();
+                }
+            });
+        is_changed |= ui
+            .checkbox(&mut settings.is_generate_tangents, "Generate Tangents")
+            .changed();
+        is_changed |= ui
+            .checkbox(&mut settings.is_merge_meshes, "Merge Meshes")
+            .changed();
+        if is_changed {
+            self.click = Some(EEventType::UpdateModelImportSettings(
+                asset_reference.clone(),
+                settings.clone(),
+            ));
+        }
+        if ui.button("Reimport").clicked() {
+            self.click = Some(EEventType::Reimport(asset_reference.clone()));
+        }
+    }
+}
+
+fn render_sub_levels(
+    sub_levels: &mut Vec<SubLevelReference>,
+    sub_level_reference: &mut String,
+    ui: &mut egui::Ui,
+) {
+    ui.separator();
+    ui.label("Sub Levels");
+    let mut delete_index: Option<usize> = None;
+    for (index, sub_level) in sub_levels.iter_mut().enumerate() {
+        ui.push_id(index, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(sub_level.url.get_name_in_editor());
+                if ui.button("-").clicked() {
+                    delete_index = Some(index);
+                }
+            });
+            ui.checkbox(&mut sub_level.is_initially_loaded, "Is Initially Loaded");
+            let mut is_streaming = sub_level.streaming_volume.is_some();
+            if ui.checkbox(&mut is_streaming, "Streaming Volume").changed() {
+                sub_level.streaming_volume = is_streaming.then_some(StreamingVolume {
+                    min: glam::Vec3::splat(-50.0),
+                    max: glam::Vec3::splat(50.0),
+                    load_distance: 50.0,
+                    unload_distance: 100.0,
+                });
+            }
+            let Some(volume) = sub_level.streaming_volume.as_mut() else {
+                return;
+            };
+            for (label, value) in [("Min: ", &mut volume.min), ("Max: ", &mut volume.max)] {
+                ui.horizontal(|ui| {
+                    ui.label(label);
+                    ui.add(egui::DragValue::new(&mut value.x).prefix("x: "));
+                    ui.add(egui::DragValue::new(&mut value.y).prefix("y: "));
+                    ui.add(egui::DragValue::new(&mut value.z).prefix("z: "));
+                });
+            }
+            ui.add(
+                egui::DragValue::new(&mut volume.load_distance)
+                    .prefix("Load Distance: ")
+                    .range(0.0..=f32::MAX),
+            );
+            ui.add(
+                egui::DragValue::new(&mut volume.unload_distance)
+                    .prefix("Unload Distance: ")
+                    .range(volume.load_distance..=f32::MAX),
+            );
+        });
+    }
+    if let Some(delete_index) = delete_index {
+        sub_levels.remove(delete_index);
+    }
+    ui.horizontal(|ui| {
+        ui.text_edit_singleline(sub_level_reference);
+        if ui.button("+").clicked() {
+            match url::Url::parse(sub_level_reference) {
+                Ok(url) => {
+                    if !sub_levels.iter().any(|x| x.url == url) {
+                        sub_levels.push(SubLevelReference::new(url));
+                    }
+                    sub_level_reference.clear();
+                }
+                Err(err) => log::warn!("{}", err),
+            }
         }
+    });
+}
+
+fn render_texture_import_settings(settings: &mut TextureImportSettings, ui: &mut egui::Ui) -> bool {
+    ui.separator();
+    ui.label("Import Settings");
+    let mut is_changed = false;
+    egui::ComboBox::from_label("Color Space")
+        .selected_text(format!("{:?}", settings.color_space))
+        .show_ui(ui, |ui| {
+            for color_space in [ETextureColorSpace::Srgb, ETextureColorSpace::Linear] {
+                is_changed |= ui
+                    .selectable_value(
+                        &mut settings.color_space,
+                        color_space,
+                        format!("{:?}", color_space),
+                    )
+                    .changed();
+            }
+        });
+    is_changed |= ui
+        .checkbox(&mut settings.is_normal_map, "Normal Map")
+        .changed();
+    is_changed |= ui
+        .add_enabled(
+            settings.is_normal_map,
+            egui::Checkbox::new(&mut settings.is_flip_green_channel, "Flip Green Channel"),
+        )
+        .changed();
+    let mut is_limit_size = settings.max_size.is_some();
+    if ui.checkbox(&mut is_limit_size, "Limit Size").changed() {
+        settings.max_size = if is_limit_size { Some(2048) } else { None };
+        is_changed = true;
+    }
+    if let Some(max_size) = &mut settings.max_size {
+        is_changed |= ui
+            .add(
+                egui::DragValue::new(max_size)
+                    .prefix("Max Size: ")
+                    .range(1..=16384),
+            )
+            .changed();
     }
+    egui::ComboBox::from_label("Mipmap Filter")
+        .selected_text(format!("{:?}", settings.mipmap_filter))
+        .show_ui(ui, |ui| {
+            for mipmap_filter in [
+                EMipmapFilter::Nearest,
+                EMipmapFilter::Triangle,
+                EMipmapFilter::CatmullRom,
+                EMipmapFilter::Gaussian,
+                EMipmapFilter::Lanczos3,
+            ] {
+                is_changed |= ui
+                    .selectable_value(
+                        &mut settings.mipmap_filter,
+                        mipmap_filter,
+                        format!("{:?}", mipmap_filter),
+                    )
+                    .changed();
+            }
+        });
+    egui::ComboBox::from_label("Address Mode")
+        .selected_text(format!("{:?}", settings.address_mode))
+        .show_ui(ui, |ui| {
+            for address_mode in [
+                ETextureAddressMode::Repeat,
+                ETextureAddressMode::MirrorRepeat,
+                ETextureAddressMode::ClampToEdge,
+            ] {
+                is_changed |= ui
+                    .selectable_value(
+                        &mut settings.address_mode,
+                        address_mode,
+                        format!("{:?}", address_mode),
+                    )
+                    .changed();
+            }
+        });
+    egui::ComboBox::from_label("Filter Mode")
+        .selected_text(format!("{:?}", settings.filter_mode))
+        .show_ui(ui, |ui| {
+            for filter_mode in [ETextureFilterMode::Nearest, ETextureFilterMode::Linear] {
+                is_changed |= ui
+                    .selectable_value(
+                        &mut settings.filter_mode,
+                        filter_mode,
+                        format!("{:?}", filter_mode),
+                    )
+                    .changed();
+            }
+        });
+    is_changed
 }
 
 fn get_base_data_type_text(base_data_type: &BaseDataValueType) -> String {
@@ -9,6 +9,9 @@ pub enum EClickEventType {
     CreateCameraHere,
     DeleteActor(SingleThreadMutType<Actor>),
     DuplicateActor(SingleThreadMutType<Actor>),
+    CreatePrefab(SingleThreadMutType<Actor>),
+    ApplyPrefabOverrides(SingleThreadMutType<Actor>),
+    RevertPrefabOverrides(SingleThreadMutType<Actor>),
     SingleClickSceneNode(SingleThreadMutType<SceneNode>),
     CreateDirectionalLight,
     DirectionalLight(SingleThreadMutType<DirectionalLight>),
@@ -120,6 +123,26 @@ fn level_node(
                         ui.close_menu();
                     }
 
+                    if _actor.prefab.is_some() {
+                        ui.menu_button("Prefab", |ui| {
+                            if ui.button("Apply").clicked() {
+                                *event = Some(EClickEventType::ApplyPrefabOverrides(actor.clone()));
+                                ui.close_menu();
+                            }
+                            if ui.button("Revert").clicked() {
+                                *event =
+                                    Some(EClickEventType::RevertPrefabOverrides(actor.clone()));
+                                ui.close_menu();
+                            }
+                        });
+                    } else {
+                        let response = ui.button("Create Prefab");
+                        if response.clicked() {
+                            *event = Some(EClickEventType::CreatePrefab(actor.clone()));
+                            ui.close_menu();
+                        }
+                    }
+
                     let response = ui.button("Delete");
                     if response.clicked() {
                         *event = Some(EClickEventType::DeleteActor(actor.clone()));
@@ -16,6 +16,8 @@ use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
 const NODE_IO_COLOR: Color32 = Color32::WHITE;
 
+pub const TEXTURE_OUTPUT_NAMES: [&str; 5] = ["RGB", "R", "G", "B", "A"];
+
 pub struct GraphViewer {
     pub texture_urls: Vec<url::Url>,
     pub virtual_texture_urls: Vec<url::Url>,
@@ -108,8 +110,9 @@ impl SnarlViewer<MaterialNode> for GraphViewer {
     fn outputs(&mut self, node: &MaterialNode) -> usize {
         match node.node_type {
             EMaterialNodeType::Add(..) => 1,
+            EMaterialNodeType::Multiply(..) => 1,
             EMaterialNodeType::Sink(..) => 0,
-            EMaterialNodeType::Texture(_) => 1,
+            EMaterialNodeType::Texture(_) => TEXTURE_OUTPUT_NAMES.len(),
             EMaterialNodeType::TexCoord(_) => 1,
             EMaterialNodeType::VirtualTexture(_) => 1,
             EMaterialNodeType::Time => 1,
@@ -121,7 +124,8 @@ impl SnarlViewer<MaterialNode> for GraphViewer {
     fn inputs(&mut self, node: &MaterialNode) -> usize {
         match node.node_type {
             EMaterialNodeType::Add(..) => 2,
-            EMaterialNodeType::Sink(..) => 7,
+            EMaterialNodeType::Multiply(..) => 2,
+            EMaterialNodeType::Sink(..) => 9,
             EMaterialNodeType::Texture(_) => 2,
             EMaterialNodeType::TexCoord(_) => 0,
             EMaterialNodeType::VirtualTexture(_) => 1,
@@ -140,7 +144,7 @@ impl SnarlViewer<MaterialNode> for GraphViewer {
     ) -> PinInfo {
         let node = &mut snarl[pin.id.node];
         match &mut node.node_type {
-            EMaterialNodeType::Add(v1, v2) => {
+            EMaterialNodeType::Add(v1, v2) | EMaterialNodeType::Multiply(v1, v2) => {
                 if !pin.remotes.is_empty() {
                     return PinInfo::square().with_fill(NODE_IO_COLOR);
                 }
@@ -179,6 +183,8 @@ impl SnarlViewer<MaterialNode> for GraphViewer {
                     "Opacity",
                     "Clear Coat",
                     "Clear Coat Roughness",
+                    "Ambient Occlusion",
+                    "Emissive",
                 ];
                 ui.label(names[pin.id.input]);
                 if !pin.remotes.is_empty() {
@@ -210,6 +216,16 @@ impl SnarlViewer<MaterialNode> for GraphViewer {
                             ui,
                         );
                     }
+                    7 => {
+                        self.value_type_combo_box(
+                            "Ambient Occlusion",
+                            &mut attribute.ambient_occlusion,
+                            ui,
+                        );
+                    }
+                    8 => {
+                        self.value_type_combo_box("Emissive", &mut attribute.emissive, ui);
+                    }
                     _ => unreachable!(),
                 }
                 PinInfo::square().with_fill(NODE_IO_COLOR)
@@ -408,8 +424,12 @@ impl SnarlViewer<MaterialNode> for GraphViewer {
         let node = &mut snarl[pin.id.node];
         match &mut node.node_type {
             EMaterialNodeType::Add(..) => PinInfo::square().with_fill(NODE_IO_COLOR),
+            EMaterialNodeType::Multiply(..) => PinInfo::square().with_fill(NODE_IO_COLOR),
             EMaterialNodeType::Sink(..) => PinInfo::default(),
-            EMaterialNodeType::Texture(_) => PinInfo::square().with_fill(NODE_IO_COLOR),
+            EMaterialNodeType::Texture(_) => {
+                ui.label(TEXTURE_OUTPUT_NAMES[pin.id.output]);
+                PinInfo::square().with_fill(NODE_IO_COLOR)
+            }
             EMaterialNodeType::TexCoord(index) => {
                 let is_changed = egui::ComboBox::from_label("TexCoord")
                     .selected_text(format!("{}", index))
@@ -502,6 +522,7 @@ impl SnarlViewer<MaterialNode> for GraphViewer {
     ) {
         let node_types = vec![
             EMaterialNodeType::Add(EValueType::F32(0.0), EValueType::F32(0.0)),
+            EMaterialNodeType::Multiply(EValueType::F32(1.0), EValueType::F32(1.0)),
             EMaterialNodeType::Texture(None),
             EMaterialNodeType::TexCoord(0),
             EMaterialNodeType::VirtualTexture(None),
@@ -542,6 +563,18 @@ pub struct Attribute {
     pub opacity: EValueType,
     pub clear_coat: EValueType,
     pub clear_coat_roughness: EValueType,
+    #[serde(default = "default_ambient_occlusion")]
+    pub ambient_occlusion: EValueType,
+    #[serde(default = "default_emissive")]
+    pub emissive: EValueType,
+}
+
+fn default_ambient_occlusion() -> EValueType {
+    EValueType::F32(1.0)
+}
+
+fn default_emissive() -> EValueType {
+    EValueType::Vec3(glam::Vec3::ZERO)
 }
 
 impl Default for Attribute {
@@ -554,6 +587,8 @@ impl Default for Attribute {
             opacity: EValueType::F32(1.0),
             clear_coat: EValueType::F32(0.0),
             clear_coat_roughness: EValueType::F32(0.0),
+            ambient_occlusion: default_ambient_occlusion(),
+            emissive: default_emissive(),
         }
     }
 }
@@ -618,6 +653,7 @@ impl EValueType {
 #[derive(Serialize, Deserialize)]
 pub enum EMaterialNodeType {
     Add(EValueType, EValueType),
+    /// The outputs are the color, then each channel of the color.
     Texture(Option<url::Url>),
     VirtualTexture(Option<url::Url>),
     TexCoord(i32),
@@ -630,6 +666,7 @@ pub enum EMaterialNodeType {
             Option<String>,
         ),
     ),
+    Multiply(EValueType, EValueType),
 }
 
 impl EMaterialNodeType {
@@ -645,6 +682,7 @@ impl EMaterialNodeType {
             EMaterialNodeType::MaterialParamentersCollection(_) => {
                 format!("MaterialParamentersCollection")
             }
+            EMaterialNodeType::Multiply(_, _) => format!("Multiply"),
         }
     }
 }
@@ -3,7 +3,6 @@ use crate::{editor_context::EWindowType, windows_manager::WindowsManager};
 use anyhow::anyhow;
 use egui_winit::State;
 use rs_artifact::skin_mesh::SkinMeshVertex;
-use rs_core_minimal::file_manager::get_gpmetis_program_path;
 use rs_engine::{
     camera::Camera,
     camera_input_event_handle::{CameraInputEventHandle, DefaultCameraInputEventHandle},
@@ -148,6 +147,7 @@ impl UIWindow for MeshUIWindow {
                         window_id,
                     ),
                     draw_objects: present_draw_objects,
+                    shadow_draw_objects: vec![],
                     virtual_texture_pass: None,
                     scene_viewport: SceneViewport::new(),
                     depth_texture_handle: None,
@@ -256,7 +256,7 @@ impl MeshUIWindow {
             &indices,
             vertices.as_slice(),
             num_parts as u32,
-            get_gpmetis_program_path(),
+            &rs_metis::metis::PartitionOptions::default(),
         );
         let mesh_clusters = match mesh_clusters {
             Ok(mesh_clusters) => mesh_clusters,
@@ -159,6 +159,7 @@ impl UIWindow for MultipleDrawUiWindow {
                         window_id,
                     ),
                     draw_objects: present_draw_objects,
+                    shadow_draw_objects: vec![],
                     virtual_texture_pass: None,
                     scene_viewport: SceneViewport::new(),
                     depth_texture_handle: None,
@@ -409,6 +409,28 @@ impl ObjectPropertyView {
                             );
                         });
                     }
+                    EComponentType::CustomComponent(component) => {
+                        let mut component = component.borrow_mut();
+                        ui.label(format!("Type: {}", component.get_type_name()));
+                        if component.is_loaded() {
+                            if let Some(new_name) = Self::edit_name(&component.get_name(), ui) {
+                                event = Some(EEventType::ChangeName(
+                                    selected_object_clone.clone(),
+                                    new_name,
+                                ));
+                            }
+                            let mut transformation = component.get_transformation();
+                            Self::transformation_detail_mut(&mut transformation, ui);
+                            component.set_transformation(transformation);
+                            Self::transformation_detail(&component.get_final_transformation(), ui);
+
+                            ui.vertical(|ui| {
+                                component.on_property_ui(ui);
+                            });
+                        } else {
+                            ui.label("The type of the component is not registered.");
+                        }
+                    }
                 }
             }
             ESelectedObjectType::DirectionalLight(directional_light) => {
@@ -278,6 +278,7 @@ impl UIWindow for ParticleSystemUIWindow {
                         window_id,
                     ),
                     draw_objects,
+                    shadow_draw_objects: vec![],
                     virtual_texture_pass: None,
                     scene_viewport: SceneViewport::new(),
                     depth_texture_handle: None,
@@ -177,7 +177,11 @@ impl StandaloneUiWindow {
         })
     }
 
-    pub fn reload_plugins(&mut self, plugins: Vec<Box<dyn Plugin>>) {
-        self.application.reload_plugins(plugins);
+    pub fn unload_custom_components(&self) {
+        self.application.unload_custom_components();
+    }
+
+    pub fn reload_plugins(&mut self, engine: &mut Engine, plugins: Vec<Box<dyn Plugin>>) {
+        self.application.reload_plugins(engine, plugins);
     }
 }
@@ -32,6 +32,7 @@ pub enum EClickEventType {
     OpenProjectSettings,
     SaveProject,
     Export,
+    ExportPatch,
     OpenVisualStudioCode,
     Run,
     Standalone,
@@ -82,6 +83,10 @@ impl TopMenu {
                         click = Some(EClickEventType::Export);
                         ui.close_menu();
                     }
+                    if ui.add(Button::new("Export Patch")).clicked() {
+                        click = Some(EClickEventType::ExportPatch);
+                        ui.close_menu();
+                    }
                     if ui.add(Button::new("Open Visual Studio Code")).clicked() {
                         click = Some(EClickEventType::OpenVisualStudioCode);
                         ui.close_menu();
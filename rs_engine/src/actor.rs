@@ -1,32 +1,136 @@
 use crate::{
-    content::content_file_type::EContentFileType,
+    content::{
+        content_file_type::EContentFileType,
+        prefab::{Prefab, PrefabInstance},
+    },
+    culling::{CullingStats, FrustumCulling, PassVisibility, VisibleDrawObject},
     drawable::EDrawObjectType,
     engine::Engine,
+    error::Error,
     misc,
     player_viewport::PlayerViewport,
     scene_node::{EComponentType, SceneNode},
 };
 use rapier3d::prelude::{ColliderSet, RigidBodySet};
 use rs_foundation::new::{SingleThreadMut, SingleThreadMutType};
-use serde::{Deserialize, Serialize};
+use serde::{ser::SerializeStruct, Deserialize, Serialize};
 use std::{collections::HashMap, rc::Rc};
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Deserialize, Clone)]
 pub struct Actor {
     pub name: String,
     pub scene_node: SingleThreadMutType<SceneNode>,
+    /// The prefab which the actor was created from. The hierarchy is created again from the
+    /// prefab and the overrides when the actor is initialized.
+    #[serde(default)]
+    pub prefab: Option<PrefabInstance>,
+}
+
+impl Serialize for Actor {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let prefab = match &self.prefab {
+            Some(prefab) => {
+                let mut prefab = prefab.clone();
+                prefab.overrides = prefab
+                    .compute_overrides(&self.scene_node.borrow())
+                    .map_err(serde::ser::Error::custom)?;
+                Some(prefab)
+            }
+            None => None,
+        };
+        let mut state = serializer.serialize_struct("Actor", 3)?;
+        state.serialize_field("name", &self.name)?;
+        state.serialize_field("scene_node", &self.scene_node)?;
+        state.serialize_field("prefab", &prefab)?;
+        state.end()
+    }
 }
 
 impl Actor {
     pub fn new(name: String) -> Actor {
         let scene_node = SceneNode::new_sp("Scene".to_string());
-        Actor { name, scene_node }
+        Actor {
+            name,
+            scene_node,
+            prefab: None,
+        }
     }
 
     pub fn new_sp(name: String) -> SingleThreadMutType<Actor> {
         SingleThreadMut::new(Self::new(name))
     }
 
+    pub fn new_from_prefab(
+        name: String,
+        prefab: SingleThreadMutType<Prefab>,
+    ) -> crate::error::Result<Actor> {
+        let scene_node = prefab.borrow().instantiate(&[])?;
+        Ok(Actor {
+            name,
+            scene_node: SingleThreadMut::new(scene_node),
+            prefab: Some(PrefabInstance::new(prefab)),
+        })
+    }
+
+    /// Saves the differences between the hierarchy and the prefab as the overrides.
+    pub fn update_prefab_overrides(&mut self) -> crate::error::Result<()> {
+        let Some(prefab) = self.prefab.as_mut() else {
+            return Ok(());
+        };
+        prefab.overrides = prefab.compute_overrides(&self.scene_node.borrow())?;
+        Ok(())
+    }
+
+    /// Resolves the prefab of the actor and creates the hierarchy again with the saved overrides
+    /// if it was not built from the current version of the prefab.
+    pub fn sync_with_prefab(&mut self, files: &[EContentFileType]) -> crate::error::Result<()> {
+        let Some(prefab_instance) = self.prefab.as_mut() else {
+            return Ok(());
+        };
+        if prefab_instance.get_prefab().is_none() {
+            let prefab = files
+                .iter()
+                .find_map(|x| match x {
+                    EContentFileType::Prefab(prefab)
+                        if prefab.borrow().url == prefab_instance.url =>
+                    {
+                        Some(prefab.clone())
+                    }
+                    _ => None,
+                })
+                .ok_or(Error::NullReference(Some(format!(
+                    "Prefab {} is not found",
+                    prefab_instance.url
+                ))))?;
+            prefab_instance.set_prefab(prefab);
+        }
+        if prefab_instance.is_up_to_date() {
+            return Ok(());
+        }
+        self.rebuild_from_prefab()
+    }
+
+    /// Creates the hierarchy again from the resolved prefab with the saved overrides.
+    pub fn rebuild_from_prefab(&mut self) -> crate::error::Result<()> {
+        let Some(prefab_instance) = self.prefab.as_mut() else {
+            return Ok(());
+        };
+        let prefab = prefab_instance
+            .get_prefab()
+            .ok_or(Error::NullReference(Some(format!(
+                "Prefab {} is not resolved",
+                prefab_instance.url
+            ))))?;
+        let prefab = prefab.borrow();
+        let scene_node = prefab.instantiate(&prefab_instance.overrides)?;
+        prefab_instance.set_built_version(prefab.get_version());
+        self.scene_node = SingleThreadMut::new(scene_node);
+        Ok(())
+    }
+
     pub fn walk_node(
         node: SingleThreadMutType<SceneNode>,
         walk: &mut impl FnMut(SingleThreadMutType<SceneNode>) -> (),
@@ -44,6 +148,9 @@ impl Actor {
         files: &[EContentFileType],
         player_viewport: &mut PlayerViewport,
     ) {
+        if let Err(err) = self.sync_with_prefab(files) {
+            log::warn!("{}, {}", self.name, err);
+        }
         Actor::walk_node(self.scene_node.clone(), &mut |node| {
             node.borrow_mut().initialize(engine, files, player_viewport);
         });
@@ -63,61 +170,125 @@ impl Actor {
 
     pub fn collect_draw_objects(&self) -> Vec<EDrawObjectType> {
         let mut draw_objects = vec![];
-        Actor::walk_node(
-            self.scene_node.clone(),
-            &mut |node| match &node.borrow().component {
-                EComponentType::SceneComponent(_) => {}
-                EComponentType::StaticMeshComponent(component) => {
-                    let component = component.borrow();
-                    let mut sub_draw_objects: Vec<_> = component
-                        .get_draw_objects()
-                        .iter()
-                        .map(|x| (*x).clone())
-                        .collect();
-                    draw_objects.append(&mut sub_draw_objects);
-                }
-                EComponentType::SkeletonMeshComponent(component) => {
-                    let component = component.borrow();
-                    let mut sub_draw_objects: Vec<_> = component
-                        .get_draw_objects()
-                        .iter()
-                        .map(|x| (*x).clone())
-                        .collect();
-                    draw_objects.append(&mut sub_draw_objects);
-                }
-                EComponentType::CameraComponent(component) => {
-                    let component = component.borrow();
-                    let mut sub_draw_objects: Vec<_> = component
-                        .get_draw_objects()
-                        .iter()
-                        .map(|x| (*x).clone())
-                        .collect();
-                    draw_objects.append(&mut sub_draw_objects);
-                }
-                EComponentType::CollisionComponent(component) => {
-                    let component = component.borrow();
-                    let mut sub_draw_objects: Vec<_> = component
-                        .get_draw_objects()
-                        .iter()
-                        .map(|x| (*x).clone())
-                        .collect();
-                    draw_objects.append(&mut sub_draw_objects);
-                }
-                EComponentType::SpotLightComponent(_) => {}
-                EComponentType::PointLightComponent(component) => {
-                    let component = component.borrow();
-                    let mut sub_draw_objects: Vec<_> = component
-                        .get_draw_objects()
-                        .iter()
-                        .map(|x| (*x).clone())
-                        .collect();
-                    draw_objects.append(&mut sub_draw_objects);
+        Actor::walk_node(self.scene_node.clone(), &mut |node| {
+            Self::collect_node_draw_objects(&node.borrow(), &mut draw_objects);
+        });
+        draw_objects
+    }
+
+    /// Collects the draw objects of the components with the passes they are visible to, the
+    /// components without world bounds are visible to every pass.
+    pub fn collect_visible_draw_objects(
+        &self,
+        culling: &FrustumCulling,
+        stats: &mut CullingStats,
+    ) -> Vec<VisibleDrawObject> {
+        let mut draw_objects = vec![];
+        Actor::walk_node(self.scene_node.clone(), &mut |node| {
+            let node = node.borrow();
+            let visibility = match node.get_world_bounds() {
+                Some(bounds) => culling.get_visibility(&bounds),
+                None => PassVisibility::ALL,
+            };
+            if !visibility.is_visible() {
+                let count = Self::count_node_draw_objects(&node);
+                stats.total_draw_objects += count;
+                stats.culled_draw_objects += count;
+                return;
+            }
+            let mut node_draw_objects = vec![];
+            Self::collect_node_draw_objects(&node, &mut node_draw_objects);
+            stats.total_draw_objects += node_draw_objects.len();
+            if !visibility.is_visible_to_camera {
+                stats.shadow_only_draw_objects += node_draw_objects.len();
+            }
+            draw_objects.extend(node_draw_objects.into_iter().map(|draw_object| {
+                VisibleDrawObject {
+                    draw_object,
+                    visibility,
                 }
-            },
-        );
+            }));
+        });
         draw_objects
     }
 
+    /// Only the components with world bounds are culled.
+    fn count_node_draw_objects(node: &SceneNode) -> usize {
+        match &node.component {
+            EComponentType::StaticMeshComponent(component) => {
+                component.borrow().get_draw_objects().len()
+            }
+            EComponentType::SkeletonMeshComponent(component) => {
+                component.borrow().get_draw_objects().len()
+            }
+            EComponentType::CustomComponent(component) => {
+                component.borrow().get_draw_objects().len()
+            }
+            _ => 0,
+        }
+    }
+
+    fn collect_node_draw_objects(node: &SceneNode, draw_objects: &mut Vec<EDrawObjectType>) {
+        match &node.component {
+            EComponentType::SceneComponent(_) => {}
+            EComponentType::StaticMeshComponent(component) => {
+                let component = component.borrow();
+                let mut sub_draw_objects: Vec<_> = component
+                    .get_draw_objects()
+                    .iter()
+                    .map(|x| (*x).clone())
+                    .collect();
+                draw_objects.append(&mut sub_draw_objects);
+            }
+            EComponentType::SkeletonMeshComponent(component) => {
+                let component = component.borrow();
+                let mut sub_draw_objects: Vec<_> = component
+                    .get_draw_objects()
+                    .iter()
+                    .map(|x| (*x).clone())
+                    .collect();
+                draw_objects.append(&mut sub_draw_objects);
+            }
+            EComponentType::CameraComponent(component) => {
+                let component = component.borrow();
+                let mut sub_draw_objects: Vec<_> = component
+                    .get_draw_objects()
+                    .iter()
+                    .map(|x| (*x).clone())
+                    .collect();
+                draw_objects.append(&mut sub_draw_objects);
+            }
+            EComponentType::CollisionComponent(component) => {
+                let component = component.borrow();
+                let mut sub_draw_objects: Vec<_> = component
+                    .get_draw_objects()
+                    .iter()
+                    .map(|x| (*x).clone())
+                    .collect();
+                draw_objects.append(&mut sub_draw_objects);
+            }
+            EComponentType::SpotLightComponent(_) => {}
+            EComponentType::PointLightComponent(component) => {
+                let component = component.borrow();
+                let mut sub_draw_objects: Vec<_> = component
+                    .get_draw_objects()
+                    .iter()
+                    .map(|x| (*x).clone())
+                    .collect();
+                draw_objects.append(&mut sub_draw_objects);
+            }
+            EComponentType::CustomComponent(component) => {
+                let component = component.borrow();
+                let mut sub_draw_objects: Vec<_> = component
+                    .get_draw_objects()
+                    .iter()
+                    .map(|x| (*x).clone())
+                    .collect();
+                draw_objects.append(&mut sub_draw_objects);
+            }
+        }
+    }
+
     pub fn tick(
         &mut self,
         time: f32,
@@ -304,6 +475,7 @@ impl Actor {
                     }
                     EComponentType::SpotLightComponent(_) => return false,
                     EComponentType::PointLightComponent(_) => return false,
+                    EComponentType::CustomComponent(_) => return false,
                 }
                 false
             })();
@@ -324,12 +496,41 @@ impl Actor {
         misc::merge_aabb(&aabbs)
     }
 
+    /// Returns the urls of the meshes, skeletons, animations and materials which are referenced
+    /// by the components.
+    pub fn collect_content_references(&self) -> Vec<url::Url> {
+        let mut urls = vec![];
+        Self::walk_node(self.scene_node.clone(), &mut |node| {
+            let node = node.borrow();
+            match &node.component {
+                EComponentType::StaticMeshComponent(component) => {
+                    let component = component.borrow();
+                    urls.extend(component.static_mesh.clone());
+                    urls.extend(component.material_url.clone());
+                }
+                EComponentType::SkeletonMeshComponent(component) => {
+                    let component = component.borrow();
+                    urls.extend(component.skeleton_url.clone());
+                    urls.extend(component.material_url.clone());
+                    urls.extend(component.skeleton_mesh_urls.iter().cloned());
+                    urls.extend(component.animation_url.clone());
+                }
+                _ => {}
+            }
+        });
+        urls
+    }
+
     pub fn copy_without_initialization(&self, name: String) -> Actor {
         let copy_root_scene_node = Self::copy_recursion(&self.scene_node.borrow());
-        let copy_actor = Actor {
+        let mut copy_actor = Actor {
             name,
             scene_node: SingleThreadMut::new(copy_root_scene_node),
+            prefab: self.prefab.clone(),
         };
+        if let Err(err) = copy_actor.update_prefab_overrides() {
+            log::warn!("{}, {}", copy_actor.name, err);
+        }
         copy_actor
     }
 
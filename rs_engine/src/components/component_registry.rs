@@ -0,0 +1,75 @@
+use super::custom_component::TCustomComponent;
+use serde::{de::DeserializeOwned, Serialize};
+use std::collections::HashMap;
+
+struct ComponentDescriptor {
+    create_default: Box<dyn Fn() -> Box<dyn TCustomComponent>>,
+    create_from_json: Box<dyn Fn(&str) -> serde_json::Result<Box<dyn TCustomComponent>>>,
+}
+
+/// Custom component types by their type names. Plugins register their types in
+/// `Plugin::register_components`.
+#[derive(Default)]
+pub struct ComponentRegistry {
+    descriptors: HashMap<String, ComponentDescriptor>,
+}
+
+impl ComponentRegistry {
+    pub fn register<T>(&mut self)
+    where
+        T: TCustomComponent + Serialize + DeserializeOwned + Clone + Default + 'static,
+    {
+        let type_name = T::default().get_type_name().to_string();
+        let descriptor = ComponentDescriptor {
+            create_default: Box::new(|| -> Box<dyn TCustomComponent> { Box::new(T::default()) }),
+            create_from_json: Box::new(
+                |data: &str| -> serde_json::Result<Box<dyn TCustomComponent>> {
+                    let component: T = serde_json::from_str(data)?;
+                    Ok(Box::new(component))
+                },
+            ),
+        };
+        if self
+            .descriptors
+            .insert(type_name.clone(), descriptor)
+            .is_some()
+        {
+            log::trace!("Replace component type {}", type_name);
+        }
+    }
+
+    pub fn unregister(&mut self, type_name: &str) {
+        self.descriptors.remove(type_name);
+    }
+
+    /// Removes every type, which must be done before the libraries defining them are unloaded.
+    pub fn clear(&mut self) {
+        self.descriptors.clear();
+    }
+
+    pub fn contains(&self, type_name: &str) -> bool {
+        self.descriptors.contains_key(type_name)
+    }
+
+    pub fn get_type_names(&self) -> Vec<String> {
+        let mut type_names: Vec<String> = self.descriptors.keys().cloned().collect();
+        type_names.sort();
+        type_names
+    }
+
+    pub fn create(&self, type_name: &str) -> Option<Box<dyn TCustomComponent>> {
+        self.descriptors
+            .get(type_name)
+            .map(|x| (x.create_default)())
+    }
+
+    pub fn create_from_json(
+        &self,
+        type_name: &str,
+        data: &str,
+    ) -> Option<serde_json::Result<Box<dyn TCustomComponent>>> {
+        self.descriptors
+            .get(type_name)
+            .map(|x| (x.create_from_json)(data))
+    }
+}
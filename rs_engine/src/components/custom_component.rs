@@ -0,0 +1,395 @@
+use super::{component::Component, component_registry::ComponentRegistry};
+use crate::{
+    content::content_file_type::EContentFileType, drawable::EDrawObjectType, engine::Engine,
+    player_viewport::PlayerViewport,
+};
+use rapier3d::prelude::{ColliderSet, RigidBodySet};
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+
+/// Object safe operations of custom components, implemented for every serializable type.
+pub trait TCustomComponentObject {
+    fn clone_box(&self) -> Box<dyn TCustomComponent>;
+
+    fn to_json(&self) -> serde_json::Result<String>;
+
+    fn as_any(&self) -> &dyn Any;
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T> TCustomComponentObject for T
+where
+    T: TCustomComponent + Serialize + Clone + 'static,
+{
+    fn clone_box(&self) -> Box<dyn TCustomComponent> {
+        Box::new(self.clone())
+    }
+
+    fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// A component which is defined outside of the engine, usually by a plugin.
+/// The type must be registered in `ComponentRegistry` before a level containing it is loaded.
+pub trait TCustomComponent: Component + TCustomComponentObject {
+    /// The unique name of the type, which is saved with the component.
+    fn get_type_name(&self) -> &str;
+
+    fn get_draw_objects(&self) -> Vec<&EDrawObjectType> {
+        vec![]
+    }
+
+    fn get_aabb(&self) -> Option<rapier3d::prelude::Aabb> {
+        None
+    }
+
+    /// Draws the properties of the component in the editor.
+    fn on_property_ui(&mut self, ui: &mut egui::Ui) {
+        let _ = ui;
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct CustomComponentData {
+    type_name: String,
+    data: String,
+}
+
+/// Holds a custom component in a scene node. The component is saved as json with its type name
+/// and is created again by the registered type when the level is initialized.
+pub struct CustomComponent {
+    type_name: String,
+    data: String,
+    component: Option<Box<dyn TCustomComponent>>,
+}
+
+impl CustomComponent {
+    pub fn new(component: Box<dyn TCustomComponent>) -> CustomComponent {
+        CustomComponent {
+            type_name: component.get_type_name().to_string(),
+            data: String::new(),
+            component: Some(component),
+        }
+    }
+
+    pub fn get_type_name(&self) -> &str {
+        &self.type_name
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        self.component.is_some()
+    }
+
+    pub fn get_component(&self) -> Option<&dyn TCustomComponent> {
+        self.component.as_deref()
+    }
+
+    pub fn get_component_mut(&mut self) -> Option<&mut (dyn TCustomComponent + 'static)> {
+        self.component.as_deref_mut()
+    }
+
+    pub fn downcast_ref<T: 'static>(&self) -> Option<&T> {
+        self.component
+            .as_ref()
+            .and_then(|x| x.as_any().downcast_ref::<T>())
+    }
+
+    pub fn downcast_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        self.component
+            .as_mut()
+            .and_then(|x| x.as_any_mut().downcast_mut::<T>())
+    }
+
+    /// Creates the component from the saved data, returns false if the type is not registered.
+    pub fn load(&mut self, registry: &ComponentRegistry) -> bool {
+        if self.component.is_some() {
+            return true;
+        }
+        match registry.create_from_json(&self.type_name, &self.data) {
+            Some(Ok(component)) => {
+                self.component = Some(component);
+                true
+            }
+            Some(Err(err)) => {
+                log::warn!("Can not load component {}, {}", self.type_name, err);
+                false
+            }
+            None => {
+                log::warn!("Component {} is not registered", self.type_name);
+                false
+            }
+        }
+    }
+
+    /// Saves and drops the component, so the library which defines it can be unloaded.
+    pub fn unload(&mut self) {
+        let Some(component) = self.component.take() else {
+            return;
+        };
+        match component.to_json() {
+            Ok(data) => self.data = data,
+            Err(err) => log::warn!("Can not save component {}, {}", self.type_name, err),
+        }
+    }
+
+    pub fn get_draw_objects(&self) -> Vec<&EDrawObjectType> {
+        self.component
+            .as_ref()
+            .map(|x| x.get_draw_objects())
+            .unwrap_or_default()
+    }
+
+    pub fn get_aabb(&self) -> Option<rapier3d::prelude::Aabb> {
+        self.component.as_ref().and_then(|x| x.get_aabb())
+    }
+
+    pub fn on_property_ui(&mut self, ui: &mut egui::Ui) {
+        if let Some(component) = self.component.as_mut() {
+            component.on_property_ui(ui);
+        }
+    }
+}
+
+impl Clone for CustomComponent {
+    fn clone(&self) -> Self {
+        CustomComponent {
+            type_name: self.type_name.clone(),
+            data: self.data.clone(),
+            component: self.component.as_ref().map(|x| x.clone_box()),
+        }
+    }
+}
+
+impl Serialize for CustomComponent {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let data = match &self.component {
+            Some(component) => component.to_json().map_err(serde::ser::Error::custom)?,
+            None => self.data.clone(),
+        };
+        CustomComponentData {
+            type_name: self.type_name.clone(),
+            data,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for CustomComponent {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let data = CustomComponentData::deserialize(deserializer)?;
+        Ok(CustomComponent {
+            type_name: data.type_name,
+            data: data.data,
+            component: None,
+        })
+    }
+}
+
+impl Component for CustomComponent {
+    fn get_name(&self) -> String {
+        match &self.component {
+            Some(component) => component.get_name(),
+            None => self.type_name.clone(),
+        }
+    }
+
+    fn set_name(&mut self, new_name: String) {
+        if let Some(component) = self.component.as_mut() {
+            component.set_name(new_name);
+        }
+    }
+
+    fn get_final_transformation(&self) -> glam::Mat4 {
+        self.component
+            .as_ref()
+            .map(|x| x.get_final_transformation())
+            .unwrap_or_default()
+    }
+
+    fn set_transformation(&mut self, transformation: glam::Mat4) {
+        if let Some(component) = self.component.as_mut() {
+            component.set_transformation(transformation);
+        }
+    }
+
+    fn get_transformation(&self) -> glam::Mat4 {
+        self.component
+            .as_ref()
+            .map(|x| x.get_transformation())
+            .unwrap_or_default()
+    }
+
+    fn on_post_update_transformation(
+        &mut self,
+        level_physics: Option<&mut crate::content::level::Physics>,
+    ) {
+        if let Some(component) = self.component.as_mut() {
+            component.on_post_update_transformation(level_physics);
+        }
+    }
+
+    fn set_final_transformation(&mut self, final_transformation: glam::Mat4) {
+        if let Some(component) = self.component.as_mut() {
+            component.set_final_transformation(final_transformation);
+        }
+    }
+
+    fn set_parent_final_transformation(&mut self, parent_final_transformation: glam::Mat4) {
+        if let Some(component) = self.component.as_mut() {
+            component.set_parent_final_transformation(parent_final_transformation);
+        }
+    }
+
+    fn get_parent_final_transformation(&self) -> glam::Mat4 {
+        self.component
+            .as_ref()
+            .map(|x| x.get_parent_final_transformation())
+            .unwrap_or_default()
+    }
+
+    fn initialize(
+        &mut self,
+        engine: &mut Engine,
+        files: &[EContentFileType],
+        player_viewport: &mut PlayerViewport,
+    ) {
+        if !self.load(engine.get_component_registry()) {
+            return;
+        }
+        if let Some(component) = self.component.as_mut() {
+            component.initialize(engine, files, player_viewport);
+        }
+    }
+
+    fn initialize_physics(
+        &mut self,
+        rigid_body_set: &mut RigidBodySet,
+        collider_set: &mut ColliderSet,
+    ) {
+        if let Some(component) = self.component.as_mut() {
+            component.initialize_physics(rigid_body_set, collider_set);
+        }
+    }
+
+    fn tick(
+        &mut self,
+        time: f32,
+        engine: &mut Engine,
+        rigid_body_set: &mut RigidBodySet,
+        collider_set: &mut ColliderSet,
+    ) {
+        if let Some(component) = self.component.as_mut() {
+            component.tick(time, engine, rigid_body_set, collider_set);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{CustomComponent, TCustomComponent};
+    use crate::{
+        components::{component::Component, component_registry::ComponentRegistry},
+        content::content_file_type::EContentFileType,
+        engine::Engine,
+        player_viewport::PlayerViewport,
+    };
+    use rapier3d::prelude::{ColliderSet, RigidBodySet};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct HealthComponent {
+        name: String,
+        transformation: glam::Mat4,
+        health: f32,
+    }
+
+    impl Component for HealthComponent {
+        fn get_name(&self) -> String {
+            self.name.clone()
+        }
+
+        fn set_name(&mut self, new_name: String) {
+            self.name = new_name;
+        }
+
+        fn get_final_transformation(&self) -> glam::Mat4 {
+            self.transformation
+        }
+
+        fn set_transformation(&mut self, transformation: glam::Mat4) {
+            self.transformation = transformation;
+        }
+
+        fn get_transformation(&self) -> glam::Mat4 {
+            self.transformation
+        }
+
+        fn on_post_update_transformation(
+            &mut self,
+            _: Option<&mut crate::content::level::Physics>,
+        ) {
+        }
+
+        fn set_final_transformation(&mut self, _: glam::Mat4) {}
+
+        fn set_parent_final_transformation(&mut self, _: glam::Mat4) {}
+
+        fn get_parent_final_transformation(&self) -> glam::Mat4 {
+            glam::Mat4::IDENTITY
+        }
+
+        fn initialize(&mut self, _: &mut Engine, _: &[EContentFileType], _: &mut PlayerViewport) {}
+
+        fn initialize_physics(&mut self, _: &mut RigidBodySet, _: &mut ColliderSet) {}
+
+        fn tick(&mut self, _: f32, _: &mut Engine, _: &mut RigidBodySet, _: &mut ColliderSet) {}
+    }
+
+    impl TCustomComponent for HealthComponent {
+        fn get_type_name(&self) -> &str {
+            "HealthComponent"
+        }
+    }
+
+    #[test]
+    fn test_case_serialize() {
+        let component = CustomComponent::new(Box::new(HealthComponent {
+            name: "Health".to_string(),
+            transformation: glam::Mat4::IDENTITY,
+            health: 42.0,
+        }));
+        let data = serde_json::to_string(&component).unwrap();
+
+        let mut unknown_component: CustomComponent = serde_json::from_str(&data).unwrap();
+        assert!(!unknown_component.load(&ComponentRegistry::default()));
+        assert_eq!(unknown_component.get_name(), "HealthComponent");
+        assert_eq!(serde_json::to_string(&unknown_component).unwrap(), data);
+
+        let mut registry = ComponentRegistry::default();
+        registry.register::<HealthComponent>();
+        assert!(unknown_component.load(&registry));
+        assert_eq!(unknown_component.get_name(), "Health");
+        let health_component = unknown_component.downcast_ref::<HealthComponent>().unwrap();
+        assert_eq!(health_component.health, 42.0);
+
+        unknown_component.unload();
+        assert!(!unknown_component.is_loaded());
+        assert_eq!(serde_json::to_string(&unknown_component).unwrap(), data);
+    }
+}
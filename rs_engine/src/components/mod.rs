@@ -1,3 +1,5 @@
 pub mod component;
+pub mod component_registry;
+pub mod custom_component;
 pub mod point_light_component;
 pub mod spot_light_component;
@@ -1,8 +1,9 @@
 use super::{
     blend_animations::BlendAnimations, curve::Curve, ibl::IBL, level::Level, material::Material,
     material_paramenters_collection::MaterialParamentersCollection,
-    particle_system::ParticleSystem, skeleton::Skeleton, skeleton_animation::SkeletonAnimation,
-    skeleton_mesh::SkeletonMesh, sound::Sound, static_mesh::StaticMesh, texture::TextureFile,
+    particle_system::ParticleSystem, prefab::Prefab, skeleton::Skeleton,
+    skeleton_animation::SkeletonAnimation, skeleton_mesh::SkeletonMesh, sound::Sound,
+    static_mesh::StaticMesh, texture::TextureFile,
 };
 use crate::url_extension::UrlExtension;
 use rs_artifact::asset::Asset;
@@ -24,6 +25,7 @@ pub enum EContentFileType {
     Curve(Rc<RefCell<Curve>>),
     BlendAnimations(Rc<RefCell<BlendAnimations>>),
     MaterialParamentersCollection(Rc<RefCell<MaterialParamentersCollection>>),
+    Prefab(Rc<RefCell<Prefab>>),
 }
 
 macro_rules! common_fn {
@@ -73,6 +75,7 @@ impl EContentFileType {
         Sound,
         Curve,
         BlendAnimations,
-        MaterialParamentersCollection
+        MaterialParamentersCollection,
+        Prefab
     );
 }
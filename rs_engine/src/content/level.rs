@@ -1,8 +1,12 @@
 use super::content_file_type::EContentFileType;
 use crate::actor::Actor;
+use crate::camera::Camera;
 use crate::camera_component::CameraComponent;
 use crate::components::point_light_component::PointLightComponent;
 use crate::components::spot_light_component::SpotLightComponent;
+use crate::content::prefab::{copy_scene_node, Prefab};
+use crate::content::sub_level::{ESubLevelState, SubLevelReference, SubLevelStreaming};
+use crate::culling::{CullingStats, PassVisibility, VisibleDrawObject};
 use crate::directional_light::DirectionalLight;
 use crate::drawable::EDrawObjectType;
 use crate::engine::Engine;
@@ -16,7 +20,7 @@ use rs_core_minimal::name_generator::make_unique_name;
 use rs_foundation::new::{SingleThreadMut, SingleThreadMutType};
 use serde::{Deserialize, Serialize};
 use std::cell::RefCell;
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
 use std::rc::Rc;
 
 pub struct Physics {
@@ -135,6 +139,20 @@ impl Physics {
 pub struct Runtime {
     pub physics: Physics,
     pub is_simulate: bool,
+    pub(crate) sub_levels: Vec<SubLevelStreaming>,
+    /// The maximum number of actors of sub-levels which are initialized or released in a frame.
+    pub streaming_budget: usize,
+}
+
+impl Runtime {
+    fn new(physics: Physics) -> Runtime {
+        Runtime {
+            physics,
+            is_simulate: false,
+            sub_levels: vec![],
+            streaming_budget: 8,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -142,6 +160,8 @@ pub struct Level {
     pub url: url::Url,
     pub actors: Vec<Rc<RefCell<crate::actor::Actor>>>,
     pub directional_lights: Vec<SingleThreadMutType<DirectionalLight>>,
+    #[serde(default)]
+    pub sub_levels: Vec<SubLevelReference>,
 
     #[serde(skip)]
     runtime: Option<Runtime>,
@@ -163,10 +183,8 @@ impl Level {
             actors: vec![],
             url: build_content_file_url("Empty").unwrap(),
             directional_lights: vec![],
-            runtime: Some(Runtime {
-                physics: Self::default_physics(),
-                is_simulate: false,
-            }),
+            sub_levels: vec![],
+            runtime: Some(Runtime::new(Self::default_physics())),
         }
     }
 
@@ -265,16 +283,239 @@ impl Level {
         //     collision_events: VecDeque::new(),
         //     contact_force_events: VecDeque::new(),
         // };
-        self.runtime = Some(Runtime {
-            physics: Self::default_physics(),
-            is_simulate: false,
-        });
+        self.runtime = Some(Runtime::new(Self::default_physics()));
         let actors = self.actors.clone();
         self.init_actors(engine, actors, files, player_viewport);
         let actors = self.actors.clone();
         for actor in actors {
             self.init_actor_physics(actor.clone());
         }
+
+        self.sync_sub_levels(None);
+        self.process_sub_levels(engine, files, player_viewport, usize::MAX);
+    }
+
+    /// The actors of the level and of the sub-levels which are loaded.
+    pub fn get_active_actors(&self) -> Vec<SingleThreadMutType<Actor>> {
+        let mut actors = self.actors.clone();
+        if let Some(runtime) = self.runtime.as_ref() {
+            for sub_level in runtime.sub_levels.iter() {
+                actors.extend(sub_level.actors.iter().cloned());
+            }
+        }
+        actors
+    }
+
+    /// Loads and unloads the sub-levels by their streaming volumes and the requests of
+    /// `load_sub_level` and `unload_sub_level`. The actors are initialized and released over
+    /// several frames, at most `Runtime::streaming_budget` in a frame.
+    pub fn update_streaming(
+        &mut self,
+        engine: &mut Engine,
+        files: &[EContentFileType],
+        player_viewport: &mut PlayerViewport,
+    ) {
+        let Some(budget) = self.runtime.as_ref().map(|x| x.streaming_budget) else {
+            return;
+        };
+        self.rebind_streamed_resources(engine, files, player_viewport);
+        self.sync_sub_levels(Some(player_viewport.camera.get_world_location()));
+        self.process_sub_levels(engine, files, player_viewport, budget);
+    }
+
+    /// Initializes the actors again whose meshes, skeletons or animations were not streamed in
+    /// when they were initialized. Textures need no rebinding, the default texture is replaced
+    /// once the texture is uploaded.
+    fn rebind_streamed_resources(
+        &mut self,
+        engine: &mut Engine,
+        files: &[EContentFileType],
+        player_viewport: &mut PlayerViewport,
+    ) {
+        let asset_urls: HashSet<url::Url> = engine
+            .get_resource_manager()
+            .take_finished_streaming_urls()
+            .into_iter()
+            .collect();
+        if asset_urls.is_empty() {
+            return;
+        }
+        let content_urls: HashSet<url::Url> = files
+            .iter()
+            .filter_map(|file| {
+                let (url, asset_url) = match file {
+                    EContentFileType::StaticMesh(content) => {
+                        let content = content.borrow();
+                        (content.url.clone(), content.asset_info.get_url())
+                    }
+                    EContentFileType::SkeletonMesh(content) => {
+                        let content = content.borrow();
+                        (content.url.clone(), content.asset_url.clone())
+                    }
+                    EContentFileType::Skeleton(content) => {
+                        let content = content.borrow();
+                        (content.url.clone(), content.asset_url.clone())
+                    }
+                    EContentFileType::SkeletonAnimation(content) => {
+                        let content = content.borrow();
+                        (content.url.clone(), content.asset_url.clone())
+                    }
+                    _ => return None,
+                };
+                asset_urls.contains(&asset_url).then_some(url)
+            })
+            .collect();
+        self.reinitialize_actors_referencing(&content_urls, engine, files, player_viewport);
+    }
+
+    /// Initializes the active actors again which reference any of the contents, the physics of
+    /// the actors is created again too.
+    pub fn reinitialize_actors_referencing(
+        &mut self,
+        content_urls: &HashSet<url::Url>,
+        engine: &mut Engine,
+        files: &[EContentFileType],
+        player_viewport: &mut PlayerViewport,
+    ) {
+        if content_urls.is_empty() {
+            return;
+        }
+        for actor in self.get_active_actors() {
+            let is_referencing = actor
+                .borrow()
+                .collect_content_references()
+                .iter()
+                .any(|x| content_urls.contains(x));
+            if is_referencing {
+                self.reinitialize_actor(actor, engine, files, player_viewport);
+            }
+        }
+    }
+
+    pub fn load_sub_level(&mut self, url: &url::Url) -> bool {
+        self.request_sub_level(url, true)
+    }
+
+    pub fn unload_sub_level(&mut self, url: &url::Url) -> bool {
+        self.request_sub_level(url, false)
+    }
+
+    pub fn get_sub_level_state(&self, url: &url::Url) -> ESubLevelState {
+        self.runtime
+            .as_ref()
+            .and_then(|x| x.sub_levels.iter().find(|x| &x.url == url))
+            .map(|x| x.state)
+            .unwrap_or(ESubLevelState::Unloaded)
+    }
+
+    pub fn set_streaming_budget(&mut self, streaming_budget: usize) {
+        if let Some(runtime) = self.runtime.as_mut() {
+            runtime.streaming_budget = streaming_budget.max(1);
+        }
+    }
+
+    fn request_sub_level(&mut self, url: &url::Url, is_requested: bool) -> bool {
+        let Some(reference) = self.sub_levels.iter().find(|x| &x.url == url) else {
+            return false;
+        };
+        let Some(runtime) = self.runtime.as_mut() else {
+            return false;
+        };
+        match runtime.sub_levels.iter_mut().find(|x| &x.url == url) {
+            Some(sub_level) => {
+                sub_level.is_requested = is_requested;
+                if sub_level.is_reload_queued() {
+                    log::trace!("Sub-level {} is loaded again after it is unloaded", url);
+                }
+            }
+            None => {
+                let mut sub_level = SubLevelStreaming::new(reference);
+                sub_level.is_requested = is_requested;
+                runtime.sub_levels.push(sub_level);
+            }
+        }
+        true
+    }
+
+    /// Matches the streaming states with the references, which may be changed in the editor,
+    /// and updates the requests of the sub-levels with streaming volumes.
+    fn sync_sub_levels(&mut self, location: Option<glam::Vec3>) {
+        let Some(runtime) = self.runtime.as_mut() else {
+            return;
+        };
+        for reference in self.sub_levels.iter() {
+            let index = match runtime
+                .sub_levels
+                .iter()
+                .position(|x| x.url == reference.url)
+            {
+                Some(index) => index,
+                None => {
+                    runtime.sub_levels.push(SubLevelStreaming::new(reference));
+                    runtime.sub_levels.len() - 1
+                }
+            };
+            let should_load = reference
+                .streaming_volume
+                .zip(location)
+                .and_then(|(volume, location)| volume.should_load(location));
+            if let Some(should_load) = should_load {
+                runtime.sub_levels[index].is_requested = should_load;
+            }
+        }
+        for sub_level in runtime.sub_levels.iter_mut() {
+            if !self.sub_levels.iter().any(|x| x.url == sub_level.url) {
+                sub_level.is_requested = false;
+            }
+        }
+    }
+
+    fn process_sub_levels(
+        &mut self,
+        engine: &mut Engine,
+        files: &[EContentFileType],
+        player_viewport: &mut PlayerViewport,
+        mut budget: usize,
+    ) {
+        let Some(runtime) = self.runtime.as_mut() else {
+            return;
+        };
+        let physics = &mut runtime.physics;
+        for sub_level in runtime.sub_levels.iter_mut() {
+            sub_level.update_state(files);
+            while budget > 0 {
+                match sub_level.state {
+                    ESubLevelState::Loading => {
+                        let Some(actor) = sub_level.pending_actors.pop_front() else {
+                            break;
+                        };
+                        {
+                            let mut actor = actor.borrow_mut();
+                            actor.initialize(engine, files, player_viewport);
+                            actor.initialize_physics(
+                                &mut physics.rigid_body_set,
+                                &mut physics.collider_set,
+                            );
+                        }
+                        sub_level.actors.push(actor);
+                    }
+                    ESubLevelState::Unloading => {
+                        // The gpu resources are released when the last handles are dropped.
+                        let Some(actor) = sub_level.actors.pop() else {
+                            break;
+                        };
+                        Self::remove_actor_physics(physics, &actor.borrow());
+                    }
+                    ESubLevelState::Unloaded | ESubLevelState::Loaded => break,
+                }
+                budget -= 1;
+            }
+            sub_level.complete_transition(files);
+        }
+        let sub_levels = &self.sub_levels;
+        runtime.sub_levels.retain(|x| {
+            x.state != ESubLevelState::Unloaded || sub_levels.iter().any(|y| y.url == x.url)
+        });
     }
 
     pub fn init_actors(
@@ -327,6 +568,7 @@ impl Level {
             }
         }
 
+        let actors = self.get_active_actors();
         let Some(runtime) = self.runtime.as_mut() else {
             return;
         };
@@ -337,11 +579,12 @@ impl Level {
         }
         let rigid_body_set = &mut runtime.physics.rigid_body_set;
         let collider_set = &mut runtime.physics.collider_set;
-        for actor in self.actors.clone() {
+        for actor in actors {
             let mut actor = actor.borrow_mut();
             actor.tick(time, engine, rigid_body_set, collider_set);
             // actor.tick_physics(rigid_body_set, collider_set);
         }
+        self.update_lods(engine, &player_viewport.camera, player_viewport.height);
 
         let light_components = self.collect_point_light_components();
         player_viewport.update_point_lights(engine, light_components);
@@ -349,6 +592,26 @@ impl Level {
         player_viewport.update_spot_lights(spot_light_components);
     }
 
+    fn update_lods(&self, engine: &mut Engine, camera: &Camera, viewport_height: u32) {
+        for actor in self.get_active_actors() {
+            let actor = actor.borrow();
+            Actor::walk_node(actor.scene_node.clone(), &mut |node| {
+                let node = node.borrow();
+                match &node.component {
+                    EComponentType::StaticMeshComponent(component) => {
+                        component
+                            .borrow_mut()
+                            .update_lod(engine, camera, viewport_height);
+                    }
+                    EComponentType::SkeletonMeshComponent(component) => {
+                        component.borrow_mut().update_lod(camera);
+                    }
+                    _ => {}
+                }
+            });
+        }
+    }
+
     pub fn get_rigid_body_set_mut(&mut self) -> Option<&mut RigidBodySet> {
         self.runtime.as_mut().map(|x| &mut x.physics.rigid_body_set)
     }
@@ -450,7 +713,7 @@ impl Level {
         );
         if let Some((handle, _)) = hit {
             let mut search_node: Option<SingleThreadMutType<SceneNode>> = None;
-            for actor in self.actors.clone() {
+            for actor in self.get_active_actors() {
                 let actor = actor.borrow_mut();
                 self.find_node(actor.scene_node.clone(), handle, &mut search_node);
             }
@@ -522,6 +785,7 @@ impl Level {
             }
             EComponentType::SpotLightComponent(_) => {}
             EComponentType::PointLightComponent(_) => {}
+            EComponentType::CustomComponent(_) => {}
         }
         for child in scene_node.childs.clone() {
             self.find_node(child, handle, search_node);
@@ -529,6 +793,64 @@ impl Level {
     }
 
     pub fn collect_draw_objects(&self) -> Vec<EDrawObjectType> {
+        let mut draw_objects = self.collect_directional_light_draw_objects();
+        for actor in self.get_active_actors() {
+            let actor = actor.borrow_mut();
+            let mut sub_draw_objects = actor.collect_draw_objects();
+            draw_objects.append(&mut sub_draw_objects);
+        }
+        draw_objects
+    }
+
+    /// Collects the draw objects with the passes of the player viewport they are visible to, the
+    /// shadow pass is culled by the views of all the directional lights.
+    pub fn collect_visible_draw_objects(
+        &self,
+        player_viewport: &PlayerViewport,
+    ) -> Vec<VisibleDrawObject> {
+        let light_space_matrices: Vec<glam::Mat4> = self
+            .directional_lights
+            .iter()
+            .map(|x| x.borrow_mut().get_light_space_matrix())
+            .collect();
+        let mut draw_objects: Vec<VisibleDrawObject> = self
+            .collect_directional_light_draw_objects()
+            .into_iter()
+            .map(|draw_object| VisibleDrawObject {
+                draw_object,
+                visibility: PassVisibility::ALL,
+            })
+            .collect();
+        let Some(culling) = player_viewport.make_frustum_culling(&light_space_matrices) else {
+            for actor in self.get_active_actors() {
+                let actor = actor.borrow();
+                draw_objects.extend(actor.collect_draw_objects().into_iter().map(|draw_object| {
+                    VisibleDrawObject {
+                        draw_object,
+                        visibility: PassVisibility::ALL,
+                    }
+                }));
+            }
+            return draw_objects;
+        };
+        let span = tracy_client::span!();
+        let mut stats = CullingStats {
+            total_draw_objects: draw_objects.len(),
+            ..Default::default()
+        };
+        for actor in self.get_active_actors() {
+            let actor = actor.borrow();
+            let mut sub_draw_objects = actor.collect_visible_draw_objects(&culling, &mut stats);
+            draw_objects.append(&mut sub_draw_objects);
+        }
+        span.emit_text(&format!(
+            "draw objects: {}, culled draw objects: {}, shadow only draw objects: {}",
+            stats.total_draw_objects, stats.culled_draw_objects, stats.shadow_only_draw_objects
+        ));
+        draw_objects
+    }
+
+    fn collect_directional_light_draw_objects(&self) -> Vec<EDrawObjectType> {
         let mut draw_objects = vec![];
         for light in self.directional_lights.clone() {
             let light = light.borrow_mut();
@@ -539,17 +861,12 @@ impl Level {
                 .collect();
             draw_objects.append(&mut sub_draw_objects);
         }
-        for actor in self.actors.clone() {
-            let actor = actor.borrow_mut();
-            let mut sub_draw_objects = actor.collect_draw_objects();
-            draw_objects.append(&mut sub_draw_objects);
-        }
         draw_objects
     }
 
     pub fn collect_camera_componenets(&self) -> Vec<SingleThreadMutType<CameraComponent>> {
         let mut camera_componenets = vec![];
-        for actor in self.actors.clone() {
+        for actor in self.get_active_actors() {
             let actor = actor.borrow_mut();
             Actor::walk_node(actor.scene_node.clone(), &mut |node| {
                 let node = node.borrow();
@@ -614,6 +931,7 @@ impl Level {
                 }
                 EComponentType::SpotLightComponent(_) => {}
                 EComponentType::PointLightComponent(_) => {}
+                EComponentType::CustomComponent(_) => {}
             }
         });
     }
@@ -629,7 +947,7 @@ impl Level {
         &self,
         collider: &rapier3d::prelude::ColliderHandle,
     ) -> Option<(SingleThreadMutType<Actor>, SingleThreadMutType<SceneNode>)> {
-        for actor in self.actors.clone() {
+        for actor in self.get_active_actors() {
             let node = {
                 let actor = actor.borrow();
                 actor.find_node_by_collider_handle(collider)
@@ -643,7 +961,7 @@ impl Level {
 
     pub fn compute_scene_aabb(&self) -> Option<rapier3d::prelude::Aabb> {
         let mut aabbs: Vec<rapier3d::prelude::Aabb> = vec![];
-        for actor in self.actors.clone() {
+        for actor in self.get_active_actors() {
             let actor = actor.borrow();
             if let Some(aabb) = actor.compute_components_aabb() {
                 aabbs.push(aabb);
@@ -665,9 +983,138 @@ impl Level {
         self.add_new_actors(engine, vec![duplicated_actor], files, player_viewport);
     }
 
+    pub fn add_prefab_instance(
+        &mut self,
+        prefab: SingleThreadMutType<Prefab>,
+        engine: &mut crate::engine::Engine,
+        files: &[EContentFileType],
+        player_viewport: &mut PlayerViewport,
+    ) -> crate::error::Result<SingleThreadMutType<Actor>> {
+        let name = self.make_actor_name(&prefab.borrow().get_name());
+        let actor = SingleThreadMut::new(Actor::new_from_prefab(name, prefab)?);
+        self.add_new_actors(engine, vec![actor.clone()], files, player_viewport);
+        Ok(actor)
+    }
+
+    /// Replaces the hierarchy of the prefab with the hierarchy of the actor, the other instances
+    /// of the prefab in the level keep their own overrides.
+    pub fn apply_prefab_overrides(
+        &mut self,
+        actor: SingleThreadMutType<Actor>,
+        engine: &mut crate::engine::Engine,
+        files: &[EContentFileType],
+        player_viewport: &mut PlayerViewport,
+    ) -> crate::error::Result<()> {
+        let prefab = actor
+            .borrow()
+            .prefab
+            .as_ref()
+            .and_then(|x| x.get_prefab())
+            .ok_or(crate::error::Error::NullReference(Some(format!(
+                "{} is not a prefab instance",
+                actor.borrow().name
+            ))))?;
+        let instances: Vec<SingleThreadMutType<Actor>> = self
+            .find_prefab_instances(&prefab)
+            .into_iter()
+            .filter(|x| !Rc::ptr_eq(x, &actor))
+            .collect();
+        for instance in instances.iter() {
+            instance.borrow_mut().update_prefab_overrides()?;
+        }
+        let scene_node = copy_scene_node(&actor.borrow().scene_node.borrow())?;
+        prefab.borrow_mut().set_scene_node(scene_node);
+        if let Some(prefab_instance) = actor.borrow_mut().prefab.as_mut() {
+            prefab_instance.overrides.clear();
+            prefab_instance.set_built_version(prefab.borrow().get_version());
+        }
+        for instance in instances {
+            self.rebuild_actor_from_prefab(instance, engine, files, player_viewport)?;
+        }
+        Ok(())
+    }
+
+    /// Creates the hierarchy of the actor again from its prefab without any overrides.
+    pub fn revert_prefab_overrides(
+        &mut self,
+        actor: SingleThreadMutType<Actor>,
+        engine: &mut crate::engine::Engine,
+        files: &[EContentFileType],
+        player_viewport: &mut PlayerViewport,
+    ) -> crate::error::Result<()> {
+        {
+            let mut actor = actor.borrow_mut();
+            let Some(prefab) = actor.prefab.as_mut() else {
+                return Ok(());
+            };
+            prefab.overrides.clear();
+        }
+        self.rebuild_actor_from_prefab(actor, engine, files, player_viewport)
+    }
+
+    /// The physics of the old hierarchy is removed before the hierarchy is replaced, the actor
+    /// is initialized even if the hierarchy can not be built.
+    fn rebuild_actor_from_prefab(
+        &mut self,
+        actor: SingleThreadMutType<Actor>,
+        engine: &mut crate::engine::Engine,
+        files: &[EContentFileType],
+        player_viewport: &mut PlayerViewport,
+    ) -> crate::error::Result<()> {
+        if let Some(level_physics) = self.get_physics_mut() {
+            Self::remove_actor_physics(level_physics, &actor.borrow());
+        }
+        let result = actor.borrow_mut().rebuild_from_prefab();
+        self.initialize_actor(actor, engine, files, player_viewport);
+        result
+    }
+
+    pub fn find_prefab_instances(
+        &self,
+        prefab: &SingleThreadMutType<Prefab>,
+    ) -> Vec<SingleThreadMutType<Actor>> {
+        self.actors
+            .iter()
+            .filter(|x| {
+                x.borrow()
+                    .prefab
+                    .as_ref()
+                    .and_then(|x| x.get_prefab())
+                    .is_some_and(|x| Rc::ptr_eq(&x, prefab))
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn reinitialize_actor(
+        &mut self,
+        actor: SingleThreadMutType<Actor>,
+        engine: &mut crate::engine::Engine,
+        files: &[EContentFileType],
+        player_viewport: &mut PlayerViewport,
+    ) {
+        if let Some(level_physics) = self.get_physics_mut() {
+            Self::remove_actor_physics(level_physics, &actor.borrow());
+        }
+        self.initialize_actor(actor, engine, files, player_viewport);
+    }
+
+    fn initialize_actor(
+        &mut self,
+        actor: SingleThreadMutType<Actor>,
+        engine: &mut crate::engine::Engine,
+        files: &[EContentFileType],
+        player_viewport: &mut PlayerViewport,
+    ) {
+        actor
+            .borrow_mut()
+            .initialize(engine, files, player_viewport);
+        self.init_actor_physics(actor);
+    }
+
     pub fn collect_point_light_components(&self) -> Vec<SingleThreadMutType<PointLightComponent>> {
         let mut lights = vec![];
-        for actor in self.actors.clone() {
+        for actor in self.get_active_actors() {
             let actor = actor.borrow();
             let scene_node = actor.scene_node.clone();
             Actor::walk_node(scene_node, &mut |node| {
@@ -685,7 +1132,7 @@ impl Level {
 
     pub fn collect_spot_light_components(&self) -> Vec<SingleThreadMutType<SpotLightComponent>> {
         let mut lights = vec![];
-        for actor in self.actors.clone() {
+        for actor in self.get_active_actors() {
             let actor = actor.borrow();
             let scene_node = actor.scene_node.clone();
             Actor::walk_node(scene_node, &mut |node| {
@@ -702,7 +1149,7 @@ impl Level {
     }
 
     pub fn set_debug_show_flag(&mut self, flag: crate::debug_show_flag::DebugShowFlag) {
-        for actor in self.actors.clone() {
+        for actor in self.get_active_actors() {
             let actor = actor.borrow_mut();
             let scene_node = actor.scene_node.clone();
             Actor::walk_node(scene_node, &mut |node| {
@@ -725,4 +1172,43 @@ impl Level {
             });
         }
     }
+
+    /// Creates the custom components whose types were registered after the level was initialized.
+    pub fn load_custom_components(
+        &mut self,
+        engine: &mut Engine,
+        files: &[EContentFileType],
+        player_viewport: &mut PlayerViewport,
+    ) {
+        let mut nodes = vec![];
+        for actor in self.get_active_actors() {
+            let actor = actor.borrow();
+            Actor::walk_node(actor.scene_node.clone(), &mut |node| {
+                if let EComponentType::CustomComponent(component) = &node.borrow().component {
+                    if !component.borrow().is_loaded() {
+                        nodes.push(node.clone());
+                    }
+                }
+            });
+        }
+        for node in nodes {
+            let mut node = node.borrow_mut();
+            node.initialize(engine, files, player_viewport);
+            if let Some(physics) = self.get_physics_mut() {
+                node.initialize_physics(&mut physics.rigid_body_set, &mut physics.collider_set);
+            }
+        }
+    }
+
+    /// Saves and drops every custom component, so the plugins defining them can be unloaded.
+    pub fn unload_custom_components(&self) {
+        for actor in self.get_active_actors() {
+            let actor = actor.borrow();
+            Actor::walk_node(actor.scene_node.clone(), &mut |node| {
+                if let EComponentType::CustomComponent(component) = &node.borrow().component {
+                    component.borrow_mut().unload();
+                }
+            });
+        }
+    }
 }
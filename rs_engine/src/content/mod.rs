@@ -7,9 +7,11 @@ pub mod material;
 pub mod material_paramenters_collection;
 pub mod media_source;
 pub mod particle_system;
+pub mod prefab;
 pub mod skeleton;
 pub mod skeleton_animation;
 pub mod skeleton_mesh;
 pub mod sound;
 pub mod static_mesh;
+pub mod sub_level;
 pub mod texture;
@@ -0,0 +1,262 @@
+use crate::{
+    error::{Error, Result},
+    scene_node::SceneNode,
+    url_extension::UrlExtension,
+};
+use rs_artifact::{asset::Asset, resource_type::EResourceType};
+use rs_foundation::new::{SingleThreadMut, SingleThreadMutType};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A property of a prefab instance which differs from the prefab.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PropertyOverride {
+    /// The json pointer of the property in the serialized scene node.
+    pub path: String,
+    /// The value of the property as json.
+    pub value: String,
+}
+
+/// A reusable scene node hierarchy, actors reference it with `PrefabInstance`.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Prefab {
+    pub url: url::Url,
+    scene_node: SingleThreadMutType<SceneNode>,
+    /// Increased when the hierarchy is replaced, instances are built again if they were built
+    /// from another version.
+    #[serde(skip)]
+    version: u64,
+}
+
+impl Prefab {
+    pub fn new(url: url::Url, scene_node: &SceneNode) -> Result<Prefab> {
+        Ok(Prefab {
+            url,
+            scene_node: SingleThreadMut::new(copy_scene_node(scene_node)?),
+            version: 0,
+        })
+    }
+
+    pub fn get_name(&self) -> String {
+        self.url.get_name_in_editor()
+    }
+
+    pub fn get_version(&self) -> u64 {
+        self.version
+    }
+
+    pub fn set_scene_node(&mut self, scene_node: SceneNode) {
+        self.scene_node = SingleThreadMut::new(scene_node);
+        self.version += 1;
+    }
+
+    /// Creates a new hierarchy from the prefab with the overrides applied.
+    pub fn instantiate(&self, overrides: &[PropertyOverride]) -> Result<SceneNode> {
+        let mut value =
+            serde_json::to_value(&*self.scene_node.borrow()).map_err(Error::SerdeJsonError)?;
+        for property_override in overrides {
+            let new_value: Value =
+                serde_json::from_str(&property_override.value).map_err(Error::SerdeJsonError)?;
+            match value.pointer_mut(&property_override.path) {
+                Some(old_value) => *old_value = new_value,
+                None => log::trace!(
+                    "{} is not in prefab {}",
+                    property_override.path,
+                    self.get_name()
+                ),
+            }
+        }
+        serde_json::from_value(value).map_err(Error::SerdeJsonError)
+    }
+
+    /// Finds the properties of the hierarchy which differ from the prefab.
+    pub fn diff(&self, scene_node: &SceneNode) -> Result<Vec<PropertyOverride>> {
+        let base =
+            serde_json::to_value(&*self.scene_node.borrow()).map_err(Error::SerdeJsonError)?;
+        let target = serde_json::to_value(scene_node).map_err(Error::SerdeJsonError)?;
+        let mut overrides = vec![];
+        diff_value(&base, &target, &mut String::new(), &mut overrides)?;
+        Ok(overrides)
+    }
+}
+
+impl Asset for Prefab {
+    fn get_url(&self) -> url::Url {
+        self.url.clone()
+    }
+
+    fn get_resource_type(&self) -> EResourceType {
+        EResourceType::Content(rs_artifact::content_type::EContentType::Prefab)
+    }
+}
+
+/// The reference of an actor to the prefab it was created from.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PrefabInstance {
+    pub url: url::Url,
+    pub overrides: Vec<PropertyOverride>,
+    #[serde(skip)]
+    prefab: Option<SingleThreadMutType<Prefab>>,
+    /// The version of the prefab which the hierarchy of the actor was built from.
+    #[serde(skip)]
+    built_version: Option<u64>,
+}
+
+impl PrefabInstance {
+    /// The hierarchy of the actor is expected to be instantiated from the current version.
+    pub fn new(prefab: SingleThreadMutType<Prefab>) -> PrefabInstance {
+        let url = prefab.borrow().url.clone();
+        let built_version = Some(prefab.borrow().get_version());
+        PrefabInstance {
+            url,
+            overrides: vec![],
+            prefab: Some(prefab),
+            built_version,
+        }
+    }
+
+    pub fn get_prefab(&self) -> Option<SingleThreadMutType<Prefab>> {
+        self.prefab.clone()
+    }
+
+    pub fn set_prefab(&mut self, prefab: SingleThreadMutType<Prefab>) {
+        self.prefab = Some(prefab);
+        self.built_version = None;
+    }
+
+    /// Whether the hierarchy was built from the current version of the resolved prefab.
+    pub fn is_up_to_date(&self) -> bool {
+        self.prefab
+            .as_ref()
+            .is_some_and(|x| self.built_version == Some(x.borrow().get_version()))
+    }
+
+    pub fn set_built_version(&mut self, version: u64) {
+        self.built_version = Some(version);
+    }
+
+    /// Returns the overrides of the hierarchy, or the saved overrides if the prefab is not
+    /// resolved yet.
+    pub fn compute_overrides(&self, scene_node: &SceneNode) -> Result<Vec<PropertyOverride>> {
+        match &self.prefab {
+            Some(prefab) => prefab.borrow().diff(scene_node),
+            None => Ok(self.overrides.clone()),
+        }
+    }
+}
+
+/// Copies the hierarchy without any runtime state.
+pub fn copy_scene_node(scene_node: &SceneNode) -> Result<SceneNode> {
+    let value = serde_json::to_value(scene_node).map_err(Error::SerdeJsonError)?;
+    serde_json::from_value(value).map_err(Error::SerdeJsonError)
+}
+
+fn escape_json_pointer_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+fn diff_value(
+    base: &Value,
+    target: &Value,
+    path: &mut String,
+    overrides: &mut Vec<PropertyOverride>,
+) -> Result<()> {
+    if base == target {
+        return Ok(());
+    }
+    match (base, target) {
+        (Value::Object(base), Value::Object(target))
+            if base.len() == target.len() && base.keys().all(|x| target.contains_key(x)) =>
+        {
+            for (key, base_value) in base {
+                let length = path.len();
+                path.push('/');
+                path.push_str(&escape_json_pointer_token(key));
+                diff_value(base_value, &target[key], path, overrides)?;
+                path.truncate(length);
+            }
+        }
+        (Value::Array(base), Value::Array(target)) if base.len() == target.len() => {
+            for (index, (base_value, target_value)) in base.iter().zip(target).enumerate() {
+                let length = path.len();
+                path.push_str(&format!("/{}", index));
+                diff_value(base_value, target_value, path, overrides)?;
+                path.truncate(length);
+            }
+        }
+        _ => {
+            overrides.push(PropertyOverride {
+                path: path.clone(),
+                value: serde_json::to_string(target).map_err(Error::SerdeJsonError)?,
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Prefab, PrefabInstance, PropertyOverride};
+    use crate::scene_node::SceneNode;
+    use rs_foundation::new::SingleThreadMut;
+
+    #[test]
+    fn test_case_overrides() {
+        let url = url::Url::parse("content://Prefab").unwrap();
+        let mut scene_node = SceneNode::new("Root".to_string());
+        scene_node
+            .childs
+            .push(SceneNode::new_sp("Child".to_string()));
+        let prefab = Prefab::new(url, &scene_node).unwrap();
+        assert!(prefab.diff(&scene_node).unwrap().is_empty());
+
+        let transformation = glam::Mat4::from_translation(glam::vec3(1.0, 2.0, 3.0));
+        scene_node.childs[0]
+            .borrow_mut()
+            .set_transformation(transformation);
+        scene_node.set_name("Renamed".to_string());
+        let mut overrides = prefab.diff(&scene_node).unwrap();
+        overrides.sort_by(|a, b| a.path.cmp(&b.path));
+        let property_override = |path: String, value: &str| PropertyOverride {
+            path,
+            value: value.to_string(),
+        };
+        // The translation is in the last column of the matrix.
+        let transformation_path = "/childs/0/component/SceneComponent/transformation";
+        let expected = vec![
+            property_override(format!("{}/12", transformation_path), "1.0"),
+            property_override(format!("{}/13", transformation_path), "2.0"),
+            property_override(format!("{}/14", transformation_path), "3.0"),
+            property_override(
+                String::from("/component/SceneComponent/name"),
+                "\"Renamed\"",
+            ),
+        ];
+        assert_eq!(overrides, expected);
+
+        let instance = prefab.instantiate(&overrides).unwrap();
+        assert_eq!(instance.get_name(), "Renamed");
+        assert_eq!(
+            instance.childs[0].borrow().get_transformation(),
+            transformation
+        );
+        assert_eq!(prefab.instantiate(&[]).unwrap().get_name(), "Root");
+    }
+
+    #[test]
+    fn test_case_instance_version() {
+        let url = url::Url::parse("content://Prefab").unwrap();
+        let scene_node = SceneNode::new("Root".to_string());
+        let prefab = SingleThreadMut::new(Prefab::new(url, &scene_node).unwrap());
+        let mut instance = PrefabInstance::new(prefab.clone());
+        assert!(instance.is_up_to_date());
+
+        prefab.borrow_mut().set_scene_node(scene_node);
+        assert!(!instance.is_up_to_date());
+        instance.set_built_version(prefab.borrow().get_version());
+        assert!(instance.is_up_to_date());
+
+        instance.set_prefab(prefab);
+        assert!(!instance.is_up_to_date());
+    }
+}
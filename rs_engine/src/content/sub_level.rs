@@ -0,0 +1,245 @@
+use super::content_file_type::EContentFileType;
+use crate::actor::Actor;
+use rs_foundation::new::{SingleThreadMut, SingleThreadMutType};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// A box around the content of a sub-level. The sub-level is loaded when the camera comes
+/// within `load_distance` of the box and unloaded when it goes farther than `unload_distance`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct StreamingVolume {
+    pub min: glam::Vec3,
+    pub max: glam::Vec3,
+    pub load_distance: f32,
+    /// Should not be less than `load_distance`, the gap keeps the sub-level from being loaded
+    /// and unloaded repeatedly near the border.
+    pub unload_distance: f32,
+}
+
+impl StreamingVolume {
+    pub fn distance(&self, location: glam::Vec3) -> f32 {
+        let closest = location.clamp(self.min, self.max);
+        closest.distance(location)
+    }
+
+    /// Returns whether the sub-level should be loaded, or None if it should keep its state.
+    pub fn should_load(&self, location: glam::Vec3) -> Option<bool> {
+        let distance = self.distance(location);
+        if distance <= self.load_distance {
+            Some(true)
+        } else if distance > self.unload_distance.max(self.load_distance) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+}
+
+/// The reference of a persistent level to a level which is streamed in and out at runtime.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SubLevelReference {
+    pub url: url::Url,
+    /// Sub-levels without a streaming volume are only loaded and unloaded by
+    /// `Level::load_sub_level` and `Level::unload_sub_level`.
+    pub streaming_volume: Option<StreamingVolume>,
+    pub is_initially_loaded: bool,
+}
+
+impl SubLevelReference {
+    pub fn new(url: url::Url) -> SubLevelReference {
+        SubLevelReference {
+            url,
+            streaming_volume: None,
+            is_initially_loaded: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ESubLevelState {
+    Unloaded,
+    Loading,
+    Loaded,
+    Unloading,
+}
+
+/// The runtime state of a sub-level in the persistent level.
+pub(crate) struct SubLevelStreaming {
+    pub(crate) url: url::Url,
+    pub(crate) state: ESubLevelState,
+    pub(crate) is_requested: bool,
+    /// Copied actors which are not initialized yet.
+    pub(crate) pending_actors: VecDeque<SingleThreadMutType<Actor>>,
+    /// Initialized actors, which are ticked and drawn with the actors of the persistent level.
+    pub(crate) actors: Vec<SingleThreadMutType<Actor>>,
+}
+
+impl SubLevelStreaming {
+    pub(crate) fn new(reference: &SubLevelReference) -> SubLevelStreaming {
+        SubLevelStreaming {
+            url: reference.url.clone(),
+            state: ESubLevelState::Unloaded,
+            is_requested: reference.is_initially_loaded,
+            pending_actors: VecDeque::new(),
+            actors: vec![],
+        }
+    }
+
+    /// Starts or cancels loading when the request changes, the actors are initialized and
+    /// released later by the level.
+    pub(crate) fn update_state(&mut self, files: &[EContentFileType]) {
+        match (self.state, self.is_requested) {
+            (ESubLevelState::Unloaded, true) => match copy_actors(&self.url, files) {
+                Some(actors) => {
+                    self.pending_actors = actors.into();
+                    self.state = ESubLevelState::Loading;
+                }
+                None => {
+                    log::warn!("Sub-level {} is not found", self.url.to_string());
+                    self.is_requested = false;
+                }
+            },
+            (ESubLevelState::Loading, false) => {
+                self.pending_actors.clear();
+                self.state = ESubLevelState::Unloading;
+            }
+            (ESubLevelState::Loaded, false) => {
+                self.state = ESubLevelState::Unloading;
+            }
+            (ESubLevelState::Unloading, true) => {
+                // The released actors can not be restored, so the reload is queued and starts
+                // when the remaining actors are released.
+            }
+            _ => {}
+        }
+    }
+
+    /// Whether the sub-level is requested again while it is being unloaded.
+    pub(crate) fn is_reload_queued(&self) -> bool {
+        self.state == ESubLevelState::Unloading && self.is_requested
+    }
+
+    /// Completes loading or unloading when every actor is processed, a queued reload starts
+    /// right after the sub-level is unloaded.
+    pub(crate) fn complete_transition(&mut self, files: &[EContentFileType]) {
+        match self.state {
+            ESubLevelState::Loading if self.pending_actors.is_empty() => {
+                self.state = ESubLevelState::Loaded;
+            }
+            ESubLevelState::Unloading if self.actors.is_empty() => {
+                self.state = ESubLevelState::Unloaded;
+                if self.is_requested {
+                    self.update_state(files);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Copies the actors of the level without initialization. The level which is being edited or
+/// ticked is borrowed, so it can not be a sub-level of itself.
+fn copy_actors(
+    url: &url::Url,
+    files: &[EContentFileType],
+) -> Option<Vec<SingleThreadMutType<Actor>>> {
+    files.iter().find_map(|file| {
+        let EContentFileType::Level(level) = file else {
+            return None;
+        };
+        let level = level.try_borrow().ok()?;
+        if &level.url != url {
+            return None;
+        }
+        let actors = level
+            .actors
+            .iter()
+            .map(|actor| {
+                let actor = actor.borrow();
+                SingleThreadMut::new(actor.copy_without_initialization(actor.name.clone()))
+            })
+            .collect();
+        Some(actors)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ESubLevelState, StreamingVolume, SubLevelReference, SubLevelStreaming};
+    use crate::{
+        actor::Actor,
+        content::{content_file_type::EContentFileType, level::Level},
+    };
+    use rs_foundation::new::SingleThreadMut;
+
+    #[test]
+    fn test_case_streaming_volume() {
+        let volume = StreamingVolume {
+            min: glam::Vec3::splat(-1.0),
+            max: glam::Vec3::splat(1.0),
+            load_distance: 10.0,
+            unload_distance: 20.0,
+        };
+        assert_eq!(volume.distance(glam::Vec3::ZERO), 0.0);
+        assert_eq!(volume.distance(glam::vec3(6.0, 0.0, 0.0)), 5.0);
+        assert_eq!(volume.should_load(glam::vec3(6.0, 0.0, 0.0)), Some(true));
+        assert_eq!(volume.should_load(glam::vec3(16.0, 0.0, 0.0)), None);
+        assert_eq!(volume.should_load(glam::vec3(26.0, 0.0, 0.0)), Some(false));
+    }
+
+    /// Initializes or releases the actors like the level does without a budget.
+    fn process(sub_level: &mut SubLevelStreaming, files: &[EContentFileType]) {
+        sub_level.update_state(files);
+        match sub_level.state {
+            ESubLevelState::Loading => {
+                let actors: Vec<_> = sub_level.pending_actors.drain(..).collect();
+                sub_level.actors.extend(actors);
+            }
+            ESubLevelState::Unloading => sub_level.actors.clear(),
+            ESubLevelState::Unloaded | ESubLevelState::Loaded => {}
+        }
+        sub_level.complete_transition(files);
+    }
+
+    #[test]
+    fn test_case_streaming_states() {
+        let mut level = Level::empty_level();
+        level.url = url::Url::parse("content://SubLevel").unwrap();
+        level.actors.push(Actor::new_sp("Actor".to_string()));
+        let reference = SubLevelReference::new(level.url.clone());
+        let files = vec![EContentFileType::Level(SingleThreadMut::new(level))];
+        let mut sub_level = SubLevelStreaming::new(&reference);
+        process(&mut sub_level, &files);
+        assert_eq!(sub_level.state, ESubLevelState::Unloaded);
+
+        sub_level.is_requested = true;
+        sub_level.update_state(&files);
+        assert_eq!(sub_level.state, ESubLevelState::Loading);
+        assert_eq!(sub_level.pending_actors.len(), 1);
+        process(&mut sub_level, &files);
+        assert_eq!(sub_level.state, ESubLevelState::Loaded);
+        assert_eq!(sub_level.actors.len(), 1);
+
+        sub_level.is_requested = false;
+        sub_level.update_state(&files);
+        assert_eq!(sub_level.state, ESubLevelState::Unloading);
+        assert_eq!(sub_level.actors.len(), 1);
+
+        // Requested again while unloading, the reload starts when the actors are released.
+        sub_level.is_requested = true;
+        sub_level.update_state(&files);
+        assert_eq!(sub_level.state, ESubLevelState::Unloading);
+        assert!(sub_level.is_reload_queued());
+        sub_level.actors.clear();
+        sub_level.complete_transition(&files);
+        assert_eq!(sub_level.state, ESubLevelState::Loading);
+        assert_eq!(sub_level.pending_actors.len(), 1);
+        process(&mut sub_level, &files);
+        assert_eq!(sub_level.state, ESubLevelState::Loaded);
+
+        sub_level.is_requested = false;
+        process(&mut sub_level, &files);
+        assert_eq!(sub_level.state, ESubLevelState::Unloaded);
+        assert!(sub_level.actors.is_empty());
+    }
+}
@@ -8,15 +8,244 @@ use std::{
     str::FromStr,
 };
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ETextureColorSpace {
+    Srgb,
+    Linear,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EMipmapFilter {
+    Nearest,
+    Triangle,
+    CatmullRom,
+    Gaussian,
+    Lanczos3,
+}
+
+impl EMipmapFilter {
+    pub fn to_filter_type(&self) -> image::imageops::FilterType {
+        match self {
+            EMipmapFilter::Nearest => image::imageops::FilterType::Nearest,
+            EMipmapFilter::Triangle => image::imageops::FilterType::Triangle,
+            EMipmapFilter::CatmullRom => image::imageops::FilterType::CatmullRom,
+            EMipmapFilter::Gaussian => image::imageops::FilterType::Gaussian,
+            EMipmapFilter::Lanczos3 => image::imageops::FilterType::Lanczos3,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ETextureAddressMode {
+    Repeat,
+    MirrorRepeat,
+    ClampToEdge,
+}
+
+impl ETextureAddressMode {
+    pub fn to_wgpu(&self) -> wgpu::AddressMode {
+        match self {
+            ETextureAddressMode::Repeat => wgpu::AddressMode::Repeat,
+            ETextureAddressMode::MirrorRepeat => wgpu::AddressMode::MirrorRepeat,
+            ETextureAddressMode::ClampToEdge => wgpu::AddressMode::ClampToEdge,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ETextureFilterMode {
+    Nearest,
+    Linear,
+}
+
+impl ETextureFilterMode {
+    pub fn to_wgpu(&self) -> wgpu::FilterMode {
+        match self {
+            ETextureFilterMode::Nearest => wgpu::FilterMode::Nearest,
+            ETextureFilterMode::Linear => wgpu::FilterMode::Linear,
+        }
+    }
+}
+
+/// Options of turning the source image into a texture.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TextureImportSettings {
+    /// Normal maps are always linear.
+    pub color_space: ETextureColorSpace,
+    /// The mip levels are normalized again after downsampling.
+    pub is_normal_map: bool,
+    /// Converts normal maps between the DirectX and the OpenGL convention.
+    pub is_flip_green_channel: bool,
+    /// Larger images are downscaled until the longest side fits.
+    pub max_size: Option<u32>,
+    pub mipmap_filter: EMipmapFilter,
+    pub address_mode: ETextureAddressMode,
+    pub filter_mode: ETextureFilterMode,
+}
+
+impl Default for TextureImportSettings {
+    fn default() -> Self {
+        Self {
+            color_space: ETextureColorSpace::Srgb,
+            is_normal_map: false,
+            is_flip_green_channel: false,
+            max_size: None,
+            mipmap_filter: EMipmapFilter::Triangle,
+            address_mode: ETextureAddressMode::Repeat,
+            filter_mode: ETextureFilterMode::Linear,
+        }
+    }
+}
+
+impl TextureImportSettings {
+    pub fn is_srgb(&self) -> bool {
+        !self.is_normal_map && self.color_space == ETextureColorSpace::Srgb
+    }
+
+    /// Applies the settings which change the largest mip level.
+    pub fn prepare_image(&self, image: image::DynamicImage) -> image::RgbaImage {
+        let image = match self.max_size {
+            Some(max_size) if image.width().max(image.height()) > max_size => {
+                let scale = max_size as f32 / image.width().max(image.height()) as f32;
+                image.resize_exact(
+                    ((image.width() as f32 * scale).round() as u32).max(1),
+                    ((image.height() as f32 * scale).round() as u32).max(1),
+                    self.mipmap_filter.to_filter_type(),
+                )
+            }
+            _ => image,
+        };
+        let mut image = image.to_rgba8();
+        if self.is_normal_map && self.is_flip_green_channel {
+            for pixel in image.pixels_mut() {
+                pixel.0[1] = u8::MAX - pixel.0[1];
+            }
+        }
+        image
+    }
+
+    /// Every mip level from the largest one down to 1x1, the image should be prepared.
+    pub fn generate_mip_levels(&self, image: image::RgbaImage) -> Vec<image::RgbaImage> {
+        let (width, height) = image.dimensions();
+        let mip_level_count = rs_core_minimal::misc::calculate_max_mips(width.max(height));
+        let mut mip_levels = Vec::with_capacity(mip_level_count as usize);
+        mip_levels.push(image);
+        for mip_level in 1..mip_level_count {
+            let mut mip_level_image = image::imageops::resize(
+                mip_levels.last().unwrap(),
+                (width >> mip_level).max(1),
+                (height >> mip_level).max(1),
+                self.mipmap_filter.to_filter_type(),
+            );
+            if self.is_normal_map {
+                renormalize(&mut mip_level_image);
+            }
+            mip_levels.push(mip_level_image);
+        }
+        mip_levels
+    }
+
+    pub fn make_mip_levels(&self, image: image::DynamicImage) -> Vec<image::RgbaImage> {
+        self.generate_mip_levels(self.prepare_image(image))
+    }
+
+    pub fn to_sampler_descriptor(&self) -> wgpu::SamplerDescriptor<'static> {
+        let address_mode = self.address_mode.to_wgpu();
+        let filter_mode = self.filter_mode.to_wgpu();
+        wgpu::SamplerDescriptor {
+            address_mode_u: address_mode,
+            address_mode_v: address_mode,
+            address_mode_w: address_mode,
+            mag_filter: filter_mode,
+            min_filter: filter_mode,
+            mipmap_filter: filter_mode,
+            ..Default::default()
+        }
+    }
+}
+
+/// Averaged normals are shorter than one.
+fn renormalize(image: &mut image::RgbaImage) {
+    for pixel in image.pixels_mut() {
+        let normal = glam::vec3(pixel.0[0] as f32, pixel.0[1] as f32, pixel.0[2] as f32)
+            / u8::MAX as f32
+            * 2.0
+            - 1.0;
+        let Some(normal) = normal.try_normalize() else {
+            continue;
+        };
+        let normal = (normal + 1.0) * 0.5 * u8::MAX as f32;
+        pixel.0[0] = normal.x.round() as u8;
+        pixel.0[1] = normal.y.round() as u8;
+        pixel.0[2] = normal.z.round() as u8;
+    }
+}
+
+/// Decodes every mip level for devices which do not support the compressed format.
+pub fn decode_compressed_texture(
+    compressed_texture: &rs_artifact::image::CompressedTexture,
+) -> crate::error::Result<Vec<image::RgbaImage>> {
+    use rs_artifact::image::ECompressedTextureFormat;
+
+    let mut mip_levels = Vec::with_capacity(compressed_texture.mip_level_count as usize);
+    for mip_level in 0..compressed_texture.mip_level_count {
+        let width = (compressed_texture.width >> mip_level).max(1);
+        let height = (compressed_texture.height >> mip_level).max(1);
+        let Some(data) = compressed_texture.get_mip_level_data(mip_level) else {
+            return Err(crate::error::Error::Other(Some(format!(
+                "Mip level {} is out of range of the data",
+                mip_level
+            ))));
+        };
+        let decode = match compressed_texture.format {
+            ECompressedTextureFormat::BC1RgbaUnorm => texture2ddecoder::decode_bc1,
+            ECompressedTextureFormat::BC3RgbaUnorm => texture2ddecoder::decode_bc3,
+            ECompressedTextureFormat::BC5RgUnorm => texture2ddecoder::decode_bc5,
+            ECompressedTextureFormat::BC7RgbaUnorm => texture2ddecoder::decode_bc7,
+            ECompressedTextureFormat::Etc2Rgb8Unorm => texture2ddecoder::decode_etc2_rgb,
+            ECompressedTextureFormat::Astc4x4Unorm => texture2ddecoder::decode_astc_4_4,
+        };
+        let mut pixels = vec![0_u32; (width * height) as usize];
+        decode(data, width as usize, height as usize, &mut pixels)
+            .map_err(|err| crate::error::Error::Other(Some(err.to_string())))?;
+        // The decoded pixels are BGRA.
+        let data = pixels
+            .iter()
+            .flat_map(|x| {
+                let [b, g, r, a] = x.to_le_bytes();
+                [r, g, b, a]
+            })
+            .collect();
+        let mip_level_image = image::RgbaImage::from_raw(width, height, data)
+            .ok_or(crate::error::Error::Other(None))?;
+        mip_levels.push(mip_level_image);
+    }
+    Ok(mip_levels)
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct TextureFile {
     pub url: url::Url,
     pub image_reference: Option<url::Url>,
     pub is_virtual_texture: bool,
     pub virtual_image_reference: Option<String>,
+    #[serde(default)]
+    pub import_settings: TextureImportSettings,
+}
+
+/// The layout of `TextureFile` before import settings were added.
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+struct TextureFileV0 {
+    url: url::Url,
+    image_reference: Option<url::Url>,
+    is_virtual_texture: bool,
+    virtual_image_reference: Option<String>,
 }
 
 impl Asset for TextureFile {
+    const VERSION: u32 = 1;
+
     fn get_url(&self) -> url::Url {
         self.url.clone()
     }
@@ -24,6 +253,24 @@ impl Asset for TextureFile {
     fn get_resource_type(&self) -> EResourceType {
         EResourceType::Content(rs_artifact::content_type::EContentType::Texture)
     }
+
+    fn migrate(version: u32, payload: &[u8]) -> rs_artifact::error::Result<Self> {
+        match version {
+            0 => {
+                let texture_file = rs_artifact::asset::decode_payload::<TextureFileV0>(payload)?;
+                Ok(TextureFile {
+                    url: texture_file.url,
+                    image_reference: texture_file.image_reference,
+                    is_virtual_texture: texture_file.is_virtual_texture,
+                    virtual_image_reference: texture_file.virtual_image_reference,
+                    import_settings: TextureImportSettings::default(),
+                })
+            }
+            _ => Err(rs_artifact::error::Error::VersionNotSupported(Some(
+                format!("No migration of texture file from version {}.", version),
+            ))),
+        }
+    }
 }
 
 impl TextureFile {
@@ -33,6 +280,7 @@ impl TextureFile {
             image_reference: None,
             is_virtual_texture: false,
             virtual_image_reference: None,
+            import_settings: TextureImportSettings::default(),
         }
     }
 
@@ -89,7 +337,7 @@ impl TextureFile {
         asset_folder: P,
         output: P,
         endian_type: Option<rs_artifact::EEndianType>,
-        tile_size: u32,
+        tile_settings: rs_artifact::virtual_texture::image::TileSettings,
     ) -> anyhow::Result<()> {
         // self.is_virtual_image_cache_vaild(endian_type)?;
         let image_reference = self
@@ -100,7 +348,7 @@ impl TextureFile {
             asset_folder.as_ref().join(image_reference),
             output.as_ref().to_path_buf(),
             endian_type,
-            tile_size,
+            tile_settings,
         );
 
         // if create_result.is_ok() {
@@ -160,8 +408,10 @@ pub fn create_virtual_texture_cache_file<P: AsRef<Path>>(
     file_path: P,
     output: P,
     endian_type: Option<rs_artifact::EEndianType>,
-    tile_size: u32,
+    tile_settings: rs_artifact::virtual_texture::image::TileSettings,
 ) -> anyhow::Result<()> {
+    let tile_size = tile_settings.tile_size;
+    let border_size = tile_settings.border_size;
     assert!(tile_size.is_power_of_two());
     let image = image::open(file_path.as_ref())
         .context(format!("Can not open file {:?}", file_path.as_ref()))?;
@@ -182,18 +432,15 @@ pub fn create_virtual_texture_cache_file<P: AsRef<Path>>(
         lod_sizes.push(glam::uvec2(image.width(), image.height()));
         let mut images: std::collections::HashMap<glam::UVec2, image::DynamicImage> =
             std::collections::HashMap::new();
+        let rgba_image = image.to_rgba8();
         for x in 0..image.width() / tile_size {
             for y in 0..image.height() / tile_size {
-                let sub_image = image::DynamicImage::ImageRgba8(
-                    image::GenericImage::sub_image(
-                        image,
-                        x * tile_size,
-                        y * tile_size,
-                        tile_size,
-                        tile_size,
-                    )
-                    .to_image(),
-                );
+                let sub_image = image::DynamicImage::ImageRgba8(extract_tile_with_border(
+                    &rgba_image,
+                    glam::uvec2(x, y) * tile_size,
+                    tile_size,
+                    border_size,
+                ));
                 images.insert(glam::uvec2(x, y), sub_image);
             }
         }
@@ -207,5 +454,56 @@ pub fn create_virtual_texture_cache_file<P: AsRef<Path>>(
         endian_type,
         lod_sizes,
         tiles,
+        tile_settings,
     )?)
 }
+
+/// Copies a tile and the texels around it, texels outside of the image are clamped to the edge.
+#[cfg(feature = "editor")]
+fn extract_tile_with_border(
+    image: &image::RgbaImage,
+    origin: glam::UVec2,
+    tile_size: u32,
+    border_size: u32,
+) -> image::RgbaImage {
+    let stored_tile_size = tile_size + 2 * border_size;
+    let max = glam::ivec2(image.width() as i32 - 1, image.height() as i32 - 1);
+    image::RgbaImage::from_fn(stored_tile_size, stored_tile_size, |x, y| {
+        let position = origin.as_ivec2() + glam::uvec2(x, y).as_ivec2()
+            - glam::IVec2::splat(border_size as i32);
+        let position = position.clamp(glam::IVec2::ZERO, max);
+        *image.get_pixel(position.x as u32, position.y as u32)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{TextureFile, TextureFileV0, TextureImportSettings};
+    use rs_artifact::asset::Asset;
+
+    #[test]
+    fn test_case_migrate() {
+        let url = url::Url::parse("content://texture").unwrap();
+        let image_reference = url::Url::parse("asset://image.png").unwrap();
+        let payload = bincode::serialize(&TextureFileV0 {
+            url: url.clone(),
+            image_reference: Some(image_reference.clone()),
+            is_virtual_texture: true,
+            virtual_image_reference: Some(String::from("virtual")),
+        })
+        .unwrap();
+        let texture_file = TextureFile::migrate(0, &payload).unwrap();
+        assert_eq!(texture_file.url, url);
+        assert_eq!(texture_file.image_reference, Some(image_reference));
+        assert!(texture_file.is_virtual_texture);
+        assert_eq!(
+            texture_file.virtual_image_reference.as_deref(),
+            Some("virtual")
+        );
+        assert_eq!(
+            texture_file.import_settings,
+            TextureImportSettings::default()
+        );
+        assert!(TextureFile::migrate(1, &payload).is_err());
+    }
+}
@@ -0,0 +1,101 @@
+use crate::drawable::EDrawObjectType;
+use rs_core_minimal::{frustum::Frustum, misc::is_sphere_visible_to_frustum, sphere_3d::Sphere3D};
+
+/// The number of draw objects of a frame culled from every pass or drawn only in the shadow
+/// pass, reported to the profiler.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CullingStats {
+    pub total_draw_objects: usize,
+    pub culled_draw_objects: usize,
+    pub shadow_only_draw_objects: usize,
+}
+
+/// The passes of a frame which a draw object is drawn in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PassVisibility {
+    pub is_visible_to_camera: bool,
+    pub is_visible_to_shadow: bool,
+}
+
+impl PassVisibility {
+    pub const ALL: PassVisibility = PassVisibility {
+        is_visible_to_camera: true,
+        is_visible_to_shadow: true,
+    };
+
+    pub fn is_visible(&self) -> bool {
+        self.is_visible_to_camera || self.is_visible_to_shadow
+    }
+}
+
+#[derive(Clone)]
+pub struct VisibleDrawObject {
+    pub draw_object: EDrawObjectType,
+    pub visibility: PassVisibility,
+}
+
+/// Culls draw objects by the world bounds of their components, separately for the camera pass
+/// and for the shadow pass of every directional light.
+pub struct FrustumCulling {
+    pub camera_frustum: Frustum,
+    pub shadow_frustums: Vec<Frustum>,
+}
+
+impl FrustumCulling {
+    pub fn new(
+        camera_view_projection: &glam::Mat4,
+        light_space_matrices: &[glam::Mat4],
+    ) -> Option<FrustumCulling> {
+        Some(FrustumCulling {
+            camera_frustum: Frustum::from_view_projection(camera_view_projection)?,
+            shadow_frustums: light_space_matrices
+                .iter()
+                .filter_map(Frustum::from_view_projection)
+                .collect(),
+        })
+    }
+
+    pub fn get_visibility(&self, bounds: &Sphere3D) -> PassVisibility {
+        PassVisibility {
+            is_visible_to_camera: is_sphere_visible_to_frustum(bounds, &self.camera_frustum),
+            is_visible_to_shadow: self
+                .shadow_frustums
+                .iter()
+                .any(|x| is_sphere_visible_to_frustum(bounds, x)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::FrustumCulling;
+    use rs_core_minimal::sphere_3d::Sphere3D;
+
+    #[test]
+    fn test_case_shadow_view() {
+        let view = glam::Mat4::look_to_rh(glam::Vec3::ZERO, -glam::Vec3::Z, glam::Vec3::Y);
+        let projection = glam::Mat4::perspective_rh(60.0_f32.to_radians(), 1.0, 0.1, 100.0);
+        let light_projection = glam::Mat4::orthographic_rh(-10.0, 10.0, -10.0, 10.0, 0.0, 50.0);
+        let light_space_matrices = [glam::Vec3::Z, glam::Vec3::X].map(|direction| {
+            light_projection * glam::Mat4::look_to_rh(glam::Vec3::ZERO, direction, glam::Vec3::Y)
+        });
+        let behind_camera = Sphere3D::new(glam::vec3(0.0, 0.0, 10.0), 1.0);
+        let in_front_of_camera = Sphere3D::new(glam::vec3(0.0, 0.0, -20.0), 1.0);
+        let right_of_camera = Sphere3D::new(glam::vec3(10.0, 0.0, 0.0), 1.0);
+
+        let culling = FrustumCulling::new(&(projection * view), &[]).unwrap();
+        let visibility = culling.get_visibility(&in_front_of_camera);
+        assert!(visibility.is_visible_to_camera);
+        assert!(!visibility.is_visible_to_shadow);
+        assert!(!culling.get_visibility(&behind_camera).is_visible());
+
+        let culling = FrustumCulling::new(&(projection * view), &light_space_matrices).unwrap();
+        let visibility = culling.get_visibility(&behind_camera);
+        assert!(!visibility.is_visible_to_camera);
+        assert!(visibility.is_visible_to_shadow);
+        let visibility = culling.get_visibility(&right_of_camera);
+        assert!(visibility.is_visible_to_shadow);
+        let visibility = culling.get_visibility(&in_front_of_camera);
+        assert!(!visibility.is_visible_to_shadow);
+    }
+}
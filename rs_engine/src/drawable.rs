@@ -1,6 +1,6 @@
 use crate::{handle::BufferHandle, player_viewport::PlayerViewport};
 use rs_artifact::material::GroupBinding;
-use rs_render::command::{DrawObject, EBindingResource};
+use rs_render::command::{DrawObject, EBindingResource, MultiDrawIndirect};
 use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
 #[derive(Clone)]
@@ -33,6 +33,61 @@ impl EDrawObjectType {
             }
         }
     }
+
+    pub fn get_index_buffer(&self) -> Option<(BufferHandle, u32)> {
+        let (index_buffer, index_count) = match self {
+            EDrawObjectType::Static(draw_object) => {
+                (&draw_object.index_buffer, draw_object.index_count)
+            }
+            EDrawObjectType::Skin(draw_object) => {
+                (&draw_object.index_buffer, draw_object.index_count)
+            }
+            EDrawObjectType::SkinMaterial(draw_object) => {
+                (&draw_object.index_buffer, draw_object.index_count)
+            }
+            EDrawObjectType::StaticMeshMaterial(draw_object) => {
+                (&draw_object.index_buffer, draw_object.index_count)
+            }
+            EDrawObjectType::Custom(_) => return None,
+        };
+        Some((index_buffer.clone()?, index_count?))
+    }
+
+    pub fn set_index_buffer(&mut self, index_buffer: BufferHandle, index_count: u32) {
+        match self {
+            EDrawObjectType::Static(draw_object) => {
+                draw_object.index_buffer = Some(index_buffer);
+                draw_object.index_count = Some(index_count);
+            }
+            EDrawObjectType::Skin(draw_object) => {
+                draw_object.index_buffer = Some(index_buffer);
+                draw_object.index_count = Some(index_count);
+            }
+            EDrawObjectType::SkinMaterial(draw_object) => {
+                draw_object.index_buffer = Some(index_buffer);
+                draw_object.index_count = Some(index_count);
+            }
+            EDrawObjectType::StaticMeshMaterial(draw_object) => {
+                draw_object.index_buffer = Some(index_buffer);
+                draw_object.index_count = Some(index_count);
+            }
+            EDrawObjectType::Custom(_) => {}
+        }
+    }
+
+    /// Draws parts of the index buffer with the arguments of an indirect buffer, only static
+    /// meshes support it.
+    pub fn set_multi_draw_indirect(&mut self, multi_draw_indirect: Option<MultiDrawIndirect>) {
+        match self {
+            EDrawObjectType::Static(draw_object) => {
+                draw_object.multi_draw_indirect = multi_draw_indirect;
+            }
+            EDrawObjectType::StaticMeshMaterial(draw_object) => {
+                draw_object.multi_draw_indirect = multi_draw_indirect;
+            }
+            _ => {}
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -56,6 +111,7 @@ pub struct StaticMeshDrawObject {
     pub(crate) specular_texture_resource: EBindingResource,
     pub(crate) constants_resource: EBindingResource,
     pub(crate) constants_buffer_handle: BufferHandle,
+    pub(crate) multi_draw_indirect: Option<MultiDrawIndirect>,
     pub window_id: isize,
     pub constants: rs_render::render_pipeline::shading::Constants,
     pub diffuse_texture_url: Option<url::Url>,
@@ -76,6 +132,7 @@ pub struct SkinMeshDrawObject {
     pub(crate) diffuse_texture_resource: EBindingResource,
     pub(crate) specular_texture_resource: EBindingResource,
     pub(crate) constants_resource: EBindingResource,
+    pub(crate) morph_target_deltas_resource: EBindingResource,
 
     pub(crate) constants_buffer_handle: BufferHandle,
     pub window_id: isize,
@@ -113,6 +170,7 @@ pub struct MaterialDrawObject {
     pub(crate) pbr_binding_resources: PBRBindingResources,
 
     pub(crate) skin_constants_resource: EBindingResource,
+    pub(crate) morph_target_deltas_resource: EBindingResource,
 
     pub(crate) user_textures_resources: Vec<EBindingResource>,
 
@@ -135,6 +193,7 @@ pub struct StaticMeshMaterialDrawObject {
     pub(crate) vertex_count: u32,
     pub(crate) index_buffer: Option<BufferHandle>,
     pub(crate) index_count: Option<u32>,
+    pub(crate) multi_draw_indirect: Option<MultiDrawIndirect>,
     pub(crate) pbr_binding_resources: PBRBindingResources,
 
     pub(crate) user_textures_resources: Vec<EBindingResource>,
@@ -1,7 +1,9 @@
 use crate::build_built_in_resouce_url;
 use crate::camera::Camera;
+use crate::components::component_registry::ComponentRegistry;
 use crate::console_cmd::ConsoleCmd;
 use crate::content::content_file_type::EContentFileType;
+use crate::content::texture::TextureImportSettings;
 use crate::default_textures::DefaultTextures;
 use crate::drawable::{
     EDrawObjectType, MaterialDrawObject, PBRBindingResources, SkinMeshDrawObject,
@@ -11,9 +13,13 @@ use crate::error::Result;
 use crate::handle::{EGUITextureHandle, TextureHandle};
 use crate::player_viewport::PlayerViewport;
 use crate::render_thread_mode::ERenderThreadMode;
+use crate::resource_streaming::{
+    is_streamable, EStreamedTexture, EStreamingState, StreamingRequest,
+};
 use crate::{logger::Logger, resource_manager::ResourceManager};
 use rs_artifact::artifact::ArtifactReader;
 use rs_artifact::content_type::EContentType;
+use rs_artifact::image::ECompressedTextureFormat;
 use rs_artifact::resource_info::ResourceInfo;
 use rs_artifact::resource_type::EResourceType;
 use rs_audio::audio_device::AudioDevice;
@@ -41,6 +47,9 @@ use std::path::Path;
 use std::rc::Rc;
 use std::sync::Arc;
 
+/// Not larger than the max texture size of downlevel devices.
+const MORPH_TARGET_DELTAS_TEXTURE_WIDTH: u32 = 1024;
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct VirtualPassHandle {
     pub physical_texture_handle: crate::handle::TextureHandle,
@@ -78,10 +87,13 @@ pub struct Engine {
         HashMap<url::Url, MultipleThreadMutType<Box<dyn TVirtualTextureSource>>>,
     >,
     console_cmds: SingleThreadMutType<HashMap<String, SingleThreadMutType<ConsoleCmd>>>,
+    component_registry: ComponentRegistry,
     pub content_files: HashMap<url::Url, EContentFileType>,
     main_window_id: isize,
     default_textures: DefaultTextures,
     virtual_pass_handle: Option<VirtualPassHandle>,
+    device_features: wgpu::Features,
+    device_limits: wgpu::Limits,
     _audio_device: Option<AudioDevice>,
 }
 
@@ -147,6 +159,8 @@ impl Engine {
         )
         .map_err(|err| crate::error::Error::RendererError(err))?;
 
+        let device_features = renderer.get_device().features();
+        let device_limits = renderer.get_device().limits();
         let mut render_thread_mode = ERenderThreadMode::from(renderer, true);
         let mut virtual_pass_handle: Option<VirtualPassHandle> = None;
         if settings.render_setting.virtual_texture_setting.is_enable {
@@ -206,10 +220,13 @@ impl Engine {
 
             virtual_texture_source_infos: virtual_texture_source_infos.clone(),
             console_cmds: SingleThreadMut::new(HashMap::new()),
+            component_registry: ComponentRegistry::default(),
             content_files: Self::collect_content_files(),
             main_window_id: window_id,
             default_textures,
             virtual_pass_handle,
+            device_features,
+            device_limits,
             // shadow_depth_texture_handle: None,
             _audio_device: Some(audio_device),
         };
@@ -442,6 +459,22 @@ impl Engine {
                             }
                         }
                     },
+                    EContentType::Prefab => {
+                        match resource_manager.get_resource::<crate::content::prefab::Prefab>(
+                            url,
+                            Some(EResourceType::Content(EContentType::Prefab)),
+                        ) {
+                            Ok(prefab) => {
+                                files.insert(
+                                    url.clone(),
+                                    EContentFileType::Prefab(SingleThreadMut::new(prefab)),
+                                );
+                            }
+                            Err(err) => {
+                                log::warn!("{err}");
+                            }
+                        }
+                    }
                 },
                 _ => {}
             }
@@ -455,145 +488,214 @@ impl Engine {
         };
 
         for (url, resource_info) in resource_map {
-            match resource_info.resource_type {
-                rs_artifact::resource_type::EResourceType::SkinMesh => {
-                    if let Ok(skin_mesh) = self
-                        .resource_manager
-                        .get_resource::<rs_artifact::skin_mesh::SkinMesh>(
-                            &url,
-                            Some(resource_info.resource_type),
-                        )
-                    {
-                        self.resource_manager
-                            .add_skin_mesh(url.clone(), Arc::new(skin_mesh));
-                    }
+            self.init_resource(url, resource_info);
+        }
+    }
+
+    /// Like `init_resources`, but meshes, skeletons, animations, sounds and textures are streamed
+    /// in on the io thread, so the first frames are rendered with the default textures.
+    pub fn init_resources_streaming(&mut self) -> Vec<StreamingRequest> {
+        let Ok(resource_map) = self.resource_manager.get_resource_map() else {
+            return vec![];
+        };
+
+        self.resource_manager.set_streaming(true);
+        let mut requests = vec![];
+        for (url, resource_info) in resource_map {
+            if !is_streamable(resource_info.resource_type) {
+                self.init_resource(url, resource_info);
+                continue;
+            }
+            let priority = match resource_info.resource_type {
+                EResourceType::Skeleton => 3,
+                EResourceType::StaticMesh | EResourceType::SkinMesh => 2,
+                EResourceType::SkeletonAnimation => 1,
+                EResourceType::Content(EContentType::Texture) => 1,
+                _ => 0,
+            };
+            match self.resource_manager.request_resource(&url, priority) {
+                Ok(request) => requests.push(request),
+                Err(err) => log::warn!("Failed to request {}, {}", url, err),
+            }
+        }
+        requests
+    }
+
+    fn upload_streamed_textures(&mut self) {
+        for (request, texture) in self.resource_manager.take_streamed_textures() {
+            let url = request.get_url();
+            let result = match &texture {
+                EStreamedTexture::Rgba(mip_levels, import_settings) => {
+                    self.create_texture_from_mip_levels(url, mip_levels, import_settings)
                 }
-                rs_artifact::resource_type::EResourceType::StaticMesh => {
-                    if let Ok(static_mesh) = self
-                        .resource_manager
-                        .get_resource::<rs_artifact::static_mesh::StaticMesh>(
+                EStreamedTexture::Compressed(compressed_texture, import_settings) => self
+                    .create_texture_from_compressed_texture(
+                        url,
+                        compressed_texture,
+                        import_settings,
+                    ),
+            };
+            let state = match result {
+                Ok(_) => EStreamingState::Loaded,
+                Err(err) => {
+                    log::warn!("Failed to upload streamed texture {}, {}", url, err);
+                    EStreamingState::Failed(err.to_string())
+                }
+            };
+            self.resource_manager
+                .finish_streaming_request(&request, state);
+        }
+    }
+
+    fn init_resource(&mut self, url: url::Url, resource_info: ResourceInfo) {
+        match resource_info.resource_type {
+            rs_artifact::resource_type::EResourceType::SkinMesh => {
+                if let Ok(skin_mesh) = self
+                    .resource_manager
+                    .get_resource::<rs_artifact::skin_mesh::SkinMesh>(
                         &url,
                         Some(resource_info.resource_type),
-                    ) {
-                        self.resource_manager
-                            .add_static_mesh(url.clone(), Arc::new(static_mesh));
-                    }
+                    )
+                {
+                    self.resource_manager
+                        .add_skin_mesh(url.clone(), Arc::new(skin_mesh));
                 }
-                rs_artifact::resource_type::EResourceType::SkeletonAnimation => {
-                    if let Ok(skeleton_animation) =
-                        self.resource_manager
-                            .get_resource::<rs_artifact::skeleton_animation::SkeletonAnimation>(
-                                &url,
-                                Some(resource_info.resource_type),
-                            )
-                    {
-                        self.resource_manager
-                            .add_skeleton_animation(url.clone(), Arc::new(skeleton_animation));
-                    }
+            }
+            rs_artifact::resource_type::EResourceType::StaticMesh => {
+                if let Ok(static_mesh) = self
+                    .resource_manager
+                    .get_resource::<rs_artifact::static_mesh::StaticMesh>(
+                        &url,
+                        Some(resource_info.resource_type),
+                    )
+                {
+                    self.resource_manager
+                        .add_static_mesh(url.clone(), Arc::new(static_mesh));
                 }
-                rs_artifact::resource_type::EResourceType::Skeleton => {
-                    if let Ok(skeleton) = self
-                        .resource_manager
-                        .get_resource::<rs_artifact::skeleton::Skeleton>(
-                            &url,
-                            Some(resource_info.resource_type),
-                        )
-                    {
-                        self.resource_manager
-                            .add_skeleton(url.clone(), Arc::new(skeleton));
-                    }
+            }
+            rs_artifact::resource_type::EResourceType::SkeletonAnimation => {
+                if let Ok(skeleton_animation) = self
+                    .resource_manager
+                    .get_resource::<rs_artifact::skeleton_animation::SkeletonAnimation>(
+                    &url,
+                    Some(resource_info.resource_type),
+                ) {
+                    self.resource_manager
+                        .add_skeleton_animation(url.clone(), Arc::new(skeleton_animation));
                 }
-                rs_artifact::resource_type::EResourceType::IBLBaking => {
-                    if let Ok(ibl_baking) = self
-                        .resource_manager
-                        .get_resource::<rs_artifact::ibl_baking::IBLBaking>(
-                            &url,
-                            Some(resource_info.resource_type),
-                        )
-                    {
-                        self.upload_prebake_ibl(ibl_baking.url.clone(), ibl_baking);
-                    }
+            }
+            rs_artifact::resource_type::EResourceType::Skeleton => {
+                if let Ok(skeleton) = self
+                    .resource_manager
+                    .get_resource::<rs_artifact::skeleton::Skeleton>(
+                        &url,
+                        Some(resource_info.resource_type),
+                    )
+                {
+                    self.resource_manager
+                        .add_skeleton(url.clone(), Arc::new(skeleton));
+                }
+            }
+            rs_artifact::resource_type::EResourceType::IBLBaking => {
+                if let Ok(ibl_baking) = self
+                    .resource_manager
+                    .get_resource::<rs_artifact::ibl_baking::IBLBaking>(
+                        &url,
+                        Some(resource_info.resource_type),
+                    )
+                {
+                    self.upload_prebake_ibl(ibl_baking.url.clone(), ibl_baking);
                 }
-                rs_artifact::resource_type::EResourceType::Material => {
-                    if let Ok(material) = self
-                        .resource_manager
-                        .get_resource::<rs_artifact::material::Material>(
-                            &url,
-                            Some(resource_info.resource_type),
-                        )
-                    {
-                        let material_content = self.content_files.values().find_map(|x| match x {
-                            EContentFileType::Material(material_content) => {
-                                if material_content.borrow().asset_url == material.url {
-                                    Some(material_content.clone())
-                                } else {
-                                    None
-                                }
+            }
+            rs_artifact::resource_type::EResourceType::Material => {
+                if let Ok(material) = self
+                    .resource_manager
+                    .get_resource::<rs_artifact::material::Material>(
+                        &url,
+                        Some(resource_info.resource_type),
+                    )
+                {
+                    let material_content = self.content_files.values().find_map(|x| match x {
+                        EContentFileType::Material(material_content) => {
+                            if material_content.borrow().asset_url == material.url {
+                                Some(material_content.clone())
+                            } else {
+                                None
                             }
-                            _ => None,
-                        });
-
-                        if let Some(material_content) = material_content {
-                            let pipeline_handle = self.create_material(material.code);
-                            let mut material_content = material_content.borrow_mut();
-                            material_content.set_pipeline_handle(pipeline_handle);
-                            material_content.set_material_info(material.material_info);
                         }
+                        _ => None,
+                    });
+
+                    if let Some(material_content) = material_content {
+                        let pipeline_handle = self.create_material(material.code);
+                        let mut material_content = material_content.borrow_mut();
+                        material_content.set_pipeline_handle(pipeline_handle);
+                        material_content.set_material_info(material.material_info);
                     }
                 }
-                rs_artifact::resource_type::EResourceType::Sound => {
-                    if let Ok(sound) = self
-                        .resource_manager
-                        .get_resource::<rs_artifact::sound::Sound>(
-                            &url,
-                            Some(resource_info.resource_type),
-                        )
-                    {
-                        let url = sound.url.clone();
-                        self.resource_manager.add_sound(url, Arc::new(sound));
-                    }
+            }
+            rs_artifact::resource_type::EResourceType::Sound => {
+                if let Ok(sound) = self
+                    .resource_manager
+                    .get_resource::<rs_artifact::sound::Sound>(
+                        &url,
+                        Some(resource_info.resource_type),
+                    )
+                {
+                    let url = sound.url.clone();
+                    self.resource_manager.add_sound(url, Arc::new(sound));
                 }
-                rs_artifact::resource_type::EResourceType::Content(content_type) => {
-                    match content_type {
-                        EContentType::Texture => {
-                            let result: crate::error::Result<()> = (|| {
-                                let texture = self
-                                    .resource_manager
-                                    .get_resource::<crate::content::texture::TextureFile>(
+            }
+            rs_artifact::resource_type::EResourceType::Content(content_type) => {
+                match content_type {
+                    EContentType::Texture => {
+                        let result: crate::error::Result<()> = (|| {
+                            let texture = self
+                                .resource_manager
+                                .get_resource::<crate::content::texture::TextureFile>(
+                                &url,
+                                Some(resource_info.resource_type),
+                            )?;
+
+                            let image_reference = texture.image_reference.ok_or(
+                                crate::error::Error::NullReference(Some(
+                                    "No image reference".to_string(),
+                                )),
+                            )?;
+                            log::trace!("Image reference: {}", image_reference.to_string());
+                            let image = self
+                                .resource_manager
+                                .get_resource::<rs_artifact::image::Image>(
+                                    &image_reference,
+                                    Some(EResourceType::Image),
+                                )?;
+                            if let Some(compressed_texture) = &image.compressed_texture {
+                                log::trace!("{:?}", compressed_texture.format);
+                                self.create_texture_from_compressed_texture(
                                     &url,
-                                    Some(resource_info.resource_type),
+                                    compressed_texture,
+                                    &texture.import_settings,
                                 )?;
+                                return Ok(());
+                            }
 
-                                let image_reference = texture.image_reference.ok_or(
-                                    crate::error::Error::NullReference(Some(
-                                        "No image reference".to_string(),
-                                    )),
-                                )?;
-                                log::trace!("Image reference: {}", image_reference.to_string());
-                                let image = self
-                                    .resource_manager
-                                    .get_resource::<rs_artifact::image::Image>(
-                                        &image_reference,
-                                        Some(EResourceType::Image),
-                                    )?;
-
-                                let dyn_image = image::load_from_memory(&image.data)
-                                    .map_err(|err| crate::error::Error::ImageError(err, None))?;
-                                let rgba_image = match dyn_image.as_rgba8() {
-                                    Some(_) => dyn_image.as_rgba8().unwrap().clone(),
-                                    None => dyn_image.to_rgba8(),
-                                };
-                                log::trace!("{:?}", image.image_format);
-                                self.create_texture_from_image(&url, &rgba_image)?;
-                                Ok(())
-                            })();
-                            log::trace!("Laod texture: {}, {:?}", url.to_string(), result);
-                        }
-                        _ => {}
+                            let dyn_image = image::load_from_memory(&image.data)
+                                .map_err(|err| crate::error::Error::ImageError(err, None))?;
+                            log::trace!("{:?}", image.image_format);
+                            let mip_levels = texture.import_settings.make_mip_levels(dyn_image);
+                            self.create_texture_from_mip_levels(
+                                &url,
+                                &mip_levels,
+                                &texture.import_settings,
+                            )?;
+                            Ok(())
+                        })();
+                        log::trace!("Laod texture: {}, {:?}", url.to_string(), result);
                     }
+                    _ => {}
                 }
-                _ => {}
             }
+            _ => {}
         }
     }
 
@@ -673,6 +775,7 @@ impl Engine {
             .send_command(RenderCommand::Present(PresentInfo {
                 render_target_type: *player_viewport.get_render_target_type(),
                 draw_objects,
+                shadow_draw_objects: player_viewport.shadow_draw_objects.drain(..).collect(),
                 virtual_texture_pass,
                 scene_viewport: player_viewport.scene_viewport.clone(),
                 depth_texture_handle: player_viewport
@@ -864,6 +967,7 @@ impl Engine {
             vertex_count: vertexes0.len() as u32,
             index_buffer: Some(index_buffer_handle),
             index_count: Some(indexes.len() as u32),
+            multi_draw_indirect: None,
             constants: Default::default(),
             diffuse_texture_url: Default::default(),
             specular_texture_url: Default::default(),
@@ -896,16 +1000,92 @@ impl Engine {
         EDrawObjectType::Static(object)
     }
 
+    /// Creates a texture of the deltas of all morph targets, the deltas of a target are stored
+    /// contiguously as `[position, 0, normal, 0]` per vertex, row by row. A texture is used
+    /// instead of a storage buffer, because reading storage buffers in the vertex stage needs
+    /// `DownlevelFlags::VERTEX_STORAGE` and `max_storage_buffers_per_shader_stage`, which GL and
+    /// many mobile devices do not have.
+    fn create_morph_target_deltas_texture(
+        &mut self,
+        id: u32,
+        morph_targets: &[rs_artifact::morph_target::MorphTarget],
+        vertex_count: usize,
+    ) -> crate::handle::TextureHandle {
+        let mut deltas: Vec<glam::Vec4> =
+            Vec::with_capacity(morph_targets.len() * vertex_count * 2);
+        for morph_target in morph_targets {
+            for vertex_index in 0..vertex_count {
+                let position = morph_target
+                    .position_deltas
+                    .get(vertex_index)
+                    .copied()
+                    .unwrap_or(glam::Vec3::ZERO);
+                let normal = morph_target
+                    .normal_deltas
+                    .get(vertex_index)
+                    .copied()
+                    .unwrap_or(glam::Vec3::ZERO);
+                deltas.push(position.extend(0.0));
+                deltas.push(normal.extend(0.0));
+            }
+        }
+        let width = (deltas.len() as u32).clamp(1, MORPH_TARGET_DELTAS_TEXTURE_WIDTH);
+        let height = (deltas.len() as u32).div_ceil(width).max(1);
+        if height > self.device_limits.max_texture_dimension_2d {
+            log::warn!(
+                "{} morph target deltas exceed the max texture size, the morph targets are ignored",
+                deltas.len()
+            );
+            deltas.clear();
+        }
+        let (width, height) = if deltas.is_empty() {
+            (1, 1)
+        } else {
+            (width, height)
+        };
+        deltas.resize((width * height) as usize, glam::Vec4::ZERO);
+        let url = build_built_in_resouce_url(format!("MorphTargetDeltas/{}", id)).unwrap();
+        let handle = self.resource_manager.next_texture(url.clone());
+        let mut texture_descriptor_create_info = TextureDescriptorCreateInfo::d2(
+            Some(String::from(format!("{:?}", url.as_str()))),
+            width,
+            height,
+            // The bits of the floats, integer textures can be loaded without a filterable
+            // sample type.
+            Some(wgpu::TextureFormat::Rgba32Uint),
+        );
+        texture_descriptor_create_info.usage =
+            wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST;
+        let create_texture = CreateTexture {
+            handle: *handle,
+            texture_descriptor_create_info,
+            init_data: Some(InitTextureData {
+                data: rs_foundation::cast_to_raw_buffer(&deltas).to_vec(),
+                data_layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(width * std::mem::size_of::<glam::Vec4>() as u32),
+                    rows_per_image: None,
+                },
+            }),
+        };
+        self.render_thread_mode
+            .send_command(RenderCommand::CreateTexture(create_texture));
+        handle
+    }
+
     pub fn create_draw_object_from_skin_mesh(
         &mut self,
         vertexes: &[rs_artifact::skin_mesh::SkinMeshVertex],
         indexes: &[u32],
+        morph_targets: &[rs_artifact::morph_target::MorphTarget],
         name: Option<String>,
         global_constants_handle: crate::handle::BufferHandle,
     ) -> EDrawObjectType {
         let name = name.unwrap_or("".to_string());
         let (vertexes0, vertexes1, vertexes2) = Self::convert_vertex2(vertexes);
         let id = self.next_draw_object_id();
+        let morph_target_deltas_texture_handle =
+            self.create_morph_target_deltas_texture(id, morph_targets, vertexes.len());
         let index_buffer_handle = self.resource_manager.next_buffer();
         let buffer_create_info = BufferCreateInfo {
             label: Some(format!("rs.IndexBuffer.{}", name.clone())),
@@ -1004,6 +1184,9 @@ impl Engine {
                 *self.default_textures.get_texture_handle(),
             ),
             constants_resource: EBindingResource::Constants(*constants_buffer_handle),
+            morph_target_deltas_resource: EBindingResource::Texture(
+                *morph_target_deltas_texture_handle,
+            ),
         };
         EDrawObjectType::Skin(object)
     }
@@ -1012,6 +1195,7 @@ impl Engine {
         &mut self,
         vertexes: &[rs_artifact::skin_mesh::SkinMeshVertex],
         indexes: &[u32],
+        morph_targets: &[rs_artifact::morph_target::MorphTarget],
         name: Option<String>,
         material: Rc<RefCell<crate::content::material::Material>>,
         global_constants_handle: crate::handle::BufferHandle,
@@ -1021,6 +1205,8 @@ impl Engine {
         let name = name.unwrap_or("".to_string());
         let (vertexes0, vertexes1, vertexes2) = Self::convert_vertex2(vertexes);
         let id = self.next_draw_object_id();
+        let morph_target_deltas_texture_handle =
+            self.create_morph_target_deltas_texture(id, morph_targets, vertexes.len());
         let index_buffer_handle = self.resource_manager.next_buffer();
         let buffer_create_info = BufferCreateInfo {
             label: Some(format!("rs.IndexBuffer.{}", name.clone())),
@@ -1118,6 +1304,9 @@ impl Engine {
             index_count: Some(indexes.len() as u32),
             pbr_binding_resources,
             skin_constants_resource: EBindingResource::Constants(*skin_constants_buffer_handle),
+            morph_target_deltas_resource: EBindingResource::Texture(
+                *morph_target_deltas_texture_handle,
+            ),
             user_textures_resources: vec![],
             material,
             constants_buffer_handle,
@@ -1351,6 +1540,7 @@ impl Engine {
             vertex_count: vertexes0.len() as u32,
             index_buffer: Some(index_buffer_handle),
             index_count: Some(indexes.len() as u32),
+            multi_draw_indirect: None,
             pbr_binding_resources,
             user_textures_resources: vec![],
             material,
@@ -1412,18 +1602,138 @@ impl Engine {
         Ok(handle)
     }
 
+    /// Uploads the mip levels which are made with the import settings, the color space and the
+    /// sampler of the texture follow the settings as well.
+    pub fn create_texture_from_mip_levels(
+        &mut self,
+        url: &url::Url,
+        mip_levels: &[image::RgbaImage],
+        import_settings: &TextureImportSettings,
+    ) -> Result<crate::handle::TextureHandle> {
+        let Some(image) = mip_levels.first() else {
+            return Err(crate::error::Error::Other(Some(format!(
+                "{} has no mip levels",
+                url
+            ))));
+        };
+        let texture_descriptor_create_info =
+            make_mip_levels_texture_descriptor(url, image, mip_levels.len(), import_settings);
+        let handle = self.resource_manager.next_texture(url.clone());
+        let create_texture = CreateTexture {
+            handle: *handle,
+            texture_descriptor_create_info,
+            init_data: Some(InitTextureData {
+                data: mip_levels
+                    .iter()
+                    .flat_map(|x| x.as_raw().iter().copied())
+                    .collect(),
+                data_layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(image.width() * 4),
+                    rows_per_image: None,
+                },
+            }),
+        };
+        let render_command = RenderCommand::CreateTexture(create_texture);
+        self.render_thread_mode.send_command(render_command);
+        self.create_texture_sampler(url, import_settings);
+        Ok(handle)
+    }
+
+    fn create_texture_sampler(&mut self, url: &url::Url, import_settings: &TextureImportSettings) {
+        let handle = self.resource_manager.next_texture_sampler(url.clone());
+        let command = RenderCommand::CreateSampler(CreateSampler {
+            handle: *handle,
+            sampler_descriptor: import_settings.to_sampler_descriptor(),
+        });
+        self.render_thread_mode.send_command(command);
+    }
+
+    /// Uploads the mip levels of a block compressed texture without decoding.
+    pub fn create_texture_from_compressed_texture(
+        &mut self,
+        url: &url::Url,
+        compressed_texture: &rs_artifact::image::CompressedTexture,
+        import_settings: &TextureImportSettings,
+    ) -> Result<crate::handle::TextureHandle> {
+        let is_srgb = import_settings.is_srgb();
+        let format = match (compressed_texture.format, is_srgb) {
+            (ECompressedTextureFormat::BC1RgbaUnorm, false) => wgpu::TextureFormat::Bc1RgbaUnorm,
+            (ECompressedTextureFormat::BC1RgbaUnorm, true) => wgpu::TextureFormat::Bc1RgbaUnormSrgb,
+            (ECompressedTextureFormat::BC3RgbaUnorm, false) => wgpu::TextureFormat::Bc3RgbaUnorm,
+            (ECompressedTextureFormat::BC3RgbaUnorm, true) => wgpu::TextureFormat::Bc3RgbaUnormSrgb,
+            (ECompressedTextureFormat::BC5RgUnorm, _) => wgpu::TextureFormat::Bc5RgUnorm,
+            (ECompressedTextureFormat::BC7RgbaUnorm, false) => wgpu::TextureFormat::Bc7RgbaUnorm,
+            (ECompressedTextureFormat::BC7RgbaUnorm, true) => wgpu::TextureFormat::Bc7RgbaUnormSrgb,
+            (ECompressedTextureFormat::Etc2Rgb8Unorm, false) => wgpu::TextureFormat::Etc2Rgb8Unorm,
+            (ECompressedTextureFormat::Etc2Rgb8Unorm, true) => {
+                wgpu::TextureFormat::Etc2Rgb8UnormSrgb
+            }
+            (ECompressedTextureFormat::Astc4x4Unorm, _) => wgpu::TextureFormat::Astc {
+                block: wgpu::AstcBlock::B4x4,
+                channel: if is_srgb {
+                    wgpu::AstcChannel::UnormSrgb
+                } else {
+                    wgpu::AstcChannel::Unorm
+                },
+            },
+        };
+        // TEXTURE_COMPRESSION_BC, TEXTURE_COMPRESSION_ETC2 or TEXTURE_COMPRESSION_ASTC.
+        if !self.device_features.contains(format.required_features()) {
+            log::trace!(
+                "{:?} is not supported by the device, {} is decoded to Rgba8",
+                format,
+                url.as_str()
+            );
+            let mip_levels =
+                crate::content::texture::decode_compressed_texture(compressed_texture)?;
+            return self.create_texture_from_mip_levels(url, &mip_levels, import_settings);
+        }
+        let (blocks_x, _) = compressed_texture.format.mip_level_blocks(
+            compressed_texture.width,
+            compressed_texture.height,
+            0,
+        );
+        let mut texture_descriptor_create_info = TextureDescriptorCreateInfo::d2(
+            Some(String::from(format!("{:?}", url.as_str()))),
+            compressed_texture.width,
+            compressed_texture.height,
+            Some(format),
+        );
+        texture_descriptor_create_info.mip_level_count = compressed_texture.mip_level_count;
+        // Block compressed textures can not be render attachments or storage textures.
+        texture_descriptor_create_info.usage = wgpu::TextureUsages::TEXTURE_BINDING
+            | wgpu::TextureUsages::COPY_SRC
+            | wgpu::TextureUsages::COPY_DST;
+        let handle = self.resource_manager.next_texture(url.clone());
+        let create_texture = CreateTexture {
+            handle: *handle,
+            texture_descriptor_create_info,
+            init_data: Some(InitTextureData {
+                data: compressed_texture.data.clone(),
+                data_layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(blocks_x * compressed_texture.format.block_size()),
+                    rows_per_image: None,
+                },
+            }),
+        };
+        let render_command = RenderCommand::CreateTexture(create_texture);
+        self.render_thread_mode.send_command(render_command);
+        self.create_texture_sampler(url, import_settings);
+        Ok(handle)
+    }
+
     pub fn create_texture_from_path(
         &mut self,
         path: &Path,
         url: &url::Url,
+        import_settings: &TextureImportSettings,
     ) -> Result<crate::handle::TextureHandle> {
         let dynamic_image =
             image::open(path).map_err(|err| crate::error::Error::ImageError(err, None))?;
-        let image = match dynamic_image {
-            image::DynamicImage::ImageRgba8(image) => image,
-            x => x.to_rgba8(),
-        };
-        self.create_texture_from_image(url, &image)
+        let mip_levels = import_settings.make_mip_levels(dynamic_image);
+        self.create_texture_from_mip_levels(url, &mip_levels, import_settings)
     }
 
     pub fn create_virtual_texture_source(
@@ -1501,6 +1811,7 @@ impl Engine {
         let now = std::time::Instant::now();
         self.game_time_sec += (now - self.game_time).as_secs_f32();
         self.game_time = now;
+        self.upload_streamed_textures();
     }
 
     pub fn get_game_time(&self) -> f32 {
@@ -1571,6 +1882,14 @@ impl Engine {
         self.console_cmds.clone()
     }
 
+    pub fn get_component_registry(&self) -> &ComponentRegistry {
+        &self.component_registry
+    }
+
+    pub fn get_component_registry_mut(&mut self) -> &mut ComponentRegistry {
+        &mut self.component_registry
+    }
+
     #[cfg(feature = "editor")]
     pub fn create_grid_draw_object(
         &mut self,
@@ -1762,3 +2081,61 @@ impl Drop for Engine {
         self.logger.flush();
     }
 }
+
+/// The mip levels are only sampled, sRGB formats do not support storage usage.
+fn make_mip_levels_texture_descriptor(
+    url: &url::Url,
+    image: &image::RgbaImage,
+    mip_level_count: usize,
+    import_settings: &TextureImportSettings,
+) -> TextureDescriptorCreateInfo {
+    let format = if import_settings.is_srgb() {
+        wgpu::TextureFormat::Rgba8UnormSrgb
+    } else {
+        wgpu::TextureFormat::Rgba8Unorm
+    };
+    let mut texture_descriptor_create_info = TextureDescriptorCreateInfo::d2(
+        Some(String::from(format!("{:?}", url.as_str()))),
+        image.width(),
+        image.height(),
+        Some(format),
+    );
+    texture_descriptor_create_info.mip_level_count = mip_level_count as u32;
+    texture_descriptor_create_info.usage =
+        wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST;
+    texture_descriptor_create_info
+}
+
+#[cfg(test)]
+mod test {
+    use super::make_mip_levels_texture_descriptor;
+    use crate::content::texture::{ETextureColorSpace, TextureImportSettings};
+
+    #[test]
+    fn test_case_mip_levels_texture_descriptor() {
+        let url = url::Url::parse("content://Texture").unwrap();
+        let image = image::RgbaImage::new(4, 4);
+        for (color_space, format) in [
+            (
+                ETextureColorSpace::Srgb,
+                wgpu::TextureFormat::Rgba8UnormSrgb,
+            ),
+            (ETextureColorSpace::Linear, wgpu::TextureFormat::Rgba8Unorm),
+        ] {
+            let import_settings = TextureImportSettings {
+                color_space,
+                ..Default::default()
+            };
+            let texture_descriptor_create_info =
+                make_mip_levels_texture_descriptor(&url, &image, 3, &import_settings);
+            let texture_descriptor = texture_descriptor_create_info.get();
+            assert_eq!(texture_descriptor.format, format);
+            assert_eq!(texture_descriptor.mip_level_count, 3);
+            // Creating the texture is invalid if the format does not support the usages.
+            let allowed_usages = format
+                .guaranteed_format_features(wgpu::Features::empty())
+                .allowed_usages;
+            assert!(allowed_usages.contains(texture_descriptor.usage));
+        }
+    }
+}
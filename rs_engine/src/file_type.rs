@@ -4,6 +4,7 @@ use std::path::Path;
 pub enum EFileType {
     Fbx,
     Glb,
+    Gltf,
     Jpeg,
     Jpg,
     Png,
@@ -44,6 +45,7 @@ impl EFileType {
             "exr" => Some(EFileType::Exr),
             "hdr" => Some(EFileType::Hdr),
             "glb" => Some(EFileType::Glb),
+            "gltf" => Some(EFileType::Gltf),
             "blend" => Some(EFileType::Blend),
             "dae" => Some(EFileType::Dae),
             "mp4" => Some(EFileType::Mp4),
@@ -57,6 +59,7 @@ impl EFileType {
         match self {
             EFileType::Fbx => "fbx",
             EFileType::Glb => "glb",
+            EFileType::Gltf => "gltf",
             EFileType::Jpeg => "jpeg",
             EFileType::Png => "png",
             EFileType::Exr => "exr",
@@ -72,7 +75,11 @@ impl EFileType {
 
     pub fn is_model(&self) -> bool {
         match self {
-            EFileType::Fbx | EFileType::Glb | EFileType::Blend | EFileType::Dae => true,
+            EFileType::Fbx
+            | EFileType::Glb
+            | EFileType::Gltf
+            | EFileType::Blend
+            | EFileType::Dae => true,
             _ => false,
         }
     }
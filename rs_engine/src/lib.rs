@@ -8,6 +8,7 @@ pub mod collision_componenet;
 pub mod components;
 pub mod console_cmd;
 pub mod content;
+pub mod culling;
 pub mod debug_show_flag;
 pub mod default_textures;
 pub mod directional_light;
@@ -24,6 +25,7 @@ pub mod input_type;
 pub mod kinematic_component;
 pub mod logger;
 pub mod mesh_buffer;
+pub mod mesh_lod;
 pub mod mipmap_generator;
 pub mod misc;
 pub mod particle;
@@ -35,6 +37,7 @@ pub mod plugin;
 pub mod property;
 pub mod render_thread_mode;
 pub mod resource_manager;
+pub mod resource_streaming;
 pub mod rotator;
 pub mod scene_node;
 pub mod skeleton_animation_provider;
@@ -0,0 +1,258 @@
+use crate::{
+    camera::{Camera, ECameraType},
+    drawable::EDrawObjectType,
+    engine::Engine,
+    handle::BufferHandle,
+    misc::aabb_as_sphere,
+};
+use rs_artifact::{
+    cluster_lod::{BoundingSphere, ClusterLod},
+    mesh_lod::{select_lod, MeshLod},
+};
+use rs_core_minimal::settings::ClusterLodSetting;
+use rs_render::command::MultiDrawIndirect;
+use wgpu::util::DrawIndexedIndirectArgs;
+
+/// Doubles the pixel error this many times at most to fit into the triangle budget.
+const MAX_RELAX_COUNT: usize = 8;
+
+/// The diameter of the bounding sphere projected to the screen, relative to the screen height.
+pub fn compute_screen_size(aabb: &rapier3d::prelude::Aabb, camera: &Camera) -> f32 {
+    let sphere = aabb_as_sphere(aabb);
+    let projection = camera.get_projection_matrix();
+    let scale = projection.x_axis.x.abs().max(projection.y_axis.y.abs());
+    match camera.get_camera_type() {
+        ECameraType::Perspective(_) => {
+            let distance = (sphere.center - camera.get_world_location())
+                .length()
+                .max(camera.get_z_near());
+            sphere.radius * scale / distance
+        }
+        ECameraType::Orthographic(_) => sphere.radius * scale,
+    }
+}
+
+/// The index buffers of every level of detail of a draw object, the buffer of the draw object
+/// is swapped when a different level is selected.
+#[derive(Clone)]
+pub struct LodIndexBuffers {
+    lod_screen_sizes: Vec<f32>,
+    index_buffers: Vec<(BufferHandle, u32)>,
+    current_lod: usize,
+}
+
+impl LodIndexBuffers {
+    pub fn new(
+        engine: &mut Engine,
+        draw_object: &EDrawObjectType,
+        lods: &[MeshLod],
+        name: &str,
+    ) -> Option<LodIndexBuffers> {
+        if lods.is_empty() {
+            return None;
+        }
+        let mut index_buffers = vec![draw_object.get_index_buffer()?];
+        for (level, lod) in lods.iter().enumerate() {
+            let index_buffer = engine
+                .create_buffer(
+                    rs_foundation::cast_to_raw_buffer(&lod.indexes).to_vec(),
+                    wgpu::BufferUsages::INDEX,
+                    Some(format!("rs.IndexBuffer.{}.Lod{}", name, level + 1)),
+                )
+                .ok()?;
+            index_buffers.push((index_buffer, lod.indexes.len() as u32));
+        }
+        Some(LodIndexBuffers {
+            lod_screen_sizes: lods.iter().map(|x| x.screen_size).collect(),
+            index_buffers,
+            current_lod: 0,
+        })
+    }
+
+    pub fn get_current_lod(&self) -> usize {
+        self.current_lod
+    }
+
+    pub fn update(&mut self, screen_size: f32, draw_object: &mut EDrawObjectType) {
+        let lod = select_lod(self.lod_screen_sizes.iter().copied(), screen_size);
+        if lod == self.current_lod {
+            return;
+        }
+        let (index_buffer, index_count) = self.index_buffers[lod].clone();
+        draw_object.set_index_buffer(index_buffer, index_count);
+        self.current_lod = lod;
+    }
+}
+
+/// The simplification error of a cluster projected to the screen in pixels.
+pub fn compute_projected_error(
+    error_bounds: &BoundingSphere,
+    error: f32,
+    model: &glam::Mat4,
+    camera: &Camera,
+    viewport_height: u32,
+) -> f32 {
+    if error == 0.0 {
+        return 0.0;
+    }
+    let scale = model
+        .x_axis
+        .truncate()
+        .length()
+        .max(model.y_axis.truncate().length())
+        .max(model.z_axis.truncate().length());
+    let center = model.transform_point3(error_bounds.center);
+    let pixels_per_unit =
+        camera.get_projection_matrix().y_axis.y.abs() * 0.5 * viewport_height as f32;
+    match camera.get_camera_type() {
+        ECameraType::Perspective(_) => {
+            let distance = ((center - camera.get_world_location()).length()
+                - error_bounds.radius * scale)
+                .max(camera.get_z_near());
+            error * scale * pixels_per_unit / distance
+        }
+        ECameraType::Orthographic(_) => error * scale * pixels_per_unit,
+    }
+}
+
+/// Draws the clusters of a cluster level of detail hierarchy which are selected by their
+/// projected error with one multi draw indirect call.
+#[derive(Clone)]
+pub struct ClusterLodBuffers {
+    cluster_lod: ClusterLod,
+    index_buffer: BufferHandle,
+    index_count: u32,
+    indirect_buffer: BufferHandle,
+    selected_clusters: Option<Vec<usize>>,
+    selected_triangle_count: u32,
+}
+
+impl ClusterLodBuffers {
+    pub fn new(
+        engine: &mut Engine,
+        cluster_lod: &ClusterLod,
+        name: &str,
+    ) -> Option<ClusterLodBuffers> {
+        if cluster_lod.clusters.is_empty() {
+            return None;
+        }
+        let index_buffer = engine
+            .create_buffer(
+                rs_foundation::cast_to_raw_buffer(&cluster_lod.indexes).to_vec(),
+                wgpu::BufferUsages::INDEX,
+                Some(format!("rs.IndexBuffer.{}.ClusterLod", name)),
+            )
+            .ok()?;
+        let draw_indexed_indirect_args_array =
+            vec![Self::make_draw_indexed_indirect_args(0, 0); cluster_lod.clusters.len()];
+        let indirect_buffer = engine
+            .create_buffer(
+                rs_foundation::cast_to_raw_buffer(&draw_indexed_indirect_args_array).to_vec(),
+                wgpu::BufferUsages::INDIRECT | wgpu::BufferUsages::MAP_WRITE,
+                Some(format!("rs.IndirectBuffer.{}.ClusterLod", name)),
+            )
+            .ok()?;
+        Some(ClusterLodBuffers {
+            cluster_lod: ClusterLod {
+                indexes: vec![],
+                clusters: cluster_lod.clusters.clone(),
+            },
+            index_buffer,
+            index_count: cluster_lod.indexes.len() as u32,
+            indirect_buffer,
+            selected_clusters: None,
+            selected_triangle_count: 0,
+        })
+    }
+
+    fn make_draw_indexed_indirect_args(
+        first_index: u32,
+        index_count: u32,
+    ) -> DrawIndexedIndirectArgs {
+        DrawIndexedIndirectArgs {
+            index_count,
+            instance_count: 1,
+            first_index,
+            base_vertex: 0,
+            first_instance: 0,
+        }
+    }
+
+    pub fn get_selected_cluster_count(&self) -> usize {
+        self.selected_clusters.as_ref().map_or(0, |x| x.len())
+    }
+
+    pub fn get_selected_triangle_count(&self) -> u32 {
+        self.selected_triangle_count
+    }
+
+    fn select_clusters(
+        &self,
+        max_pixel_error: f32,
+        model: &glam::Mat4,
+        camera: &Camera,
+        viewport_height: u32,
+    ) -> (Vec<usize>, u32) {
+        let selected_clusters = self.cluster_lod.select_clusters(|error_bounds, error| {
+            compute_projected_error(error_bounds, error, model, camera, viewport_height)
+                <= max_pixel_error
+        });
+        let triangle_count = selected_clusters
+            .iter()
+            .map(|x| self.cluster_lod.clusters[*x].index_count / 3)
+            .sum();
+        (selected_clusters, triangle_count)
+    }
+
+    /// Selects the clusters whose error is within the pixel error of the setting, the error is
+    /// relaxed while the selection exceeds the triangle budget.
+    pub fn update(
+        &mut self,
+        engine: &mut Engine,
+        model: &glam::Mat4,
+        camera: &Camera,
+        viewport_height: u32,
+        setting: &ClusterLodSetting,
+        draw_object: &mut EDrawObjectType,
+    ) {
+        let mut max_pixel_error = setting.max_pixel_error.max(f32::EPSILON);
+        let (mut selected_clusters, mut triangle_count) =
+            self.select_clusters(max_pixel_error, model, camera, viewport_height);
+        for _ in 0..MAX_RELAX_COUNT {
+            if triangle_count <= setting.triangle_budget {
+                break;
+            }
+            max_pixel_error *= 2.0;
+            (selected_clusters, triangle_count) =
+                self.select_clusters(max_pixel_error, model, camera, viewport_height);
+        }
+        if self.selected_clusters.as_ref() == Some(&selected_clusters) {
+            return;
+        }
+
+        let mut draw_indexed_indirect_args_array = selected_clusters
+            .iter()
+            .map(|x| {
+                let cluster = &self.cluster_lod.clusters[*x];
+                Self::make_draw_indexed_indirect_args(cluster.index_offset, cluster.index_count)
+            })
+            .collect::<Vec<DrawIndexedIndirectArgs>>();
+        // The buffer is always written as a whole.
+        draw_indexed_indirect_args_array.resize(
+            self.cluster_lod.clusters.len(),
+            Self::make_draw_indexed_indirect_args(0, 0),
+        );
+        engine.update_buffer(
+            self.indirect_buffer.clone(),
+            rs_foundation::cast_to_raw_buffer(&draw_indexed_indirect_args_array),
+        );
+        draw_object.set_index_buffer(self.index_buffer.clone(), self.index_count);
+        draw_object.set_multi_draw_indirect(Some(MultiDrawIndirect {
+            indirect_buffer_handle: *self.indirect_buffer,
+            indirect_offset: 0,
+            count: selected_clusters.len() as u32,
+        }));
+        self.selected_clusters = Some(selected_clusters);
+        self.selected_triangle_count = triangle_count;
+    }
+}
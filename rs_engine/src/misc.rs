@@ -82,6 +82,21 @@ pub fn aabb_as_sphere(aabb: &rapier3d::prelude::Aabb) -> Sphere3D {
     Sphere3D::new(center, half_extents.length())
 }
 
+/// Returns a sphere which contains the transformed sphere, the radius is scaled by the largest
+/// scale of the transformation.
+pub fn transform_sphere(sphere: &Sphere3D, transformation: &glam::Mat4) -> Sphere3D {
+    let scale = transformation
+        .x_axis
+        .truncate()
+        .length()
+        .max(transformation.y_axis.truncate().length())
+        .max(transformation.z_axis.truncate().length());
+    Sphere3D::new(
+        transformation.transform_point3(sphere.center),
+        sphere.radius * scale,
+    )
+}
+
 pub fn compute_appropriate_offset_look_and_projection_matrix(
     level: &crate::content::level::Level,
 ) -> Option<(f32, glam::Vec3, glam::Mat4)> {
@@ -1,6 +1,7 @@
 use crate::camera::Camera;
 use crate::components::component::Component;
 use crate::content::content_file_type::EContentFileType;
+use crate::culling::{FrustumCulling, VisibleDrawObject};
 use crate::directional_light::DirectionalLight;
 use crate::drawable::{EDrawObjectType, PBRBindingResources};
 use crate::engine::{Engine, VirtualPassHandle};
@@ -12,12 +13,12 @@ use crate::resource_manager::ResourceManager;
 use crate::{build_built_in_resouce_url, BUILT_IN_RESOURCE};
 use glam::Vec4Swizzles;
 use rapier3d::prelude::*;
-use rs_artifact::material::GroupBinding;
+use rs_artifact::material::{GroupBinding, TextureBinding};
 use rs_foundation::new::{MultipleThreadMutType, SingleThreadMutType};
 use rs_render::antialias_type::{FXAAInfo, MSAAInfo};
 use rs_render::command::{
-    BufferCreateInfo, CreateBuffer, DrawObject, EBindingResource, ERenderTargetType, RenderCommand,
-    ShadowMapping, TextureDescriptorCreateInfo, UpdateBuffer, VirtualPassSet,
+    BufferCreateInfo, CreateBuffer, DrawObject, EBindingResource, EDrawCallType, ERenderTargetType,
+    RenderCommand, ShadowMapping, TextureDescriptorCreateInfo, UpdateBuffer, VirtualPassSet,
 };
 use rs_render::constants::Constants;
 use rs_render::global_uniform;
@@ -26,7 +27,7 @@ use rs_render::vertex_data_type::mesh_vertex::MeshVertex3;
 use rs_render::virtual_texture_source::TVirtualTextureSource;
 use rs_render::{antialias_type::EAntialiasType, scene_viewport::SceneViewport};
 use rs_render_types::MaterialOptions;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::ops::Deref;
 
 bitflags::bitflags! {
@@ -54,6 +55,7 @@ pub struct PlayerViewport {
     pub shadow_depth_texture_handle: Option<TextureHandle>,
     grid_draw_object: Option<DrawObject>,
     pub draw_objects: Vec<DrawObject>,
+    pub shadow_draw_objects: Vec<DrawObject>,
     pub particle_draw_objects: Vec<DrawObject>,
     pub camera: Camera,
     virtual_texture_source_infos: SingleThreadMutType<
@@ -68,6 +70,7 @@ pub struct PlayerViewport {
     pub is_use_default_input_process: bool,
     pub is_grid_visible: bool,
     cluster_light: Option<crate::cluster_light::ClusterLight>,
+    pub is_frustum_culling_enabled: bool,
 }
 
 impl PlayerViewport {
@@ -82,7 +85,15 @@ impl PlayerViewport {
         let scene_viewport = SceneViewport::new();
 
         let global_constants_handle = engine.get_resource_manager().next_buffer();
-        let global_constants = global_uniform::Constants::default();
+        let mut global_constants = global_uniform::Constants::default();
+        {
+            let virtual_texture_setting =
+                &engine.get_settings().render_setting.virtual_texture_setting;
+            global_constants.physical_texture_size =
+                virtual_texture_setting.physical_texture_size as f32;
+            global_constants.tile_size = virtual_texture_setting.tile_size as f32;
+            global_constants.tile_border_size = virtual_texture_setting.tile_border_size as f32;
+        }
         let command = RenderCommand::CreateBuffer(CreateBuffer {
             handle: *global_constants_handle,
             buffer_create_info: BufferCreateInfo {
@@ -181,6 +192,7 @@ impl PlayerViewport {
             shadow_depth_texture_handle: Some(shadow_depth_texture_handle),
             grid_draw_object,
             draw_objects: vec![],
+            shadow_draw_objects: vec![],
             particle_draw_objects: vec![],
             camera,
             virtual_texture_source_infos,
@@ -197,6 +209,7 @@ impl PlayerViewport {
             spot_lights_constants,
             spot_lights_constants_handle,
             cluster_light: None,
+            is_frustum_culling_enabled: true,
         }
     }
 
@@ -423,6 +436,42 @@ impl PlayerViewport {
         engine.get_render_thread_mode_mut().send_command(command);
     }
 
+    /// Textures are ordered by binding, and each texture with a sampler binding is followed by
+    /// its own sampler, or the global sampler if the texture has none.
+    fn make_user_textures_resources(
+        &self,
+        engine: &Engine,
+        map_textures: &HashSet<TextureBinding>,
+    ) -> Vec<EBindingResource> {
+        let resource_manager = engine.get_resource_manager();
+        let mut map_textures: Vec<&TextureBinding> = map_textures.iter().collect();
+        map_textures.sort_by_key(|x| x.binding);
+        let expected_len = map_textures.len()
+            + map_textures
+                .iter()
+                .filter(|x| x.sampler_binding.is_some())
+                .count();
+        let mut binding_resources: Vec<EBindingResource> = Vec::with_capacity(expected_len);
+        for map_texture in map_textures {
+            // The default texture is bound until the texture is streamed in.
+            let handle = resource_manager
+                .get_texture_by_url(&map_texture.texture_url)
+                .unwrap_or_else(|| {
+                    log::trace!("Can not find {}", map_texture.texture_url.to_string());
+                    engine.get_default_textures().get_texture_handle()
+                });
+            binding_resources.push(EBindingResource::Texture(*handle));
+            if map_texture.sampler_binding.is_some() {
+                let sampler_handle = resource_manager
+                    .get_texture_sampler_by_url(&map_texture.texture_url)
+                    .unwrap_or_else(|| self.global_sampler_handle.clone());
+                binding_resources.push(EBindingResource::Sampler(*sampler_handle));
+            }
+        }
+        assert_eq!(binding_resources.len(), expected_len);
+        binding_resources
+    }
+
     pub fn update_draw_object(&mut self, engine: &mut Engine, object: &mut EDrawObjectType) {
         match object {
             EDrawObjectType::Static(object) => {
@@ -558,21 +607,8 @@ impl PlayerViewport {
                     rs_foundation::cast_any_as_u8_slice(&object.virtual_texture_constants),
                 );
 
-                let mut binding_resources: Vec<EBindingResource> =
-                    Vec::with_capacity(map_textures.len());
-                for map_texture in map_textures {
-                    let resource_manager = engine.get_resource_manager();
-
-                    if let Some(handle) =
-                        resource_manager.get_texture_by_url(&map_texture.texture_url)
-                    {
-                        binding_resources.push(EBindingResource::Texture(*handle));
-                    } else {
-                        log::trace!("Can not find {}", map_texture.texture_url.to_string());
-                    }
-                }
-                assert_eq!(binding_resources.len(), map_textures.len());
-                object.user_textures_resources = binding_resources;
+                object.user_textures_resources =
+                    self.make_user_textures_resources(engine, map_textures);
                 let resource_manager = engine.get_resource_manager();
 
                 let ibl_textures = resource_manager.get_ibl_textures();
@@ -692,20 +728,8 @@ impl PlayerViewport {
                     rs_foundation::cast_any_as_u8_slice(&object.virtual_texture_constants),
                 );
 
-                let mut binding_resources: Vec<EBindingResource> =
-                    Vec::with_capacity(map_textures.len());
-                for map_texture in map_textures {
-                    let resource_manager = engine.get_resource_manager();
-                    if let Some(handle) =
-                        resource_manager.get_texture_by_url(&map_texture.texture_url)
-                    {
-                        binding_resources.push(EBindingResource::Texture(*handle));
-                    } else {
-                        log::trace!("Can not find {}", map_texture.texture_url.to_string());
-                    }
-                }
-                assert_eq!(binding_resources.len(), map_textures.len());
-                object.user_textures_resources = binding_resources;
+                object.user_textures_resources =
+                    self.make_user_textures_resources(engine, map_textures);
 
                 let ibl_textures = {
                     let resource_manager = engine.get_resource_manager();
@@ -884,7 +908,7 @@ impl PlayerViewport {
         match draw_object {
             EDrawObjectType::Static(static_objcet) => {
                 let static_objcet = static_objcet.clone();
-                let draw_object = DrawObject::new(
+                let mut draw_object = DrawObject::new(
                     static_objcet.id,
                     static_objcet.vertex_buffers.iter().map(|x| **x).collect(),
                     static_objcet.vertex_count,
@@ -905,6 +929,10 @@ impl PlayerViewport {
                         vec![static_objcet.constants_resource],
                     ],
                 );
+                if let Some(multi_draw_indirect) = static_objcet.multi_draw_indirect {
+                    draw_object.draw_call_type =
+                        EDrawCallType::MultiDrawIndirect(multi_draw_indirect);
+                }
 
                 Ok(draw_object)
             }
@@ -929,7 +957,10 @@ impl PlayerViewport {
                             skin_objcet.diffuse_texture_resource,
                             skin_objcet.specular_texture_resource,
                         ],
-                        vec![skin_objcet.constants_resource],
+                        vec![
+                            skin_objcet.constants_resource,
+                            skin_objcet.morph_target_deltas_resource,
+                        ],
                     ],
                 );
                 Ok(draw_object)
@@ -957,6 +988,12 @@ impl PlayerViewport {
                     group_binding_to_resource
                         .push((*group_binding, skin_objcet.skin_constants_resource.clone()));
                 }
+                if let Some(group_binding) = &material_info.morph_target_deltas_binding {
+                    group_binding_to_resource.push((
+                        *group_binding,
+                        skin_objcet.morph_target_deltas_resource.clone(),
+                    ));
+                }
 
                 let mut binding_resources = Self::make_binding_resources(group_binding_to_resource);
                 binding_resources[0].append(&mut skin_objcet.user_textures_resources.clone());
@@ -989,6 +1026,7 @@ impl PlayerViewport {
                         vec![
                             constants_resource.clone(),
                             skin_objcet.skin_constants_resource.clone(),
+                            skin_objcet.morph_target_deltas_resource.clone(),
                         ],
                     ],
                 });
@@ -1002,6 +1040,7 @@ impl PlayerViewport {
                             global_constants_resource.clone(),
                             constants_resource.clone(),
                             skin_objcet.skin_constants_resource.clone(),
+                            skin_objcet.morph_target_deltas_resource.clone(),
                         ]],
                         is_skin: true,
                     });
@@ -1054,6 +1093,10 @@ impl PlayerViewport {
                     binding_resources,
                 );
                 draw_object.debug_group_label = static_mesh_draw_objcet.debug_group_label;
+                if let Some(multi_draw_indirect) = static_mesh_draw_objcet.multi_draw_indirect {
+                    draw_object.draw_call_type =
+                        EDrawCallType::MultiDrawIndirect(multi_draw_indirect);
+                }
                 draw_object.virtual_pass_set = Some(VirtualPassSet {
                     vertex_buffers: vec![*static_mesh_draw_objcet.vertex_buffers[0]],
                     binding_resources: vec![
@@ -1105,6 +1148,26 @@ impl PlayerViewport {
         self.draw_objects.append(&mut draw_objects);
     }
 
+    /// The draw objects visible only to the shadow pass are drawn only into the shadow map.
+    pub fn append_visible_to_draw_list(&mut self, draw_objects: &[VisibleDrawObject]) {
+        let is_enable_shadow = self.shadow_depth_texture_handle.is_some();
+        for x in draw_objects {
+            let is_visible_to_shadow = is_enable_shadow && x.visibility.is_visible_to_shadow;
+            if !x.visibility.is_visible_to_camera && !is_visible_to_shadow {
+                continue;
+            }
+            let Ok(draw_object) = Self::to_render_draw_object(&x.draw_object, is_visible_to_shadow)
+            else {
+                continue;
+            };
+            if x.visibility.is_visible_to_camera {
+                self.draw_objects.push(draw_object);
+            } else {
+                self.shadow_draw_objects.push(draw_object);
+            }
+        }
+    }
+
     pub fn draw_debug_line(
         &mut self,
         engine: &mut Engine,
@@ -1418,6 +1481,24 @@ impl PlayerViewport {
         self.is_grid_visible = !self.is_grid_visible;
     }
 
+    /// Returns None if culling is disabled. The shadow pass is culled by the light space of
+    /// every directional light, it is skipped if the viewport has no shadow map.
+    pub fn make_frustum_culling(
+        &self,
+        light_space_matrices: &[glam::Mat4],
+    ) -> Option<FrustumCulling> {
+        if !self.is_frustum_culling_enabled {
+            return None;
+        }
+        let view_projection = self.camera.get_projection_matrix() * self.camera.get_view_matrix();
+        let light_space_matrices: &[glam::Mat4] = if self.shadow_depth_texture_handle.is_some() {
+            light_space_matrices
+        } else {
+            &[]
+        };
+        FrustumCulling::new(&view_projection, light_space_matrices)
+    }
+
     pub fn get_grid_draw_object(&self) -> Option<&DrawObject> {
         if self.is_grid_visible {
             self.grid_draw_object.as_ref()
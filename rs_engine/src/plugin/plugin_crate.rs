@@ -1,10 +1,17 @@
 use crate::{
+    components::component_registry::ComponentRegistry,
     content::{content_file_type::EContentFileType, level::Level},
     engine::Engine,
     player_viewport::PlayerViewport,
 };
 
 pub trait Plugin {
+    /// Registers the custom components of the plugin, which is called before any level is
+    /// initialized with the plugin.
+    fn register_components(&mut self, registry: &mut ComponentRegistry) {
+        let _ = registry;
+    }
+
     fn on_init(&mut self, engine: &mut Engine, level: &mut Level, files: &[EContentFileType]);
     fn tick(
         &mut self,
@@ -1,6 +1,9 @@
 use crate::content::level::Level;
 use crate::engine::Engine;
 use crate::handle::SamplerHandle;
+use crate::resource_streaming::{
+    EStreamedTexture, EStreamingState, StreamingQueue, StreamingRequest,
+};
 use crate::thread_pool::ThreadPool;
 use crate::{error::Result, handle::HandleManager};
 use lazy_static::lazy_static;
@@ -9,7 +12,9 @@ use rs_artifact::resource_info::ResourceInfo;
 use rs_artifact::sound::Sound;
 use rs_artifact::static_mesh::StaticMesh;
 use rs_artifact::{
-    artifact::ArtifactReader, resource_type::EResourceType, shader_source_code::ShaderSourceCode,
+    artifact::{ArtifactReader, ResourceDataLocation},
+    resource_type::EResourceType,
+    shader_source_code::ShaderSourceCode,
 };
 use rs_core_minimal::name_generator;
 use rs_render::command::IBLTexturesKey;
@@ -44,12 +49,117 @@ pub struct BuiltinResources {
     pub global_sampler_handle: SamplerHandle,
 }
 
+const MAIN_ARTIFACT_NAME: &str = "main";
+
+struct MountedArtifact {
+    name: String,
+    priority: i32,
+    reader: ArtifactReader,
+}
+
+/// Artifacts sorted from the highest priority to the lowest, a resource is read from the first
+/// artifact that contains its url. A mounted artifact can override or add resources, but can not
+/// hide resources of the artifacts below it.
+struct ArtifactMounts {
+    artifacts: Vec<MountedArtifact>,
+}
+
+impl ArtifactMounts {
+    fn new() -> ArtifactMounts {
+        ArtifactMounts { artifacts: vec![] }
+    }
+
+    fn mount(&mut self, name: String, reader: ArtifactReader, priority: i32) {
+        self.unmount(&name);
+        self.artifacts.push(MountedArtifact {
+            name,
+            priority,
+            reader,
+        });
+        self.artifacts
+            .sort_by(|lhs, rhs| rhs.priority.cmp(&lhs.priority));
+    }
+
+    fn unmount(&mut self, name: &str) -> bool {
+        let len = self.artifacts.len();
+        self.artifacts.retain(|x| x.name != name);
+        len != self.artifacts.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.artifacts.is_empty()
+    }
+
+    fn names(&self) -> Vec<String> {
+        self.artifacts.iter().map(|x| x.name.clone()).collect()
+    }
+
+    fn find_reader(&mut self, url: &url::Url) -> Result<&mut ArtifactReader> {
+        if self.artifacts.is_empty() {
+            return Err(crate::error::Error::ArtifactReaderNotSet);
+        }
+        self.artifacts
+            .iter_mut()
+            .find(|x| {
+                x.reader
+                    .get_artifact_file_header()
+                    .resource_map
+                    .contains_key(url)
+            })
+            .map(|x| &mut x.reader)
+            .ok_or(crate::error::Error::Artifact(
+                rs_artifact::error::Error::NotFound(Some(format!(
+                    "Resource does not contain {}.",
+                    url
+                ))),
+                None,
+            ))
+    }
+
+    fn resource_map(&self) -> HashMap<url::Url, ResourceInfo> {
+        let mut resource_map = HashMap::new();
+        for artifact in self.artifacts.iter().rev() {
+            for (url, resource_info) in &artifact.reader.get_artifact_file_header().resource_map {
+                resource_map.insert(url.clone(), resource_info.clone());
+            }
+        }
+        resource_map
+    }
+
+    fn get_resource<T: Asset>(
+        &mut self,
+        url: &url::Url,
+        expected_resource_type: Option<EResourceType>,
+    ) -> Result<T> {
+        self.find_reader(url)?
+            .get_resource::<T>(url, expected_resource_type)
+            .map_err(|err| crate::error::Error::Artifact(err, None))
+    }
+
+    fn get_resource_data_location(
+        &mut self,
+        url: &url::Url,
+        expected_resource_type: Option<EResourceType>,
+    ) -> Result<ResourceDataLocation> {
+        self.find_reader(url)?
+            .get_resource_data_location(url, expected_resource_type)
+            .map_err(|err| crate::error::Error::Artifact(err, None))
+    }
+
+    fn get_resource_type(&mut self, url: &url::Url) -> Result<EResourceType> {
+        let reader = self.find_reader(url)?;
+        Ok(reader.get_artifact_file_header().resource_map[url].resource_type)
+    }
+}
+
 struct STResourceManager {
     image_sync_cache: moka::sync::Cache<String, Arc<image::DynamicImage>>,
     textures: HashMap<url::Url, crate::handle::TextureHandle>,
+    /// The samplers of textures which are imported with their own address and filter modes.
+    texture_samplers: HashMap<url::Url, SamplerHandle>,
     ui_textures: HashMap<url::Url, crate::handle::EGUITextureHandle>,
     virtual_textures: HashMap<url::Url, crate::handle::TextureHandle>,
-    artifact_reader: Option<ArtifactReader>,
+    artifact_mounts: ArtifactMounts,
     handle_manager: HandleManager,
     static_meshs: HashMap<url::Url, Arc<StaticMesh>>,
     skin_meshes: HashMap<url::Url, Arc<rs_artifact::skin_mesh::SkinMesh>>,
@@ -65,6 +175,10 @@ struct STResourceManager {
     sounds: HashMap<url::Url, Arc<Sound>>,
 
     builtin_resources: Option<Arc<BuiltinResources>>,
+
+    streaming_queue: StreamingQueue,
+    /// Resources are no longer loaded on demand once they are streamed in.
+    is_streaming: bool,
 }
 
 impl STResourceManager {
@@ -72,8 +186,9 @@ impl STResourceManager {
         STResourceManager {
             image_sync_cache: moka::sync::Cache::new(1000),
             textures: HashMap::new(),
+            texture_samplers: HashMap::new(),
             virtual_textures: HashMap::new(),
-            artifact_reader: None,
+            artifact_mounts: ArtifactMounts::new(),
             handle_manager: HandleManager::new(),
             static_meshs: HashMap::new(),
             skin_meshes: HashMap::new(),
@@ -85,6 +200,8 @@ impl STResourceManager {
             buffer_handles: VecDeque::new(),
             sounds: HashMap::new(),
             builtin_resources: None,
+            streaming_queue: StreamingQueue::new(),
+            is_streaming: false,
             // mesh_buffers: HashMap::new(),
             // material_render_pipelines: HashMap::new(),
         }
@@ -125,6 +242,13 @@ impl STResourceManager {
         self.skin_meshes.insert(url, skin_mesh)
     }
 
+    fn remove_skin_mesh(
+        &mut self,
+        url: &url::Url,
+    ) -> Option<Arc<rs_artifact::skin_mesh::SkinMesh>> {
+        self.skin_meshes.remove(url)
+    }
+
     fn get_skin_mesh(&mut self, url: &url::Url) -> Option<Arc<rs_artifact::skin_mesh::SkinMesh>> {
         if let Some(skin_meshe) = self.skin_meshes.get(url) {
             return Some(skin_meshe.clone());
@@ -140,6 +264,13 @@ impl STResourceManager {
         self.skeleton_animations.insert(url, skin_animation)
     }
 
+    fn remove_skeleton_animation(
+        &mut self,
+        url: &url::Url,
+    ) -> Option<Arc<rs_artifact::skeleton_animation::SkeletonAnimation>> {
+        self.skeleton_animations.remove(url)
+    }
+
     fn get_skeleton_animation(
         &mut self,
         url: &url::Url,
@@ -158,6 +289,10 @@ impl STResourceManager {
         self.skeletons.insert(url, skeleton)
     }
 
+    fn remove_skeleton(&mut self, url: &url::Url) -> Option<Arc<rs_artifact::skeleton::Skeleton>> {
+        self.skeletons.remove(url)
+    }
+
     fn get_skeleton(&mut self, url: &url::Url) -> Option<Arc<rs_artifact::skeleton::Skeleton>> {
         if let Some(skeleton) = self.skeletons.get(url) {
             return Some(skeleton.clone());
@@ -167,15 +302,12 @@ impl STResourceManager {
     }
 
     fn load_static_meshs(&mut self) {
-        let Some(reader) = self.artifact_reader.as_mut() else {
-            return;
-        };
-
-        for (url, resource_info) in reader.get_artifact_file_header().resource_map.clone() {
+        for (url, resource_info) in self.artifact_mounts.resource_map() {
             if resource_info.resource_type != EResourceType::StaticMesh {
                 continue;
             }
-            let static_mesh = reader
+            let static_mesh = self
+                .artifact_mounts
                 .get_resource::<rs_artifact::static_mesh::StaticMesh>(
                     &url,
                     Some(EResourceType::StaticMesh),
@@ -187,64 +319,56 @@ impl STResourceManager {
     }
 
     fn get_shader_source_code(&mut self, url: &url::Url) -> Result<ShaderSourceCode> {
-        let reader = self
-            .artifact_reader
-            .as_mut()
-            .ok_or(crate::error::Error::ArtifactReaderNotSet)?;
-        let shader = reader
+        self.artifact_mounts
             .get_resource::<rs_artifact::shader_source_code::ShaderSourceCode>(
                 url,
                 Some(EResourceType::ShaderSourceCode),
             )
-            .map_err(|err| crate::error::Error::Artifact(err, None))?;
-        Ok(shader)
     }
 
     fn get_level(&mut self, url: &url::Url) -> Result<Level> {
-        let reader = self
-            .artifact_reader
-            .as_mut()
-            .ok_or(crate::error::Error::ArtifactReaderNotSet)?;
-        let level = reader
-            .get_resource::<Level>(
-                url,
-                Some(EResourceType::Content(
-                    rs_artifact::content_type::EContentType::Level,
-                )),
-            )
-            .map_err(|err| crate::error::Error::Artifact(err, None))?;
-        Ok(level)
+        self.artifact_mounts.get_resource::<Level>(
+            url,
+            Some(EResourceType::Content(
+                rs_artifact::content_type::EContentType::Level,
+            )),
+        )
     }
 
     fn add_static_mesh(&mut self, url: url::Url, mesh: Arc<StaticMesh>) -> Option<Arc<StaticMesh>> {
         self.static_meshs.insert(url, mesh)
     }
 
+    fn remove_static_mesh(&mut self, url: &url::Url) -> Option<Arc<StaticMesh>> {
+        self.static_meshs.remove(url)
+    }
+
     fn get_static_mesh(&mut self, url: &url::Url) -> Result<Arc<StaticMesh>> {
         if let Some(loaded_mesh) = self.static_meshs.get(url) {
             return Ok(loaded_mesh.clone());
         }
-        let reader = self
-            .artifact_reader
-            .as_mut()
-            .ok_or(crate::error::Error::ArtifactReaderNotSet)?;
-        let static_mesh = reader
+        if self.is_streaming {
+            return Err(crate::error::Error::NullReference(Some(format!(
+                "{} is not streamed in yet",
+                url
+            ))));
+        }
+        let static_mesh = self
+            .artifact_mounts
             .get_resource::<rs_artifact::static_mesh::StaticMesh>(
                 url,
                 Some(EResourceType::StaticMesh),
-            )
-            .map_err(|err| crate::error::Error::Artifact(err, None))?;
+            )?;
         let static_mesh = Arc::new(static_mesh);
         self.static_meshs.insert(url.clone(), static_mesh.clone());
         Ok(static_mesh)
     }
 
     fn get_resource_map(&self) -> Result<HashMap<url::Url, ResourceInfo>> {
-        let reader = self
-            .artifact_reader
-            .as_ref()
-            .ok_or(crate::error::Error::ArtifactReaderNotSet)?;
-        Ok(reader.get_artifact_file_header().resource_map.clone())
+        if self.artifact_mounts.is_empty() {
+            return Err(crate::error::Error::ArtifactReaderNotSet);
+        }
+        Ok(self.artifact_mounts.resource_map())
     }
 
     fn get_resource<T: Asset>(
@@ -252,26 +376,80 @@ impl STResourceManager {
         url: &url::Url,
         expected_resource_type: Option<EResourceType>,
     ) -> Result<T> {
-        let reader = self
-            .artifact_reader
-            .as_mut()
-            .ok_or(crate::error::Error::ArtifactReaderNotSet)?;
-        let level = reader
+        self.artifact_mounts
             .get_resource::<T>(url, expected_resource_type)
-            .map_err(|err| crate::error::Error::Artifact(err, None))?;
-        Ok(level)
+    }
+
+    /// Returns where the asset data is, so it can be read, decompressed and decoded without
+    /// holding the lock.
+    fn get_resource_data_location(
+        &mut self,
+        url: &url::Url,
+        expected_resource_type: Option<EResourceType>,
+    ) -> Result<ResourceDataLocation> {
+        self.artifact_mounts
+            .get_resource_data_location(url, expected_resource_type)
+    }
+
+    fn set_streaming(&mut self, is_streaming: bool) {
+        self.is_streaming = is_streaming;
+    }
+
+    /// Returns the unfinished request of the same url if there is one, the bool is true when the
+    /// request is queued, either as a new request or again with a raised priority.
+    fn enqueue_streaming_request(
+        &mut self,
+        url: &url::Url,
+        priority: i32,
+    ) -> Result<(StreamingRequest, bool)> {
+        if let Some(request) = self.streaming_queue.find(url) {
+            let is_queued = self.streaming_queue.raise_priority(&request, priority);
+            return Ok((request, is_queued));
+        }
+        let resource_type = self.artifact_mounts.get_resource_type(url)?;
+        if !crate::resource_streaming::is_streamable(resource_type) {
+            return Err(crate::error::Error::Other(Some(format!(
+                "{:?} is not streamable",
+                resource_type
+            ))));
+        }
+        let request = StreamingRequest::new(url.clone(), resource_type, priority);
+        self.streaming_queue.push(request.clone());
+        Ok((request, true))
+    }
+
+    fn pop_streaming_request(&mut self) -> Option<StreamingRequest> {
+        self.streaming_queue.pop()
+    }
+
+    fn add_streamed_texture(&mut self, request: StreamingRequest, texture: EStreamedTexture) {
+        self.streaming_queue.add_streamed_texture(request, texture);
+    }
+
+    /// Textures are decoded on the io thread and need to be uploaded by the engine, their
+    /// requests are finished by `finish_streaming_request` after the upload.
+    fn take_streamed_textures(&mut self) -> Vec<(StreamingRequest, EStreamedTexture)> {
+        self.streaming_queue.take_streamed_textures()
+    }
+
+    fn finish_streaming_request(&mut self, request: &StreamingRequest, state: EStreamingState) {
+        self.streaming_queue.finish(request, state);
+    }
+
+    /// Urls of the resources which are streamed in since the last call, so the placeholders
+    /// created for them can be replaced.
+    fn take_finished_streaming_urls(&mut self) -> Vec<url::Url> {
+        self.streaming_queue.take_finished_urls()
     }
 
     fn get_all_shader_source_codes(&mut self) -> Vec<ShaderSourceCode> {
         let mut codes: Vec<ShaderSourceCode> = vec![];
-        let Some(reader) = self.artifact_reader.as_mut() else {
-            return codes;
-        };
-        for (url, resource_info) in reader.get_artifact_file_header().resource_map.clone() {
+        for (url, resource_info) in self.artifact_mounts.resource_map() {
             if resource_info.resource_type != EResourceType::ShaderSourceCode {
                 continue;
             }
-            let shader = reader
+            let shader = self
+                .artifact_mounts
                 .get_resource::<rs_artifact::shader_source_code::ShaderSourceCode>(
                     &url,
                     Some(EResourceType::ShaderSourceCode),
@@ -283,7 +461,30 @@ impl STResourceManager {
     }
 
     fn set_artifact_reader(&mut self, reader: Option<ArtifactReader>) {
-        self.artifact_reader = reader;
+        match reader {
+            Some(reader) => {
+                self.artifact_mounts
+                    .mount(MAIN_ARTIFACT_NAME.to_string(), reader, 0);
+            }
+            None => {
+                self.artifact_mounts.unmount(MAIN_ARTIFACT_NAME);
+            }
+        }
+    }
+
+    /// Resources in artifacts with a higher priority override resources with the same url in
+    /// artifacts with a lower priority, the main artifact is mounted with priority 0.
+    fn mount_artifact(&mut self, name: String, reader: ArtifactReader, priority: i32) {
+        log::trace!("Mount artifact: {}, priority: {}", name, priority);
+        self.artifact_mounts.mount(name, reader, priority);
+    }
+
+    fn unmount_artifact(&mut self, name: &str) -> bool {
+        self.artifact_mounts.unmount(name)
+    }
+
+    fn get_mounted_artifact_names(&self) -> Vec<String> {
+        self.artifact_mounts.names()
     }
 
     fn cache_image(&self, key: &str, image: Arc<image::DynamicImage>) {
@@ -389,6 +590,12 @@ impl STResourceManager {
         self.handle_manager.next_sampler()
     }
 
+    fn next_texture_sampler(&mut self, url: url::Url) -> crate::handle::SamplerHandle {
+        let handle = self.handle_manager.next_sampler();
+        self.texture_samplers.insert(url, handle.clone());
+        handle
+    }
+
     fn next_material_render_pipeline(&mut self) -> crate::handle::MaterialRenderPipelineHandle {
         self.handle_manager.next_material_render_pipeline()
     }
@@ -397,6 +604,10 @@ impl STResourceManager {
         self.textures.get(url).cloned()
     }
 
+    fn get_texture_sampler_by_url(&self, url: &url::Url) -> Option<crate::handle::SamplerHandle> {
+        self.texture_samplers.get(url).cloned()
+    }
+
     fn get_ui_texture_by_url(&self, url: &url::Url) -> Option<crate::handle::EGUITextureHandle> {
         self.ui_textures.get(url).cloned()
     }
@@ -458,6 +669,132 @@ impl ResourceManager {
     pub fn default() -> ResourceManager {
         GLOBAL_RESOURCE_MANAGER.clone()
     }
+
+    /// Loads a resource on the io thread, pending requests with a higher priority are loaded
+    /// first. Requesting a pending resource again with a higher priority raises the priority of
+    /// the request. Meshes, skeletons, animations and sounds are added to the caches once
+    /// loaded, textures are loaded when they are uploaded by `Engine::tick`.
+    pub fn request_resource(&self, url: &url::Url, priority: i32) -> Result<StreamingRequest> {
+        let (request, is_queued) = self.enqueue_streaming_request(url, priority)?;
+        if is_queued {
+            let resource_manager = self.clone();
+            ThreadPool::io().spawn(move || resource_manager.process_streaming_request());
+        }
+        Ok(request)
+    }
+
+    fn process_streaming_request(&self) {
+        let Some(request) = self.pop_streaming_request() else {
+            return;
+        };
+        match self.load_streaming_resource(&request) {
+            Ok(is_loaded) => {
+                if is_loaded {
+                    self.finish_streaming_request(&request, EStreamingState::Loaded);
+                }
+            }
+            Err(err) => {
+                log::warn!("Failed to stream {}, {}", request.get_url(), err);
+                self.finish_streaming_request(&request, EStreamingState::Failed(err.to_string()));
+            }
+        }
+    }
+
+    fn decode_streaming_resource<T: Asset>(
+        &self,
+        url: &url::Url,
+        resource_type: EResourceType,
+    ) -> Result<T> {
+        let location = self.get_resource_data_location(url, Some(resource_type))?;
+        let data = location
+            .read()
+            .map_err(|err| crate::error::Error::Artifact(err, None))?;
+        rs_artifact::asset::decode_asset::<T>(
+            &data,
+            location.get_endian_type(),
+            Some(resource_type),
+        )
+        .map_err(|err| crate::error::Error::Artifact(err, None))
+    }
+
+    /// Returns false if the resource still needs to be uploaded by the engine.
+    fn load_streaming_resource(&self, request: &StreamingRequest) -> Result<bool> {
+        let url = request.get_url();
+        let resource_type = request.get_resource_type();
+        match resource_type {
+            EResourceType::StaticMesh => {
+                let static_mesh =
+                    self.decode_streaming_resource::<StaticMesh>(url, resource_type)?;
+                self.add_static_mesh(url.clone(), Arc::new(static_mesh));
+            }
+            EResourceType::SkinMesh => {
+                let skin_mesh = self
+                    .decode_streaming_resource::<rs_artifact::skin_mesh::SkinMesh>(
+                        url,
+                        resource_type,
+                    )?;
+                self.add_skin_mesh(url.clone(), Arc::new(skin_mesh));
+            }
+            EResourceType::Skeleton => {
+                let skeleton = self.decode_streaming_resource::<rs_artifact::skeleton::Skeleton>(
+                    url,
+                    resource_type,
+                )?;
+                self.add_skeleton(url.clone(), Arc::new(skeleton));
+            }
+            EResourceType::SkeletonAnimation => {
+                let skeleton_animation = self
+                    .decode_streaming_resource::<rs_artifact::skeleton_animation::SkeletonAnimation>(
+                        url,
+                        resource_type,
+                    )?;
+                self.add_skeleton_animation(url.clone(), Arc::new(skeleton_animation));
+            }
+            EResourceType::Sound => {
+                let sound = self.decode_streaming_resource::<Sound>(url, resource_type)?;
+                self.add_sound(url.clone(), Arc::new(sound));
+            }
+            EResourceType::Content(rs_artifact::content_type::EContentType::Texture) => {
+                let texture = self
+                    .decode_streaming_resource::<crate::content::texture::TextureFile>(
+                        url,
+                        resource_type,
+                    )?;
+                let image_reference =
+                    texture
+                        .image_reference
+                        .ok_or(crate::error::Error::NullReference(Some(
+                            "No image reference".to_string(),
+                        )))?;
+                let image = self.decode_streaming_resource::<rs_artifact::image::Image>(
+                    &image_reference,
+                    EResourceType::Image,
+                )?;
+                if let Some(compressed_texture) = image.compressed_texture {
+                    self.add_streamed_texture(
+                        request.clone(),
+                        EStreamedTexture::Compressed(compressed_texture, texture.import_settings),
+                    );
+                    return Ok(false);
+                }
+                let dyn_image = image::load_from_memory(&image.data)
+                    .map_err(|err| crate::error::Error::ImageError(err, None))?;
+                let mip_levels = texture.import_settings.make_mip_levels(dyn_image);
+                self.add_streamed_texture(
+                    request.clone(),
+                    EStreamedTexture::Rgba(mip_levels, texture.import_settings),
+                );
+                return Ok(false);
+            }
+            _ => {
+                return Err(crate::error::Error::Other(Some(format!(
+                    "{:?} is not streamable",
+                    resource_type
+                ))));
+            }
+        }
+        Ok(true)
+    }
 }
 
 lazy_static! {
@@ -0,0 +1,379 @@
+use crate::content::texture::TextureImportSettings;
+use rs_artifact::{
+    content_type::EContentType, image::CompressedTexture, resource_type::EResourceType,
+};
+use std::{
+    collections::{BinaryHeap, HashMap},
+    sync::{
+        atomic::{AtomicI32, Ordering},
+        Arc, Condvar, Mutex,
+    },
+    task::Waker,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EStreamingState {
+    Pending,
+    Loading,
+    Loaded,
+    Failed(String),
+    Cancelled,
+}
+
+impl EStreamingState {
+    pub fn is_finished(&self) -> bool {
+        match self {
+            EStreamingState::Pending | EStreamingState::Loading => false,
+            EStreamingState::Loaded | EStreamingState::Failed(_) | EStreamingState::Cancelled => {
+                true
+            }
+        }
+    }
+}
+
+pub fn is_streamable(resource_type: EResourceType) -> bool {
+    match resource_type {
+        EResourceType::StaticMesh
+        | EResourceType::SkinMesh
+        | EResourceType::Skeleton
+        | EResourceType::SkeletonAnimation
+        | EResourceType::Sound
+        | EResourceType::Content(EContentType::Texture) => true,
+        _ => false,
+    }
+}
+
+struct StreamingRequestStatus {
+    state: EStreamingState,
+    wakers: Vec<Waker>,
+}
+
+struct StreamingRequestInner {
+    url: url::Url,
+    resource_type: EResourceType,
+    priority: AtomicI32,
+    status: Mutex<StreamingRequestStatus>,
+    condvar: Condvar,
+}
+
+/// A handle of a resource which is loading on the io thread. The handle can be polled, waited
+/// or awaited, and dropping it does not cancel the request.
+#[derive(Clone)]
+pub struct StreamingRequest {
+    inner: Arc<StreamingRequestInner>,
+}
+
+impl StreamingRequest {
+    pub(crate) fn new(
+        url: url::Url,
+        resource_type: EResourceType,
+        priority: i32,
+    ) -> StreamingRequest {
+        StreamingRequest {
+            inner: Arc::new(StreamingRequestInner {
+                url,
+                resource_type,
+                priority: AtomicI32::new(priority),
+                status: Mutex::new(StreamingRequestStatus {
+                    state: EStreamingState::Pending,
+                    wakers: vec![],
+                }),
+                condvar: Condvar::new(),
+            }),
+        }
+    }
+
+    pub fn get_url(&self) -> &url::Url {
+        &self.inner.url
+    }
+
+    pub fn get_resource_type(&self) -> EResourceType {
+        self.inner.resource_type
+    }
+
+    pub fn get_priority(&self) -> i32 {
+        self.inner.priority.load(Ordering::Relaxed)
+    }
+
+    pub fn get_state(&self) -> EStreamingState {
+        self.inner.status.lock().unwrap().state.clone()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.get_state().is_finished()
+    }
+
+    /// Returns false if the request is already loading or finished.
+    pub fn cancel(&self) -> bool {
+        self.set_state_if_pending(EStreamingState::Cancelled)
+    }
+
+    /// Blocks the current thread until the request is finished.
+    pub fn wait(&self) -> EStreamingState {
+        let mut status = self.inner.status.lock().unwrap();
+        while !status.state.is_finished() {
+            status = self.inner.condvar.wait(status).unwrap();
+        }
+        status.state.clone()
+    }
+
+    pub fn wait_timeout(&self, timeout: std::time::Duration) -> EStreamingState {
+        let status = self.inner.status.lock().unwrap();
+        let (status, _) = self
+            .inner
+            .condvar
+            .wait_timeout_while(status, timeout, |status| !status.state.is_finished())
+            .unwrap();
+        status.state.clone()
+    }
+
+    pub(crate) fn set_state(&self, state: EStreamingState) {
+        let mut status = self.inner.status.lock().unwrap();
+        status.state = state;
+        self.notify(status);
+    }
+
+    /// Returns false if the request is not pending or already has a higher priority.
+    fn raise_priority(&self, priority: i32) -> bool {
+        let status = self.inner.status.lock().unwrap();
+        if status.state != EStreamingState::Pending || priority <= self.get_priority() {
+            return false;
+        }
+        self.inner.priority.store(priority, Ordering::Relaxed);
+        true
+    }
+
+    fn set_state_if_pending(&self, state: EStreamingState) -> bool {
+        let mut status = self.inner.status.lock().unwrap();
+        if status.state != EStreamingState::Pending {
+            return false;
+        }
+        status.state = state;
+        self.notify(status);
+        true
+    }
+
+    fn notify(&self, mut status: std::sync::MutexGuard<'_, StreamingRequestStatus>) {
+        if !status.state.is_finished() {
+            return;
+        }
+        let wakers = std::mem::take(&mut status.wakers);
+        drop(status);
+        self.inner.condvar.notify_all();
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+}
+
+impl std::future::Future for StreamingRequest {
+    type Output = EStreamingState;
+
+    fn poll(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Self::Output> {
+        let mut status = self.inner.status.lock().unwrap();
+        if status.state.is_finished() {
+            return std::task::Poll::Ready(status.state.clone());
+        }
+        status.wakers.push(cx.waker().clone());
+        std::task::Poll::Pending
+    }
+}
+
+/// A texture which is read on the io thread and waits to be uploaded.
+pub enum EStreamedTexture {
+    /// Every mip level from the largest one.
+    Rgba(Vec<image::RgbaImage>, TextureImportSettings),
+    Compressed(CompressedTexture, TextureImportSettings),
+}
+
+struct QueuedRequest {
+    /// The priority when the request is queued, so raising it later does not break the heap.
+    priority: i32,
+    sequence: u64,
+    request: StreamingRequest,
+}
+
+impl PartialEq for QueuedRequest {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == std::cmp::Ordering::Equal
+    }
+}
+
+impl Eq for QueuedRequest {}
+
+impl PartialOrd for QueuedRequest {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QueuedRequest {
+    // Higher priority first, then first in first out.
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.sequence.cmp(&self.sequence))
+    }
+}
+
+/// Pending requests ordered by priority. Every queued entry spawns one job on the io thread pool
+/// and each job takes the request with the highest priority, so a late request with a higher
+/// priority overtakes the earlier ones. A request whose priority is raised is queued again, the
+/// stale entry is skipped because the request is no longer pending when it is popped.
+pub(crate) struct StreamingQueue {
+    requests: BinaryHeap<QueuedRequest>,
+    next_sequence: u64,
+    unfinished_requests: HashMap<url::Url, StreamingRequest>,
+    streamed_textures: Vec<(StreamingRequest, EStreamedTexture)>,
+    finished_urls: Vec<url::Url>,
+}
+
+impl StreamingQueue {
+    pub(crate) fn new() -> StreamingQueue {
+        StreamingQueue {
+            requests: BinaryHeap::new(),
+            next_sequence: 0,
+            unfinished_requests: HashMap::new(),
+            streamed_textures: vec![],
+            finished_urls: vec![],
+        }
+    }
+
+    pub(crate) fn find(&mut self, url: &url::Url) -> Option<StreamingRequest> {
+        self.unfinished_requests.retain(|_, x| !x.is_finished());
+        self.unfinished_requests.get(url).cloned()
+    }
+
+    pub(crate) fn push(&mut self, request: StreamingRequest) {
+        self.unfinished_requests
+            .insert(request.get_url().clone(), request.clone());
+        self.queue(request);
+    }
+
+    /// Returns true if the request is queued again with the higher priority.
+    pub(crate) fn raise_priority(&mut self, request: &StreamingRequest, priority: i32) -> bool {
+        if !request.raise_priority(priority) {
+            return false;
+        }
+        self.queue(request.clone());
+        true
+    }
+
+    fn queue(&mut self, request: StreamingRequest) {
+        self.requests.push(QueuedRequest {
+            priority: request.get_priority(),
+            sequence: self.next_sequence,
+            request,
+        });
+        self.next_sequence += 1;
+    }
+
+    pub(crate) fn pop(&mut self) -> Option<StreamingRequest> {
+        while let Some(queued_request) = self.requests.pop() {
+            let request = queued_request.request;
+            if request.set_state_if_pending(EStreamingState::Loading) {
+                return Some(request);
+            }
+        }
+        None
+    }
+
+    pub(crate) fn add_streamed_texture(
+        &mut self,
+        request: StreamingRequest,
+        texture: EStreamedTexture,
+    ) {
+        self.streamed_textures.push((request, texture));
+    }
+
+    pub(crate) fn take_streamed_textures(&mut self) -> Vec<(StreamingRequest, EStreamedTexture)> {
+        std::mem::take(&mut self.streamed_textures)
+    }
+
+    /// Finishes a request which is loading, the url of a loaded resource is kept until the
+    /// placeholders which are created for it are replaced.
+    pub(crate) fn finish(&mut self, request: &StreamingRequest, state: EStreamingState) {
+        debug_assert!(state.is_finished());
+        if state == EStreamingState::Loaded {
+            self.finished_urls.push(request.get_url().clone());
+        }
+        request.set_state(state);
+    }
+
+    pub(crate) fn take_finished_urls(&mut self) -> Vec<url::Url> {
+        std::mem::take(&mut self.finished_urls)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{EStreamingState, StreamingQueue, StreamingRequest};
+    use rs_artifact::resource_type::EResourceType;
+
+    fn make_request(name: &str, priority: i32) -> StreamingRequest {
+        let url = url::Url::parse(&format!("asset://{}", name)).unwrap();
+        StreamingRequest::new(url, EResourceType::StaticMesh, priority)
+    }
+
+    #[test]
+    fn test_case_ordering() {
+        let mut queue = StreamingQueue::new();
+        queue.push(make_request("a", 0));
+        queue.push(make_request("b", 2));
+        queue.push(make_request("c", 0));
+        queue.push(make_request("d", 2));
+        let names: Vec<String> = std::iter::from_fn(|| queue.pop())
+            .map(|x| x.get_url().host_str().unwrap().to_string())
+            .collect();
+        assert_eq!(names, vec!["b", "d", "a", "c"]);
+    }
+
+    #[test]
+    fn test_case_dedup() {
+        let mut queue = StreamingQueue::new();
+        let low = make_request("low", 0);
+        let high = make_request("high", 1);
+        queue.push(low.clone());
+        queue.push(high.clone());
+        assert!(queue.find(low.get_url()).is_some());
+        assert!(!queue.raise_priority(&low, 0));
+        assert!(queue.raise_priority(&low, 2));
+        assert_eq!(low.get_priority(), 2);
+
+        assert_eq!(queue.pop().unwrap().get_url(), low.get_url());
+        assert_eq!(queue.pop().unwrap().get_url(), high.get_url());
+        // The stale entry of the raised request is skipped.
+        assert!(queue.pop().is_none());
+        assert!(!queue.raise_priority(&low, 3));
+    }
+
+    #[test]
+    fn test_case_state_transitions() {
+        let mut queue = StreamingQueue::new();
+        let loaded = make_request("loaded", 0);
+        let cancelled = make_request("cancelled", 0);
+        queue.push(loaded.clone());
+        queue.push(cancelled.clone());
+        assert_eq!(loaded.get_state(), EStreamingState::Pending);
+
+        assert!(cancelled.cancel());
+        assert_eq!(cancelled.get_state(), EStreamingState::Cancelled);
+        assert!(queue.find(cancelled.get_url()).is_none());
+
+        let request = queue.pop().unwrap();
+        assert_eq!(request.get_state(), EStreamingState::Loading);
+        assert!(!request.cancel());
+        assert!(queue.pop().is_none());
+        assert!(queue.find(loaded.get_url()).is_some());
+        assert!(queue.take_finished_urls().is_empty());
+
+        queue.finish(&request, EStreamingState::Loaded);
+        assert_eq!(loaded.wait(), EStreamingState::Loaded);
+        assert!(queue.find(loaded.get_url()).is_none());
+        assert_eq!(queue.take_finished_urls(), vec![loaded.get_url().clone()]);
+        assert!(queue.take_finished_urls().is_empty());
+    }
+}
@@ -2,17 +2,19 @@ use crate::{
     camera_component::CameraComponent,
     collision_componenet::CollisionComponent,
     components::{
-        component::Component, point_light_component::PointLightComponent,
-        spot_light_component::SpotLightComponent,
+        component::Component, custom_component::CustomComponent,
+        point_light_component::PointLightComponent, spot_light_component::SpotLightComponent,
     },
     content::content_file_type::EContentFileType,
     engine::Engine,
+    misc::aabb_as_sphere,
     player_viewport::PlayerViewport,
     skeleton_mesh_component::SkeletonMeshComponent,
     static_mesh_component::StaticMeshComponent,
 };
 use rapier3d::prelude::ColliderSet;
 use rapier3d::prelude::RigidBodySet;
+use rs_core_minimal::sphere_3d::Sphere3D;
 use rs_foundation::new::{SingleThreadMut, SingleThreadMutType};
 use serde::{Deserialize, Serialize};
 
@@ -138,6 +140,7 @@ pub enum EComponentType {
     CollisionComponent(SingleThreadMutType<CollisionComponent>),
     SpotLightComponent(SingleThreadMutType<SpotLightComponent>),
     PointLightComponent(SingleThreadMutType<PointLightComponent>),
+    CustomComponent(SingleThreadMutType<CustomComponent>),
 }
 
 macro_rules! copy_fn {
@@ -151,6 +154,11 @@ macro_rules! copy_fn {
                         EComponentType::$x(SingleThreadMut::new(copy_component))
                     }
                 )*
+                EComponentType::CustomComponent(component) => {
+                    let component = component.borrow();
+                    let copy_component = component.clone();
+                    EComponentType::CustomComponent(SingleThreadMut::new(copy_component))
+                }
             }
         }
     }
@@ -184,6 +192,10 @@ macro_rules! common_fn {
                         component.name.clone()
                     }
                 )*
+                EComponentType::CustomComponent(component) => {
+                    let component = component.borrow();
+                    component.get_name()
+                }
             }
         }
 
@@ -195,6 +207,10 @@ macro_rules! common_fn {
                         component.name = new_name;
                     }
                 )*
+                EComponentType::CustomComponent(component) => {
+                    let mut component = component.borrow_mut();
+                    component.set_name(new_name);
+                }
             }
         }
 
@@ -206,6 +222,10 @@ macro_rules! common_fn {
                         component.get_final_transformation()
                     }
                 )*
+                EComponentType::CustomComponent(component) => {
+                    let component = component.borrow();
+                    component.get_final_transformation()
+                }
             }
         }
 
@@ -218,6 +238,10 @@ macro_rules! common_fn {
                         component.transformation = transformation;
                     }
                 )*
+                EComponentType::CustomComponent(component) => {
+                    let mut component = component.borrow_mut();
+                    component.set_transformation(transformation);
+                }
             }
         }
 
@@ -229,6 +253,10 @@ macro_rules! common_fn {
                         component.transformation
                     }
                 )*
+                EComponentType::CustomComponent(component) => {
+                    let component = component.borrow();
+                    component.get_transformation()
+                }
             }
         }
 
@@ -240,6 +268,10 @@ macro_rules! common_fn {
                         component.on_post_update_transformation(level_physics);
                     }
                 )*
+                EComponentType::CustomComponent(component) => {
+                    let mut component = component.borrow_mut();
+                    component.on_post_update_transformation(level_physics);
+                }
             }
         }
 
@@ -251,6 +283,10 @@ macro_rules! common_fn {
                         component.set_final_transformation(final_transformation);
                     }
                 )*
+                EComponentType::CustomComponent(component) => {
+                    let mut component = component.borrow_mut();
+                    component.set_final_transformation(final_transformation);
+                }
             }
         }
 
@@ -262,6 +298,10 @@ macro_rules! common_fn {
                         component.set_parent_final_transformation(parent_final_transformation);
                     }
                 )*
+                EComponentType::CustomComponent(component) => {
+                    let mut component = component.borrow_mut();
+                    component.set_parent_final_transformation(parent_final_transformation);
+                }
             }
         }
 
@@ -273,6 +313,10 @@ macro_rules! common_fn {
                         component.get_parent_final_transformation()
                     }
                 )*
+                EComponentType::CustomComponent(component) => {
+                    let component = component.borrow();
+                    component.get_parent_final_transformation()
+                }
             }
         }
 
@@ -288,6 +332,10 @@ macro_rules! common_fn {
                         component.initialize(engine, files, player_viewport);
                     }
                 )*
+                EComponentType::CustomComponent(component) => {
+                    let mut component = component.borrow_mut();
+                    component.initialize(engine, files, player_viewport);
+                }
             }
         }
 
@@ -303,6 +351,10 @@ macro_rules! common_fn {
                         component.initialize_physics(rigid_body_set, collider_set);
                     }
                 )*
+                EComponentType::CustomComponent(component) => {
+                    let mut component = component.borrow_mut();
+                    component.initialize_physics(rigid_body_set, collider_set);
+                }
             }
         }
 
@@ -320,6 +372,10 @@ macro_rules! common_fn {
                         component.tick(time, engine, rigid_body_set, collider_set);
                     }
                 )*
+                EComponentType::CustomComponent(component) => {
+                    let mut component = component.borrow_mut();
+                    component.tick(time, engine, rigid_body_set, collider_set);
+                }
             }
         }
     };
@@ -349,6 +405,27 @@ impl SceneNode {
             EComponentType::CollisionComponent(_) => None,
             EComponentType::SpotLightComponent(_) => None,
             EComponentType::PointLightComponent(_) => None,
+            EComponentType::CustomComponent(component) => component.borrow().get_aabb(),
+        }
+    }
+
+    /// The bounding sphere of the component in world space, None if the component is never culled.
+    pub fn get_world_bounds(&self) -> Option<Sphere3D> {
+        match &self.component {
+            EComponentType::SceneComponent(_) => None,
+            EComponentType::StaticMeshComponent(component) => {
+                component.borrow().get_world_bounds().cloned()
+            }
+            EComponentType::SkeletonMeshComponent(component) => {
+                component.borrow().get_world_bounds()
+            }
+            EComponentType::CameraComponent(_) => None,
+            EComponentType::CollisionComponent(_) => None,
+            EComponentType::SpotLightComponent(_) => None,
+            EComponentType::PointLightComponent(_) => None,
+            EComponentType::CustomComponent(component) => {
+                component.borrow().get_aabb().map(|x| aabb_as_sphere(&x))
+            }
         }
     }
 
@@ -15,6 +15,9 @@ use std::{collections::HashMap, iter::zip, sync::Arc};
 pub trait SkeletonAnimationProvider: DynClone + Downcast {
     fn transforms(&mut self) -> &HashMap<String, glam::Mat4>;
     fn seek(&mut self, time: f32);
+    /// Returns the morph target weights of the mesh at the current time, or `None` if the mesh is
+    /// not animated.
+    fn morph_weights(&self, mesh: &str, default_weights: &[f32]) -> Option<Vec<f32>>;
 
     fn calculate_lcoal_transformation(
         skeleton_bone: &SkeletonBone,
@@ -95,48 +98,19 @@ pub trait SkeletonAnimationProvider: DynClone + Downcast {
     where
         Self: Sized,
     {
-        let mut position = glam::Vec3::ZERO;
-        let mut scale = glam::Vec3::ONE;
-        let mut rotation = glam::Quat::IDENTITY;
-        if animation.position_keys.len() == 1 {
-            position = animation.position_keys[0].value;
-        } else {
-            if let Some(position_keys) = animation.position_keys.windows(2).find(|position_keys| {
-                animation_time <= (position_keys[1].time / ticks_per_second) as f32
-            }) {
-                let alpha = (animation_time - (position_keys[0].time / ticks_per_second) as f32)
-                    / ((position_keys[1].time / ticks_per_second)
-                        - (position_keys[0].time / ticks_per_second)) as f32;
-                position = position_keys[0].value.lerp(position_keys[1].value, alpha);
-            }
-        }
-
-        if animation.scaling_keys.len() == 1 {
-            scale = animation.scaling_keys[0].value;
-        } else {
-            if let Some(scaling_keys) = animation.scaling_keys.windows(2).find(|scaling_keys| {
-                animation_time <= (scaling_keys[1].time / ticks_per_second) as f32
-            }) {
-                let alpha = (animation_time - (scaling_keys[0].time / ticks_per_second) as f32)
-                    / ((scaling_keys[1].time / ticks_per_second)
-                        - (scaling_keys[0].time / ticks_per_second)) as f32;
-                scale = scaling_keys[0].value.lerp(scaling_keys[1].value, alpha);
-            }
-        }
-
-        if animation.rotation_keys.len() == 1 {
-            rotation = animation.rotation_keys[0].value;
-        } else {
-            if let Some(rotation_keys) = animation.rotation_keys.windows(2).find(|rotation_keys| {
-                animation_time <= (rotation_keys[1].time / ticks_per_second) as f32
-            }) {
-                let alpha = (animation_time - (rotation_keys[0].time / ticks_per_second) as f32)
-                    / ((rotation_keys[1].time / ticks_per_second)
-                        - (rotation_keys[0].time / ticks_per_second)) as f32;
-                rotation = rotation_keys[0].value.slerp(rotation_keys[1].value, alpha);
-            }
-        }
-
+        let time = animation_time as f64 * ticks_per_second;
+        let position = animation
+            .position_track
+            .sample(time)
+            .unwrap_or(glam::Vec3::ZERO);
+        let scale = animation
+            .scaling_track
+            .sample(time)
+            .unwrap_or(glam::Vec3::ONE);
+        let rotation = animation
+            .rotation_track
+            .sample(time)
+            .unwrap_or(glam::Quat::IDENTITY);
         (scale, rotation, position)
     }
 
@@ -164,9 +138,6 @@ pub trait SkeletonAnimationProvider: DynClone + Downcast {
     ) where
         Self: Sized,
     {
-        fn to_local_time(time: f32, time_range: std::ops::RangeInclusive<f32>) -> f32 {
-            (time - *time_range.start()).clamp(*time_range.start(), *time_range.end())
-        }
         let Some(node) = skeleton_mesh_hierarchy.get(&skeleton_bone.path) else {
             return;
         };
@@ -216,6 +187,26 @@ pub trait SkeletonAnimationProvider: DynClone + Downcast {
     }
 }
 
+fn to_local_time(time: f32, time_range: std::ops::RangeInclusive<f32>) -> f32 {
+    (time - *time_range.start()).clamp(*time_range.start(), *time_range.end())
+}
+
+fn sample_morph_weights(
+    skeleton_animation: &SkeletonAnimation,
+    mesh: &str,
+    animation_time: f32,
+    target_count: usize,
+) -> Option<Vec<f32>> {
+    let morph_channel = skeleton_animation
+        .morph_channels
+        .iter()
+        .find(|x| x.mesh == mesh)?;
+    morph_channel.sample(
+        animation_time as f64 * skeleton_animation.ticks_per_second,
+        target_count,
+    )
+}
+
 downcast_rs::impl_downcast!(SkeletonAnimationProvider);
 dyn_clone::clone_trait_object!(SkeletonAnimationProvider);
 
@@ -251,6 +242,15 @@ impl SkeletonAnimationProvider for SingleSkeletonAnimationProvider {
             1.0,
         );
     }
+
+    fn morph_weights(&self, mesh: &str, default_weights: &[f32]) -> Option<Vec<f32>> {
+        sample_morph_weights(
+            &self.skeleton_animation,
+            mesh,
+            self.animation_time,
+            default_weights.len(),
+        )
+    }
 }
 
 impl SingleSkeletonAnimationProvider {
@@ -473,4 +473,35 @@ impl SkeletonAnimationProvider for BlendSkeletonAnimationsProvider {
             parent_global_transformation,
         );
     }
+
+    fn morph_weights(&self, mesh: &str, default_weights: &[f32]) -> Option<Vec<f32>> {
+        let mut morph_weights: Option<Vec<f32>> = None;
+        for skeleton_animation_blend in self.skeleton_animations.iter() {
+            let local_time = to_local_time(
+                self.animation_time,
+                skeleton_animation_blend.time_range.clone(),
+            );
+            let local_total_duration = skeleton_animation_blend
+                .skeleton_animation
+                .duration_as_secs_f32();
+            let local_time = local_time.clamp(0.0, local_total_duration);
+            let Some(weights) = sample_morph_weights(
+                &skeleton_animation_blend.skeleton_animation,
+                mesh,
+                local_time,
+                default_weights.len(),
+            ) else {
+                continue;
+            };
+            let current = morph_weights.get_or_insert_with(|| default_weights.to_vec());
+            match skeleton_animation_blend.blend_type {
+                SkeletonAnimationBlendType::Combine(factor) => {
+                    for (current, weight) in current.iter_mut().zip(weights) {
+                        *current += (weight - *current) * factor;
+                    }
+                }
+            }
+        }
+        morph_weights
+    }
 }
@@ -1,7 +1,10 @@
 use crate::{
+    camera::Camera,
     content::content_file_type::EContentFileType,
     drawable::EDrawObjectType,
     engine::Engine,
+    mesh_lod::{compute_screen_size, LodIndexBuffers},
+    misc::{aabb_as_sphere, points_to_aabb, transform_aabb, transform_sphere},
     player_viewport::PlayerViewport,
     resource_manager::ResourceManager,
     skeleton_animation_provider::{
@@ -11,13 +14,19 @@ use crate::{
 };
 use rapier3d::{na::point, prelude::*};
 use rs_artifact::{skeleton::Skeleton, skin_mesh::SkinMesh};
-use rs_render::global_shaders::skeleton_shading::NUM_MAX_BONE;
+use rs_core_minimal::sphere_3d::Sphere3D;
+use rs_render::global_shaders::skeleton_shading::{NUM_MAX_BONE, NUM_MAX_MORPH_TARGET};
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, iter::zip, sync::Arc};
 
+/// Enlarges the bounds in bind pose to cover the vertexes moved by animations.
+const ANIMATED_BOUNDS_MARGIN_SCALE: f32 = 1.5;
+
 #[derive(Clone)]
 struct SkeletonMeshComponentRuntime {
     draw_objects: HashMap<String, EDrawObjectType>,
+    lod_index_buffers: HashMap<String, LodIndexBuffers>,
+    aabb: Option<Aabb>,
     skeleton: Option<Arc<Skeleton>>,
     skin_meshes: Vec<Arc<SkinMesh>>,
     pub physics: Option<Physics>,
@@ -156,6 +165,8 @@ impl SkeletonMeshComponent {
 
         self.run_time = Some(SkeletonMeshComponentRuntime {
             draw_objects: HashMap::new(),
+            lod_index_buffers: HashMap::new(),
+            aabb: None,
             skeleton: skeleton.clone(),
             skin_meshes: vec![],
             physics: None,
@@ -199,6 +210,7 @@ impl SkeletonMeshComponent {
                 draw_object = engine.create_material_draw_object_from_skin_mesh(
                     &skin_mesh.vertexes,
                     &skin_mesh.indexes,
+                    &skin_mesh.morph_targets,
                     Some(format!("{} - {}", &self.name, &skin_mesh.name)),
                     material,
                     player_viewport.global_constants_handle.clone(),
@@ -209,6 +221,7 @@ impl SkeletonMeshComponent {
                 draw_object = engine.create_draw_object_from_skin_mesh(
                     &skin_mesh.vertexes,
                     &skin_mesh.indexes,
+                    &skin_mesh.morph_targets,
                     Some(skin_mesh.name.clone()),
                     player_viewport.global_constants_handle.clone(),
                 );
@@ -223,6 +236,13 @@ impl SkeletonMeshComponent {
                 }
                 _ => unimplemented!(),
             }
+            if let Some(lod_index_buffers) =
+                LodIndexBuffers::new(engine, &draw_object, &skin_mesh.lods, &skin_mesh.name)
+            {
+                run_time
+                    .lod_index_buffers
+                    .insert(skin_mesh.name.clone(), lod_index_buffers);
+            }
             run_time
                 .draw_objects
                 .insert(skin_mesh.name.clone(), draw_object);
@@ -230,6 +250,33 @@ impl SkeletonMeshComponent {
             run_time.skin_meshes.push(skin_mesh);
         }
 
+        let points: Vec<glam::Vec3> = self
+            .run_time
+            .as_ref()
+            .unwrap()
+            .skin_meshes
+            .iter()
+            .flat_map(|skin_mesh| {
+                let transformation = skeleton
+                    .as_ref()
+                    .and_then(|skeleton| {
+                        skeleton
+                            .skeleton_mesh_hierarchy
+                            .iter()
+                            .find(|x| x.0.ends_with(&skin_mesh.name))
+                    })
+                    .map(|x| x.1.transformation)
+                    .unwrap_or(glam::Mat4::IDENTITY);
+                skin_mesh
+                    .vertexes
+                    .iter()
+                    .map(move |x| transformation.transform_point3(x.position))
+            })
+            .collect();
+        if !points.is_empty() {
+            self.run_time.as_mut().unwrap().aabb = Some(points_to_aabb(&points));
+        }
+
         let physics = Self::build_physics(
             self.run_time.as_mut().unwrap().skin_meshes.clone(),
             false,
@@ -273,10 +320,19 @@ impl SkeletonMeshComponent {
                     }
                 }
             }
+            let morph_weights = Self::make_morph_weights(
+                &skin_mesh,
+                run_time.skeleton_animation_provider.as_deref(),
+            );
+            let morph_target_count = skin_mesh.morph_targets.len().min(NUM_MAX_MORPH_TARGET) as u32;
+            let vertex_count = skin_mesh.vertexes.len() as u32;
             let draw_object = run_time.draw_objects.get_mut(&skin_mesh.name).unwrap();
             match draw_object {
                 EDrawObjectType::Skin(draw_object) => {
                     draw_object.constants.bones.copy_from_slice(&bones);
+                    draw_object.constants.morph_weights = morph_weights;
+                    draw_object.constants.morph_target_count = morph_target_count;
+                    draw_object.constants.vertex_count = vertex_count;
                     let mut model = self.transformation;
                     if let Some((_, skeleton_mesh_hierarchy_node)) = skeleton
                         .skeleton_mesh_hierarchy
@@ -289,6 +345,9 @@ impl SkeletonMeshComponent {
                 }
                 EDrawObjectType::SkinMaterial(draw_object) => {
                     draw_object.skin_constants.bones.copy_from_slice(&bones);
+                    draw_object.skin_constants.morph_weights = morph_weights;
+                    draw_object.skin_constants.morph_target_count = morph_target_count;
+                    draw_object.skin_constants.vertex_count = vertex_count;
                     let mut model = self.transformation;
                     if let Some((_, skeleton_mesh_hierarchy_node)) = skeleton
                         .skeleton_mesh_hierarchy
@@ -304,6 +363,28 @@ impl SkeletonMeshComponent {
         }
     }
 
+    /// Packs the morph target weights of the skin mesh into vectors of four, the default weights
+    /// are used when the animation does not drive the mesh.
+    fn make_morph_weights(
+        skin_mesh: &SkinMesh,
+        skeleton_animation_provider: Option<&dyn SkeletonAnimationProvider>,
+    ) -> [glam::Vec4; NUM_MAX_MORPH_TARGET / 4] {
+        let default_weights: Vec<f32> = skin_mesh
+            .morph_targets
+            .iter()
+            .take(NUM_MAX_MORPH_TARGET)
+            .map(|x| x.default_weight)
+            .collect();
+        let weights = skeleton_animation_provider
+            .and_then(|x| x.morph_weights(&skin_mesh.name, &default_weights))
+            .unwrap_or(default_weights);
+        let mut morph_weights = [glam::Vec4::ZERO; NUM_MAX_MORPH_TARGET / 4];
+        for (index, weight) in weights.iter().enumerate() {
+            morph_weights[index / 4][index % 4] = *weight;
+        }
+        morph_weights
+    }
+
     pub fn get_draw_objects(&self) -> Vec<&EDrawObjectType> {
         match &self.run_time {
             Some(x) => x.draw_objects.values().map(|x| x).collect(),
@@ -356,12 +437,24 @@ impl SkeletonMeshComponent {
                         *draw_object = engine.create_material_draw_object_from_skin_mesh(
                             &skin_mesh.vertexes,
                             &skin_mesh.indexes,
+                            &skin_mesh.morph_targets,
                             Some(format!("{} - {}", &self.name, &skin_mesh.name)),
                             material.clone(),
                             player_viewport.global_constants_handle.clone(),
                             player_viewport.point_lights_constants_handle.clone(),
                             player_viewport.spot_lights_constants_handle.clone(),
                         );
+                        run_time.lod_index_buffers.remove(name);
+                        if let Some(lod_index_buffers) = LodIndexBuffers::new(
+                            engine,
+                            draw_object,
+                            &skin_mesh.lods,
+                            &skin_mesh.name,
+                        ) {
+                            run_time
+                                .lod_index_buffers
+                                .insert(name.clone(), lod_index_buffers);
+                        }
                     }
                 }
                 _ => unimplemented!(),
@@ -369,6 +462,32 @@ impl SkeletonMeshComponent {
         }
     }
 
+    /// The bounding sphere of the meshes in bind pose in world space, enlarged by a margin since
+    /// the animated vertexes are not tracked.
+    pub fn get_world_bounds(&self) -> Option<Sphere3D> {
+        let aabb = self.run_time.as_ref()?.aabb.as_ref()?;
+        let mut bounds = aabb_as_sphere(aabb);
+        bounds.radius *= ANIMATED_BOUNDS_MARGIN_SCALE;
+        Some(transform_sphere(&bounds, &self.transformation))
+    }
+
+    /// Selects the level of detail of every skin mesh by the projected size of the bounds in
+    /// bind pose.
+    pub fn update_lod(&mut self, camera: &Camera) {
+        let Some(run_time) = self.run_time.as_mut() else {
+            return;
+        };
+        let Some(aabb) = run_time.aabb.as_ref() else {
+            return;
+        };
+        let screen_size = compute_screen_size(&transform_aabb(aabb, &self.transformation), camera);
+        for (name, lod_index_buffers) in run_time.lod_index_buffers.iter_mut() {
+            if let Some(draw_object) = run_time.draw_objects.get_mut(name) {
+                lod_index_buffers.update(screen_size, draw_object);
+            }
+        }
+    }
+
     fn build_physics(
         meshes: Vec<Arc<SkinMesh>>,
         is_use_convex_decomposition: bool,
@@ -43,6 +43,10 @@ impl Application {
             engine, // infos,
             input_mode, false,
         );
+        #[cfg(feature = "plugin_shared_crate")]
+        for plugin in plugins.iter_mut() {
+            plugin.register_components(engine.get_component_registry_mut());
+        }
         let mut current_active_level =
             current_active_level.make_copy_for_standalone(engine, &contents, &mut player_view_port);
 
@@ -130,14 +134,16 @@ impl Application {
         if let Some(physics) = active_level.get_physics_mut() {
             physics.collision_events.clear();
         }
+        active_level.update_streaming(engine, &self._contents, &mut self.player_view_port);
         active_level.tick(engine.get_game_time(), engine, &mut self.player_view_port);
-        let mut draw_objects = active_level.collect_draw_objects();
-        for draw_object in draw_objects.iter_mut() {
+        let mut draw_objects = active_level.collect_visible_draw_objects(&self.player_view_port);
+        for x in draw_objects.iter_mut() {
             self.player_view_port
-                .update_draw_object(engine, draw_object);
-            draw_object.switch_player_viewport(&self.player_view_port);
+                .update_draw_object(engine, &mut x.draw_object);
+            x.draw_object.switch_player_viewport(&self.player_view_port);
         }
-        self.player_view_port.append_to_draw_list(&draw_objects);
+        self.player_view_port
+            .append_visible_to_draw_list(&draw_objects);
 
         if let Some(physics) = active_level.get_physics_mut() {
             self.player_view_port.physics_debug(
@@ -154,7 +160,19 @@ impl Application {
     }
 
     #[cfg(feature = "plugin_shared_crate")]
-    pub fn reload_plugins(&mut self, plugins: Vec<Box<dyn Plugin>>) {
+    /// Saves and drops the custom components of the level before the plugins are unloaded.
+    pub fn unload_custom_components(&self) {
+        self.current_active_level
+            .borrow()
+            .unload_custom_components();
+    }
+
+    #[cfg(feature = "plugin_shared_crate")]
+    /// The custom components of the new plugins must be already registered.
+    pub fn reload_plugins(&mut self, engine: &mut Engine, plugins: Vec<Box<dyn Plugin>>) {
         self.plugins = plugins;
+        self.current_active_level
+            .borrow_mut()
+            .load_custom_components(engine, &self._contents, &mut self.player_view_port);
     }
 }
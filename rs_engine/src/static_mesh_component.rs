@@ -1,13 +1,16 @@
 use crate::{
+    camera::Camera,
     content::{content_file_type::EContentFileType, material::Material},
     drawable::EDrawObjectType,
     engine::Engine,
-    misc::{static_mesh_get_aabb, transform_aabb},
+    mesh_lod::{compute_screen_size, ClusterLodBuffers, LodIndexBuffers},
+    misc::{aabb_as_sphere, static_mesh_get_aabb, transform_aabb, transform_sphere},
     player_viewport::PlayerViewport,
     resource_manager::ResourceManager,
 };
 use rapier3d::prelude::*;
 use rs_artifact::static_mesh::StaticMesh;
+use rs_core_minimal::sphere_3d::Sphere3D;
 use rs_foundation::new::SingleThreadMutType;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -30,11 +33,23 @@ impl Physics {
 #[derive(Clone)]
 pub struct StaticMeshComponentRuntime {
     draw_objects: Option<EDrawObjectType>,
+    lod_index_buffers: Option<LodIndexBuffers>,
+    cluster_lod_buffers: Option<ClusterLodBuffers>,
     _mesh: Option<Arc<StaticMesh>>,
     pub physics: Option<Physics>,
     pub parent_final_transformation: glam::Mat4,
     pub final_transformation: glam::Mat4,
     aabb: Option<Aabb>,
+    world_bounds: Option<Sphere3D>,
+}
+
+impl StaticMeshComponentRuntime {
+    fn update_world_bounds(&mut self) {
+        self.world_bounds = self
+            .aabb
+            .as_ref()
+            .map(|x| transform_sphere(&aabb_as_sphere(x), &self.final_transformation));
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -78,6 +93,7 @@ impl StaticMeshComponent {
             return;
         };
         run_time.final_transformation = final_transformation;
+        run_time.update_world_bounds();
     }
 
     pub fn get_final_transformation(&self) -> glam::Mat4 {
@@ -168,14 +184,21 @@ impl StaticMeshComponent {
                 _ => unimplemented!(),
             }
             let aabb = static_mesh_get_aabb(&find_static_mesh);
-            self.run_time = Some(StaticMeshComponentRuntime {
+            let (lod_index_buffers, cluster_lod_buffers) =
+                Self::make_lod_buffers(engine, &draw_object, &find_static_mesh);
+            let mut run_time = StaticMeshComponentRuntime {
                 draw_objects: Some(draw_object),
+                lod_index_buffers,
+                cluster_lod_buffers,
                 _mesh: Some(find_static_mesh),
                 physics: None,
                 final_transformation: glam::Mat4::IDENTITY,
                 parent_final_transformation: glam::Mat4::IDENTITY,
                 aabb: Some(aabb),
-            })
+                world_bounds: None,
+            };
+            run_time.update_world_bounds();
+            self.run_time = Some(run_time);
         }
     }
 
@@ -317,9 +340,68 @@ impl StaticMeshComponent {
                 player_viewport.global_constants_handle.clone(),
             );
         }
+        (run_time.lod_index_buffers, run_time.cluster_lod_buffers) =
+            Self::make_lod_buffers(engine, &draw_object, static_mesh);
         run_time.draw_objects = Some(draw_object);
     }
 
+    /// Meshes with a cluster level of detail hierarchy select clusters instead of levels.
+    fn make_lod_buffers(
+        engine: &mut Engine,
+        draw_object: &EDrawObjectType,
+        static_mesh: &StaticMesh,
+    ) -> (Option<LodIndexBuffers>, Option<ClusterLodBuffers>) {
+        if let Some(cluster_lod) = &static_mesh.cluster_lod {
+            let cluster_lod_buffers =
+                ClusterLodBuffers::new(engine, cluster_lod, &static_mesh.name);
+            if cluster_lod_buffers.is_some() {
+                return (None, cluster_lod_buffers);
+            }
+        }
+        let lod_index_buffers =
+            LodIndexBuffers::new(engine, draw_object, &static_mesh.lods, &static_mesh.name);
+        (lod_index_buffers, None)
+    }
+
+    /// Selects the level of detail by the projected size of the bounds, or the clusters by
+    /// their projected error.
+    pub fn update_lod(&mut self, engine: &mut Engine, camera: &Camera, viewport_height: u32) {
+        let Some(aabb) = self.get_aabb() else {
+            return;
+        };
+        let screen_size = compute_screen_size(&aabb, camera);
+        let model = self.get_final_transformation();
+        let Some(run_time) = self.run_time.as_mut() else {
+            return;
+        };
+        if let (Some(cluster_lod_buffers), Some(draw_objects)) = (
+            run_time.cluster_lod_buffers.as_mut(),
+            run_time.draw_objects.as_mut(),
+        ) {
+            let setting = engine
+                .get_settings()
+                .render_setting
+                .cluster_lod_setting
+                .clone();
+            cluster_lod_buffers.update(
+                engine,
+                &model,
+                camera,
+                viewport_height,
+                &setting,
+                draw_objects,
+            );
+            return;
+        }
+        let (Some(lod_index_buffers), Some(draw_objects)) = (
+            run_time.lod_index_buffers.as_mut(),
+            run_time.draw_objects.as_mut(),
+        ) else {
+            return;
+        };
+        lod_index_buffers.update(screen_size, draw_objects);
+    }
+
     fn build_physics(
         mesh: &StaticMesh,
         is_use_convex_decomposition: bool,
@@ -467,6 +549,16 @@ impl StaticMeshComponent {
         ));
     }
 
+    /// The number of triangles of the selected clusters, `None` if the mesh has no cluster
+    /// level of detail hierarchy.
+    pub fn get_cluster_lod_triangle_count(&self) -> Option<u32> {
+        self.run_time
+            .as_ref()?
+            .cluster_lod_buffers
+            .as_ref()
+            .map(|x| x.get_selected_triangle_count())
+    }
+
     pub fn get_physics_mut(&mut self) -> Option<&mut Physics> {
         self.run_time.as_mut().map(|x| x.physics.as_mut()).flatten()
     }
@@ -488,6 +580,12 @@ impl StaticMeshComponent {
             .flatten()
     }
 
+    /// The bounding sphere of the mesh in world space, which is updated with the final
+    /// transformation.
+    pub fn get_world_bounds(&self) -> Option<&Sphere3D> {
+        self.run_time.as_ref().and_then(|x| x.world_bounds.as_ref())
+    }
+
     pub fn set_static_mesh_url(
         &mut self,
         static_mesh_url: Option<url::Url>,
@@ -504,7 +602,10 @@ impl StaticMeshComponent {
         if self.static_mesh.is_none() {
             run_time._mesh = None;
             run_time.aabb = None;
+            run_time.world_bounds = None;
             run_time.draw_objects = None;
+            run_time.lod_index_buffers = None;
+            run_time.cluster_lod_buffers = None;
             run_time.physics = None;
             return;
         }
@@ -584,6 +685,9 @@ impl StaticMeshComponent {
         }
         let aabb = static_mesh_get_aabb(&find_static_mesh);
         run_time.aabb = Some(aabb);
+        run_time.update_world_bounds();
+        (run_time.lod_index_buffers, run_time.cluster_lod_buffers) =
+            Self::make_lod_buffers(engine, &draw_object, &find_static_mesh);
         run_time.draw_objects = Some(draw_object);
         run_time._mesh = Some(find_static_mesh);
     }
@@ -49,4 +49,10 @@ impl TVirtualTextureSource for StaticVirtualTextureSource {
             ESourceType::File(image) => image.get_size(),
         }
     }
+
+    fn get_border_size(&self) -> u32 {
+        match &self.source {
+            ESourceType::File(image) => image.get_tile_settings().border_size,
+        }
+    }
 }
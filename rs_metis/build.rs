@@ -36,6 +36,7 @@ fn main() {
     }
 
     println!("cargo:rustc-link-lib=metis");
+    println!("cargo:rustc-link-lib=GKlib");
     let bindings = bindgen::Builder::default()
         .header("../.xmake/deps/METIS/include/metis.h")
         .header("../.xmake/deps/METIS/libmetis/metislib.h")
@@ -1,11 +1,8 @@
-use std::{num::ParseIntError, string::FromUtf8Error};
-
 #[derive(Debug)]
 pub enum Error {
-    IO(std::io::Error, Option<String>),
-    FromUtf8Error(FromUtf8Error),
-    ProcessFail(Option<String>),
-    ParseIntError(ParseIntError),
+    /// A METIS routine returned a status other than `METIS_OK`.
+    Metis(std::os::raw::c_int),
+    InvalidGraph(Option<String>),
     Other(Option<String>),
 }
 
@@ -1,4 +1,4 @@
-use crate::edge::Edge;
+use crate::{bindings::idx_t, edge::Edge};
 use std::collections::HashSet;
 
 pub type GraphVertexIndex = u32;
@@ -19,4 +19,94 @@ impl Graph {
     pub fn get_num_edges(&self) -> u32 {
         self.edges.len() as u32
     }
+
+    pub fn to_csr_graph(&self) -> CsrGraph {
+        CsrGraph::from_adjoin_indices(&self.adjoin_indices)
+    }
+}
+
+/// A graph in the compressed sparse row format that METIS reads.
+/// The neighbors of vertex `i` are `adjncy[xadj[i]..xadj[i + 1]]`.
+#[derive(Debug, Clone, Default)]
+pub struct CsrGraph {
+    pub xadj: Vec<idx_t>,
+    pub adjncy: Vec<idx_t>,
+    /// One weight per vertex.
+    pub vertex_weights: Option<Vec<idx_t>>,
+    /// One weight per entry of `adjncy`, an edge must have the same weight in both directions.
+    pub edge_weights: Option<Vec<idx_t>>,
+}
+
+impl CsrGraph {
+    /// Neighbors are sorted so the same graph always produces the same partition.
+    /// Self loops are dropped, METIS rejects them.
+    pub fn from_adjoin_indices(adjoin_indices: &[HashSet<GraphVertexIndex>]) -> CsrGraph {
+        let mut xadj: Vec<idx_t> = Vec::with_capacity(adjoin_indices.len() + 1);
+        let mut adjncy: Vec<idx_t> = Vec::new();
+        xadj.push(0);
+        for (graph_vertex_index, adjoin_indices) in adjoin_indices.iter().enumerate() {
+            let mut neighbors = adjoin_indices
+                .iter()
+                .filter(|x| **x as usize != graph_vertex_index)
+                .map(|x| *x as idx_t)
+                .collect::<Vec<idx_t>>();
+            neighbors.sort();
+            adjncy.extend(neighbors);
+            xadj.push(adjncy.len() as idx_t);
+        }
+        CsrGraph {
+            xadj,
+            adjncy,
+            vertex_weights: None,
+            edge_weights: None,
+        }
+    }
+
+    pub fn get_num_vertices(&self) -> usize {
+        self.xadj.len().saturating_sub(1)
+    }
+
+    pub fn validate(&self) -> crate::error::Result<()> {
+        let invalid = |message: String| Err(crate::error::Error::InvalidGraph(Some(message)));
+        let num_vertices = self.get_num_vertices();
+        if self.xadj.first().copied().unwrap_or(0) != 0 {
+            return invalid(String::from("xadj must start with 0"));
+        }
+        if self.xadj.windows(2).any(|x| x[0] > x[1]) {
+            return invalid(String::from("xadj must be non-decreasing"));
+        }
+        if self.xadj.last().copied().unwrap_or(0) as usize != self.adjncy.len() {
+            return invalid(format!(
+                "xadj ends at {:?}, but adjncy has {} entries",
+                self.xadj.last(),
+                self.adjncy.len()
+            ));
+        }
+        if let Some(index) = self
+            .adjncy
+            .iter()
+            .find(|x| **x < 0 || **x as usize >= num_vertices)
+        {
+            return invalid(format!("Vertex index {index} is out of range"));
+        }
+        if let Some(vertex_weights) = &self.vertex_weights {
+            if vertex_weights.len() != num_vertices {
+                return invalid(format!(
+                    "Expected {} vertex weights, found {}",
+                    num_vertices,
+                    vertex_weights.len()
+                ));
+            }
+        }
+        if let Some(edge_weights) = &self.edge_weights {
+            if edge_weights.len() != self.adjncy.len() {
+                return invalid(format!(
+                    "Expected {} edge weights, found {}",
+                    self.adjncy.len(),
+                    edge_weights.len()
+                ));
+            }
+        }
+        Ok(())
+    }
 }
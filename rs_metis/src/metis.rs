@@ -1,8 +1,9 @@
 use crate::{
+    bindings::*,
     edge::Edge,
-    graph::{Graph, GraphVertexIndex},
+    graph::{CsrGraph, Graph, GraphVertexIndex},
 };
-use std::{collections::HashSet, ops::Range, process::Command};
+use std::{collections::HashSet, ops::Range};
 
 fn loop_range_next(value: usize, range: Range<usize>) -> usize {
     range.start + (value + 1) % (range.end - range.start)
@@ -14,6 +15,74 @@ fn loop_range_triangle_next(value: usize) -> usize {
     loop_range_next(value, start..end)
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EPartitionMethod {
+    /// `METIS_PartGraphKway`
+    Kway,
+    /// `METIS_PartGraphRecursive`
+    RecursiveBisection,
+}
+
+#[derive(Debug, Clone)]
+pub struct PartitionOptions {
+    pub method: EPartitionMethod,
+    /// Maximum allowed load imbalance, 1.03 lets a part be 3% heavier than the average.
+    /// `None` keeps the METIS default of the chosen method.
+    pub imbalance: Option<f32>,
+    /// Number of different partitionings to compute, the one with the best cut is kept.
+    pub num_cuts: u32,
+    /// Number of refinement iterations at each uncoarsening step.
+    pub num_iterations: u32,
+    /// Seed of the random number generator, `None` keeps the METIS default.
+    pub seed: Option<i32>,
+    /// Force every part to be contiguous. Only used by `EPartitionMethod::Kway`.
+    pub is_contiguous: bool,
+    /// Minimize the maximum connectivity between parts. Only used by `EPartitionMethod::Kway`.
+    pub is_minimize_connectivity: bool,
+}
+
+impl Default for PartitionOptions {
+    fn default() -> Self {
+        Self {
+            method: EPartitionMethod::Kway,
+            imbalance: None,
+            num_cuts: 1,
+            num_iterations: 10,
+            seed: None,
+            is_contiguous: false,
+            is_minimize_connectivity: false,
+        }
+    }
+}
+
+impl PartitionOptions {
+    fn to_metis_options(&self) -> [idx_t; METIS_NOPTIONS as usize] {
+        let mut options: [idx_t; METIS_NOPTIONS as usize] = [0; METIS_NOPTIONS as usize];
+        unsafe {
+            METIS_SetDefaultOptions(options.as_mut_ptr());
+        }
+        options[moptions_et_METIS_OPTION_NUMBERING as usize] = 0;
+        options[moptions_et_METIS_OPTION_NCUTS as usize] = self.num_cuts.max(1) as idx_t;
+        options[moptions_et_METIS_OPTION_NITER as usize] = self.num_iterations.max(1) as idx_t;
+        if let Some(imbalance) = self.imbalance {
+            let ufactor = ((imbalance - 1.0).max(0.0) * 1000.0).round() as idx_t;
+            options[moptions_et_METIS_OPTION_UFACTOR as usize] = ufactor.max(1);
+        }
+        if let Some(seed) = self.seed {
+            options[moptions_et_METIS_OPTION_SEED as usize] = seed as idx_t;
+        }
+        if self.method == EPartitionMethod::Kway {
+            options[moptions_et_METIS_OPTION_CONTIG as usize] = self.is_contiguous as idx_t;
+            options[moptions_et_METIS_OPTION_MINCONN as usize] =
+                self.is_minimize_connectivity as idx_t;
+        }
+        options
+    }
+}
+
+/// Partitioning runs in-process through the METIS C API.
+/// Every call owns its arrays and options and METIS keeps its scratch memory in thread local
+/// storage, so partitions can be computed from several threads at the same time.
 pub struct Metis {}
 
 impl Metis {
@@ -136,17 +205,12 @@ impl Metis {
         indices: &[u32],
         vertices: &[glam::Vec3],
         num_parts: u32,
-        gpmetis_program_path: impl AsRef<std::path::Path>,
+        options: &PartitionOptions,
     ) -> crate::error::Result<Vec<Vec<usize>>> {
-        let output_path = std::path::Path::new("./t.graph").to_path_buf();
-        let output_path = rs_foundation::absolute_path(output_path)
-            .map_err(|err| crate::error::Error::IO(err, None))?;
         let graph = Self::to_graph(indices, vertices);
-        Self::write_graph(&graph, output_path.clone())?;
-        let partition = Self::internal_partition(gpmetis_program_path, output_path, num_parts)?;
-        // let _ = std::fs::remove_file(output_path);
+        let partition = Self::partition_graph(&graph.to_csr_graph(), num_parts, options)?;
 
-        let mut partition_ret: Vec<Vec<GraphVertexIndex>> = vec![vec![]; num_parts as usize];
+        let mut partition_ret: Vec<Vec<GraphVertexIndex>> = vec![vec![]; num_parts.max(1) as usize];
 
         for (graph_vertex_index, which_part) in partition.iter().enumerate() {
             let value = partition_ret
@@ -158,100 +222,147 @@ impl Metis {
         Ok(Self::build_mesh_clusters(&graph, &partition_ret))
     }
 
-    fn internal_partition(
-        gpmetis_program_path: impl AsRef<std::path::Path>,
-        graph_file_path: impl AsRef<std::path::Path>,
+    /// Returns the part of each vertex of the graph.
+    pub fn partition_graph(
+        graph: &CsrGraph,
         num_parts: u32,
+        options: &PartitionOptions,
     ) -> crate::error::Result<Vec<u32>> {
-        let mut cmd = Command::new(gpmetis_program_path.as_ref());
-        cmd.args([
-            graph_file_path
-                .as_ref()
-                .to_str()
-                .ok_or(crate::error::Error::Other(Some(format!(""))))?,
-            &num_parts.to_string(),
-        ]);
-        let output = cmd.output();
-        match output {
-            Ok(output) => {
-                if !output.status.success() {
-                    return Err(crate::error::Error::Other(Some(format!(
-                        "{}",
-                        String::from_utf8(output.stderr)
-                            .map_err(|err| crate::error::Error::FromUtf8Error(err))?
-                    ))));
-                }
-            }
-            Err(err) => {
-                return Err(crate::error::Error::IO(err, None));
-            }
+        graph.validate()?;
+        let num_vertices = graph.get_num_vertices();
+        if num_vertices == 0 {
+            return Ok(vec![]);
+        }
+        if num_parts <= 1 {
+            return Ok(vec![0; num_vertices]);
         }
 
-        let file_name = graph_file_path
-            .as_ref()
-            .file_name()
-            .map(|x| x.to_str().map(|x| format!("{x}.part.{}", num_parts)))
-            .flatten()
-            .ok_or(crate::error::Error::Other(Some(format!("No parent"))))?;
+        // METIS takes non-const pointers, give it copies so the caller's graph is never touched.
+        let mut xadj = graph.xadj.clone();
+        let mut adjncy = graph.adjncy.clone();
+        let mut vertex_weights = graph.vertex_weights.clone();
+        let mut edge_weights = graph.edge_weights.clone();
+        let mut metis_options = options.to_metis_options();
+        let mut nvtxs = num_vertices as idx_t;
+        let mut ncon: idx_t = 1;
+        let mut nparts = num_parts as idx_t;
+        let mut edgecut: idx_t = 0;
+        let mut part: Vec<idx_t> = vec![0; num_vertices];
 
-        let graph_partition_file_path = graph_file_path.as_ref().with_file_name(file_name);
-        let file = std::fs::File::open(graph_partition_file_path.clone()).map_err(|err| {
-            crate::error::Error::IO(err, Some(format!("{:?}", graph_partition_file_path)))
-        })?;
-        let reader = std::io::BufReader::new(file);
-        let mut partition: Vec<u32> = Vec::new();
-        for line in std::io::BufRead::lines(reader) {
-            let which_part: u32 = line
-                .map_err(|err| crate::error::Error::IO(err, None))?
-                .trim()
-                .parse()
-                .map_err(|err| crate::error::Error::ParseIntError(err))?;
-            partition.push(which_part);
-        }
-        // let _ = std::fs::remove_file(graph_partition_file_path);
-        Ok(partition)
-    }
+        let vwgt = vertex_weights
+            .as_mut()
+            .map_or(std::ptr::null_mut(), |x| x.as_mut_ptr());
+        let adjwgt = edge_weights
+            .as_mut()
+            .map_or(std::ptr::null_mut(), |x| x.as_mut_ptr());
+        let partition_function = match options.method {
+            EPartitionMethod::Kway => METIS_PartGraphKway,
+            EPartitionMethod::RecursiveBisection => METIS_PartGraphRecursive,
+        };
 
-    fn write_graph(
-        graph: &Graph,
-        output_path: impl AsRef<std::path::Path>,
-    ) -> crate::error::Result<()> {
-        let mut content = String::new();
-        content.push_str(&format!(
-            "{} {}\n",
-            graph.get_num_vertices(),
-            graph.get_num_edges()
-        ));
-        for indices in &graph.adjoin_indices {
-            let line: String = indices
-                .iter()
-                .map(|x| (x + 1).to_string())
-                .collect::<Vec<String>>()
-                .join(" ");
-            content.push_str(&format!("{}\n", line));
+        let status = unsafe {
+            partition_function(
+                &mut nvtxs,
+                &mut ncon,
+                xadj.as_mut_ptr(),
+                adjncy.as_mut_ptr(),
+                vwgt,
+                std::ptr::null_mut(),
+                adjwgt,
+                &mut nparts,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                metis_options.as_mut_ptr(),
+                &mut edgecut,
+                part.as_mut_ptr(),
+            )
+        };
+        if status != rstatus_et_METIS_OK {
+            return Err(crate::error::Error::Metis(status));
         }
-        std::fs::create_dir_all(
-            output_path
-                .as_ref()
-                .parent()
-                .ok_or(crate::error::Error::Other(Some(format!("No parent"))))?,
-        )
-        .map_err(|err| crate::error::Error::IO(err, None))?;
-        if output_path.as_ref().exists() {
-            std::fs::remove_file(output_path.as_ref())
-                .map_err(|err| crate::error::Error::IO(err, None))?;
-        }
-        std::fs::write(output_path, content).map_err(|err| crate::error::Error::IO(err, None))
+        Ok(part.iter().map(|x| *x as u32).collect())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::bindings::*;
+    use crate::graph::CsrGraph;
     use crate::metis::*;
 
+    fn make_grid(size: u32) -> (Vec<u32>, Vec<glam::Vec3>) {
+        let mut vertices: Vec<glam::Vec3> = vec![];
+        for y in 0..=size {
+            for x in 0..=size {
+                vertices.push(glam::vec3(x as f32, y as f32, 0.0));
+            }
+        }
+        let mut indices: Vec<u32> = vec![];
+        for y in 0..size {
+            for x in 0..size {
+                let i = y * (size + 1) + x;
+                indices.extend([i, i + 1, i + size + 1]);
+                indices.extend([i + 1, i + size + 2, i + size + 1]);
+            }
+        }
+        (indices, vertices)
+    }
+
     #[test]
-    fn test_case() {
-        let graph_t = unsafe { ::std::mem::zeroed::<graph_t>() };
+    fn test_case_partition() {
+        let (indices, vertices) = make_grid(16);
+        let num_parts = 4;
+        let mesh_clusters =
+            Metis::partition(&indices, &vertices, num_parts, &PartitionOptions::default()).unwrap();
+        assert_eq!(mesh_clusters.len(), num_parts as usize);
+        let triangles = mesh_clusters
+            .iter()
+            .flatten()
+            .copied()
+            .collect::<HashSet<usize>>();
+        assert_eq!(triangles.len(), indices.len() / 3);
+    }
+
+    #[test]
+    fn test_case_partition_graph_weights() {
+        let graph = CsrGraph {
+            xadj: vec![0, 1, 3, 5, 6],
+            adjncy: vec![1, 0, 2, 1, 3, 2],
+            vertex_weights: Some(vec![1, 1, 1, 1]),
+            edge_weights: Some(vec![10, 10, 1, 1, 10, 10]),
+        };
+        let options = PartitionOptions {
+            method: EPartitionMethod::RecursiveBisection,
+            seed: Some(1),
+            ..Default::default()
+        };
+        let partition = Metis::partition_graph(&graph, 2, &options).unwrap();
+        assert_eq!(partition[0], partition[1]);
+        assert_eq!(partition[2], partition[3]);
+        assert_ne!(partition[1], partition[2]);
+
+        let mut invalid_graph = graph.clone();
+        invalid_graph.edge_weights = Some(vec![1]);
+        assert!(Metis::partition_graph(&invalid_graph, 2, &options).is_err());
+    }
+
+    #[test]
+    fn test_case_partition_threads() {
+        let (indices, vertices) = make_grid(32);
+        let expected =
+            Metis::partition(&indices, &vertices, 8, &PartitionOptions::default()).unwrap();
+        std::thread::scope(|scope| {
+            let handles = (0..4)
+                .map(|_| {
+                    scope.spawn(|| {
+                        Metis::partition(&indices, &vertices, 8, &PartitionOptions::default())
+                            .unwrap()
+                    })
+                })
+                .collect::<Vec<_>>();
+            for handle in handles {
+                let mesh_clusters = handle.join().unwrap();
+                assert_eq!(mesh_clusters.len(), expected.len());
+            }
+        });
     }
 }
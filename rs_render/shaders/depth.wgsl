@@ -1,5 +1,6 @@
 #include "common.wgsl"
 #include "global_constants.wgsl"
+#include "morph_target.wgsl"
 
 struct VertexIn {
     @location(0) position: vec3<f32>,
@@ -7,6 +8,7 @@ struct VertexIn {
 #ifdef SKELETON_MAX_BONES
     @location(2) bone_ids: vec4<i32>,
     @location(3) bone_weights: vec4<f32>,
+    @builtin(vertex_index) vertex_index: u32,
 #endif    
 };
 
@@ -18,6 +20,9 @@ struct Constants {
 #ifdef SKELETON_MAX_BONES
 struct SkinConstants {
     bones: array<mat4x4<f32>, SKELETON_MAX_BONES>,
+    morph_weights: array<vec4<f32>, MORPH_WEIGHT_VECTORS>,
+    morph_target_count: u32,
+    vertex_count: u32,
 };
 #endif
 
@@ -35,6 +40,7 @@ struct FragmentOutput {
 
 #ifdef SKELETON_MAX_BONES
 @group(0) @binding(2) var<uniform> skin_constants: SkinConstants;
+@group(0) @binding(3) var morph_target_deltas: texture_2d<u32>;
 #endif
 
 @vertex fn vs_main(vertex_in: VertexIn) -> VertexOutput {
@@ -43,11 +49,20 @@ struct FragmentOutput {
     bone_transform += skin_constants.bones[vertex_in.bone_ids[1]] * vertex_in.bone_weights[1];
     bone_transform += skin_constants.bones[vertex_in.bone_ids[2]] * vertex_in.bone_weights[2];
     bone_transform += skin_constants.bones[vertex_in.bone_ids[3]] * vertex_in.bone_weights[3];
+    let morphed_vertex = apply_morph_targets(
+        morph_target_deltas,
+        skin_constants.morph_weights,
+        skin_constants.morph_target_count,
+        skin_constants.vertex_count,
+        vertex_in.vertex_index,
+        vertex_in.position,
+        vec3<f32>(0.0)
+    );
 #endif
     let mvp = global_constants.light_space_matrix * constants.model;
     var vertex_output: VertexOutput;
 #ifdef SKELETON_MAX_BONES
-    vertex_output.position = mvp * bone_transform * vec4<f32>(vertex_in.position, 1.0);
+    vertex_output.position = mvp * bone_transform * vec4<f32>(morphed_vertex.position, 1.0);
 #else
     vertex_output.position = mvp * vec4<f32>(vertex_in.position, 1.0);
 #endif
@@ -24,6 +24,7 @@ struct GlobalConstants {
     feedback_bias: f32,
     debug_shading: i32,
     time: f32,
+    tile_border_size: f32,
     camera_frustum_apply_transformation: Frustum,
 };
 
@@ -0,0 +1,46 @@
+#ifndef MORPH_TARGET_WGSL
+#define MORPH_TARGET_WGSL
+
+struct MorphedVertex {
+    position: vec3<f32>,
+    normal: vec3<f32>,
+};
+
+#ifdef MORPH_WEIGHT_VECTORS
+// The deltas are the bits of the floats, stored row by row as `[position, 0, normal, 0]` per
+// vertex. The deltas of a target are stored contiguously, so the delta of a vertex is at
+// `target * vertex_count + vertex_index`.
+fn load_morph_target_delta(morph_target_deltas: texture_2d<u32>, index: u32) -> vec4<f32> {
+    let width = textureDimensions(morph_target_deltas).x;
+    let texel = textureLoad(morph_target_deltas, vec2<u32>(index % width, index / width), 0);
+    return bitcast<vec4<f32>>(texel);
+}
+
+fn apply_morph_targets(
+    morph_target_deltas: texture_2d<u32>,
+    morph_weights: array<vec4<f32>, MORPH_WEIGHT_VECTORS>,
+    morph_target_count: u32,
+    vertex_count: u32,
+    vertex_index: u32,
+    position: vec3<f32>,
+    normal: vec3<f32>
+) -> MorphedVertex {
+    var morphed_vertex: MorphedVertex;
+    morphed_vertex.position = position;
+    morphed_vertex.normal = normal;
+    for (var i = 0u; i < morph_target_count; i++) {
+        let weight = morph_weights[i / 4u][i % 4u];
+        if weight == 0.0 {
+            continue;
+        }
+        let index = (i * vertex_count + vertex_index) * 2u;
+        let position_delta = load_morph_target_delta(morph_target_deltas, index);
+        let normal_delta = load_morph_target_delta(morph_target_deltas, index + 1u);
+        morphed_vertex.position += position_delta.xyz * weight;
+        morphed_vertex.normal += normal_delta.xyz * weight;
+    }
+    return morphed_vertex;
+}
+#endif
+
+#endif
@@ -4,6 +4,7 @@
 #include "virtual_texture.wgsl"
 #include "light.wgsl"
 #include "cluster_light.wgsl"
+#include "morph_target.wgsl"
 
 struct VertexIn {
     @location(0) position: vec3<f32>,
@@ -15,6 +16,7 @@ struct VertexIn {
 #ifdef SKELETON_MAX_BONES
     @location(6) bone_ids: vec4<i32>,
     @location(7) bone_weights: vec4<f32>,
+    @builtin(vertex_index) vertex_index: u32,
 #endif
 };
 
@@ -30,6 +32,9 @@ struct Constants {
 #ifdef SKELETON_MAX_BONES
 struct SkinConstants {
     bones: array<mat4x4<f32>, SKELETON_MAX_BONES>,
+    morph_weights: array<vec4<f32>, MORPH_WEIGHT_VECTORS>,
+    morph_target_count: u32,
+    vertex_count: u32,
 };
 #endif
 
@@ -48,6 +53,8 @@ struct UserAttributes {
     opacity: f32,
     clear_coat: f32,
     clear_coat_roughness: f32,
+    ambient_occlusion: f32,
+    emissive: vec3<f32>,
 };
 
 struct VertexOutput {
@@ -111,6 +118,7 @@ GROUP_BINDING(POINT_LIGHTS) var<uniform> point_lights: PointLights;
 
 #ifdef SKELETON_MAX_BONES
 GROUP_BINDING(SKIN_CONSTANTS) var<uniform> skin_constants: SkinConstants;
+GROUP_BINDING(MORPH_TARGET_DELTAS) var morph_target_deltas: texture_2d<u32>;
 #endif
 
 #ifdef VIRTUAL_TEXTURE
@@ -245,13 +253,16 @@ fn fetch_clear_coat_info(
 }
 
 fn get_normal(i_normal: vec3<f32>, tbn: mat3x3<f32>) -> vec3<f32> {
-    var normal = normalize(i_normal * 2.0 - 1.0);
+    // Two channel normal maps have no z.
+    let xy = i_normal.xy * 2.0 - 1.0;
+    var normal = vec3<f32>(xy, sqrt(saturate(1.0 - dot(xy, xy))));
     var normal_w = normalize(tbn * normal);
     return normal_w;
 }
 
 fn get_user_attributes(vertex_output: VertexOutput) -> UserAttributes {
     var user_attributes: UserAttributes;
+    user_attributes.ambient_occlusion = 1.0;
 #ifdef MATERIAL_SHADER_CODE
     MATERIAL_SHADER_CODE
 #endif
@@ -296,6 +307,15 @@ fn get_shading_info(user_attributes: UserAttributes, vertex_output: VertexOutput
     bone_transform += skin_constants.bones[bone_index_1] * vertex_in.bone_weights[1];
     bone_transform += skin_constants.bones[bone_index_2] * vertex_in.bone_weights[2];
     bone_transform += skin_constants.bones[bone_index_3] * vertex_in.bone_weights[3];
+    let morphed_vertex = apply_morph_targets(
+        morph_target_deltas,
+        skin_constants.morph_weights,
+        skin_constants.morph_target_count,
+        skin_constants.vertex_count,
+        vertex_in.vertex_index,
+        vertex_in.position,
+        vertex_in.normal
+    );
 #endif
     let mvp = global_constants.view_projection * constants.model;
     var vertex_output: VertexOutput;
@@ -305,9 +325,9 @@ fn get_shading_info(user_attributes: UserAttributes, vertex_output: VertexOutput
     vertex_output.frag_position = (constants.model * vec4<f32>(vertex_in.position, 1.0)).xyz;
 
 #ifdef SKELETON_MAX_BONES
-    vertex_output.position = mvp * bone_transform * vec4<f32>(vertex_in.position, 1.0);
-    vertex_output.frag_position = (constants.model * bone_transform * vec4<f32>(vertex_in.position, 1.0)).xyz;
-    vertex_output.normal = (transpose(inverse(constants.model * bone_transform)) * vec4<f32>(vertex_in.normal, 0.0)).xyz;
+    vertex_output.position = mvp * bone_transform * vec4<f32>(morphed_vertex.position, 1.0);
+    vertex_output.frag_position = (constants.model * bone_transform * vec4<f32>(morphed_vertex.position, 1.0)).xyz;
+    vertex_output.normal = (transpose(inverse(constants.model * bone_transform)) * vec4<f32>(morphed_vertex.normal, 0.0)).xyz;
 #else
     vertex_output.position = mvp * vec4<f32>(vertex_in.position, 1.0);
     vertex_output.frag_position = (constants.model * vec4<f32>(vertex_in.position, 1.0)).xyz;
@@ -330,6 +350,7 @@ fn get_shading_info(user_attributes: UserAttributes, vertex_output: VertexOutput
     var shading_info = get_shading_info(user_attributes, vertex_output);
 
     var ibl_color = ibl_light(shading_info, irradiance_texture, pre_filter_cube_map_texture, brdflut_texture);
+    ibl_color = ibl_color * user_attributes.ambient_occlusion;
 
     var shadow = shadow_calculation(shadow_map, vertex_output.frag_position_at_light_space);
 
@@ -367,7 +388,8 @@ fn get_shading_info(user_attributes: UserAttributes, vertex_output: VertexOutput
     }
 
     var light_color = ibl_color + point_light_color + spot_light_color;
-    fragment_output.color = vec4<f32>(mix(light_color, light_color * vec3<f32>(0.5), shadow), 1.0);
+    var shadowed_light_color = mix(light_color, light_color * vec3<f32>(0.5), shadow);
+    fragment_output.color = vec4<f32>(shadowed_light_color + user_attributes.emissive, 1.0);
 
     // fragment_output.color = vec4<f32>(ibl_color, 1.0);
     if (global_constants.debug_shading == DEBUG_SHADING_TYPE_BASE_COLOR) {
@@ -1,6 +1,7 @@
 #include "common.wgsl"
 #include "global_constants.wgsl"
 #include "virtual_texture.wgsl"
+#include "morph_target.wgsl"
 
 struct VertexIn {
     @location(0) position: vec3<f32>,
@@ -12,6 +13,7 @@ struct VertexIn {
 #ifdef SKELETON_MAX_BONES
     @location(6) bone_ids: vec4<i32>,
     @location(7) bone_weights: vec4<f32>,
+    @builtin(vertex_index) vertex_index: u32,
 #endif
 };
 
@@ -39,6 +41,9 @@ struct Constants
     id: u32,
 #ifdef SKELETON_MAX_BONES
     bones: array<mat4x4<f32>, SKELETON_MAX_BONES>,
+    morph_weights: array<vec4<f32>, MORPH_WEIGHT_VECTORS>,
+    morph_target_count: u32,
+    vertex_count: u32,
 #endif
 };
 
@@ -56,12 +61,25 @@ struct Constants
 
 @group(2) @binding(0) var<uniform> constants: Constants;
 
+#ifdef SKELETON_MAX_BONES
+@group(2) @binding(1) var morph_target_deltas: texture_2d<u32>;
+#endif
+
 @vertex fn vs_main(vertex_in: VertexIn) -> VertexOutput {
 #ifdef SKELETON_MAX_BONES
     var bone_transform = constants.bones[vertex_in.bone_ids[0]] * vertex_in.bone_weights[0];
     bone_transform += constants.bones[vertex_in.bone_ids[1]] * vertex_in.bone_weights[1];
     bone_transform += constants.bones[vertex_in.bone_ids[2]] * vertex_in.bone_weights[2];
     bone_transform += constants.bones[vertex_in.bone_ids[3]] * vertex_in.bone_weights[3];
+    let morphed_vertex = apply_morph_targets(
+        morph_target_deltas,
+        constants.morph_weights,
+        constants.morph_target_count,
+        constants.vertex_count,
+        vertex_in.vertex_index,
+        vertex_in.position,
+        vertex_in.normal
+    );
 #endif
 
     let mvp = global_constants.view_projection * constants.model;
@@ -69,9 +87,9 @@ struct Constants
     result.tex_coord = vertex_in.tex_coord;
     result.vertex_color = vertex_in.vertex_color;
 #ifdef SKELETON_MAX_BONES
-    result.position = mvp * bone_transform * vec4<f32>(vertex_in.position, 1.0);
-    result.frag_position = (constants.model * bone_transform * vec4<f32>(vertex_in.position, 1.0)).xyz;
-    result.normal = (transpose(inverse(constants.model * bone_transform)) * vec4<f32>(vertex_in.normal, 0.0)).xyz;
+    result.position = mvp * bone_transform * vec4<f32>(morphed_vertex.position, 1.0);
+    result.frag_position = (constants.model * bone_transform * vec4<f32>(morphed_vertex.position, 1.0)).xyz;
+    result.normal = (transpose(inverse(constants.model * bone_transform)) * vec4<f32>(morphed_vertex.normal, 0.0)).xyz;
 #else
     result.position = mvp * vec4<f32>(vertex_in.position, 1.0);
     result.frag_position = (constants.model * vec4<f32>(vertex_in.position, 1.0)).xyz;
@@ -5,8 +5,10 @@ fn virtual_texture_sample(tex_coord: vec2<f32>, max_lod: u32, texture_size: vec2
     let lod = min(u32(mipmap_level(tex_coord, vec2<f32>(physical_size))), max_lod);
     let texture_mip_size = mipmap_size(texture_size, lod);
     let tile_size = global_constants.tile_size;
+    let tile_border_size = global_constants.tile_border_size;
     let tiles = texture_mip_size / tile_size;
-    let origin = vec2<f32>(textureLoad(page_table_texture, vec2<i32>(tex_coord * tiles), i32(lod)).xy * u32(tile_size));
+    let page = vec2<f32>(textureLoad(page_table_texture, vec2<i32>(tex_coord * tiles), i32(lod)).xy);
+    let origin = page * (tile_size + 2.0 * tile_border_size) + tile_border_size;
     let factor = (tex_coord * texture_mip_size % tile_size) / tile_size;
     var uv = vec2<f32>(0.0, 0.0);
     uv.x = mix(origin.x, origin.x + tile_size, factor.x);
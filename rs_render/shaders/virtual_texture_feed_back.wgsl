@@ -1,4 +1,5 @@
 #include "global_constants.wgsl"
+#include "morph_target.wgsl"
 
 const U32_MAX: u32 = 4294967295;
 
@@ -8,6 +9,7 @@ struct VertexIn {
 #ifdef SKELETON_MAX_BONES
     @location(2) bone_ids: vec4<i32>,
     @location(3) bone_weights: vec4<f32>,
+    @builtin(vertex_index) vertex_index: u32,
 #endif
 };
 
@@ -24,6 +26,9 @@ struct Constants {
 #ifdef SKELETON_MAX_BONES
 struct SkinConstants {
     bones: array<mat4x4<f32>, SKELETON_MAX_BONES>,
+    morph_weights: array<vec4<f32>, MORPH_WEIGHT_VECTORS>,
+    morph_target_count: u32,
+    vertex_count: u32,
 }
 #endif
 
@@ -40,6 +45,7 @@ fn mipmap_level(uv: vec2<f32>, texture_size: vec2<f32>) -> f32 {
 
 #ifdef SKELETON_MAX_BONES
 @group(1) @binding(1) var<uniform> skin_constants: SkinConstants;
+@group(1) @binding(2) var morph_target_deltas: texture_2d<u32>;
 #endif
 
 @vertex
@@ -49,6 +55,15 @@ fn vs_main(vertex_in: VertexIn) -> VertexOutput {
     bone_transform += skin_constants.bones[vertex_in.bone_ids[1]] * vertex_in.bone_weights[1];
     bone_transform += skin_constants.bones[vertex_in.bone_ids[2]] * vertex_in.bone_weights[2];
     bone_transform += skin_constants.bones[vertex_in.bone_ids[3]] * vertex_in.bone_weights[3];
+    let morphed_vertex = apply_morph_targets(
+        morph_target_deltas,
+        skin_constants.morph_weights,
+        skin_constants.morph_target_count,
+        skin_constants.vertex_count,
+        vertex_in.vertex_index,
+        vertex_in.position,
+        vec3<f32>(0.0)
+    );
 #endif
 
     let mvp = global_constants.view_projection * constants.model;
@@ -56,7 +71,7 @@ fn vs_main(vertex_in: VertexIn) -> VertexOutput {
     result.tex_coord = vertex_in.tex_coord;
     result.position = mvp * vec4<f32>(vertex_in.position, 1.0);
 #ifdef SKELETON_MAX_BONES
-    result.position = mvp * bone_transform * vec4<f32>(vertex_in.position, 1.0);
+    result.position = mvp * bone_transform * vec4<f32>(morphed_vertex.position, 1.0);
 #else
     result.position = mvp * vec4<f32>(vertex_in.position, 1.0);
 #endif
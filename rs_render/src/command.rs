@@ -163,7 +163,7 @@ pub struct VirtualPassSet {
     pub binding_resources: Vec<Vec<EBindingResource>>,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct MultiDrawIndirect {
     pub indirect_buffer_handle: BufferHandle,
     pub indirect_offset: wgpu::BufferAddress,
@@ -331,6 +331,8 @@ pub type TaskType = Arc<Mutex<Box<dyn FnMut(&mut crate::renderer::Renderer) + Se
 pub struct PresentInfo {
     pub render_target_type: ERenderTargetType,
     pub draw_objects: Vec<DrawObject>,
+    /// The draw objects which are drawn only into the shadow map.
+    pub shadow_draw_objects: Vec<DrawObject>,
     pub virtual_texture_pass: Option<VirtualTexturePassKey>,
     pub scene_viewport: SceneViewport,
     pub depth_texture_handle: Option<TextureHandle>,
@@ -1,4 +1,4 @@
-use crate::global_shaders::skeleton_shading::NUM_MAX_BONE;
+use crate::global_shaders::skeleton_shading::{NUM_MAX_BONE, NUM_MAX_MORPH_TARGET};
 
 pub const MAX_POINT_LIGHTS_NUM: u32 = 2;
 pub const MAX_SPOT_LIGHTS_NUM: u32 = 2;
@@ -41,12 +41,22 @@ impl Default for Constants {
 #[derive(Clone, Copy, Debug)]
 pub struct SkinConstants {
     pub bones: [glam::Mat4; NUM_MAX_BONE],
+    pub morph_weights: [glam::Vec4; NUM_MAX_MORPH_TARGET / 4],
+    pub morph_target_count: u32,
+    pub vertex_count: u32,
+    _pad_0: u32,
+    _pad_1: u32,
 }
 
 impl Default for SkinConstants {
     fn default() -> Self {
         Self {
             bones: [glam::Mat4::IDENTITY; NUM_MAX_BONE],
+            morph_weights: [glam::Vec4::ZERO; NUM_MAX_MORPH_TARGET / 4],
+            morph_target_count: 0,
+            vertex_count: 0,
+            _pad_0: 0,
+            _pad_1: 0,
         }
     }
 }
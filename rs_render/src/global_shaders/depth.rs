@@ -1,4 +1,7 @@
-use super::{global_shader::GlobalShader, skeleton_shading::NUM_MAX_BONE};
+use super::{
+    global_shader::GlobalShader,
+    skeleton_shading::{NUM_MAX_BONE, NUM_MAX_MORPH_TARGET},
+};
 use crate::get_buildin_shader_dir;
 use rs_shader_compiler::pre_process::ShaderDescription;
 
@@ -26,7 +29,10 @@ impl GlobalShader for DepthSkinShader {
         let shader_description = ShaderDescription {
             shader_path: get_buildin_shader_dir().join("depth.wgsl"),
             include_dirs: vec![],
-            definitions: vec![format!("SKELETON_MAX_BONES={}", NUM_MAX_BONE)],
+            definitions: vec![
+                format!("SKELETON_MAX_BONES={}", NUM_MAX_BONE),
+                format!("MORPH_WEIGHT_VECTORS={}", NUM_MAX_MORPH_TARGET / 4),
+            ],
         };
         shader_description
     }
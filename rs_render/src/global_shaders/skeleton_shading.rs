@@ -4,6 +4,9 @@ use rs_shader_compiler::pre_process::ShaderDescription;
 
 pub const NUM_MAX_BONE: usize = 255;
 
+/// The weights are packed into vectors of four, so this should be a multiple of four.
+pub const NUM_MAX_MORPH_TARGET: usize = 64;
+
 pub struct SkeletonShadingShader {}
 
 impl GlobalShader for SkeletonShadingShader {
@@ -11,7 +14,10 @@ impl GlobalShader for SkeletonShadingShader {
         let shader_description = ShaderDescription {
             shader_path: get_buildin_shader_dir().join("phong_shading.wgsl"),
             include_dirs: vec![],
-            definitions: vec![format!("SKELETON_MAX_BONES={NUM_MAX_BONE}")],
+            definitions: vec![
+                format!("SKELETON_MAX_BONES={NUM_MAX_BONE}"),
+                format!("MORPH_WEIGHT_VECTORS={}", NUM_MAX_MORPH_TARGET / 4),
+            ],
         };
         shader_description
     }
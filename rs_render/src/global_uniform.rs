@@ -60,7 +60,8 @@ pub struct Constants {
     pub feedback_bias: f32,
     debug_shading: i32,
     pub time: f32,
-    _pad_0: [i32; 2],
+    pub tile_border_size: f32,
+    _pad_0: i32,
     pub camera_frustum: CameraFrustum,
 }
 
@@ -3,7 +3,7 @@ use crate::{
     base_render_pipeline_pool::{BaseRenderPipelineBuilder, BaseRenderPipelinePool},
     global_shaders::{
         global_shader::GlobalShader,
-        skeleton_shading::{SkeletonShadingShader, NUM_MAX_BONE},
+        skeleton_shading::{SkeletonShadingShader, NUM_MAX_BONE, NUM_MAX_MORPH_TARGET},
     },
     gpu_vertex_buffer::GpuVertexBufferImp,
     shader_library::ShaderLibrary,
@@ -30,6 +30,11 @@ pub struct Constants {
     _pad8_1: u32,
     _pad8_2: u32,
     pub bones: [glam::Mat4; 255],
+    pub morph_weights: [glam::Vec4; NUM_MAX_MORPH_TARGET / 4],
+    pub morph_target_count: u32,
+    pub vertex_count: u32,
+    _pad16_0: u32,
+    _pad16_1: u32,
 }
 
 impl Default for Constants {
@@ -43,6 +48,11 @@ impl Default for Constants {
             specular_texture_max_lod: Default::default(),
             is_virtual_specular_texture: Default::default(),
             bones: [glam::Mat4::IDENTITY; NUM_MAX_BONE],
+            morph_weights: [glam::Vec4::ZERO; NUM_MAX_MORPH_TARGET / 4],
+            morph_target_count: Default::default(),
+            vertex_count: Default::default(),
+            _pad16_0: Default::default(),
+            _pad16_1: Default::default(),
             id: Default::default(),
             _pad8_0: Default::default(),
             _pad8_1: Default::default(),
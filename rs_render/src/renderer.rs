@@ -115,6 +115,54 @@ pub struct Renderer {
 }
 
 impl Renderer {
+    /// Writes every mip level of the texture, `data` contains the levels in order from the
+    /// largest one and each level is tightly packed.
+    fn write_texture_mip_levels(queue: &Queue, texture: &Texture, data: &[u8]) {
+        let format = texture.format();
+        let (block_width, block_height) = format.block_dimensions();
+        let Some(block_size) = format.block_copy_size(None) else {
+            log::warn!("Can not write texture of format {:?}", format);
+            return;
+        };
+        let mut offset: usize = 0;
+        for mip_level in 0..texture.mip_level_count() {
+            let size = texture
+                .size()
+                .mip_level_size(mip_level, texture.dimension())
+                .physical_size(format);
+            let blocks_x = size.width / block_width;
+            let blocks_y = size.height / block_height;
+            let length = blocks_x as usize
+                * blocks_y as usize
+                * size.depth_or_array_layers as usize
+                * block_size as usize;
+            let Some(mip_level_data) = data.get(offset..offset + length) else {
+                log::warn!(
+                    "Texture data is too short, mip level: {}, length: {}",
+                    mip_level,
+                    data.len()
+                );
+                return;
+            };
+            queue.write_texture(
+                ImageCopyTexture {
+                    texture,
+                    mip_level,
+                    origin: Origin3d::ZERO,
+                    aspect: TextureAspect::All,
+                },
+                mip_level_data,
+                ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(blocks_x * block_size),
+                    rows_per_image: Some(blocks_y),
+                },
+                size,
+            );
+            offset += length;
+        }
+    }
+
     pub fn from_context(
         wgpu_context: WGPUContext,
         surface_width: u32,
@@ -570,10 +618,12 @@ impl Renderer {
         // }
         self.bind_groups_collection.run_pending_tasks();
         self.vt_pass(&present_info);
-        self.shadow_for_draw_objects(
-            present_info.draw_objects.as_slice(),
-            present_info.depth_texture_handle,
-        );
+        let shadow_draw_objects: Vec<&DrawObject> = present_info
+            .draw_objects
+            .iter()
+            .chain(present_info.shadow_draw_objects.iter())
+            .collect();
+        self.shadow_for_draw_objects(&shadow_draw_objects, present_info.depth_texture_handle);
 
         let color_texture = match present_info.render_target_type {
             ERenderTargetType::SurfaceTexture(window_id) => {
@@ -803,12 +853,20 @@ impl Renderer {
                 let texture = device
                     .create_texture(&create_texture_command.texture_descriptor_create_info.get());
                 if let Some(init_data) = &create_texture_command.init_data {
-                    queue.write_texture(
-                        texture.as_image_copy(),
-                        &init_data.data,
-                        init_data.data_layout,
-                        create_texture_command.texture_descriptor_create_info.size,
-                    );
+                    let texture_descriptor_create_info =
+                        &create_texture_command.texture_descriptor_create_info;
+                    if texture_descriptor_create_info.mip_level_count > 1
+                        || texture_descriptor_create_info.format.is_compressed()
+                    {
+                        Self::write_texture_mip_levels(queue, &texture, &init_data.data);
+                    } else {
+                        queue.write_texture(
+                            texture.as_image_copy(),
+                            &init_data.data,
+                            init_data.data_layout,
+                            texture_descriptor_create_info.size,
+                        );
+                    }
                 }
                 let handle = create_texture_command.handle;
                 self.texture_views.insert(
@@ -1269,8 +1327,18 @@ impl Renderer {
                 index_buffer,
                 index_count: draw_object_command.index_count,
                 draw_type: match &draw_object_command.draw_call_type {
-                    EDrawCallType::MultiDrawIndirect(_) => {
-                        todo!()
+                    EDrawCallType::MultiDrawIndirect(multi_draw_indirect) => {
+                        let indirect_buffer = self
+                            .buffers
+                            .get(&multi_draw_indirect.indirect_buffer_handle)
+                            .unwrap();
+                        crate::gpu_vertex_buffer::EDrawCallType::MultiDrawIndirect(
+                            crate::gpu_vertex_buffer::MultiDrawIndirect {
+                                indirect_buffer,
+                                indirect_offset: multi_draw_indirect.indirect_offset,
+                                count: multi_draw_indirect.count,
+                            },
+                        )
                     }
                     EDrawCallType::Draw(draw) => crate::gpu_vertex_buffer::EDrawCallType::Draw(
                         crate::gpu_vertex_buffer::Draw {
@@ -2337,7 +2405,7 @@ impl Renderer {
 
     fn shadow_for_draw_objects(
         &mut self,
-        draw_objects: &[DrawObject],
+        draw_objects: &[&DrawObject],
         depth_texture_handle: Option<TextureHandle>,
     ) {
         let Some(shadow_pipilines) = self.shadow_pipilines.as_mut() else {
@@ -2481,7 +2549,7 @@ impl Renderer {
             });
 
             for (i, bind_groups_key) in bind_groupss.drain(..).enumerate() {
-                let draw_object = &draw_objects[i];
+                let draw_object = draw_objects[i];
                 let Some(shadow_mapping) = &draw_object.shadow_mapping else {
                     continue;
                 };
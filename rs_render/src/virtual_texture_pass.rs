@@ -278,6 +278,39 @@ impl VirtualTexturePass {
         }
     }
 
+    /// The size of a tile and its borders in the physical texture.
+    fn get_slot_size(&self) -> u32 {
+        self.settings.tile_size + 2 * self.settings.tile_border_size
+    }
+
+    /// Crops or extends the border of a tile, extended texels are clamped to the edge.
+    fn fit_tile_border(
+        image: image::DynamicImage,
+        border_size: u32,
+        target_border_size: u32,
+    ) -> image::DynamicImage {
+        if border_size == target_border_size {
+            return image;
+        }
+        let rgba_image = image.to_rgba8();
+        let tile_size = rgba_image.width().saturating_sub(2 * border_size);
+        let target_size = tile_size + 2 * target_border_size;
+        let max = glam::ivec2(
+            rgba_image.width() as i32 - 1,
+            rgba_image.height() as i32 - 1,
+        );
+        let offset = border_size as i32 - target_border_size as i32;
+        image::DynamicImage::ImageRgba8(image::RgbaImage::from_fn(
+            target_size,
+            target_size,
+            |x, y| {
+                let position =
+                    (glam::ivec2(x as i32, y as i32) + offset).clamp(glam::IVec2::ZERO, max);
+                *rgba_image.get_pixel(position.x as u32, position.y as u32)
+            },
+        ))
+    }
+
     fn load_texture(
         &mut self,
         device: &wgpu::Device,
@@ -301,6 +334,11 @@ impl VirtualTexturePass {
         let Some(image) = virtual_texture_source.get_tile_image(index) else {
             return;
         };
+        let image = Self::fit_tile_border(
+            image,
+            virtual_texture_source.get_border_size(),
+            self.settings.tile_border_size,
+        );
         let texture_extent = wgpu::Extent3d {
             width: image.width(),
             height: image.height(),
@@ -430,8 +468,12 @@ impl VirtualTexturePass {
                         continue;
                     };
                     let physical_texture_size = self.settings.physical_texture_size;
-                    let tile_size = self.settings.tile_size;
-                    let steps = physical_texture_size / tile_size;
+                    let slot_size = self.get_slot_size();
+                    let steps = physical_texture_size / slot_size;
+                    if next_upload_index >= steps * steps {
+                        log::warn!("The physical texture is full.");
+                        continue;
+                    }
                     let x = next_upload_index % steps;
                     let y = next_upload_index / steps;
 
@@ -450,15 +492,15 @@ impl VirtualTexturePass {
                             texture: &self.physical_texture,
                             mip_level: 0,
                             origin: Origin3d {
-                                x: x * tile_size,
-                                y: y * tile_size,
+                                x: x * slot_size,
+                                y: y * slot_size,
                                 z: 0,
                             },
                             aspect: TextureAspect::All,
                         },
                         Extent3d {
-                            width: tile_size,
-                            height: tile_size,
+                            width: slot_size,
+                            height: slot_size,
                             depth_or_array_layers: 1,
                         },
                     );
@@ -594,6 +636,11 @@ impl VirtualTexturePass {
     }
 
     pub fn set_settings(&mut self, settings: VirtualTextureSetting) {
+        if settings.tile_size != self.settings.tile_size
+            || settings.tile_border_size != self.settings.tile_border_size
+        {
+            self.textures_cache.clear();
+        }
         self.settings = settings;
     }
 
@@ -3,6 +3,11 @@ use std::sync::{Arc, Mutex};
 pub trait TVirtualTextureSource: Send + Sync {
     fn get_tile_image(&mut self, index: &glam::UVec3) -> Option<image::DynamicImage>;
     fn get_size(&self) -> glam::UVec2;
+
+    /// The number of texels around every tile which are copied from the neighboring tiles.
+    fn get_border_size(&self) -> u32 {
+        0
+    }
 }
 
 pub struct VirtualTextureSource {
@@ -21,4 +26,8 @@ impl VirtualTextureSource {
     pub fn get_size(&self) -> glam::UVec2 {
         self.inner.lock().unwrap().get_size()
     }
+
+    pub fn get_border_size(&self) -> u32 {
+        self.inner.lock().unwrap().get_border_size()
+    }
 }
@@ -55,6 +55,7 @@ mod test {
             "VIRTUAL_TEXTURE_CONSTANTS_BINDING=3",
             "STANDARD_MATERIAL_CLEARCOAT",
             "SKELETON_MAX_BONES",
+            "MORPH_WEIGHT_VECTORS=16",
         ];
         macro_rules! group_binding {
             ($name:literal, $g:expr, $b:expr) => {
@@ -77,6 +78,7 @@ mod test {
         group_binding!("LIGHTS", 0, 10);
         group_binding!("SKIN_CONSTANTS", 0, 11);
         group_binding!("VIRTUAL_TEXTURE_CONSTANTS", 0, 12);
+        group_binding!("MORPH_TARGET_DELTAS", 0, 13);
 
         let shader_code = pre_process(
             &shader_path,